@@ -3,8 +3,10 @@
 //! Buckets are remote manifest sources that can be added to WenPM.
 //! They use the same manifest format as local sources.
 
+use crate::utils::http::HostHeaders;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
@@ -14,7 +16,7 @@ pub struct Bucket {
     /// Bucket name (unique identifier)
     pub name: String,
 
-    /// URL to the manifest.json file
+    /// URL to the manifest.json file (or a scoop manifest / bucket repository)
     pub url: String,
 
     /// Whether this bucket is enabled
@@ -24,6 +26,52 @@ pub struct Bucket {
     /// Priority (higher = higher priority, used for conflict resolution)
     #[serde(default = "default_priority")]
     pub priority: u32,
+
+    /// Extra request headers (e.g. `Authorization`) sent with manifest and
+    /// asset requests to the bucket's host; values may use `${VAR}`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl Bucket {
+    /// The bucket's custom headers, bound to its host
+    pub fn host_headers(&self) -> Result<HostHeaders> {
+        HostHeaders::new(&self.url, &self.headers)
+            .with_context(|| format!("Invalid headers for bucket '{}'", self.name))
+    }
+}
+
+/// Name of the bucket seeded on first run
+pub const DEFAULT_BUCKET_NAME: &str = "wenget";
+
+/// Manifest of the official Wenget bucket
+pub const DEFAULT_BUCKET_URL: &str =
+    "https://raw.githubusercontent.com/superyngo/wenget-bucket/refs/heads/main/manifest.json";
+
+/// Environment variable overriding the default bucket's URL; empty or
+/// `none` seeds no bucket at all
+pub const DEFAULT_BUCKET_ENV: &str = "WENGET_DEFAULT_BUCKET";
+
+/// The bucket to seed on first run, honoring [`DEFAULT_BUCKET_ENV`]
+pub fn default_bucket() -> Option<Bucket> {
+    default_bucket_for(std::env::var(DEFAULT_BUCKET_ENV).ok().as_deref())
+}
+
+/// The default bucket for a value of [`DEFAULT_BUCKET_ENV`] (None if unset)
+fn default_bucket_for(value: Option<&str>) -> Option<Bucket> {
+    let url = match value.map(str::trim) {
+        None => DEFAULT_BUCKET_URL,
+        Some(v) if v.is_empty() || v.eq_ignore_ascii_case("none") => return None,
+        Some(url) => url,
+    };
+
+    Some(Bucket {
+        name: DEFAULT_BUCKET_NAME.to_string(),
+        url: url.to_string(),
+        enabled: true,
+        priority: default_priority(),
+        headers: BTreeMap::new(),
+    })
 }
 
 fn default_enabled() -> bool {
@@ -125,7 +173,6 @@ impl BucketConfig {
     }
 
     /// Find a bucket by name
-    #[allow(dead_code)]
     pub fn find_bucket(&self, name: &str) -> Option<&Bucket> {
         self.buckets.iter().find(|b| b.name == name)
     }
@@ -163,6 +210,20 @@ impl Default for BucketConfig {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_default_bucket_for() {
+        let official = default_bucket_for(None).unwrap();
+        assert_eq!(official.name, DEFAULT_BUCKET_NAME);
+        assert_eq!(official.url, DEFAULT_BUCKET_URL);
+        assert!(official.enabled);
+
+        let mirror = default_bucket_for(Some("https://mirror.example/manifest.json")).unwrap();
+        assert_eq!(mirror.url, "https://mirror.example/manifest.json");
+
+        assert!(default_bucket_for(Some("")).is_none());
+        assert!(default_bucket_for(Some("None")).is_none());
+    }
+
     #[test]
     fn test_bucket_config_new() {
         let config = BucketConfig::new();
@@ -178,6 +239,7 @@ mod tests {
             url: "https://example.com/manifest.json".to_string(),
             enabled: true,
             priority: 100,
+            headers: BTreeMap::new(),
         };
 
         // First add should succeed
@@ -198,6 +260,7 @@ mod tests {
             url: "https://example.com/manifest.json".to_string(),
             enabled: true,
             priority: 100,
+            headers: BTreeMap::new(),
         };
 
         config.add_bucket(bucket);
@@ -218,6 +281,7 @@ mod tests {
             url: "https://example.com/1.json".to_string(),
             enabled: true,
             priority: 100,
+            headers: BTreeMap::new(),
         });
 
         config.add_bucket(Bucket {
@@ -225,6 +289,7 @@ mod tests {
             url: "https://example.com/2.json".to_string(),
             enabled: false,
             priority: 100,
+            headers: BTreeMap::new(),
         });
 
         let enabled = config.enabled_buckets();
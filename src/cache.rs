@@ -5,12 +5,32 @@
 
 use crate::bucket::{Bucket, BucketConfig};
 use crate::core::manifest::{Package, PackageSource, ScriptItem, SourceManifest};
+use crate::core::Channel;
+use crate::utils::concurrency::parallel_map;
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set by `--no-cache`: ignore every cached bucket manifest
+static BYPASS: AtomicBool = AtomicBool::new(false);
+
+/// Choose whether cached bucket manifests are ignored (`--no-cache`)
+///
+/// While bypassed, the combined manifest cache and the per-bucket entries are
+/// never reused, and buckets are refetched without ETag revalidation.
+pub fn set_bypass(bypass: bool) {
+    BYPASS.store(bypass, Ordering::Relaxed);
+}
+
+/// Whether cached bucket manifests are ignored
+pub fn is_bypassed() -> bool {
+    BYPASS.load(Ordering::Relaxed)
+}
 
 /// Package with source information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +71,10 @@ pub struct CachedSourceInfo {
     /// Bucket URL (for buckets)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
+
+    /// Why the last refresh failed, when an older cached copy is served
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale: Option<String>,
 }
 
 /// Manifest cache view
@@ -77,8 +101,12 @@ pub struct ManifestCache {
     pub scripts: HashMap<String, CachedScript>,
 }
 
+/// How long the manifest cache is used before a rebuild, unless `cache_ttl`
+/// is set
+pub const DEFAULT_TTL_SECS: u64 = 6 * 60 * 60;
+
 fn default_ttl() -> i64 {
-    86400 // 24 hours
+    DEFAULT_TTL_SECS as i64
 }
 
 impl ManifestCache {
@@ -203,7 +231,6 @@ impl ManifestCache {
     }
 
     /// Get packages filtered by source
-    #[allow(dead_code)]
     pub fn packages_by_source(&self, source_type: &PackageSource) -> Vec<&CachedPackage> {
         self.packages
             .values()
@@ -219,6 +246,23 @@ impl ManifestCache {
             .filter(|cs| &cs.source == source_type)
             .collect()
     }
+
+    /// Split `buckets` into those served from stale data and those missing
+    /// from the cache entirely because they failed with no cached copy
+    pub fn bucket_health<'a>(&self, buckets: &[&'a str]) -> (Vec<&'a str>, Vec<&'a str>) {
+        let mut stale = Vec::new();
+        let mut failed = Vec::new();
+
+        for &name in buckets {
+            match self.sources.get(&format!("bucket:{}", name)) {
+                Some(info) if info.stale.is_some() => stale.push(name),
+                Some(_) => {}
+                None => failed.push(name),
+            }
+        }
+
+        (stale, failed)
+    }
 }
 
 impl Default for ManifestCache {
@@ -227,29 +271,210 @@ impl Default for ManifestCache {
     }
 }
 
+/// Cached copy of a single bucket's manifest (cache/buckets/{name}.json)
+///
+/// Each bucket is cached separately so it can be refreshed on its own
+/// schedule; the combined [`ManifestCache`] is merged from these entries.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketCacheEntry {
+    /// URL the manifest was fetched from
+    pub url: String,
+
+    /// When the manifest was last fetched or revalidated
+    pub fetched_at: DateTime<Utc>,
+
+    /// ETag returned by the server, used for conditional requests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+
+    /// The bucket manifest
+    pub manifest: SourceManifest,
+
+    /// Set when this copy is served because a refresh failed
+    #[serde(skip)]
+    pub stale: Option<String>,
+}
+
+impl BucketCacheEntry {
+    /// Load a bucket cache entry, treating missing or corrupted files as absent
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+
+        match serde_json::from_str(&content) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                log::warn!("Ignoring corrupted bucket cache {}: {}", path.display(), e);
+                let _ = fs::remove_file(path);
+                None
+            }
+        }
+    }
+
+    /// Load the cache entry usable for a bucket: present, fetched from the
+    /// bucket's current URL, and not bypassed with `--no-cache`
+    pub fn load_for(path: &Path, bucket: &Bucket) -> Option<Self> {
+        if is_bypassed() {
+            return None;
+        }
+        Self::load(path).filter(|e| e.url == bucket.url)
+    }
+
+    /// Save bucket cache entry to file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory: {}", parent.display())
+            })?;
+        }
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize bucket cache")?;
+
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write bucket cache: {}", path.display()))
+    }
+
+    /// Check if this entry can be used for a bucket without refetching
+    ///
+    /// The entry must come from the bucket's current URL and be younger than
+    /// the TTL.
+    pub fn is_fresh(&self, bucket: &Bucket, ttl_seconds: i64) -> bool {
+        let age = Utc::now() - self.fetched_at;
+        self.url == bucket.url && age.num_seconds() < ttl_seconds
+    }
+}
+
+/// Asset sizes of a repository's latest release, from `info --latest-asset-size`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseSizes {
+    /// Tag of the release the sizes were read from
+    pub tag: String,
+
+    /// Release channel the release was picked on
+    #[serde(default)]
+    pub channel: Channel,
+
+    /// When the release was fetched
+    pub fetched_at: DateTime<Utc>,
+
+    /// Download size in bytes by platform id
+    pub sizes: BTreeMap<String, u64>,
+}
+
+/// Latest release asset sizes by repository URL (`cache/releases.json`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReleaseSizeCache {
+    #[serde(flatten)]
+    pub repos: HashMap<String, ReleaseSizes>,
+}
+
+impl ReleaseSizeCache {
+    /// Load the cache, treating a missing or corrupted file as empty
+    pub fn load(path: &Path) -> Self {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            log::warn!("Ignoring corrupted release cache {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    /// Save the cache to file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| {
+                format!("Failed to create cache directory: {}", parent.display())
+            })?;
+        }
+
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize release cache")?;
+
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write release cache: {}", path.display()))
+    }
+
+    /// The cached sizes for a repository on a channel, unless older than the
+    /// TTL or bypassed with `--no-cache`
+    pub fn get_fresh(
+        &self,
+        repo: &str,
+        channel: Channel,
+        ttl_seconds: i64,
+    ) -> Option<&ReleaseSizes> {
+        if is_bypassed() {
+            return None;
+        }
+        self.repos.get(repo).filter(|entry| {
+            let age = Utc::now() - entry.fetched_at;
+            entry.channel == channel && age.num_seconds() < ttl_seconds
+        })
+    }
+
+    /// Record the sizes just fetched for a repository
+    pub fn insert(
+        &mut self,
+        repo: &str,
+        channel: Channel,
+        tag: String,
+        sizes: BTreeMap<String, u64>,
+    ) {
+        self.repos.insert(
+            repo.to_string(),
+            ReleaseSizes {
+                tag,
+                channel,
+                fetched_at: Utc::now(),
+                sizes,
+            },
+        );
+    }
+}
+
 /// Build cache from buckets only
+///
+/// `bucket_entry_fn` supplies each enabled bucket's manifest, either from its
+/// per-bucket cache entry or by fetching it. Buckets are fetched concurrently
+/// (up to the `--concurrency` limit) but merged in bucket order, so later buckets win regardless of timing.
+/// Buckets that fail are skipped with a warning; buckets served from a stale
+/// copy are recorded as such in [`ManifestCache::sources`].
 pub fn build_cache(
     bucket_config: &BucketConfig,
-    fetch_bucket_fn: impl Fn(&Bucket) -> Result<SourceManifest>,
+    bucket_entry_fn: impl Fn(&Bucket) -> Result<BucketCacheEntry> + Sync,
 ) -> Result<ManifestCache> {
     let mut cache = ManifestCache::new();
     cache.last_updated = Utc::now();
 
     // Add packages from all enabled buckets
     let enabled_buckets = bucket_config.enabled_buckets();
+    let entries = parallel_map(&enabled_buckets, |bucket| bucket_entry_fn(bucket));
 
-    for bucket in enabled_buckets {
+    for (bucket, result) in enabled_buckets.into_iter().zip(entries) {
         let source_key = format!("bucket:{}", bucket.name);
-        let now = Utc::now();
 
-        match fetch_bucket_fn(bucket) {
-            Ok(manifest) => {
+        match result {
+            Ok(entry) => {
+                if let Some(ref error) = entry.stale {
+                    if !crate::utils::is_quiet() {
+                        eprintln!(
+                            "{} Using cached copy of bucket '{}': {}",
+                            "⚠".yellow(),
+                            bucket.name,
+                            error
+                        );
+                    }
+                }
+
+                let manifest = entry.manifest;
                 let package_count = manifest.packages.len();
                 let script_count = manifest.scripts.len();
                 let total_count = package_count + script_count;
 
-                // Add packages
-                for package in manifest.packages {
+                // Add packages, with platform keys in wenget's naming
+                for mut package in manifest.packages {
+                    package.normalize_platforms();
                     cache.add_package(
                         package,
                         PackageSource::Bucket {
@@ -276,13 +501,17 @@ pub fn build_cache(
                             name: bucket.name.clone(),
                         },
                         package_count: total_count,
-                        last_fetched: Some(now),
+                        last_fetched: Some(entry.fetched_at),
                         url: Some(bucket.url.clone()),
+                        stale: entry.stale,
                     },
                 );
             }
             Err(e) => {
-                log::warn!("Failed to fetch bucket '{}': {}", bucket.name, e);
+                log::warn!("Failed to fetch bucket '{}': {:#}", bucket.name, e);
+                if !crate::utils::is_quiet() {
+                    eprintln!("{} Skipping bucket '{}': {}", "⚠".yellow(), bucket.name, e);
+                }
                 // Continue with other buckets
             }
         }
@@ -294,7 +523,9 @@ pub fn build_cache(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::concurrency::MAX_CONCURRENCY;
     use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     #[test]
     fn test_cache_new() {
@@ -315,6 +546,12 @@ mod tests {
             homepage: None,
             license: None,
             platforms: HashMap::new(),
+            requires: Vec::new(),
+            yanked: Vec::new(),
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            post_install_message: None,
+            exe_name: None,
         };
 
         let source = PackageSource::Bucket {
@@ -328,6 +565,255 @@ mod tests {
         assert_eq!(cached.source, source);
     }
 
+    fn test_bucket(name: &str, url: &str) -> Bucket {
+        Bucket {
+            name: name.to_string(),
+            url: url.to_string(),
+            enabled: true,
+            priority: 100,
+            headers: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_bucket_entry_freshness() {
+        let bucket = test_bucket("main", "https://example.com/manifest.json");
+        let mut entry = BucketCacheEntry {
+            url: bucket.url.clone(),
+            fetched_at: Utc::now(),
+            etag: None,
+            manifest: SourceManifest::new(),
+            stale: None,
+        };
+
+        assert!(entry.is_fresh(&bucket, default_ttl()));
+
+        // A bucket pointed at a new URL must be refetched
+        let moved = test_bucket("main", "https://example.com/other.json");
+        assert!(!entry.is_fresh(&moved, default_ttl()));
+
+        // Expired entries must be refetched
+        entry.fetched_at = Utc::now() - chrono::Duration::days(2);
+        assert!(!entry.is_fresh(&bucket, default_ttl()));
+    }
+
+    #[test]
+    fn test_bucket_entry_bypass() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("buckets").join("main.json");
+        let bucket = test_bucket("main", "https://example.com/manifest.json");
+        BucketCacheEntry {
+            url: bucket.url.clone(),
+            fetched_at: Utc::now(),
+            etag: Some("\"abc\"".to_string()),
+            manifest: SourceManifest::new(),
+            stale: None,
+        }
+        .save(&path)
+        .unwrap();
+
+        assert!(BucketCacheEntry::load_for(&path, &bucket).is_some());
+
+        // --no-cache ignores the entry, so the bucket is refetched without its ETag
+        set_bypass(true);
+        let bypassed = BucketCacheEntry::load_for(&path, &bucket);
+        set_bypass(false);
+        assert!(bypassed.is_none());
+
+        let moved = test_bucket("main", "https://example.com/other.json");
+        assert!(BucketCacheEntry::load_for(&path, &moved).is_none());
+    }
+
+    #[test]
+    fn test_bucket_entry_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("buckets").join("main.json");
+
+        assert!(BucketCacheEntry::load(&path).is_none());
+
+        let entry = BucketCacheEntry {
+            url: "https://example.com/manifest.json".to_string(),
+            fetched_at: Utc::now(),
+            etag: Some("\"abc\"".to_string()),
+            manifest: SourceManifest::new(),
+            stale: None,
+        };
+        entry.save(&path).unwrap();
+
+        let loaded = BucketCacheEntry::load(&path).unwrap();
+        assert_eq!(loaded.url, entry.url);
+        assert_eq!(loaded.etag, entry.etag);
+
+        // Corrupted entries are discarded
+        fs::write(&path, "{ not json").unwrap();
+        assert!(BucketCacheEntry::load(&path).is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_build_cache_skips_failed_buckets() {
+        let mut config = BucketConfig::new();
+        config.add_bucket(test_bucket("good", "https://example.com/good.json"));
+        config.add_bucket(test_bucket("bad", "https://example.com/bad.json"));
+
+        let cache = build_cache(&config, |bucket| {
+            if bucket.name == "bad" {
+                anyhow::bail!("unreachable");
+            }
+            let mut manifest = SourceManifest::new();
+            manifest.packages.push(Package {
+                name: "tool".to_string(),
+                description: "Tool".to_string(),
+                repo: "https://github.com/test/tool".to_string(),
+                homepage: None,
+                license: None,
+                platforms: HashMap::new(),
+                requires: Vec::new(),
+                yanked: Vec::new(),
+                tags: Vec::new(),
+                aliases: Vec::new(),
+                post_install_message: None,
+                exe_name: None,
+            });
+            Ok(BucketCacheEntry {
+                url: bucket.url.clone(),
+                fetched_at: Utc::now(),
+                etag: None,
+                manifest,
+                stale: None,
+            })
+        })
+        .unwrap();
+
+        assert_eq!(cache.packages.len(), 1);
+        assert!(cache.sources.contains_key("bucket:good"));
+        assert!(!cache.sources.contains_key("bucket:bad"));
+    }
+
+    #[test]
+    fn test_build_cache_records_stale_buckets() {
+        let mut config = BucketConfig::new();
+        config.add_bucket(test_bucket("live", "https://example.com/live.json"));
+        config.add_bucket(test_bucket("down", "https://example.com/down.json"));
+
+        let cache = build_cache(&config, |bucket| {
+            Ok(BucketCacheEntry {
+                url: bucket.url.clone(),
+                fetched_at: Utc::now(),
+                etag: None,
+                manifest: SourceManifest::new(),
+                stale: (bucket.name == "down").then(|| "timed out".to_string()),
+            })
+        })
+        .unwrap();
+
+        assert_eq!(cache.sources["bucket:live"].stale, None);
+        assert_eq!(
+            cache.sources["bucket:down"].stale.as_deref(),
+            Some("timed out")
+        );
+        assert_eq!(
+            cache.bucket_health(&["live", "down", "gone"]),
+            (vec!["down"], vec!["gone"])
+        );
+    }
+
+    #[test]
+    fn test_build_cache_concurrent_merge_order() {
+        let mut config = BucketConfig::new();
+        for name in ["first", "second", "third", "fourth", "fifth", "sixth"] {
+            config.add_bucket(test_bucket(
+                name,
+                &format!("https://example.com/{}.json", name),
+            ));
+        }
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        // Earlier buckets respond slower, so fetches finish in reverse order
+        let cache = build_cache(&config, |bucket| {
+            let running = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(running, Ordering::SeqCst);
+
+            let delay = match bucket.name.as_str() {
+                "first" => 60,
+                "second" => 40,
+                "third" => 20,
+                _ => 0,
+            };
+            std::thread::sleep(std::time::Duration::from_millis(delay));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            if bucket.name == "fourth" {
+                anyhow::bail!("unreachable");
+            }
+
+            // Every bucket ships the same repo; the last one must win
+            let mut manifest = SourceManifest::new();
+            manifest.packages.push(Package {
+                name: "tool".to_string(),
+                description: format!("Tool from {}", bucket.name),
+                repo: "https://github.com/test/tool".to_string(),
+                homepage: None,
+                license: None,
+                platforms: HashMap::new(),
+                requires: Vec::new(),
+                yanked: Vec::new(),
+                tags: Vec::new(),
+                aliases: Vec::new(),
+                post_install_message: None,
+                exe_name: None,
+            });
+            Ok(BucketCacheEntry {
+                url: bucket.url.clone(),
+                fetched_at: Utc::now(),
+                etag: None,
+                manifest,
+                stale: None,
+            })
+        })
+        .unwrap();
+
+        assert!(peak.load(Ordering::SeqCst) <= MAX_CONCURRENCY);
+        assert_eq!(cache.sources.len(), 5);
+        assert!(!cache.sources.contains_key("bucket:fourth"));
+
+        let cached = cache.find_package("tool").unwrap();
+        assert_eq!(cached.package.description, "Tool from sixth");
+        assert_eq!(
+            cached.source,
+            PackageSource::Bucket {
+                name: "sixth".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_release_size_cache() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("cache").join("releases.json");
+        let repo = "https://github.com/test/tool";
+        assert!(ReleaseSizeCache::load(&path).repos.is_empty());
+
+        let mut cache = ReleaseSizeCache::default();
+        let sizes = BTreeMap::from([("linux-x86_64".to_string(), 42)]);
+        cache.insert(repo, Channel::Stable, "v1.0.0".to_string(), sizes.clone());
+        cache.save(&path).unwrap();
+
+        let cache = ReleaseSizeCache::load(&path);
+        let entry = cache.get_fresh(repo, Channel::Stable, 60).unwrap();
+        assert_eq!(entry.tag, "v1.0.0");
+        assert_eq!(entry.sizes, sizes);
+        // Another channel or an expired entry has to be fetched again
+        assert!(cache.get_fresh(repo, Channel::Prerelease, 60).is_none());
+        assert!(cache.get_fresh(repo, Channel::Stable, 0).is_none());
+
+        // A corrupted file is treated as empty
+        fs::write(&path, "{not json").unwrap();
+        assert!(ReleaseSizeCache::load(&path).repos.is_empty());
+    }
+
     #[test]
     fn test_is_valid() {
         let mut cache = ManifestCache::new();
@@ -1,6 +1,10 @@
 //! CLI argument parsing for Wenget
 
-use clap::{Parser, Subcommand};
+use crate::commands;
+use crate::core::{Arch, Compiler, ShimMode};
+use crate::downloader::checksum::ExpectedDigest;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "wenget")]
@@ -15,8 +19,26 @@ pub struct Cli {
     /// Enable verbose logging
     #[arg(short, long, global = true)]
     pub verbose: bool,
+
+    /// Suppress informational output such as progress messages
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Parallel workers for bucket fetches, update checks and verify hashing (1 = sequential)
+    #[arg(long, global = true, value_name = "N")]
+    pub concurrency: Option<usize>,
+
+    /// Refetch bucket manifests from their sources instead of using the manifest cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Show timestamps in UTC as ISO 8601 instead of local time
+    #[arg(long, global = true)]
+    pub utc: bool,
 }
 
+// Parsed once per run, so the size of `Add` doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Manage buckets (remote manifest sources)
@@ -25,6 +47,12 @@ pub enum Commands {
         command: BucketCommands,
     },
 
+    /// Inspect or manage the manifest cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
     /// Add (install) packages from cache or GitHub URL
     #[command(visible_alias = "install")]
     #[command(visible_alias = "a")]
@@ -39,6 +67,175 @@ pub enum Commands {
         /// Custom command name (overrides the default executable name)
         #[arg(short = 'n', long = "name")]
         script_name: Option<String>,
+
+        /// Place package launchers in this directory instead of ~/.wenget/bin
+        #[arg(long, value_name = "DIR", conflicts_with = "no_shim")]
+        global_bin: Option<PathBuf>,
+
+        /// Install packages without creating a launcher in the bin directory
+        #[arg(long)]
+        no_shim: bool,
+
+        /// How launchers run the executable on Unix: symlink, copy or wrapper
+        #[arg(long, value_name = "MODE", value_parser = parse_shim_mode, conflicts_with = "no_shim")]
+        shim_mode: Option<ShimMode>,
+
+        /// Install this exact release asset instead of auto-detecting one
+        #[arg(long, value_name = "FILENAME")]
+        asset: Option<String>,
+
+        /// Name of the executable inside the archive, when it differs from the package name
+        #[arg(long, value_name = "NAME")]
+        exe_name: Option<String>,
+
+        /// Use this manifest platform entry even if it doesn't match the current platform
+        #[arg(long, value_name = "ID", conflicts_with = "asset")]
+        force_platform_match: Option<String>,
+
+        /// Verify the downloaded asset against this digest before extracting it
+        #[arg(
+            long,
+            value_name = "ALGO:HEX",
+            value_parser = ExpectedDigest::parse,
+            conflicts_with_all = ["from_file", "offline_bundle"]
+        )]
+        checksum: Option<ExpectedDigest>,
+
+        /// Keep this many previous versions after an upgrade (remembered per package)
+        #[arg(long, value_name = "N")]
+        keep_versions: Option<usize>,
+
+        /// Download and extract here instead of ~/.wenget/cache (default: $WENGET_TMPDIR)
+        #[arg(long, value_name = "DIR")]
+        temp_dir: Option<PathBuf>,
+
+        /// Choose among the release assets for this platform (remembered for upgrades)
+        #[arg(short = 'i', long, conflicts_with = "asset")]
+        interactive: bool,
+
+        /// Install the build for another architecture of the current OS (e.g. arm64)
+        #[arg(long, value_name = "ARCH", value_parser = parse_arch)]
+        arch: Option<Arch>,
+
+        /// Follow prereleases for these packages (remembered for future updates)
+        #[arg(long)]
+        pre: bool,
+
+        /// Leave out packages matching this glob after wildcard expansion (repeatable)
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<String>,
+
+        /// Only look packages up in these buckets (comma-separated or repeatable)
+        #[arg(long, value_name = "BUCKETS", value_delimiter = ',')]
+        sources: Vec<String>,
+
+        /// Retry failed downloads this many times (default: download_retries setting, or 3)
+        #[arg(long, value_name = "N")]
+        retries: Option<u32>,
+
+        /// Give up on a package whose download, extraction and linking take longer than this
+        #[arg(
+            long,
+            value_name = "SECS",
+            value_parser = clap::value_parser!(u64).range(1..),
+            conflicts_with_all = ["print_url", "dest"]
+        )]
+        timeout: Option<u64>,
+
+        /// Install the packages listed in a file (one name or URL per line, `name@version` to pin)
+        #[arg(
+            long,
+            value_name = "PATH",
+            conflicts_with_all = ["names", "script_name", "asset"]
+        )]
+        from_file: Option<PathBuf>,
+
+        /// Install an older version than the one installed (e.g. name@12.0.0)
+        #[arg(long, conflicts_with = "if_not_installed")]
+        allow_downgrade: bool,
+
+        /// Do nothing for packages that are already installed, at any version (no update check)
+        #[arg(long, visible_alias = "no-upgrade", conflicts_with = "interactive")]
+        if_not_installed: bool,
+
+        /// Install without first checking that every asset URL is reachable
+        #[arg(long)]
+        no_preflight: bool,
+
+        /// Install from a directory made by `download --all-platforms`, without network access
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = ["from_file", "asset", "force_platform_match", "interactive", "pre"]
+        )]
+        offline_bundle: Option<PathBuf>,
+
+        /// Print the per-package outcome as JSON instead of the summary (implies --yes)
+        #[arg(long, conflicts_with = "interactive")]
+        json: bool,
+
+        /// Only print the download URL (and checksum, if known) of each package
+        #[arg(
+            long,
+            conflicts_with_all = [
+                "json", "interactive", "from_file", "offline_bundle", "checksum",
+                "force_platform_match"
+            ]
+        )]
+        print_url: bool,
+
+        /// Continue an interrupted multi-package install, skipping packages already done
+        #[arg(
+            long,
+            conflicts_with_all = ["names", "from_file", "offline_bundle", "print_url"]
+        )]
+        resume: bool,
+
+        /// Only download and extract into this directory; nothing is recorded or linked
+        #[arg(
+            long,
+            value_name = "DIR",
+            conflicts_with_all = [
+                "json", "interactive", "from_file", "offline_bundle", "print_url", "resume",
+                "global_bin", "no_shim", "shim_mode", "keep_versions", "if_not_installed",
+                "force_platform_match"
+            ]
+        )]
+        dest: Option<PathBuf>,
+
+        /// Platform to print the URL for with --print-url (e.g. linux-x86_64)
+        #[arg(
+            long,
+            value_name = "ID",
+            requires = "print_url",
+            conflicts_with = "arch"
+        )]
+        platform: Option<String>,
+
+        #[command(flatten)]
+        variant: VariantArgs,
+    },
+
+    /// Download package assets without installing them
+    Download {
+        /// Package names or GitHub URLs to download (supports wildcards *)
+        names: Vec<String>,
+
+        /// Platform to download for (e.g. linux-x86_64); defaults to the current one
+        #[arg(long, value_name = "ID", conflicts_with = "all_platforms")]
+        platform: Option<String>,
+
+        /// Download every platform's asset into per-platform subdirectories
+        #[arg(long)]
+        all_platforms: bool,
+
+        /// Directory to save into (default: current directory)
+        #[arg(long, value_name = "DIR")]
+        dest: Option<PathBuf>,
+
+        /// Retry failed downloads this many times (default: download_retries setting, or 3)
+        #[arg(long, value_name = "N")]
+        retries: Option<u32>,
     },
 
     /// List installed packages
@@ -47,12 +244,77 @@ pub enum Commands {
         /// Show all available packages from buckets (not just installed)
         #[arg(short = 'a', long = "all")]
         all: bool,
+
+        /// Show only packages with a newer release (checks GitHub)
+        #[arg(long, conflicts_with = "all")]
+        outdated: bool,
+
+        /// Print the installed packages as JSON, with their latest versions (checks GitHub)
+        #[arg(long, conflicts_with = "all")]
+        json: bool,
+    },
+
+    /// Show installed packages as a dependency tree
+    Tree,
+
+    /// Show the log of installs, upgrades and removals
+    History {
+        /// Show only the most recent N events
+        #[arg(short = 'l', long, value_name = "N")]
+        limit: Option<usize>,
+
+        /// Show only events for this package
+        #[arg(short = 'p', long, value_name = "NAME")]
+        package: Option<String>,
     },
 
     /// Show package information from cache or GitHub URL
     Info {
         /// Package names or GitHub URLs to show (supports wildcards * for cache queries)
         names: Vec<String>,
+
+        /// Fetch fresh data from buckets and GitHub instead of the cache
+        #[arg(long)]
+        remote: bool,
+
+        /// Diff the latest release's assets against this version (defaults to the installed one)
+        #[arg(long, value_name = "VERSION", conflicts_with = "raw_manifest")]
+        compare: Option<Option<String>>,
+
+        /// Print the parsed manifest entry as JSON instead of the summary
+        #[arg(long)]
+        raw_manifest: bool,
+
+        /// Only list assets for this platform: an id (e.g. linux-x86_64), "current" or "all"
+        #[arg(long, value_name = "ID|current|all", default_value = "all")]
+        platform: String,
+
+        /// Show download sizes from the latest release's assets (cached like version info)
+        #[arg(long, conflicts_with = "raw_manifest")]
+        latest_asset_size: bool,
+
+        /// Only look packages up in these buckets (comma-separated or repeatable)
+        #[arg(long, value_name = "BUCKETS", value_delimiter = ',')]
+        sources: Vec<String>,
+
+        /// Show every installed package, with a summary of outdated and broken ones
+        #[arg(
+            long,
+            visible_alias = "installed-only",
+            conflicts_with_all = [
+                "names",
+                "remote",
+                "compare",
+                "raw_manifest",
+                "latest_asset_size",
+                "sources"
+            ]
+        )]
+        installed: bool,
+
+        /// Print the installed packages as JSON (with --installed)
+        #[arg(long, requires = "installed")]
+        json: bool,
     },
 
     /// Search for packages
@@ -60,6 +322,14 @@ pub enum Commands {
     Search {
         /// Package names to search (supports wildcards *)
         names: Vec<String>,
+
+        /// Only show packages with this tag (repeatable; all must match)
+        #[arg(long, value_name = "TAG")]
+        tag: Vec<String>,
+
+        /// Only look packages up in these buckets (comma-separated or repeatable)
+        #[arg(long, value_name = "BUCKETS", value_delimiter = ',')]
+        sources: Vec<String>,
     },
 
     /// Upgrade installed packages
@@ -71,6 +341,49 @@ pub enum Commands {
         /// Skip confirmation prompts
         #[arg(short = 'y', long)]
         yes: bool,
+
+        /// Wait for the GitHub rate limit to reset instead of skipping packages
+        #[arg(long)]
+        wait: bool,
+
+        /// Leave out packages matching this glob (repeatable)
+        #[arg(long, value_name = "PATTERN")]
+        exclude: Vec<String>,
+
+        /// Only upgrade installed packages matching this glob (repeatable)
+        #[arg(long, value_name = "PATTERN", conflicts_with = "names")]
+        only: Vec<String>,
+
+        /// Keep this many previous versions after upgrading (remembered per package)
+        #[arg(long, value_name = "N")]
+        keep_versions: Option<usize>,
+
+        /// Retry failed downloads this many times (default: download_retries setting, or 3)
+        #[arg(long, value_name = "N")]
+        retries: Option<u32>,
+
+        /// Print the per-package outcome as JSON instead of the summary (implies --yes)
+        #[arg(long)]
+        json: bool,
+
+        /// Choose which upgradeable packages to upgrade (upgrades all when not a terminal)
+        #[arg(short = 'i', long, conflicts_with = "json")]
+        interactive: bool,
+
+        /// Stop offering this version of a package; newer releases are still offered (repeatable)
+        #[arg(long, value_name = "NAME@VERSION", conflicts_with_all = ["names", "reset_ignores"])]
+        ignore: Vec<String>,
+
+        /// Forget the ignored updates of the named packages (all when none are named)
+        #[arg(long)]
+        reset_ignores: bool,
+
+        /// Offer updates ignored with --ignore anyway
+        #[arg(long, conflicts_with_all = ["ignore", "reset_ignores"])]
+        include_ignored: bool,
+
+        #[command(flatten)]
+        variant: VariantArgs,
     },
 
     /// Delete (remove) installed packages
@@ -88,19 +401,194 @@ pub enum Commands {
         force: bool,
     },
 
+    /// Remove dependencies no longer required by any installed package
+    Autoremove {
+        /// Skip confirmation prompts
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Manage Wenget itself
+    #[command(name = "self")]
+    SelfCmd {
+        #[command(subcommand)]
+        command: SelfCommands,
+    },
+
     /// Initialize Wenget (create directories and set up PATH)
     Init {
         /// Skip confirmation prompts
         #[arg(short = 'y', long)]
         yes: bool,
+
+        /// Start without the official Wenget bucket
+        #[arg(long)]
+        no_default_bucket: bool,
+    },
+
+    /// Show or change the release channel a package follows
+    Channel {
+        /// Installed package name
+        name: String,
+
+        /// New channel
+        #[arg(value_parser = ["stable", "prerelease"])]
+        channel: Option<String>,
+    },
+
+    /// Restrict the versions `update` may move a package to
+    #[command(disable_version_flag = true)]
+    Pin {
+        /// Installed package name
+        name: String,
+
+        /// Version constraint: 13.0.5 (exact), 13.x, ^1.2 or ~1.2.3
+        /// [default: the installed version]
+        #[arg(long, value_name = "CONSTRAINT")]
+        version: Option<String>,
+    },
+
+    /// Remove a package's version pin
+    Unpin {
+        /// Installed package name
+        name: String,
+    },
+
+    /// Change the command name of an installed package's launcher
+    Rename {
+        /// Installed package name
+        name: String,
+
+        /// New command name for the launcher
+        new_name: String,
+
+        /// Replace an existing launcher with the same name
+        #[arg(short = 'f', long)]
+        force: bool,
+    },
+
+    /// Print where Wenget keeps its files (e.g. `wenget path bin`)
+    Path {
+        /// Component to print; all of them when omitted
+        #[arg(value_parser = commands::path::COMPONENTS.to_vec())]
+        component: Option<String>,
     },
 
-    /// Repair corrupted configuration files
+    /// Manage user settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+
+    /// Repair corrupted configuration files and packages whose install directory is missing
     Repair {
         /// Force rebuild all configuration files (not just corrupted ones)
         #[arg(short, long)]
         force: bool,
+
+        /// Remove packages with a missing install directory instead of reinstalling them
+        #[arg(long)]
+        prune: bool,
+
+        /// Reinstall or remove broken packages without asking
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Check for broken launchers, missing install directories and a launcher directory not on PATH
+    Doctor {
+        /// Repair the problems found, asking before each fix
+        #[arg(long)]
+        fix: bool,
+
+        /// Fix without asking for confirmation
+        #[arg(short, long, requires = "fix")]
+        yes: bool,
     },
+
+    /// Reinstall packages at their installed version, recreating launchers
+    Reinstall {
+        /// Package names to reinstall (supports wildcards *)
+        #[arg(required_unless_present = "all")]
+        names: Vec<String>,
+
+        /// Reinstall every installed package
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+
+        /// Skip confirmation prompts
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Rewrite launchers to point at each package's current executable, without moving files
+    RefreshShims {
+        /// Package names whose launchers to refresh (supports wildcards *)
+        #[arg(required_unless_present = "all")]
+        names: Vec<String>,
+
+        /// Refresh the launchers of every installed package
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+    },
+
+    /// Check installed files against the SHA-256 digests recorded at install time
+    Verify {
+        /// Package names to verify (supports wildcards *)
+        #[arg(required_unless_present = "all")]
+        names: Vec<String>,
+
+        /// Verify every installed package
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+    },
+
+    /// Show the GitHub API rate-limit status and whether a token is configured
+    Ratelimit,
+}
+
+/// Libc variant preference flags shared by add and update
+#[derive(Args)]
+pub struct VariantArgs {
+    /// Prefer glibc (gnu) builds when a release ships several variants
+    #[arg(long, conflicts_with = "prefer_musl")]
+    pub prefer_gnu: bool,
+
+    /// Prefer musl builds when a release ships several variants
+    #[arg(long)]
+    pub prefer_musl: bool,
+}
+
+impl VariantArgs {
+    /// Get the requested variant, if any
+    pub fn preference(&self) -> Option<Compiler> {
+        if self.prefer_gnu {
+            Some(Compiler::Gnu)
+        } else if self.prefer_musl {
+            Some(Compiler::Musl)
+        } else {
+            None
+        }
+    }
+}
+
+/// Parse an `--arch` value
+fn parse_shim_mode(s: &str) -> Result<ShimMode, String> {
+    ShimMode::parse(s).ok_or_else(|| {
+        format!(
+            "unknown shim mode '{}' (expected symlink, copy or wrapper)",
+            s
+        )
+    })
+}
+
+fn parse_arch(s: &str) -> Result<Arch, String> {
+    Arch::parse(s).ok_or_else(|| {
+        format!(
+            "unknown architecture '{}' (expected x86_64, i686, aarch64 or armv7)",
+            s
+        )
+    })
 }
 
 #[derive(Subcommand)]
@@ -112,6 +600,10 @@ pub enum BucketCommands {
 
         /// URL to the manifest.json file
         url: String,
+
+        /// Extra request header for this bucket's host, as 'Name: value' (repeatable; values may use ${VAR})
+        #[arg(long = "header", value_name = "HEADER")]
+        headers: Vec<String>,
     },
 
     /// Delete buckets
@@ -125,6 +617,79 @@ pub enum BucketCommands {
 
     /// Refresh cache from buckets
     Refresh,
+
+    /// Refetch one or more buckets (all if none given) and update the cache
+    Update {
+        /// Bucket names to update
+        names: Vec<String>,
+    },
+
+    /// Check that every package in a bucket still resolves to release assets
+    Verify {
+        /// Bucket name
+        name: String,
+
+        /// Print the per-package results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show the cache's age, package count, bucket breakdown and size
+    Info,
+
+    /// Refetch every bucket and rebuild the cache
+    Refresh,
+
+    /// Delete the cache (rebuilt by the next command that needs it)
+    Clear,
+}
+
+#[derive(Subcommand)]
+pub enum SelfCommands {
+    /// Remove Wenget, its launchers, installed apps and cache from this system
+    Uninstall {
+        /// Keep installed apps and their manifests (only launchers and cache are removed)
+        #[arg(long)]
+        keep_data: bool,
+
+        /// Don't delete the wenget executable
+        #[arg(long)]
+        keep_binary: bool,
+
+        /// Actually uninstall (without it, only shows what would be removed)
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Show a setting's value
+    Get {
+        /// Setting name
+        key: String,
+    },
+
+    /// Change a setting
+    Set {
+        /// Setting name
+        key: String,
+
+        /// New value
+        value: String,
+    },
+
+    /// Reset a setting to its default
+    Unset {
+        /// Setting name
+        key: String,
+    },
+
+    /// List all settings
+    List,
 }
 
 impl Cli {
@@ -143,4 +708,51 @@ mod tests {
         use clap::CommandFactory;
         Cli::command().debug_assert();
     }
+
+    #[test]
+    fn test_add_no_upgrade() {
+        let cli =
+            Cli::try_parse_from(["wenget", "add", "--from-file", "tools.txt", "--no-upgrade"])
+                .unwrap();
+        match cli.command {
+            Some(Commands::Add {
+                if_not_installed,
+                from_file,
+                ..
+            }) => {
+                assert!(if_not_installed);
+                assert_eq!(from_file, Some(PathBuf::from("tools.txt")));
+            }
+            _ => panic!("expected the add command"),
+        }
+
+        // Same flag, so it can't be combined with --interactive either
+        assert!(Cli::try_parse_from(["wenget", "add", "x", "--no-upgrade", "-i"]).is_err());
+    }
+
+    #[test]
+    fn test_add_allow_downgrade() {
+        let cli = Cli::try_parse_from(["wenget", "add", "rg@12.0.0", "--allow-downgrade"]).unwrap();
+        match cli.command {
+            Some(Commands::Add {
+                allow_downgrade,
+                names,
+                ..
+            }) => {
+                assert!(allow_downgrade);
+                assert_eq!(names, vec!["rg@12.0.0".to_string()]);
+            }
+            _ => panic!("expected the add command"),
+        }
+
+        // Installed packages are left alone, so there is nothing to downgrade
+        assert!(Cli::try_parse_from([
+            "wenget",
+            "add",
+            "rg@12.0.0",
+            "--allow-downgrade",
+            "--if-not-installed"
+        ])
+        .is_err());
+    }
 }
@@ -1,28 +1,1191 @@
 //! Add (Install) command implementation
 
-use crate::core::manifest::{PackageSource, ScriptType};
-use crate::core::{Config, InstalledPackage, Platform, WenPaths};
+use super::download::{BundleManifest, BUNDLE_MANIFEST};
+use crate::core::batch::BatchState;
+use crate::core::history::{HistoryAction, HistoryEvent};
+use crate::core::manifest::{BinEntry, PackageSource, ScriptType};
+use crate::core::paths::{find_in_path, is_dir_in_path, path_index};
+use crate::core::platform::{canonical_platform_id, emulation_allowed};
+use crate::core::version::is_downgrade;
+use crate::core::{
+    Arch, AssetPattern, Channel, Compiler, Config, InstalledManifest, InstalledPackage, Os,
+    Platform, PlatformBinary, ShimMode, VersionConstraint, WenPaths,
+};
 use crate::downloader;
+use crate::downloader::checksum::{self, ExpectedDigest};
+use crate::error::WengetError;
+use crate::installer::extractor::ExtractProgress;
+use crate::installer::linux_package;
 use crate::installer::{
-    create_script_shim, create_shim, detect_script_type, download_script, extract_archive,
-    extract_script_name, find_executable_candidates, install_script, is_script_input,
-    normalize_command_name, read_local_script,
+    create_script_shim, detect_script_type, download_script, extract_archive,
+    extract_archive_until, extract_script_name, find_executable_candidates, find_named_executable,
+    install_script, is_script_input, normalize_command_name, prune_versions, read_local_script,
+    same_filesystem, swap_into_place,
+};
+use crate::outln;
+use crate::package_resolver::{
+    is_excluded, parse_package_list, split_version, PackageInput, PackageResolver, ResolvedPackage,
 };
-use crate::package_resolver::{PackageInput, PackageResolver, ResolvedPackage};
-use crate::providers::{GitHubProvider, SourceProvider};
+use crate::providers::GitHubProvider;
+use crate::utils::deadline::Deadline;
+use crate::utils::http::HostHeaders;
+use crate::utils::output::{format_timestamp, message_lines};
+#[cfg(not(windows))]
+use crate::utils::shell::{add_path_entry, RcUpdate, Shell};
+use crate::utils::CleanupGuard;
 use anyhow::{Context, Result};
 use chrono::Utc;
 use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 #[cfg(unix)]
-use crate::installer::create_symlink;
+use crate::installer::{copy_executable, create_symlink, create_wrapper};
+
+#[cfg(windows)]
+use crate::installer::create_shim;
+
+/// Options for the add command
+#[derive(Debug, Clone, Default)]
+pub struct AddOptions {
+    /// Skip confirmation prompts
+    pub yes: bool,
+
+    /// Custom command name (overrides the default executable name)
+    pub script_name: Option<String>,
+
+    /// Directory to place package launchers in instead of ~/.wenget/bin
+    pub global_bin: Option<PathBuf>,
+
+    /// Extract and record packages without creating a launcher
+    pub no_shim: bool,
+
+    /// How launchers run the executable on Unix (--shim-mode); None keeps
+    /// the package's current mode, falling back to the `shim_mode` setting
+    pub shim_mode: Option<ShimMode>,
+
+    /// Preferred libc/compiler variant (--prefer-gnu / --prefer-musl)
+    pub prefer_variant: Option<Compiler>,
+
+    /// Release channel to follow (--pre); None keeps the package's current one
+    pub channel: Option<Channel>,
+
+    /// Exact release asset to install, bypassing platform matching (--asset)
+    pub asset: Option<String>,
+
+    /// Basename of the executable in the archive (--exe-name)
+    pub exe_name: Option<String>,
+
+    /// Manifest platform entry to install even though it doesn't match the
+    /// current platform (--force-platform-match)
+    pub force_platform: Option<String>,
+
+    /// Expected digest of the downloaded asset (--checksum), checked before
+    /// extraction
+    pub checksum: Option<ExpectedDigest>,
+
+    /// Reinstalling for `update`: keep packages marked as dependencies
+    /// instead of treating them as explicitly requested
+    pub upgrade: bool,
+
+    /// Reinstall installed packages at the version they are at instead of
+    /// the latest (`wenget reinstall`)
+    pub reinstall: bool,
+
+    /// Required version; refuse the install if the latest release differs
+    pub version: Option<String>,
+
+    /// Read package names/URLs from this file, one per line (--from-file)
+    pub from_file: Option<PathBuf>,
+
+    /// Leave packages that are already installed alone, at any version
+    /// (--if-not-installed); their latest version is never looked up
+    pub if_not_installed: bool,
+
+    /// Let an install replace a newer installed version (--allow-downgrade)
+    pub allow_downgrade: bool,
+
+    /// Skip the HEAD reachability check of all assets before installing
+    /// (--no-preflight)
+    pub no_preflight: bool,
+
+    /// Install from a directory staged by `download --all-platforms`
+    /// without touching the network (--offline-bundle)
+    pub offline_bundle: Option<PathBuf>,
+
+    /// Target architecture on the current OS (--arch); None keeps the
+    /// package's current one
+    pub arch: Option<Arch>,
+
+    /// Glob patterns of package names to leave out after expansion (--exclude)
+    pub exclude: Vec<String>,
+
+    /// Buckets packages are looked up in (--sources); all when empty
+    pub sources: Vec<String>,
+
+    /// Download retries (--retries); None uses the configured default
+    pub retries: Option<u32>,
+
+    /// Time limit for installing each package (--timeout); None waits as
+    /// long as it takes
+    pub timeout: Option<Duration>,
+
+    /// Choose among the release assets for this platform (--interactive)
+    pub interactive: bool,
+
+    /// Previous versions to keep after an upgrade (--keep-versions); None
+    /// keeps the package's current preference
+    pub keep_versions: Option<usize>,
+
+    /// Directory for downloads and extraction staging (--temp-dir); None
+    /// falls back to `WENGET_TMPDIR`, then to the cache directory
+    pub temp_dir: Option<PathBuf>,
+
+    /// Print the outcome as JSON instead of the summary (--json); implies `yes`
+    pub json: bool,
+
+    /// Only print each package's download URL, installing nothing (--print-url)
+    pub print_url: bool,
+
+    /// Platform identifier to print the URL for (--platform with --print-url);
+    /// None uses the current platform and `arch`
+    pub platform: Option<String>,
+
+    /// Continue the interrupted batch recorded in batch.json (--resume)
+    pub resume: bool,
+
+    /// Only download and extract into this directory, unmanaged (--dest)
+    pub dest: Option<PathBuf>,
+}
+
+/// Environment variable naming the default `--temp-dir`
+pub const TEMP_DIR_ENV: &str = "WENGET_TMPDIR";
+
+/// Outcome of a single install run
+#[derive(Debug, Default, Clone)]
+pub(crate) struct InstallReport {
+    installed: usize,
+    failed: usize,
+    /// Exit code of the failures so far (see [`InstallReport::result`])
+    exit_code: Option<i32>,
+    /// Per-package results, reported by `--json`
+    pub outcomes: Vec<PackageOutcome>,
+}
+
+impl InstallReport {
+    pub(crate) fn merge(&mut self, other: InstallReport) {
+        self.installed += other.installed;
+        self.failed += other.failed;
+        if let Some(code) = other.exit_code {
+            self.record_exit_code(code);
+        }
+        self.outcomes.extend(other.outcomes);
+    }
+
+    /// Record a package that could not be installed, for no specific reason
+    fn fail(&mut self, name: &str, error: impl std::fmt::Display) {
+        self.failed += 1;
+        self.record_exit_code(1);
+        self.outcomes.push(PackageOutcome::failed(name, error));
+    }
+
+    /// Record a package that failed with `error`, keeping its kind for the exit code
+    fn fail_error(&mut self, name: &str, error: &anyhow::Error) {
+        self.failed += 1;
+        self.record_exit_code(crate::error::exit_code(error));
+        self.outcomes
+            .push(PackageOutcome::failed(name, format!("{:#}", error)));
+    }
+
+    fn record_exit_code(&mut self, code: i32) {
+        self.exit_code = match self.exit_code {
+            Some(previous) if previous != code => Some(1),
+            _ => Some(code),
+        };
+    }
+
+    /// Fail when any package failed, with the exit code of those failures
+    pub(crate) fn result(&self) -> Result<()> {
+        if self.failed == 0 {
+            return Ok(());
+        }
+        Err(WengetError::PackagesFailed {
+            count: self.failed,
+            code: self.exit_code.unwrap_or(1),
+        }
+        .into())
+    }
+}
+
+/// What happened to one package in an `add`/`update` run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum OutcomeAction {
+    Installed,
+    Upgraded,
+    Skipped,
+    Failed,
+}
+
+/// Per-package entry of the `--json` result
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PackageOutcome {
+    pub name: String,
+    pub action: OutcomeAction,
+    /// Version installed before this run
+    pub from: Option<String>,
+    /// Version installed by this run (or already installed when skipped)
+    pub to: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PackageOutcome {
+    /// A fresh install (`from` is None) or an upgrade
+    pub fn installed(name: &str, from: Option<&str>, to: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            action: match from {
+                Some(_) => OutcomeAction::Upgraded,
+                None => OutcomeAction::Installed,
+            },
+            from: from.map(str::to_string),
+            to: Some(to.to_string()),
+            error: None,
+        }
+    }
+
+    /// A package left as it was, at `version`
+    pub fn skipped(name: &str, version: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            action: OutcomeAction::Skipped,
+            from: Some(version.to_string()),
+            to: Some(version.to_string()),
+            error: None,
+        }
+    }
+
+    /// A package that failed with `error`
+    pub fn failed(name: &str, error: impl std::fmt::Display) -> Self {
+        Self {
+            name: name.to_string(),
+            action: OutcomeAction::Failed,
+            from: None,
+            to: None,
+            error: Some(error.to_string()),
+        }
+    }
+}
+
+/// The `--json` result: every package outcome plus counts per action
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    packages: &'a [PackageOutcome],
+    installed: usize,
+    upgraded: usize,
+    skipped: usize,
+    failed: usize,
+}
+
+/// Print the `--json` result to stdout
+pub(crate) fn print_json_report(outcomes: &[PackageOutcome]) -> Result<()> {
+    let count = |action| outcomes.iter().filter(|o| o.action == action).count();
+    let report = JsonReport {
+        packages: outcomes,
+        installed: count(OutcomeAction::Installed),
+        upgraded: count(OutcomeAction::Upgraded),
+        skipped: count(OutcomeAction::Skipped),
+        failed: count(OutcomeAction::Failed),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    Ok(())
+}
 
 /// Install packages (smart detection: package names from cache or GitHub URLs)
-pub fn run(names: Vec<String>, yes: bool, script_name: Option<String>) -> Result<()> {
+pub fn run(names: Vec<String>, mut options: AddOptions) -> Result<()> {
+    let json = options.json;
+    crate::utils::output::set_json(json);
+
+    if options.print_url {
+        // stdout carries only the URLs
+        crate::utils::output::set_json(true);
+        return print_urls(&names, &options);
+    }
+    if let Some(ref dest) = options.dest {
+        // stdout carries only the executable paths
+        crate::utils::output::set_json(true);
+        return extract_to_dest(&names, &options, dest);
+    }
+
+    // Nothing installed yet: the launcher directory is likely not on PATH either
+    let config = Config::new()?;
+    let first_install =
+        !config.is_initialized() || config.get_or_create_installed()?.packages.is_empty();
+    let ask = !options.yes && !json && !crate::utils::is_quiet() && io::stdin().is_terminal();
+    let bin_dir = match options.global_bin {
+        Some(ref dir) => dir.clone(),
+        None => match config.load_settings()?.global_bin {
+            Some(dir) => dir,
+            None => config.paths().bin_dir(),
+        },
+    };
+
+    let report = if let Some(path) = options.from_file.take() {
+        install_from_file(&path, options)?
+    } else if let Some(dir) = options.offline_bundle.take() {
+        install_from_bundle(&config, &dir, &names, options)?
+    } else if options.resume || names.len() > 1 {
+        install_batch(&config, names, options)?
+    } else {
+        install(names, options)?
+    };
+
+    if json {
+        print_json_report(&report.outcomes)?;
+    } else if first_install && report.installed > 0 && !is_dir_in_path(&bin_dir) {
+        offer_path_setup(&bin_dir, ask)?;
+    }
+    report.result()
+}
+
+/// Install several packages, recording progress so an interrupted run can be
+/// continued with `--resume`
+///
+/// The batch state is removed once every package installed; after failures
+/// it is kept, so `--resume` retries only what is left.
+fn install_batch(
+    config: &Config,
+    names: Vec<String>,
+    options: AddOptions,
+) -> Result<InstallReport> {
+    let path = config.paths().batch_json();
+
+    let names = if options.resume {
+        let Some(state) = BatchState::load(&path)? else {
+            anyhow::bail!("No interrupted batch to resume");
+        };
+        let (done, pending): (Vec<String>, Vec<String>) = state
+            .names
+            .iter()
+            .cloned()
+            .partition(|entry| batch_entry_done(&state, entry));
+
+        outln!(
+            "{} Resuming the batch started {}: {} of {} done",
+            "ℹ".cyan(),
+            format_timestamp(&state.started),
+            done.len(),
+            state.names.len()
+        );
+        for entry in &done {
+            outln!("  {} {} {}", "•".cyan(), entry, "(done)".dimmed());
+        }
+        if pending.is_empty() {
+            BatchState::clear(&path)?;
+            outln!("{}", "Nothing left to install".green());
+            return Ok(InstallReport::default());
+        }
+        pending
+    } else {
+        if let Some(previous) = BatchState::load(&path).ok().flatten() {
+            outln!(
+                "{} Discarding the interrupted batch of {} package(s) started {}",
+                "ℹ".cyan(),
+                previous.names.len(),
+                format_timestamp(&previous.started)
+            );
+        }
+        BatchState::new(names.clone()).save(&path)?;
+        names
+    };
+
+    // Nothing happening at all means the install was cancelled
+    let report = install(names, options)?;
+    if report.failed == 0 && !report.outcomes.is_empty() {
+        BatchState::clear(&path)?;
+    } else {
+        outln!(
+            "Run {} to retry the packages that did not install",
+            "wenget add --resume".cyan()
+        );
+    }
+    Ok(report)
+}
+
+/// Whether a batch entry installed before the batch was interrupted
+///
+/// Names are matched against the completed packages and URLs or scripts
+/// against where they came from. Glob patterns are always checked again.
+fn batch_entry_done(state: &BatchState, entry: &str) -> bool {
+    if is_script_input(entry) {
+        return state.has_source(entry);
+    }
+    match PackageInput::parse(entry) {
+        PackageInput::CacheName(name) => state.has_name(&name),
+        PackageInput::DirectUrl(url) => state.has_source(&url),
+    }
+}
+
+/// Offer to put the launcher directory on PATH after the very first install
+///
+/// Without a terminal to ask on (or with `--yes`), only the change to make
+/// is shown.
+fn offer_path_setup(bin_dir: &Path, ask: bool) -> Result<()> {
+    outln!();
+    outln!(
+        "{} {} is not in PATH, so installed commands are not found yet",
+        "⚠".yellow(),
+        bin_dir.display()
+    );
+
+    #[cfg(not(windows))]
+    let (rc_file, line) = {
+        let home = dirs::home_dir().context("Failed to determine home directory")?;
+        let shell = Shell::detect();
+        (shell.rc_file(&home), shell.path_line(bin_dir))
+    };
+
+    #[cfg(not(windows))]
+    let question = format!("Add it to {}?", rc_file.display());
+    #[cfg(windows)]
+    let question = "Add it to your user Path?".to_string();
+
+    let accepted = ask && {
+        print!("{} [Y/n] ", question);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        input.is_empty() || input == "y" || input == "yes"
+    };
+
+    if !accepted {
+        #[cfg(not(windows))]
+        outln!(
+            "  Add this line to {}:\n    {}",
+            rc_file.display(),
+            line.cyan()
+        );
+        #[cfg(windows)]
+        outln!(
+            "  Add it to your user Path, or run: {}",
+            "wenget init".cyan()
+        );
+        return Ok(());
+    }
+
+    #[cfg(not(windows))]
+    {
+        match add_path_entry(&rc_file, &line, bin_dir)? {
+            RcUpdate::AlreadyPresent => outln!(
+                "  {} {} already sets it; restart your shell to pick it up",
+                "ℹ".cyan(),
+                rc_file.display()
+            ),
+            RcUpdate::Appended { backup } => {
+                outln!("  {} Added to {}:", "✓".green(), rc_file.display());
+                outln!("    {}", line);
+                if let Some(backup) = backup {
+                    outln!("  Previous version saved as {}", backup.display());
+                }
+                outln!(
+                    "  Restart your shell or run: {}",
+                    format!("source {}", rc_file.display()).cyan()
+                );
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        super::init::setup_path_windows(&bin_dir.to_string_lossy())?;
+    }
+
+    Ok(())
+}
+
+/// Print the download URL `add` would use for each package, without
+/// installing anything (--print-url)
+///
+/// One line per package on stdout: the URL, followed by the manifest's
+/// checksum when it has one. Problems are reported on stderr.
+fn print_urls(names: &[String], options: &AddOptions) -> Result<()> {
+    let config = Config::new()?;
+    let failed = for_each_download(&config, names, options, |_, binary| {
+        match binary.checksum {
+            Some(checksum) => println!("{} {}", binary.url, checksum),
+            None => println!("{}", binary.url),
+        }
+        Ok(())
+    })?;
+
+    if failed > 0 {
+        anyhow::bail!("{} package(s) could not be resolved", failed);
+    }
+    Ok(())
+}
+
+/// Download and extract each package into `dest`, without recording or
+/// linking anything (--dest)
+///
+/// The extracted files are left in place; the executable's path is printed
+/// so it can be run from there.
+fn extract_to_dest(names: &[String], options: &AddOptions, dest: &Path) -> Result<()> {
+    let config = Config::new()?;
+    let paths = config.paths();
+    let settings = config.load_settings()?;
+    let retries = options
+        .retries
+        .or(settings.download_retries)
+        .unwrap_or(downloader::DEFAULT_RETRIES);
+    let download_dir = resolve_temp_dir(options.temp_dir.clone(), paths)?
+        .map_or_else(|| paths.downloads_dir(), |t| t.join("downloads"));
+    fs::create_dir_all(&download_dir)?;
+
+    let mut extracted = 0;
+    let failed = for_each_download(&config, names, options, |resolved, binary| {
+        let pkg = &resolved.package;
+        outln!(
+            "{} {} into {}...",
+            "Extracting".cyan(),
+            pkg.name,
+            dest.display()
+        );
+        outln!("  Downloading from {}...", binary.url);
+
+        let filename = binary
+            .url
+            .split('/')
+            .next_back()
+            .context("Invalid download URL")?;
+        let download_path = download_dir.join(filename);
+        let mut cleanup = CleanupGuard::new();
+        cleanup.track(downloader::part_path(&download_path));
+        cleanup.track(&download_path);
+
+        let headers = source_headers(&config, &resolved.source)?;
+        downloader::download_file_with_headers(&binary.url, &download_path, retries, &headers)?;
+        if let Some(ref expected) = options.checksum {
+            checksum::verify_digest(&download_path, expected)?;
+            outln!(
+                "  {} {} checksum verified",
+                "✓".green(),
+                expected.algorithm.as_str()
+            );
+        }
+
+        let files = extract_archive(&download_path, dest)?;
+        let exe = binary
+            .bin
+            .first()
+            .map(|b| b.path.replace('\\', "/"))
+            .filter(|path| dest.join(path).is_file())
+            .or_else(|| {
+                let exe_name = options.exe_name.as_ref().or(pkg.exe_name.as_ref())?;
+                find_named_executable(&files, exe_name)
+            })
+            .or_else(|| {
+                find_executable_candidates(&files, &pkg.name, Some(dest))
+                    .into_iter()
+                    .next()
+                    .map(|c| c.path)
+            });
+
+        match exe {
+            Some(exe) => {
+                let exe = dest.join(exe);
+                let exe = fs::canonicalize(&exe).unwrap_or(exe);
+                outln!("  {} Extracted {} file(s)", "✓".green(), files.len());
+                println!("{}", exe.display());
+            }
+            None => outln!(
+                "  {} Extracted {} file(s), but no executable was recognized",
+                "⚠".yellow(),
+                files.len()
+            ),
+        }
+        extracted += 1;
+        Ok(())
+    })?;
+
+    outln!();
+    outln!("{}", "Summary:".bold());
+    if extracted > 0 {
+        outln!(
+            "  {} {} package(s) extracted to {} (not managed by wenget)",
+            "✓".green(),
+            extracted,
+            dest.display()
+        );
+    }
+    if failed > 0 {
+        outln!("  {} {} package(s) failed", "✗".red(), failed);
+        anyhow::bail!("{} package(s) could not be extracted", failed);
+    }
+    Ok(())
+}
+
+/// Resolve the download `add` would use for each package and hand it to
+/// `handle`, reporting failures on stderr
+///
+/// Returns the number of inputs or packages that failed.
+fn for_each_download(
+    config: &Config,
+    names: &[String],
+    options: &AddOptions,
+    mut handle: impl FnMut(&ResolvedPackage, PlatformBinary) -> Result<()>,
+) -> Result<usize> {
+    let installed = config.get_or_create_installed()?;
+    let settings = config.load_settings()?;
+    let cache = config.get_or_rebuild_cache()?;
+    let resolver = PackageResolver::new(config, &cache)?.with_sources(&options.sources)?;
+    let github = GitHubProvider::new()?;
+
+    if names.is_empty() {
+        anyhow::bail!("No package names or URLs provided");
+    }
+
+    let mut failed = 0;
+    for entry in names {
+        let (name, version) = split_version(entry);
+        let pin = match version.map(|v| (v, VersionConstraint::parse(v))) {
+            Some((version, None)) => {
+                eprintln!(
+                    "{} {}: Invalid version '{}'",
+                    "Error".red().bold(),
+                    name,
+                    version
+                );
+                failed += 1;
+                continue;
+            }
+            Some((_, constraint)) => constraint,
+            None => None,
+        };
+
+        let packages = match resolver.resolve(&PackageInput::parse(name)) {
+            Ok(packages) => packages,
+            Err(e) => {
+                eprintln!("{} {}: {:#}", "Error".red().bold(), name, e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        for resolved in packages {
+            let pkg = &resolved.package;
+            let previous = installed.get_package(&pkg.name);
+            let pin = pin
+                .clone()
+                .or_else(|| previous.and_then(InstalledPackage::version_constraint));
+            let channel = package_channel(&installed, &pkg.name, options.channel);
+            let platform_ids = match options.platform {
+                Some(ref id) => vec![canonical_platform_id(id).unwrap_or_else(|| id.clone())],
+                None => {
+                    let (variant, _) =
+                        package_variant(options.prefer_variant, previous, settings.prefer_variant);
+                    package_platform(&installed, &pkg.name, options.arch)
+                        .prefer_compiler(variant)
+                        .possible_identifiers()
+                }
+            };
+
+            let result = match options.asset {
+                Some(ref asset) => github.resolve_named_asset(&pkg.repo, asset, channel),
+                None => resolve_download(&github, pkg, &platform_ids, channel, pin.as_ref()),
+            }
+            .and_then(|binary| handle(&resolved, binary));
+            if let Err(e) = result {
+                eprintln!("{} {}: {:#}", "Error".red().bold(), pkg.name, e);
+                failed += 1;
+            }
+        }
+    }
+
+    Ok(failed)
+}
+
+/// The binary an install of `pkg` would download for the first matching
+/// platform identifier
+///
+/// Like an install, the latest release's links are preferred over the
+/// bucket's, and asset patterns are resolved against the release.
+fn resolve_download(
+    github: &GitHubProvider,
+    pkg: &crate::core::Package,
+    platform_ids: &[String],
+    channel: Channel,
+    pin: Option<&VersionConstraint>,
+) -> Result<PlatformBinary> {
+    let latest;
+    let pkg = if pkg.platforms.values().any(|b| b.asset_pattern.is_some()) {
+        pkg
+    } else {
+        match github.fetch_matching_package(&pkg.repo, channel, &pkg.yanked, pin) {
+            Ok(package) => {
+                latest = package;
+                &latest
+            }
+            // The bucket's links are for the latest release, which the pin may exclude
+            Err(e) if pin.is_some() => return Err(e),
+            Err(e) => {
+                log::warn!("Using bucket download links for {}: {:#}", pkg.name, e);
+                pkg
+            }
+        }
+    };
+
+    let binary = platform_ids
+        .iter()
+        .find_map(|id| pkg.platforms.get(id))
+        .ok_or_else(|| WengetError::PlatformUnsupported {
+            name: pkg.name.clone(),
+            platform: platform_ids.first().cloned().unwrap_or_default(),
+        })?;
+
+    match binary.asset_pattern {
+        Some(ref pattern) => {
+            github.resolve_asset_pattern(&pkg.repo, pattern, channel, &pkg.yanked, pin)
+        }
+        None => Ok(binary.clone()),
+    }
+}
+
+/// Install every entry of a package list file, reporting the result per line
+fn install_from_file(path: &Path, options: AddOptions) -> Result<InstallReport> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read package list: {}", path.display()))?;
+    let entries = parse_package_list(&content);
+
+    if entries.is_empty() {
+        outln!(
+            "{}",
+            format!("No packages listed in {}", path.display()).yellow()
+        );
+        return Ok(InstallReport::default());
+    }
+
+    outln!("{}", format!("Packages from {}:", path.display()).bold());
+    for entry in &entries {
+        match &entry.version {
+            Some(version) => outln!("  {} {} v{}", "•".green(), entry.name, version),
+            None => outln!("  {} {}", "•".green(), entry.name),
+        }
+    }
+
+    if !options.yes && !options.json {
+        print!("\nInstall {} package(s)? [Y/n] ", entries.len());
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut response = String::new();
+        io::stdin().read_line(&mut response)?;
+        let response = response.trim().to_lowercase();
+
+        if !response.is_empty() && response != "y" && response != "yes" {
+            outln!("Installation cancelled");
+            return Ok(InstallReport::default());
+        }
+    }
+
+    let mut combined = InstallReport::default();
+    let mut results = Vec::new();
+    for entry in &entries {
+        outln!();
+        outln!("{}", format!("[line {}] {}", entry.line, entry.name).bold());
+
+        let entry_options = AddOptions {
+            yes: true,
+            version: entry.version.clone(),
+            ..options.clone()
+        };
+        let outcome = match install(vec![entry.name.clone()], entry_options) {
+            Ok(report) => {
+                let failed = report.failed;
+                // Left alone by --if-not-installed/--no-upgrade: report the version kept
+                let untouched = report
+                    .outcomes
+                    .iter()
+                    .all(|o| o.action == OutcomeAction::Skipped)
+                    .then(|| report.outcomes.first().and_then(|o| o.to.clone()))
+                    .flatten();
+                combined.merge(report);
+                if failed == 0 {
+                    Ok(untouched)
+                } else {
+                    Err("failed".to_string())
+                }
+            }
+            Err(e) => {
+                combined.fail_error(&entry.name, &e);
+                Err(e.to_string())
+            }
+        };
+        results.push((entry, outcome));
+    }
+
+    outln!();
+    outln!("{}", format!("Summary ({}):", path.display()).bold());
+    for (entry, outcome) in &results {
+        match outcome {
+            Ok(None) => outln!("  {} line {}: {}", "✓".green(), entry.line, entry.name),
+            Ok(Some(version)) => outln!(
+                "  {} line {}: {} {}",
+                "•".cyan(),
+                entry.line,
+                entry.name,
+                format!("(left at v{})", version).dimmed()
+            ),
+            Err(reason) => outln!(
+                "  {} line {}: {} ({})",
+                "✗".red(),
+                entry.line,
+                entry.name,
+                reason
+            ),
+        }
+    }
+
+    let failed = results.iter().filter(|(_, o)| o.is_err()).count();
+    let untouched = results
+        .iter()
+        .filter(|(_, o)| matches!(o, Ok(Some(_))))
+        .count();
+    if untouched > 0 {
+        outln!(
+            "  {} installed, {} already installed and left untouched",
+            results.len() - untouched - failed,
+            untouched
+        );
+    }
+    if failed > 0 {
+        outln!(
+            "  {} of {} line(s) failed",
+            failed.to_string().red(),
+            results.len()
+        );
+    }
+
+    Ok(combined)
+}
+
+/// Install packages from an offline bundle directory
+///
+/// `dir` is either one package's bundle (holding `manifest.json`) or the
+/// `--dest` of `download --all-platforms`, holding one bundle per package.
+/// `names`, when given, picks which of its packages to install. The asset for
+/// the current platform is verified against the manifest checksum and
+/// installed from the bundle; nothing is downloaded.
+fn install_from_bundle(
+    config: &Config,
+    dir: &Path,
+    names: &[String],
+    options: AddOptions,
+) -> Result<InstallReport> {
+    let paths = config.paths();
+    let yes = options.yes || options.json;
+
+    if !config.is_initialized() {
+        config.init()?;
+    }
+    let mut installed = config.get_or_create_installed()?;
+
+    let mut report = InstallReport::default();
+    let mut bundles = load_bundles(dir)?;
+    if !names.is_empty() {
+        for name in names {
+            if !bundles.iter().any(|(_, b)| b.package.name == *name) {
+                outln!("{} {} is not in the bundle", "✗".red(), name);
+                report.fail_error(
+                    name,
+                    &WengetError::NotFound(format!("not found in bundle {}", dir.display())).into(),
+                );
+            }
+        }
+        bundles.retain(|(_, b)| names.contains(&b.package.name));
+    }
+    if bundles.is_empty() {
+        return Ok(report);
+    }
+
+    outln!(
+        "{}",
+        format!("Packages from bundle {}:", dir.display()).bold()
+    );
+    for (_, bundle) in &bundles {
+        outln!(
+            "  {} {} v{}",
+            "•".green(),
+            bundle.package.name,
+            bundle.version.as_deref().unwrap_or("unknown")
+        );
+    }
+
+    if !yes {
+        print!("\nInstall {} package(s)? [Y/n] ", bundles.len());
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut response = String::new();
+        io::stdin().read_line(&mut response)?;
+        let response = response.trim().to_lowercase();
+
+        if !response.is_empty() && response != "y" && response != "yes" {
+            outln!("Installation cancelled");
+            return Ok(report);
+        }
+    }
+    outln!();
+
+    if let Some(ref dir) = options.global_bin {
+        validate_bin_dir(dir)?;
+    }
+    let settings = config.load_settings()?;
+    let default_bin_dir = match settings.global_bin {
+        Some(dir) => {
+            validate_bin_dir(&dir)?;
+            dir
+        }
+        None => paths.bin_dir(),
+    };
+    let temp_dir = resolve_temp_dir(options.temp_dir.clone(), paths)?;
+    let retries = options
+        .retries
+        .or(settings.download_retries)
+        .unwrap_or(downloader::DEFAULT_RETRIES);
+
+    for (bundle_dir, bundle) in &bundles {
+        let pkg = &bundle.package;
+        let version = bundle.version.as_deref().unwrap_or("unknown");
+        if options.if_not_installed && skip_installed(&installed, &mut report, &pkg.name) {
+            continue;
+        }
+        if let Some(current) = installed.get_package(&pkg.name) {
+            if is_downgrade(&current.version, version) && !options.allow_downgrade {
+                outln!(
+                    "  {} {} v{} {} → {} {}",
+                    "✗".red(),
+                    pkg.name,
+                    current.version,
+                    "downgrade to".red(),
+                    version,
+                    "(use --allow-downgrade)".dimmed()
+                );
+                report.fail(
+                    &pkg.name,
+                    format!(
+                        "v{} is older than the installed v{}; use --allow-downgrade to install it",
+                        version, current.version
+                    ),
+                );
+                continue;
+            }
+        }
+        outln!("{} {} v{}...", "Installing".cyan(), pkg.name, version);
+
+        let previous = installed.get_package(&pkg.name).cloned();
+        let bin_dir = options
+            .global_bin
+            .clone()
+            .or_else(|| {
+                previous
+                    .as_ref()
+                    .and_then(|p| p.shim_path.as_deref())
+                    .and_then(|p| Path::new(p).parent())
+                    .map(Path::to_path_buf)
+            })
+            .unwrap_or_else(|| default_bin_dir.clone());
+        let no_shim = options.no_shim || previous.as_ref().is_some_and(|p| p.no_shim);
+        let (variant, recorded_variant) = package_variant(
+            options.prefer_variant,
+            previous.as_ref(),
+            settings.prefer_variant,
+        );
+        let platform_ids = package_platform(&installed, &pkg.name, options.arch)
+            .prefer_compiler(variant)
+            .possible_identifiers();
+        let source = PackageSource::DirectRepo {
+            url: pkg.repo.clone(),
+        };
+        // A bundle carries no channel: packages stay on the one they follow
+        let channel = package_channel(&installed, &pkg.name, None);
+
+        let mut cleanup = CleanupGuard::new();
+        let result = bundled_package(bundle_dir, pkg, &platform_ids).and_then(|local| {
+            let install = PackageInstall {
+                custom_name: options.script_name.as_deref(),
+                no_shim,
+                shim_mode: package_shim_mode(
+                    options.shim_mode,
+                    previous.as_ref(),
+                    settings.shim_mode.unwrap_or_default(),
+                ),
+                previous: previous.as_ref(),
+                channel,
+                retries,
+                keep_versions: options
+                    .keep_versions
+                    .or(previous.as_ref().and_then(|p| p.keep_versions))
+                    .unwrap_or(settings.keep_versions.unwrap_or(0)),
+                temp_dir: temp_dir.as_deref(),
+                deadline: options.timeout.map(Deadline::after),
+                ..PackageInstall::new(version, &source, &bin_dir)
+            };
+            install_package(config, &local, &platform_ids, &install, &mut cleanup)
+        });
+
+        match result {
+            Ok(mut inst_pkg) => {
+                inst_pkg.prefer_variant = recorded_variant;
+                inst_pkg.channel = channel;
+                inst_pkg.keep_versions = options
+                    .keep_versions
+                    .or(previous.as_ref().and_then(|p| p.keep_versions));
+                inst_pkg.requires = pkg.requires.clone();
+                inst_pkg.auto_installed = previous.as_ref().is_some_and(|p| p.auto_installed);
+                record_install(config, previous.as_ref(), &pkg.name, &inst_pkg);
+                report.installed += 1;
+                report.outcomes.push(PackageOutcome::installed(
+                    &pkg.name,
+                    previous.as_ref().map(|p| p.version.as_str()),
+                    &inst_pkg.version,
+                ));
+                let message = inst_pkg.post_install_message.clone();
+                installed.upsert_package(pkg.name.clone(), inst_pkg);
+                config.save_installed(&installed)?;
+                cleanup.commit();
+                outln!("  {} Installed successfully", "✓".green());
+                print_post_install_message(&pkg.name, message.as_deref());
+            }
+            Err(e) => {
+                outln!("  {} {:#}", "✗".red(), e);
+                report.fail_error(&pkg.name, &e);
+            }
+        }
+        outln!();
+    }
+
+    outln!("{}", "Summary:".bold());
+    if report.installed > 0 {
+        outln!(
+            "  {} {} package(s) installed",
+            "✓".green(),
+            report.installed
+        );
+    }
+    if report.failed > 0 {
+        outln!("  {} {} package(s) failed", "✗".red(), report.failed);
+    }
+
+    Ok(report)
+}
+
+/// Read the bundle manifest(s) in `dir`, paired with each bundle's directory
+fn load_bundles(dir: &Path) -> Result<Vec<(PathBuf, BundleManifest)>> {
+    let read = |bundle_dir: &Path| -> Result<BundleManifest> {
+        let path = bundle_dir.join(BUNDLE_MANIFEST);
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse bundle manifest {}", path.display()))
+    };
+
+    if dir.join(BUNDLE_MANIFEST).is_file() {
+        return Ok(vec![(dir.to_path_buf(), read(dir)?)]);
+    }
+
+    let mut bundles = Vec::new();
+    for entry in
+        fs::read_dir(dir).with_context(|| format!("Failed to read bundle {}", dir.display()))?
+    {
+        let bundle_dir = entry?.path();
+        if bundle_dir.join(BUNDLE_MANIFEST).is_file() {
+            bundles.push((bundle_dir.clone(), read(&bundle_dir)?));
+        }
+    }
+
+    if bundles.is_empty() {
+        anyhow::bail!(
+            "No {} found in {} or its subdirectories; create a bundle with `wenget download --all-platforms`",
+            BUNDLE_MANIFEST,
+            dir.display()
+        );
+    }
+    bundles.sort_by(|a, b| a.1.package.name.cmp(&b.1.package.name));
+    Ok(bundles)
+}
+
+/// Point a bundled package at its local asset for the first matching platform
+///
+/// The asset is checked against the checksum recorded in the manifest and
+/// against its `.sha256` sidecar, if present.
+/// Fails, naming the platforms the bundle does have, if none matches.
+fn bundled_package(
+    bundle_dir: &Path,
+    pkg: &crate::core::Package,
+    platform_ids: &[String],
+) -> Result<crate::core::Package> {
+    let Some((platform_id, binary)) = platform_ids
+        .iter()
+        .find_map(|id| pkg.platforms.get(id).map(|b| (id, b)))
+    else {
+        let mut available: Vec<&str> = pkg.platforms.keys().map(String::as_str).collect();
+        available.sort_unstable();
+        anyhow::bail!(
+            "Bundle has no asset for {} (bundled platforms: {})",
+            platform_ids.first().map_or("this platform", String::as_str),
+            available.join(", ")
+        );
+    };
+
+    let filename = binary
+        .url
+        .split('/')
+        .next_back()
+        .context("Invalid download URL in bundle manifest")?;
+    let path = bundle_dir.join(platform_id).join(filename);
+    if !path.is_file() {
+        anyhow::bail!("Bundle is missing {}", path.display());
+    }
+
+    // The sidecar written by `download` catches files changed after staging,
+    // even when the manifest has no checksum
+    if let Some(ref expected) = binary.checksum {
+        checksum::verify_sha256(&path, expected)?;
+    }
+    let sidecar_verified = checksum::verify_sidecar(&path)?;
+    if binary.checksum.is_some() || sidecar_verified {
+        outln!("  {} Checksum verified", "✓".green());
+    } else {
+        outln!(
+            "  {} No checksum recorded for {}; skipping verification",
+            "⚠".yellow(),
+            filename
+        );
+    }
+
+    let local = PlatformBinary {
+        url: path.to_string_lossy().replace('\\', "/"),
+        asset_pattern: None,
+        ..binary.clone()
+    };
+    Ok(crate::core::Package {
+        platforms: HashMap::from([(platform_id.clone(), local)]),
+        ..pkg.clone()
+    })
+}
+
+/// Run one install over the given names/URLs/scripts
+pub(crate) fn install(names: Vec<String>, options: AddOptions) -> Result<InstallReport> {
     let config = Config::new()?;
-    let paths = WenPaths::new()?;
+    let paths = config.paths();
+    let yes = options.yes || options.json;
 
     // Ensure initialized
     if !config.is_initialized() {
@@ -32,51 +1195,147 @@ pub fn run(names: Vec<String>, yes: bool, script_name: Option<String>) -> Result
     let mut installed = config.get_or_create_installed()?;
 
     if names.is_empty() {
-        println!("{}", "No package names or URLs provided".yellow());
-        println!("Usage: wenget add <name|url>...");
-        println!();
-        println!("Examples:");
-        println!("  wenget add ripgrep              # Install from cache");
-        println!("  wenget add 'rip*'               # Install matching packages (glob)");
-        println!("  wenget add https://github.com/BurntSushi/ripgrep  # Install from URL");
-        println!("  wenget add ./script.ps1         # Install local script");
-        println!(
+        outln!("{}", "No package names or URLs provided".yellow());
+        outln!("Usage: wenget add <name|url>...");
+        outln!();
+        outln!("Examples:");
+        outln!("  wenget add ripgrep              # Install from cache");
+        outln!("  wenget add 'rip*'               # Install matching packages (glob)");
+        outln!("  wenget add https://github.com/BurntSushi/ripgrep  # Install from URL");
+        outln!("  wenget add ./script.ps1         # Install local script");
+        outln!(
             "  wenget add https://raw.githubusercontent.com/.../script.sh  # Install remote script"
         );
-        return Ok(());
+        outln!("  wenget add --from-file tools.txt  # Install every package in a list");
+        return Ok(InstallReport::default());
     }
 
     // Check if any input is a script
     let script_inputs: Vec<&String> = names.iter().filter(|n| is_script_input(n)).collect();
     let package_inputs: Vec<&String> = names.iter().filter(|n| !is_script_input(n)).collect();
 
+    if options.asset.is_some() && (package_inputs.len() != 1 || !script_inputs.is_empty()) {
+        anyhow::bail!("--asset can only be used with a single package name or URL");
+    }
+    if options.checksum.is_some() && (package_inputs.len() != 1 || !script_inputs.is_empty()) {
+        anyhow::bail!("--checksum can only be used with a single package name or URL");
+    }
+    if options.exe_name.is_some() && (package_inputs.len() != 1 || !script_inputs.is_empty()) {
+        anyhow::bail!("--exe-name can only be used with a single package name or URL");
+    }
+
+    let mut report = InstallReport::default();
+
     // Handle script installations
     if !script_inputs.is_empty() {
-        install_scripts(
+        report.merge(install_scripts(
             &config,
-            &paths,
+            paths,
             &mut installed,
             script_inputs,
             yes,
-            script_name.as_deref(),
-        )?;
+            options.script_name.as_deref(),
+        )?);
     }
 
     // Handle package installations (existing logic)
     if !package_inputs.is_empty() {
-        install_packages(
+        // Validate launcher directories up front so nothing is downloaded for nothing
+        if let Some(ref dir) = options.global_bin {
+            validate_bin_dir(dir)?;
+        }
+        let settings = config.load_settings()?;
+        let default_bin_dir = match settings.global_bin {
+            Some(dir) => {
+                validate_bin_dir(&dir)?;
+                dir
+            }
+            None => paths.bin_dir(),
+        };
+        let ctx = InstallContext {
+            options: &options,
+            yes,
+            default_bin_dir,
+            default_shim_mode: settings.shim_mode.unwrap_or_default(),
+            default_variant: settings.prefer_variant,
+            default_keep_versions: settings.keep_versions.unwrap_or(0),
+            retries: options
+                .retries
+                .or(settings.download_retries)
+                .unwrap_or(downloader::DEFAULT_RETRIES),
+            temp_dir: resolve_temp_dir(options.temp_dir.clone(), paths)?,
+        };
+
+        report.merge(install_packages(
             &config,
-            &paths,
             &mut installed,
-            package_inputs,
-            yes,
-            script_name.as_deref(),
-        )?;
+            &package_inputs,
+            &ctx,
+        )?);
+    }
+
+    Ok(report)
+}
+
+/// Ensure a custom launcher directory exists and is writable, warning if it
+/// is not in PATH
+fn validate_bin_dir(dir: &Path) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create launcher directory: {}", dir.display()))?;
+
+    if !dir.is_dir() {
+        anyhow::bail!("Launcher directory is not a directory: {}", dir.display());
+    }
+
+    let probe = dir.join(".wenget-write-test");
+    fs::write(&probe, b"")
+        .with_context(|| format!("Launcher directory is not writable: {}", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+
+    if !is_dir_in_path(dir) {
+        outln!(
+            "{} Launcher directory {} is not in PATH",
+            "⚠".yellow(),
+            dir.display()
+        );
     }
 
     Ok(())
 }
 
+/// Pick the temporary directory from `--temp-dir` or `WENGET_TMPDIR`
+///
+/// The directory is created and checked for writability up front. Returns
+/// `None` when neither is set, meaning the cache directory is used.
+fn resolve_temp_dir(flag: Option<PathBuf>, paths: &WenPaths) -> Result<Option<PathBuf>> {
+    let Some(dir) = flag.or_else(|| {
+        std::env::var_os(TEMP_DIR_ENV)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    }) else {
+        return Ok(None);
+    };
+
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("Failed to create temp directory: {}", dir.display()))?;
+    let probe = dir.join(".wenget-write-test");
+    fs::write(&probe, b"")
+        .with_context(|| format!("Temp directory is not writable: {}", dir.display()))?;
+    let _ = fs::remove_file(&probe);
+
+    let apps_dir = paths.apps_dir();
+    if apps_dir.exists() && !same_filesystem(&dir, &apps_dir) {
+        outln!(
+            "{} Temp directory {} is on a different filesystem than {}; installs will be copied instead of moved atomically",
+            "⚠".yellow(),
+            dir.display(),
+            apps_dir.display()
+        );
+    }
+
+    Ok(Some(dir))
+}
+
 /// Install scripts from local paths or URLs
 fn install_scripts(
     config: &Config,
@@ -85,9 +1344,10 @@ fn install_scripts(
     script_inputs: Vec<&String>,
     yes: bool,
     custom_name: Option<&str>,
-) -> Result<()> {
-    println!("{}", "Scripts to install:".bold());
+) -> Result<InstallReport> {
+    outln!("{}", "Scripts to install:".bold());
 
+    let mut report = InstallReport::default();
     let mut scripts_to_install: Vec<(String, String, ScriptType, String)> = Vec::new(); // (name, content, type, origin)
 
     for input in script_inputs {
@@ -100,6 +1360,7 @@ fn install_scripts(
                 Ok(c) => c,
                 Err(e) => {
                     eprintln!("{} Failed to download {}: {}", "✗".red(), input, e);
+                    report.fail_error(input, &e.context("Failed to download"));
                     continue;
                 }
             }
@@ -109,6 +1370,7 @@ fn install_scripts(
                 Ok(c) => c,
                 Err(e) => {
                     eprintln!("{} Failed to read {}: {}", "✗".red(), input, e);
+                    report.fail(input, format!("Failed to read: {}", e));
                     continue;
                 }
             }
@@ -119,19 +1381,21 @@ fn install_scripts(
             Some(t) => t,
             None => {
                 eprintln!("{} Cannot detect script type for: {}", "✗".red(), input);
+                report.fail(input, "Cannot detect script type");
                 continue;
             }
         };
 
         // Check platform compatibility
         if !script_type.is_supported_on_current_platform() {
-            println!(
+            outln!(
                 "  {} {} ({}) - {}",
                 "⚠".yellow(),
                 input,
                 script_type.display_name(),
                 "not supported on this platform".yellow()
             );
+            report.fail(input, "Not supported on this platform");
             continue;
         }
 
@@ -143,6 +1407,7 @@ fn install_scripts(
                 Some(n) => n,
                 None => {
                     eprintln!("{} Cannot extract name from: {}", "✗".red(), input);
+                    report.fail(input, "Cannot extract script name");
                     continue;
                 }
             }
@@ -150,7 +1415,7 @@ fn install_scripts(
 
         // Check if already installed
         if installed.is_installed(&name) {
-            println!(
+            outln!(
                 "  {} {} ({}) - {}",
                 "•".yellow(),
                 name,
@@ -158,7 +1423,7 @@ fn install_scripts(
                 "already installed, will be replaced".yellow()
             );
         } else {
-            println!(
+            outln!(
                 "  {} {} ({}) {}",
                 "•".green(),
                 name,
@@ -171,13 +1436,13 @@ fn install_scripts(
     }
 
     if scripts_to_install.is_empty() {
-        println!("{}", "No scripts to install".yellow());
-        return Ok(());
+        outln!("{}", "No scripts to install".yellow());
+        return Ok(report);
     }
 
     // Show security warning
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}",
         "⚠  Security Warning: Review scripts before running them!"
             .yellow()
@@ -195,18 +1460,18 @@ fn install_scripts(
         let response = response.trim().to_lowercase();
 
         if !response.is_empty() && response != "y" && response != "yes" {
-            println!("Installation cancelled");
-            return Ok(());
+            outln!("Installation cancelled");
+            return Ok(report);
         }
     }
 
-    println!();
+    outln!();
 
     let mut success_count = 0;
     let mut fail_count = 0;
 
     for (name, content, script_type, origin) in scripts_to_install {
-        println!(
+        outln!(
             "{} {} ({})...",
             "Installing".cyan(),
             name,
@@ -215,28 +1480,37 @@ fn install_scripts(
 
         match install_single_script(paths, &name, &content, &script_type, &origin) {
             Ok(inst_pkg) => {
+                let previous = installed.get_package(&name);
+                record_install(config, previous, &name, &inst_pkg);
+                report.outcomes.push(PackageOutcome::installed(
+                    &name,
+                    previous.map(|p| p.version.as_str()),
+                    &inst_pkg.version,
+                ));
                 installed.upsert_package(name.clone(), inst_pkg);
                 config.save_installed(installed)?;
-                println!("  {} Installed successfully", "✓".green());
+                outln!("  {} Installed successfully", "✓".green());
                 success_count += 1;
             }
             Err(e) => {
-                println!("  {} {}", "✗".red(), e);
+                outln!("  {} {}", "✗".red(), e);
+                report.fail_error(&name, &e);
                 fail_count += 1;
             }
         }
     }
 
-    println!();
-    println!("{}", "Summary:".bold());
+    outln!();
+    outln!("{}", "Summary:".bold());
     if success_count > 0 {
-        println!("  {} {} script(s) installed", "✓".green(), success_count);
+        outln!("  {} {} script(s) installed", "✓".green(), success_count);
     }
     if fail_count > 0 {
-        println!("  {} {} script(s) failed", "✗".red(), fail_count);
+        outln!("  {} {} script(s) failed", "✗".red(), fail_count);
     }
 
-    Ok(())
+    report.installed += success_count;
+    Ok(report)
 }
 
 /// Install a single script
@@ -249,88 +1523,393 @@ fn install_single_script(
 ) -> Result<InstalledPackage> {
     // Install script to app directory
     let files = install_script(paths, name, content, script_type)?;
+    let file_digests = record_digests(&paths.app_dir(name), &files)?;
 
-    println!("  Command will be available as: {}", name);
+    outln!("  Command will be available as: {}", name);
 
     // Create shim
-    println!("  Creating launcher...");
+    outln!("  Creating launcher...");
     create_script_shim(paths, name, script_type)?;
 
     // Create installed package info
+    let now = Utc::now();
     let inst_pkg = InstalledPackage {
         version: "script".to_string(),
         platform: format!("{}-script", script_type.display_name().to_lowercase()),
-        installed_at: Utc::now(),
+        installed_at: now,
+        updated_at: Some(now),
         install_path: paths.app_dir(name).to_string_lossy().to_string(),
         files,
+        file_digests,
         source: PackageSource::Script {
             origin: origin.to_string(),
             script_type: script_type.clone(),
         },
         description: format!("{} script from {}", script_type.display_name(), origin),
         command_name: name.to_string(),
+        shim_path: None,
+        exe_path: None,
+        extra_shims: Vec::new(),
+        alias: None,
+        no_shim: false,
+        prefer_variant: None,
+        requires: Vec::new(),
+        auto_installed: false,
+        channel: Channel::Stable,
+        asset_pattern: None,
+        keep_versions: None,
+        kept_versions: Vec::new(),
+        pin: None,
+        ignored_updates: Vec::new(),
+        post_install_message: None,
+        exe_name: None,
+        shim_mode: ShimMode::Symlink,
     };
 
     Ok(inst_pkg)
 }
 
+/// Settings shared by every package of one `add` run
+struct InstallContext<'a> {
+    options: &'a AddOptions,
+    yes: bool,
+    /// Launcher directory from the settings, or the default one
+    default_bin_dir: PathBuf,
+    default_shim_mode: ShimMode,
+    default_variant: Option<Compiler>,
+    default_keep_versions: usize,
+    retries: u32,
+    temp_dir: Option<PathBuf>,
+}
+
+/// A bucket script to install: (name, url, type, origin)
+type BucketScript = (String, String, ScriptType, String);
+
+/// Everything the inputs of one run resolved to
+#[derive(Default)]
+struct InstallRequest {
+    packages: Vec<ResolvedPackage>,
+    scripts: Vec<BucketScript>,
+    /// Versions asked for with `name@version`, by package name
+    versions: HashMap<String, VersionConstraint>,
+    /// Packages pulled in through `requires` rather than asked for
+    dependencies: HashSet<String>,
+}
+
+impl InstallRequest {
+    /// The channel a package follows and the version it is pinned to
+    ///
+    /// `--pre` and `name@version` only apply to the packages asked for, not
+    /// their dependencies.
+    fn lookup(
+        &self,
+        installed: &InstalledManifest,
+        name: &str,
+        options: &AddOptions,
+    ) -> (Channel, Option<VersionConstraint>) {
+        let requested_channel = (!self.dependencies.contains(name))
+            .then_some(options.channel)
+            .flatten();
+        let channel = package_channel(installed, name, requested_channel);
+        let pin = self
+            .versions
+            .get(name)
+            .cloned()
+            .or_else(|| package_pin(installed, name, options.reinstall));
+        (channel, pin)
+    }
+}
+
 /// Install packages from cache or GitHub (existing logic)
 fn install_packages(
     config: &Config,
-    paths: &WenPaths,
-    installed: &mut crate::core::InstalledManifest,
-    names: Vec<&String>,
-    yes: bool,
-    custom_name: Option<&str>,
-) -> Result<()> {
-    // Get current platform
-    let platform = Platform::current();
-    let platform_ids = platform.possible_identifiers();
+    installed: &mut InstalledManifest,
+    names: &[&String],
+    ctx: &InstallContext,
+) -> Result<InstallReport> {
+    let mut report = InstallReport::default();
 
     // Load cache once for both script lookup and package resolution
     let cache = config.get_or_rebuild_cache()?;
 
     // Resolve all inputs and collect packages/scripts to install
-    let resolver = PackageResolver::new(config, &cache)?;
+    let resolver = PackageResolver::new(config, &cache)?.with_sources(&ctx.options.sources)?;
+    let mut request = resolve_inputs(&resolver, installed, names, ctx.options, &mut report);
+
+    if request.packages.is_empty() && request.scripts.is_empty() {
+        outln!("{}", "No packages or scripts to install".yellow());
+        return Ok(report);
+    }
+
+    // Create GitHub provider to fetch versions (for packages)
+    let github = if !request.packages.is_empty() {
+        Some(GitHubProvider::new()?)
+    } else {
+        None
+    };
+
+    let packages = std::mem::take(&mut request.packages);
+    let packages = select_packages(
+        config,
+        installed,
+        github.as_ref(),
+        packages,
+        &request,
+        ctx.options,
+        &mut report,
+    )?;
+    let scripts = std::mem::take(&mut request.scripts);
+    let scripts = select_scripts(installed, scripts, ctx.options, &mut report);
+
+    // Check if there's anything to do
+    if packages.is_empty() && scripts.is_empty() {
+        outln!();
+        outln!(
+            "{}",
+            "All packages and scripts are already up to date".green()
+        );
+        return Ok(report);
+    }
+
+    // Confirm installation
+    if !ctx.yes {
+        print!("\nProceed with installation? [Y/n] ");
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut response = String::new();
+        io::stdin().read_line(&mut response)?;
+        let response = response.trim().to_lowercase();
+
+        if !response.is_empty() && response != "y" && response != "yes" {
+            outln!("Installation cancelled");
+            return Ok(report);
+        }
+    }
+
+    outln!();
+
+    // Install/update packages
+    let (prepared, prepare_fail_count) = prepare_packages(
+        config,
+        installed,
+        github.as_ref(),
+        packages,
+        &request,
+        ctx,
+        &mut report,
+    )?;
+    let (success_count, install_fail_count) = install_prepared(
+        config,
+        installed,
+        github.as_ref(),
+        prepared,
+        &request,
+        ctx,
+        &mut report,
+    )?;
+    let fail_count = prepare_fail_count + install_fail_count;
+
+    // Install scripts from bucket cache
+    let (script_success_count, script_fail_count) =
+        install_bucket_scripts(config, installed, scripts, ctx.options, &mut report);
+
+    // Summary
+    outln!("{}", "Summary:".bold());
+    if success_count > 0 {
+        outln!("  {} {} package(s) installed", "✓".green(), success_count);
+    }
+    let untouched = report
+        .outcomes
+        .iter()
+        .filter(|o| o.action == OutcomeAction::Skipped)
+        .count();
+    if untouched > 0 {
+        outln!(
+            "  {} {} package(s) already installed, left untouched",
+            "•".cyan(),
+            untouched
+        );
+    }
+    if fail_count > 0 {
+        outln!("  {} {} package(s) failed", "✗".red(), fail_count);
+    }
+    if script_success_count > 0 {
+        outln!(
+            "  {} {} script(s) installed",
+            "✓".green(),
+            script_success_count
+        );
+    }
+    if script_fail_count > 0 {
+        outln!("  {} {} script(s) failed", "✗".red(), script_fail_count);
+    }
+
+    report.installed += success_count + script_success_count;
+    Ok(report)
+}
+
+/// Resolve the names given on the command line to packages and bucket
+/// scripts, adding the dependencies the packages require
+fn resolve_inputs(
+    resolver: &PackageResolver,
+    installed: &InstalledManifest,
+    names: &[&String],
+    options: &AddOptions,
+    report: &mut InstallReport,
+) -> InstallRequest {
+    let asset = options.asset.as_deref();
+    let force_platform = options.force_platform.as_deref();
+    let arch = options.arch;
+    let channel = options.channel;
+    let exclude = &options.exclude;
+    let if_not_installed = options.if_not_installed;
+
+    // Get target platform (current OS, --arch may pick another architecture)
+    let platform = Platform::new(Os::current(), arch.unwrap_or_else(Arch::current))
+        .with_emulation(emulation_allowed());
+    let platform_ids = platform.possible_identifiers();
+
     let mut packages_to_install: Vec<ResolvedPackage> = Vec::new();
-    let mut scripts_to_install: Vec<(String, String, ScriptType, String)> = Vec::new(); // (name, url, type, origin)
+    let mut scripts_to_install: Vec<BucketScript> = Vec::new();
+    let mut excluded: Vec<String> = Vec::new();
+    // Versions asked for with `name@version`, by package name
+    let mut requested_versions: HashMap<String, VersionConstraint> = HashMap::new();
 
-    for name in &names {
+    for entry in names {
+        // `name@13.*` installs the newest release matching the version
+        let (name, version) = split_version(entry);
         let input = PackageInput::parse(name);
+        let version = match version {
+            Some(version) => match VersionConstraint::parse(version) {
+                Some(constraint) => Some(constraint),
+                None => {
+                    eprintln!(
+                        "{} {}: Invalid version '{}'. Expected e.g. 13.0.5, 13.*, ^1.2 or *-beta",
+                        "Error".red().bold(),
+                        name,
+                        version
+                    );
+                    report.fail(name, format!("Invalid version '{}'", version));
+                    continue;
+                }
+            },
+            None => None,
+        };
+
+        // Installed packages named outright need no lookup at all
+        if if_not_installed && skip_installed(installed, report, name) {
+            continue;
+        }
+
+        // An explicit --asset replaces platform matching with the named release asset
+        let resolution = match asset {
+            Some(asset) => match resolve_with_asset(
+                resolver,
+                &input,
+                asset,
+                &package_platform(installed, name, arch).to_string(),
+                package_channel(installed, name, channel),
+            ) {
+                Ok(resolved) => Ok(resolved),
+                // Not worth a script lookup: the asset was asked for explicitly
+                Err(e) => {
+                    eprintln!("{} {}: {:#}", "Error".red().bold(), name, e);
+                    report.fail_error(name, &e);
+                    continue;
+                }
+            },
+            None => resolver.resolve(&input),
+        };
 
-        match resolver.resolve(&input) {
+        match resolution {
             Ok(resolved) => {
-                for pkg_resolved in resolved {
+                for mut pkg_resolved in resolved {
+                    if is_excluded(&pkg_resolved.package.name, exclude) {
+                        excluded.push(pkg_resolved.package.name);
+                        continue;
+                    }
+
                     // Check platform support
-                    let platform_matches = platform_ids
+                    let pkg_platform =
+                        package_platform(installed, &pkg_resolved.package.name, arch);
+                    if let Some(id) = force_platform {
+                        if let Err(e) =
+                            force_platform_entry(&mut pkg_resolved.package, id, pkg_platform)
+                        {
+                            eprintln!("{} {:#}", "Error".red().bold(), e);
+                            report.fail_error(&pkg_resolved.package.name, &e);
+                            continue;
+                        }
+                    }
+                    let platform_matches = pkg_platform
+                        .possible_identifiers()
                         .iter()
                         .any(|id| pkg_resolved.package.platforms.contains_key(id));
 
+                    if !platform_matches && arch.is_some() {
+                        let mut available: Vec<&str> = pkg_resolved
+                            .package
+                            .platforms
+                            .keys()
+                            .map(String::as_str)
+                            .collect();
+                        available.sort_unstable();
+                        outln!(
+                            "{} {} has no {} build. Available platforms: {}",
+                            "Warning:".yellow(),
+                            pkg_resolved.package.name,
+                            pkg_platform,
+                            available.join(", ")
+                        );
+                        report.fail_error(
+                            &pkg_resolved.package.name,
+                            &WengetError::PlatformUnsupported {
+                                name: pkg_resolved.package.name.clone(),
+                                platform: pkg_platform.to_string(),
+                            }
+                            .into(),
+                        );
+                        continue;
+                    }
                     if !platform_matches {
-                        println!(
+                        outln!(
                             "{} {} does not support current platform",
                             "Warning:".yellow(),
                             pkg_resolved.package.name
                         );
+                        report.fail_error(
+                            &pkg_resolved.package.name,
+                            &WengetError::PlatformUnsupported {
+                                name: pkg_resolved.package.name.clone(),
+                                platform: pkg_platform.to_string(),
+                            }
+                            .into(),
+                        );
                         continue;
                     }
 
+                    if let Some(ref version) = version {
+                        requested_versions
+                            .insert(pkg_resolved.package.name.clone(), version.clone());
+                    }
                     packages_to_install.push(pkg_resolved);
                 }
             }
-            Err(_) => {
+            Err(e) => {
                 // If not found as package, check if it's a script in cache
-                if let Some(cached_script) = cache.find_script(name) {
+                if let Some(cached_script) = resolver.find_script(name) {
                     let script = &cached_script.script;
 
                     // Check platform support
                     if !script.script_type.is_supported_on_current_platform() {
-                        println!(
+                        outln!(
                             "{} {} ({}) is not supported on current platform",
                             "Warning:".yellow(),
                             script.name,
                             script.script_type.display_name()
                         );
+                        report.fail(&script.name, "Current platform not supported");
                         continue;
                     }
 
@@ -346,28 +1925,60 @@ fn install_packages(
                         script.script_type.clone(),
                         source_name,
                     ));
+                } else if !matches!(WengetError::find(&e), Some(WengetError::NotFound(_))) {
+                    // Found, but the manifest entry could not be used
+                    eprintln!("{} {}: {:#}", "Error".red().bold(), name, e);
+                    report.fail_error(name, &e);
                 } else {
                     eprintln!("{} {}: Not found", "Error".red().bold(), name);
+                    report.fail_error(name, &e);
                 }
             }
         }
     }
 
-    if packages_to_install.is_empty() && scripts_to_install.is_empty() {
-        println!("{}", "No packages or scripts to install".yellow());
-        return Ok(());
+    if !excluded.is_empty() {
+        excluded.sort();
+        excluded.dedup();
+        outln!("{} {}", "Excluded:".bold(), excluded.join(", ").dimmed());
     }
 
-    // Create GitHub provider to fetch versions (for packages)
-    let github = if !packages_to_install.is_empty() {
-        Some(GitHubProvider::new()?)
-    } else {
-        None
-    };
+    // Pull in packages listed in `requires` that are not installed yet
+    let dependencies =
+        resolve_dependencies(resolver, installed, &mut packages_to_install, &platform_ids);
+
+    InstallRequest {
+        packages: packages_to_install,
+        scripts: scripts_to_install,
+        versions: requested_versions,
+        dependencies,
+    }
+}
+
+/// List the packages about to be installed with their versions and pick the
+/// ones to install: new installs first, then updates
+///
+/// Packages already at that version are reported as skipped, and downgrades
+/// are refused unless allowed or the installed release was yanked.
+fn select_packages(
+    config: &Config,
+    installed: &mut InstalledManifest,
+    github: Option<&GitHubProvider>,
+    packages_to_install: Vec<ResolvedPackage>,
+    request: &InstallRequest,
+    options: &AddOptions,
+    report: &mut InstallReport,
+) -> Result<Vec<ResolvedPackage>> {
+    let dependencies = &request.dependencies;
+    let required_version = options.version.as_deref();
+    let reinstall = options.reinstall;
+    let upgrade = options.upgrade;
+    let allow_downgrade = options.allow_downgrade;
+    let if_not_installed = options.if_not_installed;
 
     // Show packages to install with versions and handle already-installed packages
     if !packages_to_install.is_empty() {
-        println!("{}", "Packages to install:".bold());
+        outln!("{}", "Packages to install:".bold());
     }
 
     let mut to_install: Vec<ResolvedPackage> = Vec::new();
@@ -376,28 +1987,118 @@ fn install_packages(
     for resolved in packages_to_install {
         let pkg_name = &resolved.package.name;
         let repo = &resolved.package.repo;
+        let (pkg_channel, pin) = request.lookup(installed, pkg_name, options);
+
+        if if_not_installed && skip_installed(installed, report, pkg_name) {
+            continue;
+        }
 
-        // Fetch latest version
-        let version = if let Some(ref gh) = github {
-            gh.fetch_latest_version(repo)
+        // Fetch latest version (the newest one allowed by a pin)
+        let version = if let Some(gh) = github {
+            gh.fetch_matching_version(repo, pkg_channel, &resolved.package.yanked, pin.as_ref())
                 .unwrap_or_else(|_| "unknown".to_string())
         } else {
             "unknown".to_string()
         };
 
+        // A pinned list entry only installs when the latest release matches
+        if let Some(required) = required_version.filter(|_| !dependencies.contains(pkg_name)) {
+            if required != version {
+                outln!(
+                    "  {} {} v{} requested, latest is v{}",
+                    "✗".red(),
+                    pkg_name,
+                    required,
+                    version
+                );
+                report.fail(
+                    pkg_name,
+                    format!("v{} requested, latest is v{}", required, version),
+                );
+                continue;
+            }
+        }
+
         if installed.is_installed(pkg_name) {
             // Package already installed
             let inst_pkg = installed.get_package(pkg_name).unwrap();
-            if inst_pkg.version == version {
-                println!(
+            if reinstall {
+                outln!(
+                    "  {} {} v{} {}",
+                    "•".yellow(),
+                    pkg_name,
+                    version,
+                    "(reinstall)".yellow()
+                );
+                to_update.push(resolved);
+            } else if inst_pkg.version == version {
+                outln!(
                     "  {} {} v{} {}",
                     "•".cyan(),
                     pkg_name,
                     version,
                     "(already installed, same version)".dimmed()
                 );
+                report
+                    .outcomes
+                    .push(PackageOutcome::skipped(pkg_name, &version));
+
+                // Asking for a dependency by name makes it an explicit install
+                if !upgrade && !dependencies.contains(pkg_name) && installed.mark_manual(pkg_name) {
+                    config.save_installed(installed)?;
+                    outln!("    {} marked as explicitly installed", pkg_name);
+                }
+
+                // --pre on an up-to-date package only switches its channel
+                if let Some(pkg) = installed.packages.get_mut(pkg_name.as_str()) {
+                    if pkg.channel != pkg_channel {
+                        pkg.channel = pkg_channel;
+                        config.save_installed(installed)?;
+                        outln!(
+                            "    {} now follows the {} channel",
+                            pkg_name,
+                            pkg_channel.as_str()
+                        );
+                    }
+                }
+            } else if is_downgrade(&inst_pkg.version, &version) && !upgrade {
+                // Going back needs to be asked for, unless the installed release was yanked
+                let yanked = resolved.package.is_yanked(&inst_pkg.version);
+                if allow_downgrade || yanked {
+                    outln!(
+                        "  {} {} v{} {} → {}{}",
+                        "•".yellow(),
+                        pkg_name,
+                        inst_pkg.version.dimmed(),
+                        "downgrade to".red(),
+                        version.yellow(),
+                        if yanked {
+                            " (installed version is yanked)"
+                        } else {
+                            ""
+                        }
+                    );
+                    to_update.push(resolved);
+                } else {
+                    outln!(
+                        "  {} {} v{} {} → {} {}",
+                        "✗".red(),
+                        pkg_name,
+                        inst_pkg.version,
+                        "downgrade to".red(),
+                        version,
+                        "(use --allow-downgrade)".dimmed()
+                    );
+                    report.fail(
+                        pkg_name,
+                        format!(
+                            "v{} is older than the installed v{}; use --allow-downgrade to install it",
+                            version, inst_pkg.version
+                        ),
+                    );
+                }
             } else {
-                println!(
+                outln!(
                     "  {} {} v{} {} → {}",
                     "•".yellow(),
                     pkg_name,
@@ -409,27 +2110,47 @@ fn install_packages(
             }
         } else {
             // New installation
-            println!(
+            let note = if dependencies.contains(pkg_name) {
+                "(new, dependency)"
+            } else {
+                "(new)"
+            };
+            outln!(
                 "  {} {} v{} {}",
                 "•".green(),
                 pkg_name,
                 version,
-                "(new)".green()
+                note.green()
             );
             to_install.push(resolved);
         }
     }
 
+    Ok(to_install.into_iter().chain(to_update).collect())
+}
+
+/// List the bucket scripts about to be installed
+fn select_scripts(
+    installed: &InstalledManifest,
+    scripts_to_install: Vec<BucketScript>,
+    options: &AddOptions,
+    report: &mut InstallReport,
+) -> Vec<BucketScript> {
+    let if_not_installed = options.if_not_installed;
+
     // Show scripts to install
-    let mut scripts_to_process: Vec<(String, String, ScriptType, String)> = Vec::new();
+    let mut scripts_to_process: Vec<BucketScript> = Vec::new();
 
     if !scripts_to_install.is_empty() {
-        println!();
-        println!("{}", "Scripts to install:".bold());
+        outln!();
+        outln!("{}", "Scripts to install:".bold());
 
         for (name, url, script_type, origin) in scripts_to_install {
+            if if_not_installed && skip_installed(installed, report, &name) {
+                continue;
+            }
             if installed.is_installed(&name) {
-                println!(
+                outln!(
                     "  {} {} ({}) {}",
                     "•".yellow(),
                     name,
@@ -437,7 +2158,7 @@ fn install_packages(
                     "(already installed, will update)".dimmed()
                 );
             } else {
-                println!(
+                outln!(
                     "  {} {} ({}) {}",
                     "•".green(),
                     name,
@@ -449,58 +2170,120 @@ fn install_packages(
         }
     }
 
-    // Check if there's anything to do
-    if to_install.is_empty() && to_update.is_empty() && scripts_to_process.is_empty() {
-        println!();
-        println!(
-            "{}",
-            "All packages and scripts are already up to date".green()
-        );
-        return Ok(());
-    }
-
-    // Confirm installation
-    if !yes {
-        print!("\nProceed with installation? [Y/n] ");
-        use std::io::{self, Write};
-        io::stdout().flush()?;
-
-        let mut response = String::new();
-        io::stdin().read_line(&mut response)?;
-        let response = response.trim().to_lowercase();
-
-        if !response.is_empty() && response != "y" && response != "yes" {
-            println!("Installation cancelled");
-            return Ok(());
-        }
-    }
-
-    println!();
+    scripts_to_process
+}
 
-    // Install/update packages
-    let mut success_count = 0;
+/// Look up the release each package installs from, returning the packages
+/// ready to install and how many failed
+fn prepare_packages(
+    config: &Config,
+    installed: &InstalledManifest,
+    github: Option<&GitHubProvider>,
+    packages: Vec<ResolvedPackage>,
+    request: &InstallRequest,
+    ctx: &InstallContext,
+    report: &mut InstallReport,
+) -> Result<(Vec<PreparedPackage>, usize)> {
+    let options = ctx.options;
+    let asset = options.asset.as_deref();
+    let force_platform = options.force_platform.as_deref();
+    let arch = options.arch;
+    let prefer_variant = options.prefer_variant;
+    let default_variant = ctx.default_variant;
+    let dependencies = &request.dependencies;
     let mut fail_count = 0;
 
-    // Combine new installs and updates
-    let all_packages: Vec<_> = to_install.into_iter().chain(to_update).collect();
-
-    // Collect packages to update in cache (packages fetched from GitHub API)
-    let mut packages_to_cache: Vec<(crate::core::Package, PackageSource)> = Vec::new();
-
-    for resolved in all_packages {
+    // Look up every package's release first, so the preflight can check the
+    // exact assets that are about to be downloaded
+    let mut prepared: Vec<PreparedPackage> = Vec::new();
+    for resolved in packages {
         let pkg_name = &resolved.package.name;
         let repo_url = &resolved.package.repo;
+        let (pkg_channel, pin) = request.lookup(installed, pkg_name, options);
+        let yanked = &resolved.package.yanked;
+
+        // Packages with manifest asset patterns are resolved against the release at install
+        // time, so the bucket manifest is authoritative for them (as is an explicit --asset
+        // or a forced platform entry)
+        let uses_asset_patterns = ((asset.is_some() || force_platform.is_some())
+            && !dependencies.contains(pkg_name))
+            || resolved
+                .package
+                .platforms
+                .values()
+                .any(|b| b.asset_pattern.is_some());
+
+        // Reinstalls keep the variant chosen at first install unless a flag overrides it
+        let previous = installed.get_package(pkg_name);
+        let (variant, recorded_variant) =
+            package_variant(prefer_variant, previous, default_variant);
+        let platform = package_platform(installed, pkg_name, arch).prefer_compiler(variant);
+
+        // An earlier --interactive pick is downloaded again; --asset replaces it
+        let asset_pattern = match asset {
+            Some(_) => None,
+            None => previous.and_then(|p| p.asset_pattern.clone()),
+        };
 
         // Try to fetch latest package info from GitHub API (includes latest download links)
         // If API rate limit is hit, fallback to cached package info
-        let (pkg_to_install, version, using_fallback) = if let Some(ref gh) = github {
-            match gh.fetch_package(repo_url) {
-                Ok(latest_pkg) => {
-                    // Successfully fetched from GitHub API - use latest download links
-                    let version = gh
-                        .fetch_latest_version(repo_url)
-                        .unwrap_or_else(|_| "unknown".to_string());
-                    (latest_pkg, version, false)
+        let (pkg_to_install, version, using_fallback, binary) = if uses_asset_patterns {
+            // The version and the asset come from the same release lookup
+            match resolve_binary(
+                github,
+                &resolved.package,
+                platform,
+                asset_pattern.as_deref(),
+                pkg_channel,
+                pin.as_ref(),
+            ) {
+                Ok((Some(version), binary)) => {
+                    (resolved.package.clone(), version, false, Some(binary))
+                }
+                // Concrete links, or no entry for this platform (reported by the install)
+                Ok((None, _)) => {
+                    let version = github
+                        .as_ref()
+                        .and_then(|gh| {
+                            gh.fetch_matching_version(repo_url, pkg_channel, yanked, pin.as_ref())
+                                .ok()
+                        })
+                        .unwrap_or_else(|| "unknown".to_string());
+                    (resolved.package.clone(), version, false, None)
+                }
+                Err(e)
+                    if WengetError::find(&e)
+                        .is_some_and(|e| matches!(e, WengetError::PlatformUnsupported { .. })) =>
+                {
+                    (resolved.package.clone(), "unknown".to_string(), false, None)
+                }
+                Err(e) => {
+                    outln!("  {} {}: {:#}", "✗".red(), pkg_name, e);
+                    report.fail_error(pkg_name, &e);
+                    fail_count += 1;
+                    outln!();
+                    continue;
+                }
+            }
+        } else if let Some(gh) = github {
+            // The version and the links come from the same release lookup
+            match gh.fetch_matching_platforms(repo_url, pkg_channel, yanked, pin.as_ref()) {
+                Ok((version, platforms)) => {
+                    // Successfully fetched from GitHub API - use latest download links,
+                    // everything else comes from the manifest
+                    let latest_pkg = crate::core::Package {
+                        platforms,
+                        ..resolved.package.clone()
+                    };
+                    (latest_pkg, version, false, None)
+                }
+                Err(e) if pin.is_some() => {
+                    // The bucket's links are for the latest release, which the pin may exclude
+                    outln!("  {} {}: {:#}", "✗".red(), pkg_name, e);
+                    report.fail_error(pkg_name, &e);
+                    fail_count += 1;
+                    outln!();
+                    continue;
                 }
                 Err(e) => {
                     // Failed to fetch from GitHub API (likely rate limit) - use cached package info
@@ -509,57 +2292,233 @@ fn install_packages(
                         pkg_name,
                         e
                     );
-                    println!(
-                        "  {} Using cached download links (GitHub API unavailable)",
-                        "⚠".yellow()
+                    outln!(
+                        "  {} {}: using cached download links (GitHub API unavailable)",
+                        "⚠".yellow(),
+                        pkg_name
                     );
 
                     let version = gh
-                        .fetch_latest_version(repo_url)
+                        .fetch_matching_version(repo_url, pkg_channel, yanked, pin.as_ref())
                         .unwrap_or_else(|_| "unknown".to_string());
-                    (resolved.package.clone(), version, true)
+                    (resolved.package.clone(), version, true, None)
                 }
             }
         } else {
             // No GitHub provider available, use cached package info
-            (resolved.package.clone(), "unknown".to_string(), true)
+            (resolved.package.clone(), "unknown".to_string(), true, None)
         };
 
-        println!("{} {} v{}...", "Installing".cyan(), pkg_name, version);
+        prepared.push(PreparedPackage {
+            resolved,
+            package: pkg_to_install,
+            version,
+            using_fallback,
+            uses_asset_patterns,
+            channel: pkg_channel,
+            pin,
+            recorded_variant,
+            platform,
+            asset_pattern,
+            binary,
+        });
+    }
+
+    if !options.no_preflight && !options.interactive && !prepared.is_empty() {
+        preflight_assets(config, github, &mut prepared)?;
+    }
+
+    Ok((prepared, fail_count))
+}
+
+/// Download and install the prepared packages, returning how many were
+/// installed and how many failed
+fn install_prepared(
+    config: &Config,
+    installed: &mut InstalledManifest,
+    github: Option<&GitHubProvider>,
+    prepared: Vec<PreparedPackage>,
+    request: &InstallRequest,
+    ctx: &InstallContext,
+    report: &mut InstallReport,
+) -> Result<(usize, usize)> {
+    let options = ctx.options;
+    let global_bin = options.global_bin.as_deref();
+    let default_bin_dir = ctx.default_bin_dir.as_path();
+    let keep_versions = options.keep_versions;
+    let custom_name = options.script_name.as_deref();
+    let exe_name = options.exe_name.as_deref();
+    let interactive = options.interactive;
+    let upgrade = options.upgrade;
+    let dependencies = &request.dependencies;
+    let mut success_count = 0;
+    let mut fail_count = 0;
+
+    // Collect packages to update in cache (packages fetched from GitHub API)
+    let mut packages_to_cache: Vec<(crate::core::Package, PackageSource)> = Vec::new();
+
+    for prepared_pkg in prepared {
+        let PreparedPackage {
+            resolved,
+            package: pkg_to_install,
+            version,
+            using_fallback,
+            uses_asset_patterns,
+            channel: pkg_channel,
+            pin: _,
+            recorded_variant,
+            platform: pkg_platform,
+            asset_pattern: previous_pattern,
+            binary: resolved_binary,
+        } = prepared_pkg;
+        let pkg_name = &resolved.package.name;
+        let repo_url = &resolved.package.repo;
+
+        outln!("{} {} v{}...", "Installing".cyan(), pkg_name, version);
         if using_fallback {
-            println!(
+            outln!(
                 "  {} Falling back to bucket source download links",
                 "ℹ".cyan()
             );
         }
 
+        // Reinstalls keep their launcher where it was unless --global-bin is given
+        let previous = installed.get_package(pkg_name).cloned();
+        let bin_dir = global_bin
+            .map(Path::to_path_buf)
+            .or_else(|| {
+                previous
+                    .as_ref()
+                    .and_then(|p| p.shim_path.as_deref())
+                    .and_then(|p| Path::new(p).parent())
+                    .map(Path::to_path_buf)
+            })
+            .unwrap_or_else(|| default_bin_dir.to_path_buf());
+
+        // Packages installed with --no-shim stay launcher-less on reinstall
+        let no_shim = options.no_shim || previous.as_ref().is_some_and(|p| p.no_shim);
+
+        // Version retention is remembered per package once chosen
+        let pkg_keep_versions = keep_versions.or(previous.as_ref().and_then(|p| p.keep_versions));
+
+        let pkg_platform_ids = pkg_platform.possible_identifiers();
+
+        // An asset picked with --interactive is remembered as a pattern, so
+        // upgrades download the same kind of file; --asset replaces it
+        let picked = if interactive && !dependencies.contains(pkg_name) {
+            match pick_asset(
+                github,
+                repo_url,
+                pkg_platform,
+                pkg_channel,
+                &resolved.package.yanked,
+            ) {
+                Ok(picked) => picked,
+                Err(e) => {
+                    outln!("  {} {}", "✗".red(), e);
+                    report.fail_error(pkg_name, &e);
+                    fail_count += 1;
+                    outln!();
+                    continue;
+                }
+            }
+        } else {
+            None
+        };
+        let asset_pattern = match picked {
+            Some((_, ref pattern)) => Some(pattern.clone()),
+            None => previous_pattern,
+        };
+
+        // Assets already resolved against the release are downloaded as is
+        let mut pkg_for_install = pkg_to_install.clone();
+        let binary = match (picked, resolved_binary) {
+            (Some((binary, _)), _) | (None, Some(binary)) => Some(binary),
+            (None, None) => asset_pattern.as_ref().map(|pattern| PlatformBinary {
+                url: String::new(),
+                size: 0,
+                checksum: None,
+                asset_pattern: Some(pattern.clone()),
+                bin: Vec::new(),
+            }),
+        };
+        if let Some(binary) = binary {
+            pkg_for_install.platforms = HashMap::from([(pkg_platform_ids[0].clone(), binary)]);
+        }
+
+        // --exe-name wins over the manifest; URL installs keep theirs on upgrade
+        pkg_for_install.exe_name = exe_name
+            .filter(|_| !dependencies.contains(pkg_name))
+            .map(str::to_string)
+            .or(pkg_for_install.exe_name)
+            .or_else(|| previous.as_ref().and_then(|p| p.exe_name.clone()));
+
+        let install = PackageInstall {
+            custom_name,
+            no_shim,
+            shim_mode: package_shim_mode(
+                options.shim_mode,
+                previous.as_ref(),
+                ctx.default_shim_mode,
+            ),
+            previous: previous.as_ref(),
+            channel: pkg_channel,
+            retries: ctx.retries,
+            keep_versions: pkg_keep_versions.unwrap_or(ctx.default_keep_versions),
+            temp_dir: ctx.temp_dir.as_deref(),
+            // The digest is for the requested package, not its dependencies
+            checksum: options
+                .checksum
+                .as_ref()
+                .filter(|_| !dependencies.contains(pkg_name)),
+            deadline: options.timeout.map(Deadline::after),
+            ..PackageInstall::new(&version, &resolved.source, &bin_dir)
+        };
+        let mut cleanup = CleanupGuard::new();
         match install_package(
             config,
-            paths,
-            &pkg_to_install,
-            &platform_ids,
-            &version,
-            &resolved.source,
-            custom_name,
+            &pkg_for_install,
+            &pkg_platform_ids,
+            &install,
+            &mut cleanup,
         ) {
-            Ok(inst_pkg) => {
+            Ok(mut inst_pkg) => {
+                inst_pkg.prefer_variant = recorded_variant;
+                inst_pkg.channel = pkg_channel;
+                inst_pkg.asset_pattern = asset_pattern;
+                inst_pkg.keep_versions = pkg_keep_versions;
+                inst_pkg.requires = resolved.package.requires.clone();
+                // Adding a dependency by hand makes it an explicit install
+                inst_pkg.auto_installed = dependencies.contains(pkg_name)
+                    || (upgrade && previous.as_ref().is_some_and(|p| p.auto_installed));
+                record_install(config, previous.as_ref(), pkg_name, &inst_pkg);
+                report.outcomes.push(PackageOutcome::installed(
+                    pkg_name,
+                    previous.as_ref().map(|p| p.version.as_str()),
+                    &inst_pkg.version,
+                ));
+                let message = inst_pkg.post_install_message.clone();
                 installed.upsert_package(pkg_name.clone(), inst_pkg);
                 config.save_installed(installed)?;
+                cleanup.commit();
 
                 // Collect package for cache update if fetched from GitHub API
-                if !using_fallback {
+                // (prerelease links must not replace the stable ones in the cache)
+                if !using_fallback && !uses_asset_patterns && pkg_channel.is_stable() {
                     packages_to_cache.push((pkg_to_install.clone(), resolved.source.clone()));
                 }
 
-                println!("  {} Installed successfully", "✓".green());
+                outln!("  {} Installed successfully", "✓".green());
+                print_post_install_message(pkg_name, message.as_deref());
                 success_count += 1;
             }
             Err(e) => {
-                println!("  {} {}", "✗".red(), e);
+                outln!("  {} {}", "✗".red(), e);
+                report.fail_error(pkg_name, &e);
                 fail_count += 1;
             }
         }
-        println!();
+        outln!();
     }
 
     // Update cache with latest package info from GitHub API
@@ -575,19 +2534,32 @@ fn install_packages(
         }
     }
 
-    // Install scripts from bucket cache
+    Ok((success_count, fail_count))
+}
+
+/// Install the scripts found in the bucket cache, returning how many were
+/// installed and how many failed
+fn install_bucket_scripts(
+    config: &Config,
+    installed: &mut InstalledManifest,
+    scripts_to_process: Vec<BucketScript>,
+    options: &AddOptions,
+    report: &mut InstallReport,
+) -> (usize, usize) {
+    let custom_name = options.script_name.as_deref();
     let mut script_success_count = 0;
     let mut script_fail_count = 0;
 
     for (name, url, script_type, origin) in scripts_to_process {
-        println!(
+        outln!(
             "{}",
             format!("Installing {} ({})...", name, script_type.display_name()).bold()
         );
 
+        let previous_version = installed.get_package(&name).map(|p| p.version.clone());
         match install_script_from_bucket(
             config,
-            paths,
+            config.paths(),
             installed,
             &name,
             &url,
@@ -595,60 +2567,617 @@ fn install_packages(
             &origin,
             custom_name,
         ) {
-            Ok(_) => {
-                println!("  {} Installed successfully", "✓".green());
+            Ok(()) => {
+                let version = installed.get_package(&name).map(|p| p.version.as_str());
+                report.outcomes.push(PackageOutcome::installed(
+                    &name,
+                    previous_version.as_deref(),
+                    version.unwrap_or("script"),
+                ));
+                outln!("  {} Installed successfully", "✓".green());
                 script_success_count += 1;
             }
             Err(e) => {
-                println!("  {} {}", "✗".red(), e);
+                outln!("  {} {}", "✗".red(), e);
+                report.fail_error(&name, &e);
                 script_fail_count += 1;
             }
         }
-        println!();
+        outln!();
     }
 
-    // Summary
-    println!("{}", "Summary:".bold());
-    if success_count > 0 {
-        println!("  {} {} package(s) installed", "✓".green(), success_count);
+    (script_success_count, script_fail_count)
+}
+/// Custom request headers of the bucket a package comes from
+pub(crate) fn source_headers(config: &Config, source: &PackageSource) -> Result<HostHeaders> {
+    let PackageSource::Bucket { name } = source else {
+        return Ok(HostHeaders::default());
+    };
+
+    match config.get_or_create_buckets()?.find_bucket(name) {
+        Some(bucket) => bucket.host_headers(),
+        None => Ok(HostHeaders::default()),
     }
-    if fail_count > 0 {
-        println!("  {} {} package(s) failed", "✗".red(), fail_count);
+}
+
+/// Report an already installed package as skipped (--if-not-installed)
+///
+/// Returns false if `name` is not installed.
+fn skip_installed(installed: &InstalledManifest, report: &mut InstallReport, name: &str) -> bool {
+    let Some(pkg) = installed.get_package(name) else {
+        return false;
+    };
+
+    outln!(
+        "  {} {} {}",
+        "•".cyan(),
+        name,
+        format!("already installed (v{})", pkg.version).dimmed()
+    );
+    report
+        .outcomes
+        .push(PackageOutcome::skipped(name, &pkg.version));
+    true
+}
+
+/// Launcher mode for a package: an explicit `--shim-mode` wins, then a
+/// non-default mode it was installed with, then the configured default
+fn package_shim_mode(
+    explicit: Option<ShimMode>,
+    previous: Option<&InstalledPackage>,
+    default: ShimMode,
+) -> ShimMode {
+    explicit
+        .or(previous.map(|p| p.shim_mode).filter(|m| !m.is_symlink()))
+        .unwrap_or(default)
+}
+
+/// Channel to install a package from: an explicit `--pre` wins, otherwise
+/// the channel it already follows (stable for new installs)
+fn package_channel(
+    installed: &InstalledManifest,
+    name: &str,
+    requested: Option<Channel>,
+) -> Channel {
+    requested
+        .or_else(|| installed.get_package(name).map(|p| p.channel))
+        .unwrap_or_default()
+}
+
+/// Version constraint an installed package is pinned to (`wenget pin`)
+///
+/// A reinstall is locked to the exact version installed now, as long as it
+/// is a real version number.
+fn package_pin(
+    installed: &InstalledManifest,
+    name: &str,
+    reinstall: bool,
+) -> Option<VersionConstraint> {
+    let pkg = installed.get_package(name)?;
+    let current = VersionConstraint::parse(&pkg.version).filter(|c| c.exact().is_some());
+    match current {
+        Some(current) if reinstall => Some(current),
+        _ => pkg.version_constraint(),
     }
-    if script_success_count > 0 {
-        println!(
-            "  {} {} script(s) installed",
-            "✓".green(),
-            script_success_count
+}
+
+/// The libc variant to select assets with, and the one to record with the package
+///
+/// Only `--prefer-*` or an earlier record is saved; the `prefer_variant`
+/// setting only fills in at install time, so changing it later still
+/// applies to packages installed without a flag.
+fn package_variant(
+    flag: Option<Compiler>,
+    previous: Option<&InstalledPackage>,
+    setting: Option<Compiler>,
+) -> (Option<Compiler>, Option<Compiler>) {
+    let recorded = flag.or(previous.and_then(|p| p.prefer_variant));
+    (recorded.or(setting), recorded)
+}
+
+/// A package whose release has been looked up, ready to install
+struct PreparedPackage {
+    resolved: ResolvedPackage,
+    /// Package with the download links to use
+    package: crate::core::Package,
+    version: String,
+    /// Whether the links come from the bucket rather than the latest release
+    using_fallback: bool,
+    /// Whether the bucket manifest is authoritative: asset patterns, --asset
+    /// or a forced platform entry
+    uses_asset_patterns: bool,
+    channel: Channel,
+    /// Version constraint the release was looked up with
+    pin: Option<VersionConstraint>,
+    /// Variant to save with the package (see [`package_variant`])
+    recorded_variant: Option<Compiler>,
+    platform: Platform,
+    /// Asset pattern remembered from an earlier `--interactive` pick
+    asset_pattern: Option<String>,
+    /// Asset already resolved against the release (or by the preflight)
+    binary: Option<PlatformBinary>,
+}
+
+/// Check that every asset about to be downloaded is reachable, before
+/// anything is installed
+///
+/// Assets given by a pattern are resolved against the release here, so the
+/// install downloads exactly the asset that was checked.
+fn preflight_assets(
+    config: &Config,
+    github: Option<&GitHubProvider>,
+    prepared: &mut [PreparedPackage],
+) -> Result<()> {
+    let mut unreachable: Vec<String> = Vec::new();
+    let mut targets: Vec<(usize, String, HostHeaders)> = Vec::new();
+    for (i, pkg) in prepared.iter_mut().enumerate() {
+        let name = pkg.resolved.package.name.clone();
+        match preflight_binary(github, pkg) {
+            Ok(binary) => {
+                let headers = source_headers(config, &pkg.resolved.source)?;
+                targets.push((i, binary.url.clone(), headers));
+                pkg.binary = Some(binary);
+            }
+            // Left for the install to report, like any other failure
+            Err(e)
+                if WengetError::find(&e)
+                    .is_some_and(|e| matches!(e, WengetError::PlatformUnsupported { .. })) => {}
+            Err(e) => unreachable.push(format!("{}: {:#}", name, e)),
+        }
+    }
+
+    outln!("Checking {} asset(s)...", targets.len());
+    let probes: Vec<(String, HostHeaders)> = targets
+        .iter()
+        .map(|(_, url, headers)| (url.clone(), headers.clone()))
+        .collect();
+    let mut total: u64 = 0;
+    for ((i, _, _), probe) in targets.iter().zip(downloader::probe_all(&probes)?) {
+        match probe {
+            Ok(size) => total += size.unwrap_or(0),
+            Err(e) => unreachable.push(format!("{}: {:#}", prepared[*i].resolved.package.name, e)),
+        }
+    }
+
+    if !unreachable.is_empty() {
+        for line in &unreachable {
+            outln!("  {} {}", "✗".red(), line);
+        }
+        outln!();
+        anyhow::bail!(
+            "Preflight failed: {} asset(s) unreachable; nothing was installed (use --no-preflight to skip this check)",
+            unreachable.len()
         );
     }
-    if script_fail_count > 0 {
-        println!("  {} {} script(s) failed", "✗".red(), script_fail_count);
+
+    outln!(
+        "  {} All assets reachable (total {:.2} MB)",
+        "✓".green(),
+        total as f64 / 1024.0 / 1024.0
+    );
+    outln!();
+    Ok(())
+}
+
+/// The binary a prepared package will download, with any asset pattern
+/// resolved to a concrete URL
+fn preflight_binary(
+    github: Option<&GitHubProvider>,
+    pkg: &PreparedPackage,
+) -> Result<PlatformBinary> {
+    if let Some(ref binary) = pkg.binary {
+        return Ok(binary.clone());
+    }
+
+    resolve_binary(
+        github,
+        &pkg.package,
+        pkg.platform,
+        pkg.asset_pattern.as_deref(),
+        pkg.channel,
+        pkg.pin.as_ref(),
+    )
+    .map(|(_, binary)| binary)
+}
+
+/// The binary a package will download on `platform`, with any asset pattern
+/// (`asset_pattern` wins over the manifest's) resolved to a concrete URL
+///
+/// Returns the version of the release the pattern was resolved against, or
+/// `None` for concrete manifest links.
+fn resolve_binary(
+    github: Option<&GitHubProvider>,
+    package: &crate::core::Package,
+    platform: Platform,
+    asset_pattern: Option<&str>,
+    channel: Channel,
+    pin: Option<&VersionConstraint>,
+) -> Result<(Option<String>, PlatformBinary)> {
+    let platform_ids = platform.possible_identifiers();
+    let binary = platform_ids
+        .iter()
+        .find_map(|id| package.platforms.get(id))
+        .cloned();
+
+    let pattern = match (asset_pattern, &binary) {
+        (Some(pattern), _) => pattern.to_string(),
+        (None, Some(binary)) => match binary.asset_pattern {
+            Some(ref pattern) => pattern.clone(),
+            None => return Ok((None, binary.clone())),
+        },
+        (None, None) => {
+            return Err(WengetError::PlatformUnsupported {
+                name: package.name.clone(),
+                platform: platform_ids.first().cloned().unwrap_or_default(),
+            }
+            .into())
+        }
+    };
+
+    let github = github.context("GitHub provider unavailable")?;
+    let (version, mut resolved) =
+        github.resolve_asset_release(&package.repo, &pattern, channel, &package.yanked, pin)?;
+    resolved.asset_pattern = None;
+    resolved.bin = binary.map(|b| b.bin).unwrap_or_default();
+    Ok((Some(version), resolved))
+}
+
+/// Platform to install a package for: an explicit `--arch` wins, otherwise
+/// the architecture it was installed for (the current one for new installs)
+fn package_platform(installed: &InstalledManifest, name: &str, arch: Option<Arch>) -> Platform {
+    let arch = arch
+        .or_else(|| {
+            installed
+                .get_package(name)
+                .and_then(|p| p.platform.split('-').nth(1))
+                .and_then(Arch::parse)
+        })
+        .unwrap_or_else(Arch::current);
+
+    Platform::new(Os::current(), arch).with_emulation(emulation_allowed())
+}
+
+/// Make the manifest entry `id` the package's only binary, registered for
+/// `platform` so the regular install path picks it up (--force-platform-match)
+fn force_platform_entry(
+    package: &mut crate::core::Package,
+    id: &str,
+    platform: Platform,
+) -> Result<()> {
+    let Some(binary) = package.platforms.remove(id) else {
+        let mut available: Vec<&str> = package.platforms.keys().map(String::as_str).collect();
+        available.sort_unstable();
+        anyhow::bail!(
+            "{} has no platform entry '{}'. Available platforms: {}",
+            package.name,
+            id,
+            available.join(", ")
+        );
+    };
+
+    let target = platform.possible_identifiers().remove(0);
+    if target != id {
+        outln!(
+            "{} Installing {}'s '{}' asset on {} (--force-platform-match); it may not run here",
+            "⚠".yellow(),
+            package.name,
+            id,
+            target
+        );
+    }
+    package.platforms = HashMap::from([(target, binary)]);
+    Ok(())
+}
+
+/// Resolve a single package whose only binary is the named release asset
+///
+/// The asset is registered under `platform_id` so the regular install path
+/// picks it up without any platform matching.
+fn resolve_with_asset(
+    resolver: &PackageResolver,
+    input: &PackageInput,
+    asset_name: &str,
+    platform_id: &str,
+    channel: Channel,
+) -> Result<Vec<ResolvedPackage>> {
+    let github = GitHubProvider::new()?;
+
+    match input {
+        PackageInput::DirectUrl(url) => {
+            let package = github.fetch_package_with_asset(url, asset_name, platform_id, channel)?;
+            let source = PackageSource::DirectRepo { url: url.clone() };
+            Ok(vec![ResolvedPackage::new(package, source)])
+        }
+        PackageInput::CacheName(name) => {
+            let mut resolved = resolver.resolve(input)?;
+            if resolved.len() != 1 {
+                anyhow::bail!(
+                    "--asset needs a single package, but '{}' matched {}",
+                    name,
+                    resolved.len()
+                );
+            }
+
+            let binary =
+                github.resolve_named_asset(&resolved[0].package.repo, asset_name, channel)?;
+            resolved[0].package.platforms = HashMap::from([(platform_id.to_string(), binary)]);
+            Ok(resolved)
+        }
+    }
+}
+
+/// Let the user choose among the latest release assets for a platform
+///
+/// Returns the chosen binary and a pattern matching it in later releases, or
+/// None when there is nothing to choose (a single candidate, no terminal, or
+/// the release could not be fetched) so the usual heuristic applies.
+fn pick_asset(
+    github: Option<&GitHubProvider>,
+    repo_url: &str,
+    platform: Platform,
+    channel: Channel,
+    yanked: &[String],
+) -> Result<Option<(PlatformBinary, String)>> {
+    use std::io::{self, Write};
+
+    if !io::stdin().is_terminal() {
+        outln!(
+            "  {} Not a terminal, choosing the asset automatically",
+            "ℹ".cyan()
+        );
+        return Ok(None);
+    }
+
+    let Some(github) = github else {
+        return Ok(None);
+    };
+
+    let (tag, candidates) =
+        match github.fetch_platform_candidates(repo_url, platform, channel, yanked) {
+            Ok(found) => found,
+            Err(e) => {
+                outln!(
+                    "  {} Could not list release assets ({}), choosing automatically",
+                    "⚠".yellow(),
+                    e
+                );
+                return Ok(None);
+            }
+        };
+
+    if candidates.len() < 2 {
+        return Ok(None);
+    }
+
+    outln!("  Assets for {}:", platform);
+    for (i, candidate) in candidates.iter().enumerate() {
+        let size_mb = candidate.size as f64 / 1024.0 / 1024.0;
+        let note = if i == 0 { " (recommended)" } else { "" };
+        outln!(
+            "    {}. {} ({:.2} MB){}",
+            i + 1,
+            candidate.name,
+            size_mb,
+            note.dimmed()
+        );
+    }
+
+    print!("\n  Select asset [1-{}] (default 1): ", candidates.len());
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let selection = match input.trim() {
+        "" => 0,
+        choice => choice
+            .parse::<usize>()
+            .ok()
+            .filter(|n| *n > 0 && *n <= candidates.len())
+            .map(|n| n - 1)
+            .context("Invalid selection")?,
+    };
+
+    let chosen = &candidates[selection];
+    let pattern = AssetPattern::from_asset_name(&chosen.name, &tag).to_string();
+    outln!("  Using {} (remembered for upgrades)", chosen.name);
+
+    Ok(Some((
+        PlatformBinary {
+            url: chosen.url.clone(),
+            size: chosen.size,
+            checksum: None,
+            asset_pattern: None,
+            bin: Vec::new(),
+        },
+        pattern,
+    )))
+}
+
+/// Add the uninstalled `requires` of the packages to install, transitively
+///
+/// Returns the names of the packages that were added as dependencies.
+fn resolve_dependencies(
+    resolver: &PackageResolver,
+    installed: &crate::core::InstalledManifest,
+    packages: &mut Vec<ResolvedPackage>,
+    platform_ids: &[String],
+) -> HashSet<String> {
+    let mut dependencies = HashSet::new();
+    let mut index = 0;
+
+    while index < packages.len() {
+        let parent = packages[index].package.name.clone();
+        let requires = packages[index].package.requires.clone();
+        index += 1;
+
+        for dep in requires {
+            if installed.is_installed(&dep) || packages.iter().any(|p| p.package.name == dep) {
+                continue;
+            }
+
+            let resolved = resolver
+                .resolve(&PackageInput::CacheName(dep.clone()))
+                .ok()
+                .and_then(|r| r.into_iter().next());
+
+            match resolved {
+                Some(resolved)
+                    if platform_ids
+                        .iter()
+                        .any(|id| resolved.package.platforms.contains_key(id)) =>
+                {
+                    dependencies.insert(dep);
+                    packages.push(resolved);
+                }
+                Some(_) => outln!(
+                    "{} {} (required by {}) does not support current platform",
+                    "Warning:".yellow(),
+                    dep,
+                    parent
+                ),
+                None => outln!(
+                    "{} {} (required by {}) was not found in any bucket",
+                    "Warning:".yellow(),
+                    dep,
+                    parent
+                ),
+            }
+        }
     }
 
-    Ok(())
+    dependencies
 }
 
 /// Install a single package
+///
+/// Downloads, staging, the app and its backups all go under `config`'s
+/// paths; the launchers go to `bin_dir`.
+/// How one package is installed
+struct PackageInstall<'a> {
+    version: &'a str,
+    source: &'a PackageSource,
+    /// Launcher name replacing the package's own
+    custom_name: Option<&'a str>,
+    bin_dir: &'a Path,
+    no_shim: bool,
+    shim_mode: ShimMode,
+    /// The installed entry this install replaces
+    previous: Option<&'a InstalledPackage>,
+    channel: Channel,
+    retries: u32,
+    /// Older versions to keep next to the new one
+    keep_versions: usize,
+    temp_dir: Option<&'a Path>,
+    checksum: Option<&'a ExpectedDigest>,
+    deadline: Option<Deadline>,
+}
+
+impl<'a> PackageInstall<'a> {
+    /// A fresh stable install with default settings
+    fn new(version: &'a str, source: &'a PackageSource, bin_dir: &'a Path) -> Self {
+        Self {
+            version,
+            source,
+            custom_name: None,
+            bin_dir,
+            no_shim: false,
+            shim_mode: ShimMode::default(),
+            previous: None,
+            channel: Channel::Stable,
+            retries: 0,
+            keep_versions: 0,
+            temp_dir: None,
+            checksum: None,
+            deadline: None,
+        }
+    }
+}
+
+/// Download, extract and link one package
+///
+/// Everything written is tracked in `cleanup`, which the caller commits once
+/// the package is recorded in the installed manifest. Dropping it instead
+/// takes the install back, restoring the version it replaced.
 fn install_package(
-    _config: &Config,
-    paths: &WenPaths,
+    config: &Config,
     pkg: &crate::core::Package,
     platform_ids: &[String],
-    version: &str,
-    source: &PackageSource,
-    custom_name: Option<&str>,
+    install: &PackageInstall,
+    cleanup: &mut CleanupGuard,
 ) -> Result<InstalledPackage> {
+    let PackageInstall {
+        version,
+        source,
+        custom_name,
+        bin_dir,
+        no_shim,
+        shim_mode,
+        previous,
+        channel,
+        retries,
+        keep_versions,
+        temp_dir,
+        checksum,
+        deadline,
+    } = *install;
+    let paths = config.paths();
+
     // Find platform binary
     let (platform_id, binary) = platform_ids
         .iter()
         .find_map(|id| pkg.platforms.get(id).map(|b| (id, b)))
-        .context("No binary found for current platform")?;
+        .ok_or_else(|| WengetError::PlatformUnsupported {
+            name: pkg.name.clone(),
+            platform: platform_ids.first().cloned().unwrap_or_default(),
+        })?;
+
+    // Identifiers for another architecture only come from the emulation fallback
+    let arch_of = |id: &str| id.split('-').nth(1).map(str::to_string);
+    if let Some(native) = platform_ids.first().and_then(|id| arch_of(id)) {
+        if arch_of(platform_id).is_some_and(|arch| arch != native) {
+            outln!(
+                "  {} No {} build available, installing {} to run under emulation",
+                "ℹ".cyan(),
+                native,
+                platform_id
+            );
+        }
+    }
+
+    // The manifest's `bin` list names the executable outright; any further
+    // entries get launchers of their own
+    let declared_bins: Vec<BinEntry> = binary
+        .bin
+        .iter()
+        .map(|b| BinEntry {
+            path: b.path.replace('\\', "/"),
+            name: b.name.clone(),
+        })
+        .collect();
+    let declared_bin = declared_bins.first().cloned();
+
+    // Resolve asset pattern against the latest release if the manifest uses one
+    let resolved_binary;
+    let binary = if let Some(ref pattern) = binary.asset_pattern {
+        outln!("  Resolving asset pattern: {}", pattern);
+        let pin = previous.and_then(InstalledPackage::version_constraint);
+        resolved_binary = GitHubProvider::new()?.resolve_asset_pattern(
+            &pkg.repo,
+            pattern,
+            channel,
+            &pkg.yanked,
+            pin.as_ref(),
+        )?;
+        &resolved_binary
+    } else {
+        binary
+    };
 
     // Download binary
-    println!("  Downloading from {}...", binary.url);
+    outln!("  Downloading from {}...", binary.url);
 
-    let download_dir = paths.downloads_dir();
+    let download_dir = temp_dir.map_or_else(|| paths.downloads_dir(), |t| t.join("downloads"));
     fs::create_dir_all(&download_dir)?;
 
     // Determine file extension from URL
@@ -660,84 +3189,190 @@ fn install_package(
 
     let download_path = download_dir.join(filename);
 
-    downloader::download_file(&binary.url, &download_path)?;
+    // Remove partial files if the install fails or is interrupted
+    cleanup.track(downloader::part_path(&download_path));
+    cleanup.track(&download_path);
+
+    let headers = source_headers(config, source)?;
+    downloader::download_file_within(
+        &binary.url,
+        &download_path,
+        retries,
+        &headers,
+        deadline.as_ref(),
+    )?;
 
-    // Extract to app directory
+    // A digest given on the command line is the trust anchor for the download
+    if let Some(expected) = checksum {
+        checksum::verify_digest(&download_path, expected)?;
+        outln!(
+            "  {} {} checksum verified",
+            "✓".green(),
+            expected.algorithm.as_str()
+        );
+    }
+
+    // Extract into a staging directory; the existing install stays untouched
+    // until the new one is known to contain an executable
     let app_dir = paths.app_dir(&pkg.name);
+    let staging_dir = temp_dir
+        .map_or_else(|| paths.staging_dir(), |t| t.join("staging"))
+        .join(&pkg.name);
 
-    println!("  Extracting to {}...", app_dir.display());
+    outln!("  Extracting...");
 
-    // Remove existing installation
-    if app_dir.exists() {
-        fs::remove_dir_all(&app_dir)?;
+    if linux_package::is_linux_package(filename) {
+        outln!(
+            "  {} {} is a system package; extracting its files only (no install scripts, dependencies or system integration)",
+            "⚠".yellow(),
+            filename
+        );
+    }
+
+    if staging_dir.exists() {
+        fs::remove_dir_all(&staging_dir)?;
     }
+    cleanup.track(&staging_dir);
+
+    let progress = extraction_progress();
+    let extracted = extract_archive_until(&download_path, &staging_dir, deadline, &mut |p| {
+        update_extraction_progress(&progress, p)
+    });
+    progress.finish_and_clear();
+    let extracted_files = extracted?;
 
-    let extracted_files = extract_archive(&download_path, &app_dir)?;
+    let declared_bin = declared_bin.filter(|b| staging_dir.join(&b.path).is_file());
 
-    // Find executable candidates (pass app_dir for Unix permission checks)
-    let candidates = find_executable_candidates(&extracted_files, &pkg.name, Some(&app_dir));
+    // A named executable (manifest `exe_name` or --exe-name) needs no guessing
+    let named_exe = match (&declared_bin, &pkg.exe_name) {
+        (None, Some(exe_name)) => {
+            let found = find_named_executable(&extracted_files, exe_name);
+            if found.is_none() {
+                outln!(
+                    "  {} No file named '{}' in the archive, detecting the executable instead",
+                    "⚠".yellow(),
+                    exe_name
+                );
+            }
+            found
+        }
+        _ => None,
+    };
 
-    if candidates.is_empty() {
+    // Find executable candidates (pass staging_dir for Unix permission checks)
+    let candidates = match (&declared_bin, &named_exe) {
+        (None, None) => find_executable_candidates(&extracted_files, &pkg.name, Some(&staging_dir)),
+        _ => Vec::new(),
+    };
+
+    if declared_bin.is_none() && named_exe.is_none() && candidates.is_empty() {
         anyhow::bail!(
-            "Failed to find executable in archive. Extracted files:\n{}",
+            "Failed to find executable in archive (name it with --exe-name). Extracted files:\n{}",
             extracted_files.join("\n")
         );
     }
 
     // Select the best executable
-    let exe_relative =
-        if candidates.len() == 1 || (candidates.len() > 1 && candidates[0].score >= 80) {
-            // Auto-select if only one candidate or if the top candidate has high confidence
-            let selected = &candidates[0];
-            println!(
-                "  Found executable: {} ({})",
-                selected.path, selected.reason
+    let exe_relative = if let Some(ref bin) = declared_bin {
+        outln!("  Using executable from manifest: {}", bin.path);
+        bin.path.clone()
+    } else if let Some(exe) = named_exe {
+        outln!("  Found executable: {} (exe name)", exe);
+        exe
+    } else if candidates.len() == 1 || (candidates.len() > 1 && candidates[0].score >= 80) {
+        // Auto-select if only one candidate or if the top candidate has high confidence
+        let selected = &candidates[0];
+        outln!(
+            "  Found executable: {} ({})",
+            selected.path,
+            selected.reason
+        );
+        selected.path.clone()
+    } else {
+        // Multiple candidates with similar scores - ask user to choose
+        outln!("  Found multiple possible executables:");
+        for (i, candidate) in candidates.iter().enumerate() {
+            outln!(
+                "    {}. {} (score: {}, {})",
+                i + 1,
+                candidate.path,
+                candidate.score,
+                candidate.reason
             );
-            selected.path.clone()
-        } else {
-            // Multiple candidates with similar scores - ask user to choose
-            println!("  Found multiple possible executables:");
-            for (i, candidate) in candidates.iter().enumerate() {
-                println!(
-                    "    {}. {} (score: {}, {})",
-                    i + 1,
-                    candidate.path,
-                    candidate.score,
-                    candidate.reason
-                );
-            }
+        }
 
-            use std::io::{self, Write};
-            print!("\n  Select executable [1-{}]: ", candidates.len());
-            io::stdout().flush()?;
+        use std::io::{self, Write};
+        print!("\n  Select executable [1-{}]: ", candidates.len());
+        io::stdout().flush()?;
 
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
 
-            let selection = input
-                .trim()
-                .parse::<usize>()
-                .ok()
-                .and_then(|n| {
-                    if n > 0 && n <= candidates.len() {
-                        Some(n - 1)
-                    } else {
-                        None
-                    }
-                })
-                .context("Invalid selection")?;
+        let selection = input
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| {
+                if n > 0 && n <= candidates.len() {
+                    Some(n - 1)
+                } else {
+                    None
+                }
+            })
+            .context("Invalid selection")?;
 
-            candidates[selection].path.clone()
-        };
+        candidates[selection].path.clone()
+    };
 
-    let exe_path = app_dir.join(&exe_relative);
+    if !staging_dir.join(&exe_relative).exists() {
+        anyhow::bail!("Executable not found: {}", exe_relative);
+    }
+
+    // Recorded for `wenget verify`
+    let file_digests = record_digests(&staging_dir, &extracted_files)?;
+
+    // Last point where a timed-out install can still be dropped cleanly;
+    // swapping it in and linking it are quick and run to completion
+    if let Some(deadline) = deadline {
+        deadline.check()?;
+    }
+
+    // Swap the new install into place, replacing the previous one (which is
+    // kept for a rollback when --keep-versions asks for it, and put back if
+    // the install fails from here on)
+    let backup_dir = paths.staging_dir().join(format!("{}.old", pkg.name));
+    let versions_dir = paths.backups_dir(&pkg.name);
+    let retain = previous
+        .filter(|p| keep_versions > 0 && p.version != version)
+        .map(|p| versions_dir.join(&p.version));
+    let retained = swap_into_place(
+        &staging_dir,
+        &app_dir,
+        &backup_dir,
+        retain.as_deref(),
+        cleanup,
+    )?;
 
-    if !exe_path.exists() {
-        anyhow::bail!("Executable not found: {}", exe_path.display());
+    let mut kept_versions = previous
+        .map(|p| p.kept_versions.clone())
+        .unwrap_or_default();
+    if let Some(previous) = previous.filter(|_| retained) {
+        kept_versions.retain(|v| *v != previous.version);
+        kept_versions.push(previous.version.clone());
     }
+    let kept_versions = prune_versions(&versions_dir, &kept_versions, keep_versions, cleanup);
+
+    let exe_path = app_dir.join(&exe_relative);
+
+    // A launcher renamed with `wenget rename` keeps its alias on upgrade
+    let alias = custom_name
+        .is_none()
+        .then(|| previous.and_then(|p| p.alias.clone()))
+        .flatten();
 
     // Extract the actual command name from the executable path
-    let command_name = if let Some(custom) = custom_name {
+    let manifest_name = declared_bin.as_ref().and_then(|b| b.name.as_deref());
+    let command_name = if let Some(custom) = custom_name.or(alias.as_deref()).or(manifest_name) {
         // Use custom name if provided
         custom.to_string()
     } else {
@@ -751,41 +3386,283 @@ fn install_package(
         normalize_command_name(raw_name)
     };
 
-    println!("  Command will be available as: {}", command_name);
+    outln!("  Command will be available as: {}", command_name);
+
+    // Remove the launcher from a previous install if it lived elsewhere
+    let bin_path = (!no_shim).then(|| WenPaths::shim_path_in(bin_dir, &command_name));
+    if let Some(old_path) = previous.and_then(|p| p.shim_path.as_deref()) {
+        let old_path = Path::new(old_path);
+        if bin_path.as_deref() != Some(old_path) && (old_path.exists() || old_path.is_symlink()) {
+            log::debug!("Removing previous launcher: {}", old_path.display());
+            if let Err(e) = set_launcher_aside(cleanup, old_path) {
+                log::debug!("{:#}", e);
+            }
+        }
+    }
 
     // Create symlink/shim using the actual executable name
-    let bin_path = paths.bin_shim_path(&command_name);
+    if let Some(ref bin_path) = bin_path {
+        warn_path_conflicts(&command_name, bin_dir, bin_path);
+        outln!("  Creating launcher at {}...", bin_path.display());
+        set_launcher_aside(cleanup, bin_path)?;
+        create_launcher(&exe_path, bin_path, &command_name, shim_mode)?;
+    } else {
+        outln!(
+            "  Skipping launcher (--no-shim), run it from: {}",
+            exe_path.display()
+        );
+    }
+
+    // Launchers for the manifest's other `bin` entries
+    let mut extra_shims = Vec::new();
+    for bin in declared_bins.iter().skip(1).filter(|_| !no_shim) {
+        let exe = app_dir.join(&bin.path);
+        if !exe.is_file() {
+            outln!(
+                "  {} {} is not in the archive, skipping its launcher",
+                "⚠".yellow(),
+                bin.path
+            );
+            continue;
+        }
 
-    println!("  Creating launcher at {}...", bin_path.display());
+        let name = match bin.name {
+            Some(ref name) => name.clone(),
+            None => normalize_command_name(
+                exe.file_name()
+                    .and_then(|s| s.to_str())
+                    .context("Failed to extract command name")?,
+            ),
+        };
+        if name == command_name {
+            continue;
+        }
 
-    #[cfg(unix)]
-    {
-        create_symlink(&exe_path, &bin_path)?;
+        let shim = WenPaths::shim_path_in(bin_dir, &name);
+        warn_path_conflicts(&name, bin_dir, &shim);
+        outln!("  Creating launcher at {}...", shim.display());
+        set_launcher_aside(cleanup, &shim)?;
+        create_launcher(&exe, &shim, &name, shim_mode)?;
+        extra_shims.push(shim.to_string_lossy().to_string());
     }
 
-    #[cfg(windows)]
+    // Drop launchers for `bin` entries the new release no longer has
+    for old in previous
+        .map(|p| p.extra_shims.as_slice())
+        .unwrap_or_default()
     {
-        create_shim(&exe_path, &bin_path, &command_name)?;
+        let old_path = Path::new(old);
+        if !extra_shims.contains(old) && (old_path.exists() || old_path.is_symlink()) {
+            log::debug!("Removing previous launcher: {}", old_path.display());
+            if let Err(e) = set_launcher_aside(cleanup, old_path) {
+                log::debug!("{:#}", e);
+            }
+        }
     }
 
     // Clean up download
     fs::remove_file(&download_path)?;
 
     // Create installed package info
+    let now = Utc::now();
     let inst_pkg = InstalledPackage {
         version: version.to_string(),
         platform: platform_id.clone(),
-        installed_at: Utc::now(),
+        installed_at: now,
+        updated_at: Some(now),
         install_path: app_dir.to_string_lossy().to_string(),
         files: extracted_files,
+        file_digests,
         source: source.clone(),
         description: pkg.description.clone(),
         command_name,
+        shim_path: bin_path.map(|p| p.to_string_lossy().to_string()),
+        exe_path: Some(exe_path.to_string_lossy().to_string()),
+        extra_shims,
+        alias,
+        no_shim,
+        prefer_variant: None,
+        requires: Vec::new(),
+        auto_installed: false,
+        channel: Channel::Stable,
+        asset_pattern: None,
+        keep_versions: None,
+        kept_versions,
+        // A pin survives upgrades and reinstalls until `wenget unpin`, and
+        // ignored updates until `wenget update --reset-ignores`
+        pin: previous.and_then(|p| p.pin.clone()),
+        ignored_updates: previous
+            .map(|p| p.ignored_updates.clone())
+            .unwrap_or_default(),
+        post_install_message: pkg.post_install_message.clone(),
+        exe_name: pkg.exe_name.clone(),
+        shim_mode,
     };
 
     Ok(inst_pkg)
 }
 
+/// SHA-256 of each regular file in `files` (relative to `dir`), keyed by its relative path
+fn record_digests(dir: &Path, files: &[String]) -> Result<BTreeMap<String, String>> {
+    let files: Vec<&String> = files.iter().filter(|f| dir.join(f).is_file()).collect();
+    let paths: Vec<PathBuf> = files.iter().map(|f| dir.join(f)).collect();
+
+    files
+        .into_iter()
+        .zip(checksum::sha256_files(&paths, &|_| {}))
+        .map(|(file, digest)| Ok((file.clone(), digest?)))
+        .collect()
+}
+
+/// Move a launcher out of the way until its install is kept
+///
+/// A failed or interrupted install puts it back, committing `cleanup`
+/// deletes it. A path with no launcher yet is tracked for removal instead.
+fn set_launcher_aside(cleanup: &mut CleanupGuard, path: &Path) -> Result<()> {
+    if !path.exists() && !path.is_symlink() {
+        cleanup.track(path);
+        return Ok(());
+    }
+
+    // Next to the launcher, so this is a rename on the same filesystem
+    let file_name = path
+        .file_name()
+        .context("Invalid launcher path")?
+        .to_string_lossy();
+    let aside = path.with_file_name(format!(".{}.old", file_name));
+    if aside.exists() || aside.is_symlink() {
+        fs::remove_file(&aside).ok();
+    }
+    fs::rename(path, &aside)
+        .with_context(|| format!("Failed to move previous launcher aside: {}", path.display()))?;
+    cleanup.track_replaced(path, &aside, true);
+    Ok(())
+}
+
+/// Show the manifest's follow-up guidance after a successful install
+fn print_post_install_message(name: &str, message: Option<&str>) {
+    for line in post_install_lines(name, message) {
+        outln!("{}", line);
+    }
+}
+
+/// The lines [`print_post_install_message`] shows, none without a message
+fn post_install_lines(name: &str, message: Option<&str>) -> Vec<String> {
+    let lines = message.map(message_lines).unwrap_or_default();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let mut out = vec![
+        String::new(),
+        format!("  {} Notes from {}:", "ℹ".cyan(), name.bold()),
+    ];
+    out.extend(
+        lines
+            .iter()
+            .map(|line| format!("  {} {}", "│".cyan(), line)),
+    );
+    out
+}
+
+/// Create the launcher for an executable: on Unix a symlink, copy or wrapper
+/// script depending on `mode`, on Windows always a `.cmd` shim
+#[allow(unused_variables)] // `command_name` is only used on Windows, `mode` only on Unix
+pub(crate) fn create_launcher(
+    exe_path: &Path,
+    bin_path: &Path,
+    command_name: &str,
+    mode: ShimMode,
+) -> Result<()> {
+    #[cfg(unix)]
+    {
+        match mode {
+            ShimMode::Symlink => create_symlink(exe_path, bin_path),
+            ShimMode::Copy => copy_executable(exe_path, bin_path),
+            ShimMode::Wrapper => create_wrapper(exe_path, bin_path),
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        create_shim(exe_path, bin_path, command_name)
+    }
+}
+
+/// Warn when another executable with the launcher's name is already on PATH
+///
+/// Only informational: explains which of the two runs given the PATH order
+/// and how to pick a different name.
+fn warn_path_conflicts(command_name: &str, bin_dir: &Path, bin_path: &Path) {
+    let others: Vec<PathBuf> = find_in_path(command_name)
+        .into_iter()
+        .filter(|p| p.parent() != Some(bin_dir))
+        .collect();
+    let Some(other) = others.first() else {
+        return;
+    };
+
+    outln!(
+        "  {} '{}' is already on PATH at {}",
+        "⚠".yellow(),
+        command_name,
+        other.display()
+    );
+
+    let launcher_first = match (path_index(bin_dir), other.parent().and_then(path_index)) {
+        (Some(ours), Some(theirs)) => ours < theirs,
+        _ => false,
+    };
+    if launcher_first {
+        outln!(
+            "    The new launcher {} comes first in PATH and will shadow it",
+            bin_path.display()
+        );
+    } else {
+        outln!(
+            "    {} comes first in PATH and will run instead of the new launcher",
+            other.display()
+        );
+    }
+    outln!("    Use --name <command> to install it under another name");
+}
+
+/// Progress display for the extraction phase
+///
+/// Starts as a spinner with a running entry count and turns into a bar once
+/// the archive reports its size. Hidden in quiet mode or when stdout is not a
+/// terminal.
+fn extraction_progress() -> ProgressBar {
+    if crate::utils::is_quiet() || !std::io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new_spinner();
+    pb.set_style(
+        ProgressStyle::default_spinner()
+            .template("  {spinner:.green} {pos} entries extracted")
+            .unwrap(),
+    );
+    pb.enable_steady_tick(std::time::Duration::from_millis(100));
+    pb
+}
+
+/// Advance the extraction display, switching to a bar when the total is known
+fn update_extraction_progress(pb: &ProgressBar, progress: ExtractProgress) {
+    if let Some(total) = progress.total {
+        if pb.length() != Some(total as u64) {
+            pb.set_length(total as u64);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("  {spinner:.green} [{bar:40.cyan/blue}] {pos}/{len} entries")
+                    .unwrap()
+                    .progress_chars("#>-"),
+            );
+        }
+    }
+    pb.set_position(progress.entries as u64);
+}
+
 /// Update manifest cache with latest package info from GitHub API
 fn update_cache_with_packages(
     config: &Config,
@@ -824,7 +3701,7 @@ fn install_script_from_bucket(
     origin: &str,
     custom_name: Option<&str>,
 ) -> Result<()> {
-    println!("  Downloading script from {}...", url);
+    outln!("  Downloading script from {}...", url);
 
     // Download script content
     let content = download_script(url)?;
@@ -832,35 +3709,702 @@ fn install_script_from_bucket(
     // Determine the final command name
     let command_name = custom_name.unwrap_or(name);
 
-    println!("  Installing script as '{}'...", command_name);
+    outln!("  Installing script as '{}'...", command_name);
 
     // Install script to app directory
     let files = install_script(paths, command_name, &content, &script_type)?;
+    let file_digests = record_digests(&paths.app_dir(command_name), &files)?;
 
-    println!("  Command will be available as: {}", command_name);
+    outln!("  Command will be available as: {}", command_name);
 
     // Create shim
-    println!("  Creating launcher...");
+    outln!("  Creating launcher...");
     create_script_shim(paths, command_name, &script_type)?;
 
     // Create installed package info
+    let now = Utc::now();
     let inst_pkg = InstalledPackage {
         version: "script".to_string(),
         platform: std::env::consts::OS.to_string(),
-        installed_at: Utc::now(),
+        installed_at: now,
+        updated_at: Some(now),
         install_path: paths.app_dir(command_name).display().to_string(),
         files,
+        file_digests,
         source: PackageSource::Script {
             origin: origin.to_string(),
             script_type: script_type.clone(),
         },
         description: format!("{} script from bucket", script_type.display_name()),
         command_name: command_name.to_string(),
+        shim_path: None,
+        exe_path: None,
+        extra_shims: Vec::new(),
+        alias: None,
+        no_shim: false,
+        prefer_variant: None,
+        requires: Vec::new(),
+        auto_installed: false,
+        channel: Channel::Stable,
+        asset_pattern: None,
+        keep_versions: None,
+        kept_versions: Vec::new(),
+        pin: None,
+        ignored_updates: Vec::new(),
+        post_install_message: None,
+        exe_name: None,
+        shim_mode: ShimMode::Symlink,
     };
 
     // Update installed manifest
+    record_install(config, installed.get_package(name), name, &inst_pkg);
     installed.upsert_package(name.to_string(), inst_pkg);
     config.save_installed(installed)?;
 
     Ok(())
 }
+
+/// Record an install, or an upgrade when `previous` exists, in the history
+/// and in the progress of an unfinished batch
+fn record_install(
+    config: &Config,
+    previous: Option<&InstalledPackage>,
+    name: &str,
+    inst_pkg: &InstalledPackage,
+) {
+    let action = if previous.is_some() {
+        HistoryAction::Upgrade
+    } else {
+        HistoryAction::Install
+    };
+
+    config.record_history(HistoryEvent::new(
+        action,
+        name,
+        previous.map(|p| p.version.as_str()),
+        Some(&inst_pkg.version),
+        &inst_pkg.source,
+    ));
+    config.record_batch_progress(name, &inst_pkg.source.describe());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{BinaryAsset, BinarySelector};
+    use crate::providers::rate_limit::RateLimitBreaker;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+
+    /// A gzipped tarball with an executable and a readme under `tool-{version}/`
+    fn write_tool_archive(dir: &Path, version: &str) -> PathBuf {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (name, contents, mode) in [
+            ("tool", format!("#!/bin/sh\necho {}\n", version), 0o755),
+            ("README.md", "# tool\n".to_string(), 0o644),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(mode);
+            header.set_cksum();
+            builder
+                .append_data(
+                    &mut header,
+                    format!("tool-{}/{}", version, name),
+                    contents.as_bytes(),
+                )
+                .unwrap();
+        }
+
+        let path = dir.join(format!("tool-{}-linux.tar.gz", version));
+        let mut encoder = GzEncoder::new(fs::File::create(&path).unwrap(), Compression::default());
+        encoder.write_all(&builder.into_inner().unwrap()).unwrap();
+        encoder.finish().unwrap();
+        path
+    }
+
+    /// A manifest entry offering `archive` for every platform id given
+    fn tool_package(archive: &Path, platform_ids: &[String]) -> crate::core::Package {
+        let platforms: serde_json::Map<_, _> = platform_ids
+            .iter()
+            .map(|id| {
+                let binary = serde_json::json!({ "url": archive.to_str().unwrap(), "size": 0 });
+                (id.clone(), binary)
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "name": "tool",
+            "description": "Test tool",
+            "repo": "https://github.com/test/tool",
+            "platforms": platforms,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_upgrade_uses_checked_release_with_breaker_open() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let mut installed = InstalledManifest::new();
+        installed.upsert_package("tool".to_string(), InstalledPackage::test_default("tool"));
+
+        let body = r#"{"tag_name":"v2.0.0","assets":[
+            {"name":"tool-v2.0.0-x86_64-unknown-linux-musl.tar.gz","browser_download_url":"https://example.com/tool.tar.gz","size":2000}
+        ]}"#;
+        let (url, requests) = crate::utils::http::testing::serve_recording(vec![format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )]);
+        let breaker: &'static RateLimitBreaker = Box::leak(Box::new(RateLimitBreaker::new()));
+        let github = GitHubProvider::with_api_base(&url).with_breaker(breaker);
+
+        // update's check found the new release, then the limit ran out
+        let latest = github
+            .fetch_matching_version("https://github.com/test/tool", Channel::Stable, &[], None)
+            .unwrap();
+        assert_eq!(latest, "2.0.0");
+        breaker.trip(None);
+
+        let stale = tool_package(
+            Path::new("/stale/tool-1.0.0.tar.gz"),
+            &["linux-x86_64-musl".to_string()],
+        );
+        let source = PackageSource::Bucket {
+            name: "test-bucket".to_string(),
+        };
+        let options = AddOptions {
+            upgrade: true,
+            no_preflight: true,
+            ..Default::default()
+        };
+        let ctx = InstallContext {
+            options: &options,
+            yes: true,
+            default_bin_dir: config.paths().bin_dir(),
+            default_shim_mode: ShimMode::default(),
+            default_variant: None,
+            default_keep_versions: 0,
+            retries: 0,
+            temp_dir: None,
+        };
+        let request = InstallRequest::default();
+        let mut report = InstallReport::default();
+
+        let packages = select_packages(
+            &config,
+            &mut installed,
+            Some(&github),
+            vec![ResolvedPackage::new(stale, source)],
+            &request,
+            &options,
+            &mut report,
+        )
+        .unwrap();
+        let (prepared, failed) = prepare_packages(
+            &config,
+            &installed,
+            Some(&github),
+            packages,
+            &request,
+            &ctx,
+            &mut report,
+        )
+        .unwrap();
+
+        // Upgraded to the checked release, not reinstalled as "unknown"
+        assert_eq!(failed, 0);
+        assert_eq!(prepared.len(), 1);
+        assert_eq!(prepared[0].version, "2.0.0");
+        assert!(!prepared[0].using_fallback);
+        assert_eq!(
+            prepared[0].package.platforms["linux-x86_64-musl"].url,
+            "https://example.com/tool.tar.gz"
+        );
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_package_into_temp_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let paths = config.paths();
+        let platform_ids = Platform::current().possible_identifiers();
+        let source = PackageSource::DirectRepo {
+            url: "https://github.com/test/tool".to_string(),
+        };
+        let install = |version: &str, previous: Option<&InstalledPackage>| {
+            let archive = write_tool_archive(temp_dir.path(), version);
+            let bin_dir = paths.bin_dir();
+            let install = PackageInstall {
+                previous,
+                keep_versions: 1,
+                ..PackageInstall::new(version, &source, &bin_dir)
+            };
+            let mut cleanup = CleanupGuard::new();
+            let installed = install_package(
+                &config,
+                &tool_package(&archive, &platform_ids),
+                &platform_ids,
+                &install,
+                &mut cleanup,
+            )
+            .unwrap();
+            (installed, cleanup)
+        };
+
+        let (first, cleanup) = install("1.0.0", None);
+        cleanup.commit();
+        let app_dir = paths.app_dir("tool");
+        let exe = app_dir.join("tool-1.0.0").join("tool");
+        assert_eq!(first.version, "1.0.0");
+        assert_eq!(first.command_name, "tool");
+        assert_eq!(first.install_path, app_dir.to_string_lossy());
+        assert_eq!(first.exe_path.as_deref(), Some(&*exe.to_string_lossy()));
+        assert!(app_dir.join("tool-1.0.0").join("README.md").is_file());
+        assert!(first.files.iter().any(|f| f.ends_with("tool-1.0.0/tool")));
+        let exe_digest = first
+            .file_digests
+            .iter()
+            .find(|(f, _)| f.ends_with("tool-1.0.0/tool"));
+        assert_eq!(
+            exe_digest.map(|(_, digest)| digest.clone()),
+            Some(checksum::sha256_file(&exe).unwrap())
+        );
+
+        // The launcher links to the executable inside the apps directory
+        let launcher = paths.bin_dir().join("tool");
+        assert_eq!(
+            first.shim_path.as_deref(),
+            Some(&*launcher.to_string_lossy())
+        );
+        assert_eq!(fs::read_link(&launcher).unwrap(), exe);
+
+        // Nothing is left behind in the download and staging directories
+        let is_empty = |dir: PathBuf| fs::read_dir(dir).map_or(true, |mut d| d.next().is_none());
+        assert!(is_empty(paths.downloads_dir()));
+        assert!(is_empty(paths.staging_dir()));
+
+        // An upgrade replaces the install and keeps the previous version
+        let (second, cleanup) = install("2.0.0", Some(&first));
+        cleanup.commit();
+        assert_eq!(second.version, "2.0.0");
+        assert!(!app_dir.join("tool-1.0.0").exists());
+        let exe = app_dir.join("tool-2.0.0").join("tool");
+        assert_eq!(fs::read_link(&launcher).unwrap(), exe);
+        assert_eq!(second.kept_versions, vec!["1.0.0".to_string()]);
+        assert!(paths
+            .backups_dir("tool")
+            .join("1.0.0")
+            .join("tool-1.0.0")
+            .join("tool")
+            .is_file());
+
+        // An upgrade that is never recorded (say the launcher or the manifest
+        // could not be written) puts the previous install back
+        let (_, cleanup) = install("3.0.0", Some(&second));
+        assert!(app_dir.join("tool-3.0.0").exists());
+        drop(cleanup);
+        assert!(!app_dir.join("tool-3.0.0").exists());
+        assert!(exe.is_file());
+        assert_eq!(fs::read_link(&launcher).unwrap(), exe);
+        assert!(!paths.backups_dir("tool").join("2.0.0").exists());
+        assert!(paths.backups_dir("tool").join("1.0.0").exists());
+        assert!(is_empty(paths.staging_dir()));
+        assert!(!paths.bin_dir().join(".tool.old").is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_install_by_alias_uses_canonical_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let paths = config.paths();
+        let platform_ids = Platform::current().possible_identifiers();
+
+        let archive = write_tool_archive(temp_dir.path(), "1.0.0");
+        let mut package = tool_package(&archive, &platform_ids);
+        package.aliases = vec!["tl".to_string()];
+        let mut cache = crate::cache::ManifestCache::new();
+        let source = PackageSource::Bucket {
+            name: "main".to_string(),
+        };
+        cache.add_package(package, source);
+
+        let resolver = PackageResolver::new(&config, &cache).unwrap();
+        let resolved = resolver.resolve(&PackageInput::parse("tl")).unwrap();
+        assert_eq!(resolved.len(), 1);
+        let bin_dir = paths.bin_dir();
+        let mut cleanup = CleanupGuard::new();
+        let installed = install_package(
+            &config,
+            &resolved[0].package,
+            &platform_ids,
+            &PackageInstall::new("1.0.0", &resolved[0].source, &bin_dir),
+            &mut cleanup,
+        )
+        .unwrap();
+        cleanup.commit();
+
+        assert_eq!(installed.command_name, "tool");
+        assert!(paths
+            .app_dir("tool")
+            .join("tool-1.0.0")
+            .join("tool")
+            .is_file());
+        assert!(!paths.app_dir("tl").exists());
+        assert!(paths.bin_dir().join("tool").is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_post_install_message_after_bucket_install() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let platform_ids = Platform::current().possible_identifiers();
+
+        let archive = write_tool_archive(temp_dir.path(), "1.0.0");
+        let mut package = tool_package(&archive, &platform_ids);
+        package.post_install_message = Some("Run `tool init`\nthen restart your shell".to_string());
+        let mut cache = crate::cache::ManifestCache::new();
+        cache.add_package(
+            package,
+            PackageSource::Bucket {
+                name: "test-bucket".to_string(),
+            },
+        );
+
+        let mut installed = InstalledManifest::new();
+        let report = install_offline(
+            &config,
+            &mut installed,
+            &cache,
+            &["tool"],
+            &AddOptions::default(),
+        );
+        assert_eq!(report.installed, 1);
+
+        // The notes printed after the install are the ones it recorded
+        let recorded = config.get_or_create_installed().unwrap();
+        let message = recorded
+            .get_package("tool")
+            .unwrap()
+            .post_install_message
+            .as_deref();
+        let lines = post_install_lines("tool", message);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].is_empty());
+        assert!(lines[1].contains("Notes from"));
+        assert!(lines[2].ends_with("Run `tool init`"));
+        assert!(lines[3].ends_with("then restart your shell"));
+
+        // Nothing is printed without notes
+        assert!(post_install_lines("tool", None).is_empty());
+        assert!(post_install_lines("tool", Some(" \n")).is_empty());
+    }
+
+    /// Run the phases of `install_packages` from `cache`, without GitHub
+    /// lookups (versions are "unknown" and the cached links are used)
+    fn install_offline(
+        config: &Config,
+        installed: &mut InstalledManifest,
+        cache: &crate::cache::ManifestCache,
+        names: &[&str],
+        options: &AddOptions,
+    ) -> InstallReport {
+        let options = AddOptions {
+            no_preflight: true,
+            ..options.clone()
+        };
+        let ctx = InstallContext {
+            options: &options,
+            yes: true,
+            default_bin_dir: config.paths().bin_dir(),
+            default_shim_mode: ShimMode::default(),
+            default_variant: None,
+            default_keep_versions: 0,
+            retries: 0,
+            temp_dir: None,
+        };
+        let resolver = PackageResolver::new(config, cache).unwrap();
+        let names: Vec<String> = names.iter().map(|n| n.to_string()).collect();
+        let names: Vec<&String> = names.iter().collect();
+        let mut report = InstallReport::default();
+
+        let mut request = resolve_inputs(&resolver, installed, &names, &options, &mut report);
+        let packages = std::mem::take(&mut request.packages);
+        let packages = select_packages(
+            config,
+            installed,
+            None,
+            packages,
+            &request,
+            &options,
+            &mut report,
+        )
+        .unwrap();
+        let (prepared, _) = prepare_packages(
+            config,
+            installed,
+            None,
+            packages,
+            &request,
+            &ctx,
+            &mut report,
+        )
+        .unwrap();
+        let (success, _) = install_prepared(
+            config,
+            installed,
+            None,
+            prepared,
+            &request,
+            &ctx,
+            &mut report,
+        )
+        .unwrap();
+        report.installed += success;
+        report
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_if_not_installed_skips_installed_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let paths = config.paths();
+        let platform_ids = Platform::current().possible_identifiers();
+
+        let archive = write_tool_archive(temp_dir.path(), "1.0.0");
+        let mut cache = crate::cache::ManifestCache::new();
+        let source = PackageSource::Bucket {
+            name: "test-bucket".to_string(),
+        };
+        let mut other = tool_package(&archive, &platform_ids);
+        other.name = "other".to_string();
+        other.repo = "https://github.com/test/other".to_string();
+        cache.add_package(other, source.clone());
+        cache.add_package(tool_package(&archive, &platform_ids), source);
+
+        // "other" is installed (at an older version than the bucket's), "tool" is not
+        let mut installed = InstalledManifest::new();
+        let mut other = InstalledPackage::test_default("other");
+        other.version = "0.9.0".to_string();
+        installed.upsert_package("other".to_string(), other.clone());
+
+        let options = AddOptions {
+            if_not_installed: true,
+            ..Default::default()
+        };
+        let report = install_offline(
+            &config,
+            &mut installed,
+            &cache,
+            &["other", "tool"],
+            &options,
+        );
+
+        assert_eq!(report.installed, 1);
+        let outcomes: Vec<_> = report
+            .outcomes
+            .iter()
+            .map(|o| (o.name.as_str(), o.action, o.to.as_deref()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                ("other", OutcomeAction::Skipped, Some("0.9.0")),
+                ("tool", OutcomeAction::Installed, Some("unknown")),
+            ]
+        );
+
+        // The installed package is left as it was
+        let kept = installed.get_package("other").unwrap();
+        assert_eq!(kept.version, "0.9.0");
+        assert_eq!(kept.installed_at, other.installed_at);
+        assert!(!paths.app_dir("other").exists());
+
+        // The missing one is installed and recorded
+        assert!(installed.is_installed("tool"));
+        assert!(config
+            .get_or_create_installed()
+            .unwrap()
+            .is_installed("tool"));
+        assert!(paths.bin_dir().join("tool").is_symlink());
+    }
+
+    #[test]
+    fn test_reinstall_keeps_variant_but_not_setting() {
+        let assets: Vec<BinaryAsset> = ["gnu", "musl"]
+            .iter()
+            .map(|libc| BinaryAsset {
+                name: format!("app-x86_64-unknown-linux-{}.tar.gz", libc),
+                url: format!("https://example.com/{}.tar.gz", libc),
+                size: 1000,
+            })
+            .collect();
+        let select = |variant: Option<Compiler>| {
+            let platform = Platform::new(Os::Linux, Arch::X86_64).prefer_compiler(variant);
+            BinarySelector::select_for_platform(&assets, platform)
+                .unwrap()
+                .name
+        };
+
+        // Installed with the `prefer_variant = gnu` setting: gnu is picked, nothing is recorded
+        let (variant, recorded) = package_variant(None, None, Some(Compiler::Gnu));
+        assert!(select(variant).ends_with("gnu.tar.gz"));
+        assert_eq!(recorded, None);
+
+        // ...so a reinstall after `config set prefer_variant musl` follows the setting
+        let previous = InstalledPackage {
+            prefer_variant: recorded,
+            ..InstalledPackage::test_default("app")
+        };
+        let (variant, recorded) = package_variant(None, Some(&previous), Some(Compiler::Musl));
+        assert!(select(variant).ends_with("musl.tar.gz"));
+        assert_eq!(recorded, None);
+
+        // Installed with --prefer-gnu: recorded, and kept over the setting on reinstall
+        let (_, recorded) = package_variant(Some(Compiler::Gnu), None, Some(Compiler::Musl));
+        let previous = InstalledPackage {
+            prefer_variant: recorded,
+            ..InstalledPackage::test_default("app")
+        };
+        let (variant, recorded) = package_variant(None, Some(&previous), Some(Compiler::Musl));
+        assert!(select(variant).ends_with("gnu.tar.gz"));
+        assert_eq!(recorded, Some(Compiler::Gnu));
+
+        // A new flag replaces the recorded variant
+        let (variant, recorded) =
+            package_variant(Some(Compiler::Musl), Some(&previous), Some(Compiler::Gnu));
+        assert!(select(variant).ends_with("musl.tar.gz"));
+        assert_eq!(recorded, Some(Compiler::Musl));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bundle_install_keeps_channel_and_auto_installed() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let platform_ids = Platform::current().possible_identifiers();
+        let write_bundle = |version: &str| {
+            let bundle_dir = temp_dir.path().join(format!("bundle-{}", version));
+            let archive = write_tool_archive(temp_dir.path(), version);
+            let asset_dir = bundle_dir.join(&platform_ids[0]);
+            fs::create_dir_all(&asset_dir).unwrap();
+            fs::copy(&archive, asset_dir.join(archive.file_name().unwrap())).unwrap();
+            let manifest = BundleManifest {
+                version: Some(version.to_string()),
+                package: tool_package(&archive, &platform_ids[..1]),
+            };
+            fs::write(
+                bundle_dir.join(BUNDLE_MANIFEST),
+                serde_json::to_string(&manifest).unwrap(),
+            )
+            .unwrap();
+            bundle_dir
+        };
+        let options = || AddOptions {
+            yes: true,
+            no_preflight: true,
+            ..Default::default()
+        };
+
+        let report = install_from_bundle(&config, &write_bundle("1.0.0"), &[], options()).unwrap();
+        assert_eq!(report.installed, 1);
+
+        // A dependency following prereleases...
+        let mut installed = config.load_installed().unwrap();
+        let pkg = installed.packages.get_mut("tool").unwrap();
+        pkg.channel = Channel::Prerelease;
+        pkg.auto_installed = true;
+        config.save_installed(&installed).unwrap();
+
+        // ...stays one after a bundle upgrade
+        let report = install_from_bundle(&config, &write_bundle("1.1.0"), &[], options()).unwrap();
+        assert_eq!(report.installed, 1);
+        let installed = config.load_installed().unwrap();
+        let pkg = installed.get_package("tool").unwrap();
+        assert_eq!(pkg.version, "1.1.0");
+        assert_eq!(pkg.channel, Channel::Prerelease);
+        assert!(pkg.auto_installed);
+    }
+
+    #[test]
+    fn test_asset_pattern_resolved_with_one_release_lookup() {
+        let body = r#"{"tag_name":"v2.1.0","draft":false,"prerelease":false,"assets":[{"name":"tool-2.1.0-linux-x86_64.tar.gz","browser_download_url":"https://example.com/tool-2.1.0.tar.gz","size":42}]}"#;
+        let (url, requests) = crate::utils::http::testing::serve_recording(vec![format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )]);
+        let github = GitHubProvider::with_api_base(&url);
+        let package: crate::core::Package = serde_json::from_value(serde_json::json!({
+            "name": "tool",
+            "description": "Test tool",
+            "repo": "https://github.com/test/tool",
+            "platforms": {
+                "linux-x86_64": { "url": "", "size": 0, "asset_pattern": "tool-*-linux-x86_64.tar.gz" }
+            },
+        }))
+        .unwrap();
+
+        let (version, binary) = resolve_binary(
+            Some(&github),
+            &package,
+            Platform::new(Os::Linux, Arch::X86_64),
+            None,
+            Channel::Stable,
+            None,
+        )
+        .unwrap();
+
+        // The recorded version is the release the asset was picked from
+        assert_eq!(version.as_deref(), Some("2.1.0"));
+        assert_eq!(binary.url, "https://example.com/tool-2.1.0.tar.gz");
+        assert_eq!(binary.asset_pattern, None);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_failed_packages_set_exit_code() {
+        let not_found = || anyhow::Error::from(WengetError::NotFound("Not found".to_string()));
+        let mut report = InstallReport::default();
+        report
+            .outcomes
+            .push(PackageOutcome::installed("tool", None, "1.0.0"));
+        assert!(report.result().is_ok());
+
+        // Failures of one kind exit with that kind's code
+        report.fail_error("nope", &not_found());
+        report.fail_error("nada", &not_found().context("Failed to resolve nada"));
+        let err = report.result().unwrap_err();
+        assert_eq!(err.to_string(), "2 package(s) failed");
+        assert_eq!(crate::error::exit_code(&err), 2);
+
+        // Merged reports keep it, until a different kind of failure mixes in
+        let mut combined = InstallReport::default();
+        combined.merge(report);
+        assert_eq!(crate::error::exit_code(&combined.result().unwrap_err()), 2);
+        combined.fail_error(
+            "tool",
+            &WengetError::ChecksumMismatch {
+                file: "tool.tar.gz".to_string(),
+                expected: "a".to_string(),
+                actual: "b".to_string(),
+            }
+            .into(),
+        );
+        assert_eq!(crate::error::exit_code(&combined.result().unwrap_err()), 1);
+    }
+}
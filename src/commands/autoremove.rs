@@ -0,0 +1,83 @@
+//! Autoremove command implementation
+
+use crate::commands::delete::delete_package;
+use crate::core::{Config, WenPaths};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Remove dependency-installed packages that nothing requires anymore
+pub fn run(yes: bool) -> Result<()> {
+    let config = Config::new()?;
+    let paths = WenPaths::new()?;
+
+    let mut installed = config.get_or_create_installed()?;
+
+    let orphans: Vec<String> = installed
+        .orphaned_dependencies()
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    if orphans.is_empty() {
+        println!("{}", "No orphaned dependencies to remove".green());
+        return Ok(());
+    }
+
+    // Show packages to remove
+    println!("{}", "Orphaned dependencies to remove:".bold());
+    for name in &orphans {
+        let pkg = installed.get_package(name).unwrap();
+        println!("  • {} v{}", name.red(), pkg.version);
+    }
+
+    // Confirm deletion
+    if !yes {
+        print!("\nProceed with removal? [y/N] ");
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+
+        let mut response = String::new();
+        io::stdin().read_line(&mut response)?;
+        let response = response.trim().to_lowercase();
+
+        if response != "y" && response != "yes" {
+            println!("Removal cancelled");
+            return Ok(());
+        }
+    }
+
+    println!();
+
+    let mut success_count = 0;
+    let mut fail_count = 0;
+
+    for name in orphans {
+        println!("{} {}...", "Deleting".cyan(), name);
+
+        match delete_package(&config, &paths, &mut installed, &name) {
+            Ok(()) => {
+                println!("  {} Deleted successfully", "✓".green());
+                success_count += 1;
+            }
+            Err(e) => {
+                println!("  {} {}", "✗".red(), e);
+                fail_count += 1;
+            }
+        }
+    }
+
+    // Save updated manifest
+    config.save_installed(&installed)?;
+
+    // Summary
+    println!();
+    println!("{}", "Summary:".bold());
+    if success_count > 0 {
+        println!("  {} {} package(s) removed", "✓".green(), success_count);
+    }
+    if fail_count > 0 {
+        println!("  {} {} package(s) failed", "✗".red(), fail_count);
+    }
+
+    Ok(())
+}
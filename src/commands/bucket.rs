@@ -1,31 +1,61 @@
 //! Bucket command implementation
 
 use crate::bucket::Bucket;
-use crate::core::Config;
+use crate::cache::ManifestCache;
+use crate::core::manifest::{expand_env, PackageSource};
+use crate::core::{AssetPattern, BinarySelector, Channel, Config, Package};
+use crate::error::WengetError;
+use crate::outln;
+use crate::providers::GitHubProvider;
+use crate::utils::concurrency::parallel_map;
+use crate::utils::http::parse_header_arg;
 use anyhow::Result;
 use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Bucket subcommands
 pub enum BucketCommand {
-    Add { name: String, url: String },
-    Del { names: Vec<String> },
+    Add {
+        name: String,
+        url: String,
+        /// Extra request headers as `Name: value`
+        headers: Vec<String>,
+    },
+    Del {
+        names: Vec<String>,
+    },
     List,
     Refresh,
+    Update {
+        names: Vec<String>,
+    },
+    Verify {
+        name: String,
+        /// Print the results as JSON
+        json: bool,
+    },
 }
 
 /// Run bucket command
 pub fn run(cmd: BucketCommand) -> Result<()> {
     match cmd {
-        BucketCommand::Add { name, url } => run_add(name, url),
+        BucketCommand::Add { name, url, headers } => run_add(name, url, headers),
         BucketCommand::Del { names } => run_del(names),
         BucketCommand::List => run_list(),
         BucketCommand::Refresh => run_refresh(),
+        BucketCommand::Update { names } => run_update(names),
+        BucketCommand::Verify { name, json } => run_verify(name, json),
     }
 }
 
 /// Add a bucket
-fn run_add(name: String, url: String) -> Result<()> {
+fn run_add(name: String, url: String, headers: Vec<String>) -> Result<()> {
     let config = Config::new()?;
+    let headers = headers
+        .iter()
+        .map(|h| parse_header_arg(h))
+        .collect::<Result<BTreeMap<_, _>>>()?;
 
     // Ensure WenPM is initialized
     if !config.is_initialized() {
@@ -43,7 +73,9 @@ fn run_add(name: String, url: String) -> Result<()> {
         url: url.clone(),
         enabled: true,
         priority: 100,
+        headers,
     };
+    let header_names: Vec<String> = bucket.headers.keys().cloned().collect();
 
     // Try to add bucket
     if bucket_config.add_bucket(bucket) {
@@ -52,6 +84,9 @@ fn run_add(name: String, url: String) -> Result<()> {
 
         println!("{} Bucket '{}' added", "✓".green(), name);
         println!("  URL: {}", url);
+        if !header_names.is_empty() {
+            println!("  Headers: {}", header_names.join(", "));
+        }
 
         // Invalidate cache so it will be rebuilt on next access
         config.invalidate_cache()?;
@@ -93,6 +128,7 @@ fn run_del(names: Vec<String>) -> Result<()> {
         print!("  {} {} ... ", "Deleting".cyan(), name);
 
         if bucket_config.remove_bucket(&name) {
+            config.remove_bucket_cache(&name)?;
             println!("{}", "Deleted".green());
             deleted += 1;
         } else {
@@ -164,6 +200,16 @@ fn run_list() -> Result<()> {
             status.to_string(),
             bucket.url
         );
+        if !bucket.headers.is_empty() {
+            // Values may hold credentials, so only the names are shown
+            let names: Vec<&str> = bucket.headers.keys().map(String::as_str).collect();
+            println!(
+                "{:<20} {:<10} headers: {}",
+                "",
+                "",
+                names.join(", ").dimmed()
+            );
+        }
     }
 
     println!();
@@ -178,25 +224,339 @@ fn run_refresh() -> Result<()> {
 
     println!("{} manifest cache...\n", "Refreshing".cyan());
 
-    // Force rebuild cache
-    let cache = config.rebuild_cache()?;
+    // Force refetch of every bucket
+    let cache = config.refresh_buckets(&[])?;
+    let bucket_config = config.get_or_create_buckets()?;
+    let names: Vec<String> = bucket_config
+        .enabled_buckets()
+        .iter()
+        .map(|b| b.name.clone())
+        .collect();
 
     println!();
     println!("{}", "Summary:".bold());
+    let all_current = print_bucket_summary(&cache, &names);
 
-    // Show source statistics
-    for (source_name, info) in &cache.sources {
-        println!(
-            "  {} {} - {} package(s)",
-            "✓".green(),
-            source_name,
-            info.package_count
-        );
+    println!();
+    println!("Total packages in cache: {}", cache.packages.len());
+    if all_current {
+        println!("{}", "Cache refreshed successfully!".green());
     }
 
+    Ok(())
+}
+
+/// Print one summary line per bucket: refreshed, stale, or failed
+///
+/// Returns whether every bucket was refreshed.
+fn print_bucket_summary(cache: &ManifestCache, names: &[String]) -> bool {
+    let mut all_current = true;
+
+    for name in names {
+        match cache.sources.get(&format!("bucket:{}", name)) {
+            Some(info) => match info.stale {
+                Some(ref error) => {
+                    all_current = false;
+                    println!(
+                        "  {} {} - {} package(s) {}",
+                        "⚠".yellow(),
+                        name,
+                        info.package_count,
+                        format!("(stale, refresh failed: {})", error).yellow()
+                    );
+                }
+                None => println!(
+                    "  {} {} - {} package(s)",
+                    "✓".green(),
+                    name,
+                    info.package_count
+                ),
+            },
+            None => {
+                all_current = false;
+                println!("  {} {} - {}", "✗".red(), name, "failed to fetch".red());
+            }
+        }
+    }
+
+    all_current
+}
+
+/// Refetch specific buckets (or all) and merge them into the cache
+fn run_update(names: Vec<String>) -> Result<()> {
+    if names.is_empty() {
+        return run_refresh();
+    }
+
+    let config = Config::new()?;
+    let bucket_config = config.get_or_create_buckets()?;
+
+    // Validate bucket names before fetching anything
+    for name in &names {
+        if bucket_config.find_bucket(name).is_none() {
+            anyhow::bail!("Bucket '{}' not found", name);
+        }
+    }
+
+    println!("{} {} bucket(s)...\n", "Updating".cyan(), names.len());
+
+    let cache = config.refresh_buckets(&names)?;
+
+    println!("{}", "Summary:".bold());
+    print_bucket_summary(&cache, &names);
+
     println!();
     println!("Total packages in cache: {}", cache.packages.len());
-    println!("{}", "Cache refreshed successfully!".green());
 
     Ok(())
 }
+
+/// Outcome of checking one bucket package against its latest release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum VerifyStatus {
+    /// At least one of the package's platforms resolves to an asset
+    Ok,
+    /// The repo or release is missing, has no assets, or nothing matched
+    Failed,
+    /// Not checked because the GitHub API rate limit ran out
+    Skipped,
+}
+
+/// One package as printed by `bucket verify --json`
+#[derive(Debug, Serialize)]
+struct VerifyResult {
+    name: String,
+    repo: String,
+    status: VerifyStatus,
+    /// Tag of the latest release, if it could be fetched
+    release: Option<String>,
+    /// Platforms whose asset was found in the latest release
+    platforms: Vec<String>,
+    /// Platforms the bucket lists but the latest release has no asset for
+    missing: Vec<String>,
+    error: Option<String>,
+}
+
+/// Check every package of a bucket against its latest GitHub release
+///
+/// Fails (for CI) when any package cannot be resolved on any platform, or
+/// with the rate-limit exit code when the limit ran out before the end.
+fn run_verify(name: String, json: bool) -> Result<()> {
+    if json {
+        crate::utils::output::set_json(true);
+    }
+
+    let config = Config::new()?;
+    let bucket_config = config.get_or_create_buckets()?;
+    if bucket_config.find_bucket(&name).is_none() {
+        anyhow::bail!("Bucket '{}' not found", name);
+    }
+
+    // Verify what the bucket serves now, not a cached copy
+    let cache = config.refresh_buckets(std::slice::from_ref(&name))?;
+    match cache.sources.get(&format!("bucket:{}", name)) {
+        Some(info) => {
+            if let Some(ref error) = info.stale {
+                outln!(
+                    "{} Could not refetch bucket '{}', verifying the cached copy: {}",
+                    "⚠".yellow(),
+                    name,
+                    error
+                );
+            }
+        }
+        None => anyhow::bail!("Failed to fetch bucket '{}'", name),
+    }
+
+    let source = PackageSource::Bucket { name: name.clone() };
+    let mut packages: Vec<&Package> = cache
+        .packages_by_source(&source)
+        .into_iter()
+        .map(|cached| &cached.package)
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+    if packages.is_empty() {
+        outln!("{}", format!("Bucket '{}' has no packages", name).yellow());
+        if json {
+            println!("[]");
+        }
+        return Ok(());
+    }
+
+    let github = GitHubProvider::new()?;
+
+    // One API request per package; say so up front if the quota won't last
+    if let Ok(rate_limit) = github.fetch_rate_limit() {
+        if rate_limit.remaining < packages.len() as u64 {
+            outln!(
+                "{} {} package(s) to check but only {}/{} GitHub API requests remaining; \
+                 the rest will be skipped. Set GITHUB_TOKEN for a higher limit.",
+                "⚠".yellow(),
+                packages.len(),
+                rate_limit.remaining,
+                rate_limit.limit
+            );
+        }
+    }
+
+    outln!(
+        "{} {} package(s) in bucket '{}'...\n",
+        "Verifying".cyan(),
+        packages.len(),
+        name
+    );
+
+    // Once the limit is hit the shared breaker fails the remaining checks fast
+    let mut rate_limited = None;
+    let results: Vec<VerifyResult> = parallel_map(&packages, |pkg| verify_package(&github, pkg))
+        .into_iter()
+        .zip(&packages)
+        .map(|(result, pkg)| match result {
+            Ok(result) => result,
+            Err(e) => {
+                let status = match WengetError::rate_limit(&e) {
+                    Some(exceeded) => {
+                        rate_limited.get_or_insert_with(|| exceeded.clone());
+                        VerifyStatus::Skipped
+                    }
+                    None => VerifyStatus::Failed,
+                };
+                VerifyResult {
+                    name: pkg.name.clone(),
+                    repo: pkg.repo.clone(),
+                    status,
+                    release: None,
+                    platforms: Vec::new(),
+                    missing: Vec::new(),
+                    error: Some(format!("{:#}", e)),
+                }
+            }
+        })
+        .collect();
+
+    let count = |status| results.iter().filter(|r| r.status == status).count();
+    let (ok, failed, skipped) = (
+        count(VerifyStatus::Ok),
+        count(VerifyStatus::Failed),
+        count(VerifyStatus::Skipped),
+    );
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        print_verify_results(&results);
+        println!();
+        println!("{}", "Summary:".bold());
+        println!("  {} {} package(s) resolved", "✓".green(), ok);
+        if failed > 0 {
+            println!("  {} {} package(s) failed", "✗".red(), failed);
+        }
+        if skipped > 0 {
+            println!(
+                "  {} {} package(s) skipped (rate limit)",
+                "⚠".yellow(),
+                skipped
+            );
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!(
+            "{} package(s) in bucket '{}' failed verification",
+            failed,
+            name
+        );
+    }
+    if let Some(exceeded) = rate_limited {
+        return Err(WengetError::RateLimited(exceeded).into());
+    }
+    Ok(())
+}
+
+/// Print one line per package, with the reason for failures
+fn print_verify_results(results: &[VerifyResult]) {
+    for result in results {
+        let release = result.release.as_deref().unwrap_or("-");
+        match result.status {
+            VerifyStatus::Ok => {
+                println!(
+                    "  {} {} {} ({})",
+                    "✓".green(),
+                    result.name,
+                    release.dimmed(),
+                    result.platforms.join(", ")
+                );
+                if !result.missing.is_empty() {
+                    println!(
+                        "      {}",
+                        format!("no asset for: {}", result.missing.join(", ")).yellow()
+                    );
+                }
+            }
+            VerifyStatus::Failed => {
+                println!("  {} {} {}", "✗".red(), result.name, release.dimmed());
+                if let Some(ref error) = result.error {
+                    println!("      {}", error.red());
+                }
+            }
+            VerifyStatus::Skipped => {
+                println!("  {} {} {}", "⚠".yellow(), result.name, "skipped".yellow());
+            }
+        }
+    }
+}
+
+/// Resolve a package's platforms against its latest stable release
+///
+/// Asset patterns are matched as `add` would; other platforms count as
+/// resolved when the release has an asset detected for them. Errors are
+/// returned only for failed requests (missing repo or release, rate limit).
+fn verify_package(github: &GitHubProvider, pkg: &Package) -> Result<VerifyResult> {
+    let repo = expand_env(&pkg.repo)?;
+    let (tag, assets) = github.fetch_release_assets(&repo, None, Channel::Stable, &pkg.yanked)?;
+
+    let detected = BinarySelector::extract_platforms(&assets);
+    let mut platforms = Vec::new();
+    let mut missing = Vec::new();
+    let mut ids: Vec<&String> = pkg.platforms.keys().collect();
+    ids.sort();
+    for id in ids {
+        let resolved = match pkg.platforms[id].asset_pattern {
+            Some(ref pattern) => AssetPattern::new(pattern).select(&assets, &tag).is_ok(),
+            None => detected.contains_key(id),
+        };
+        if resolved {
+            platforms.push(id.clone());
+        } else {
+            missing.push(id.clone());
+        }
+    }
+
+    let error = if assets.is_empty() {
+        Some(format!("Release {} has no downloadable assets", tag))
+    } else if platforms.is_empty() {
+        Some(format!(
+            "No asset in release {} matches any listed platform ({})",
+            tag,
+            missing.join(", ")
+        ))
+    } else {
+        None
+    };
+
+    Ok(VerifyResult {
+        name: pkg.name.clone(),
+        repo,
+        status: if error.is_some() {
+            VerifyStatus::Failed
+        } else {
+            VerifyStatus::Ok
+        },
+        release: Some(tag),
+        platforms,
+        missing,
+        error,
+    })
+}
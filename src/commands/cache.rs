@@ -0,0 +1,142 @@
+//! Cache command implementation
+
+use super::bucket::{self, BucketCommand};
+use crate::core::settings::format_duration;
+use crate::core::Config;
+use crate::utils::output::format_timestamp;
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use colored::Colorize;
+use std::fs;
+
+/// Cache subcommands
+pub enum CacheCommand {
+    Info,
+    Refresh,
+    Clear,
+}
+
+/// Run cache command
+pub fn run(cmd: CacheCommand) -> Result<()> {
+    match cmd {
+        CacheCommand::Info => run_info(),
+        CacheCommand::Refresh => bucket::run(BucketCommand::Refresh),
+        CacheCommand::Clear => run_clear(),
+    }
+}
+
+/// Show the manifest cache's age, contents and size
+fn run_info() -> Result<()> {
+    let config = Config::new()?;
+    let path = config.paths().manifest_cache_json();
+
+    let Ok(metadata) = fs::metadata(&path) else {
+        println!("{}", "No manifest cache yet".yellow());
+        println!("It is built by the next command that needs it, or run: wenget cache refresh");
+        return Ok(());
+    };
+
+    let cache = config.load_cache()?;
+    let ttl = config.cache_ttl()?;
+    let age = Utc::now() - cache.last_updated;
+
+    println!("{}", "Manifest cache".bold());
+    println!("{}", "═".repeat(60));
+    println!("{:<16} {}", "Location:".bold(), path.display());
+    println!(
+        "{:<16} {:.1} KB",
+        "Size:".bold(),
+        metadata.len() as f64 / 1024.0
+    );
+    println!(
+        "{:<16} {} ({} ago)",
+        "Updated:".bold(),
+        format_timestamp(&cache.last_updated),
+        format_age(age)
+    );
+
+    let remaining = Duration::seconds(ttl) - age;
+    let expiry = if remaining > Duration::zero() {
+        format!("expires in {}", format_age(remaining)).green()
+    } else {
+        "expired, rebuilt on next use".yellow()
+    };
+    println!(
+        "{:<16} {} ({})",
+        "TTL:".bold(),
+        format_duration(ttl.unsigned_abs()),
+        expiry
+    );
+    println!("{:<16} {}", "Packages:".bold(), cache.packages.len());
+    if !cache.scripts.is_empty() {
+        println!("{:<16} {}", "Scripts:".bold(), cache.scripts.len());
+    }
+
+    // Per-bucket breakdown
+    let mut sources: Vec<_> = cache.sources.iter().collect();
+    sources.sort_by(|a, b| a.0.cmp(b.0));
+
+    println!();
+    println!("{}", "Buckets:".bold());
+    if sources.is_empty() {
+        println!("  {}", "(none)".dimmed());
+    }
+    for (name, info) in sources {
+        let name = name.strip_prefix("bucket:").unwrap_or(name);
+        let fetched = info
+            .last_fetched
+            .map(|at| format!("fetched {} ago", format_age(Utc::now() - at)))
+            .unwrap_or_default();
+        let mut line = format!(
+            "  {:<20} {:>5} package(s)  {}",
+            name,
+            info.package_count,
+            fetched.dimmed()
+        );
+        if info.stale.is_some() {
+            line.push_str(&format!("  {}", "(stale)".yellow()));
+        }
+        println!("{}", line);
+    }
+
+    Ok(())
+}
+
+/// Delete the manifest cache and the per-bucket copies it is built from
+fn run_clear() -> Result<()> {
+    let config = Config::new()?;
+    let paths = config.paths();
+
+    config.invalidate_cache()?;
+
+    let bucket_dir = paths.bucket_cache_dir();
+    if bucket_dir.exists() {
+        fs::remove_dir_all(&bucket_dir).with_context(|| {
+            format!(
+                "Failed to remove bucket cache directory: {}",
+                bucket_dir.display()
+            )
+        })?;
+    }
+
+    println!("{} Manifest cache cleared", "✓".green());
+    println!("Buckets are refetched by the next command that needs them");
+
+    Ok(())
+}
+
+/// Format an elapsed time coarsely: `45s`, `12m`, `2h 5m`, `3d 4h`
+fn format_age(age: Duration) -> String {
+    let seconds = age.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", seconds)
+    }
+}
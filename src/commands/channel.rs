@@ -0,0 +1,46 @@
+//! Channel command implementation
+
+use crate::core::{Channel, Config};
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// Show or set the release channel of an installed package
+pub fn run(name: String, channel: Option<String>) -> Result<()> {
+    let config = Config::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    let pkg = installed
+        .packages
+        .get_mut(&name)
+        .with_context(|| format!("Package '{}' is not installed", name))?;
+
+    let Some(channel) = channel else {
+        println!("{:<16} {}", "Channel:".bold(), pkg.channel.as_str());
+        return Ok(());
+    };
+
+    let channel = Channel::parse(&channel).with_context(|| {
+        format!(
+            "Invalid channel '{}'. Expected one of: stable, prerelease",
+            channel
+        )
+    })?;
+
+    if pkg.channel == channel {
+        println!("{} already follows the {} channel", name, channel.as_str());
+        return Ok(());
+    }
+
+    pkg.channel = channel;
+    config.save_installed(&installed)?;
+
+    println!(
+        "{} {} now follows the {} channel",
+        "✓".green(),
+        name,
+        channel.as_str()
+    );
+    println!("Run 'wenget update {}' to pick up the latest release", name);
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Config command implementation
+
+use crate::core::{Config, Settings};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Config subcommands
+pub enum ConfigCommand {
+    Get { key: String },
+    Set { key: String, value: String },
+    Unset { key: String },
+    List,
+}
+
+/// Run config command
+pub fn run(cmd: ConfigCommand) -> Result<()> {
+    match cmd {
+        ConfigCommand::Get { key } => run_get(key),
+        ConfigCommand::Set { key, value } => run_set(key, value),
+        ConfigCommand::Unset { key } => run_unset(key),
+        ConfigCommand::List => run_list(),
+    }
+}
+
+/// Show a setting's value
+fn run_get(key: String) -> Result<()> {
+    let config = Config::new()?;
+    let settings = config.load_settings()?;
+
+    match settings.get(&key)? {
+        Some(value) => println!("{}", value),
+        None => println!("{}", "(not set)".dimmed()),
+    }
+
+    Ok(())
+}
+
+/// Change a setting
+fn run_set(key: String, value: String) -> Result<()> {
+    let config = Config::new()?;
+
+    // Ensure Wenget is initialized
+    if !config.is_initialized() {
+        config.init()?;
+    }
+
+    let mut settings = config.load_settings()?;
+    settings.set(&key, &value)?;
+    config.save_settings(&settings)?;
+
+    println!("{} {} = {}", "✓".green(), key, value);
+
+    Ok(())
+}
+
+/// Reset a setting to its default
+fn run_unset(key: String) -> Result<()> {
+    let config = Config::new()?;
+
+    let mut settings = config.load_settings()?;
+    settings.unset(&key)?;
+
+    if config.is_initialized() {
+        config.save_settings(&settings)?;
+    }
+
+    println!("{} {} reset to default", "✓".green(), key);
+
+    Ok(())
+}
+
+/// List all settings
+fn run_list() -> Result<()> {
+    let config = Config::new()?;
+    let settings = config.load_settings()?;
+
+    println!("{}", "Settings:".bold());
+    for key in Settings::KEYS {
+        let value = settings
+            .get(key)?
+            .unwrap_or_else(|| "(not set)".dimmed().to_string());
+        println!("  {:<16} {}", key, value);
+    }
+
+    println!();
+    println!("File: {}", config.paths().settings_json().display());
+
+    Ok(())
+}
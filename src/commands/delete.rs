@@ -1,5 +1,7 @@
 //! Delete command implementation
 
+use super::self_uninstall::UninstallPlan;
+use crate::core::history::{HistoryAction, HistoryEvent};
 use crate::core::{Config, WenPaths};
 use anyhow::{Context, Result};
 use colored::Colorize;
@@ -7,10 +9,7 @@ use glob::Pattern;
 use std::env;
 use std::fs;
 use std::io::{self, Write as IoWrite};
-use std::path::Path;
-
-#[cfg(not(windows))]
-use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
 
 /// Delete installed packages
 pub fn run(names: Vec<String>, yes: bool, force: bool) -> Result<()> {
@@ -126,8 +125,8 @@ pub fn run(names: Vec<String>, yes: bool, force: bool) -> Result<()> {
 }
 
 /// Delete a single package
-fn delete_package(
-    _config: &Config,
+pub(crate) fn delete_package(
+    config: &Config,
     paths: &WenPaths,
     installed: &mut crate::core::InstalledManifest,
     name: &str,
@@ -138,42 +137,53 @@ fn delete_package(
         fs::remove_dir_all(&app_dir)?;
     }
 
-    // Remove symlink/shim
-    let bin_path = paths.bin_shim_path(name);
-    if bin_path.exists() {
-        fs::remove_file(&bin_path)?;
+    // Remove previous versions kept with --keep-versions
+    let versions_dir = paths.backups_dir(name);
+    if versions_dir.exists() {
+        fs::remove_dir_all(&versions_dir)?;
+    }
+
+    // Remove symlink/shim (from wherever it was placed at install time)
+    let bin_path = match installed.get_package(name) {
+        Some(pkg) if pkg.no_shim => None,
+        Some(pkg) => match pkg.shim_path {
+            Some(ref shim_path) => Some(PathBuf::from(shim_path)),
+            None => Some(paths.bin_shim_path(&pkg.command_name)),
+        },
+        None => Some(paths.bin_shim_path(name)),
+    };
+    let mut launchers: Vec<PathBuf> = bin_path.into_iter().collect();
+    if let Some(pkg) = installed.get_package(name) {
+        launchers.extend(pkg.extra_shims.iter().map(PathBuf::from));
+    }
+    for bin_path in launchers {
+        if bin_path.exists() || bin_path.is_symlink() {
+            fs::remove_file(&bin_path)?;
+        }
     }
 
     // Remove from installed manifest
-    installed.remove_package(name);
+    if let Some(pkg) = installed.remove_package(name) {
+        config.record_history(HistoryEvent::new(
+            HistoryAction::Remove,
+            name,
+            Some(&pkg.version),
+            None,
+            &pkg.source,
+        ));
+    }
 
     Ok(())
 }
 
 /// Delete Wenget itself (complete uninstallation)
+///
+/// Runs the same removal as `wenget self uninstall`, but asks for
+/// confirmation instead of only previewing without `--yes`.
 fn delete_self(yes: bool) -> Result<()> {
-    println!("{}", "Wenget Self-Deletion".bold().red());
-    println!("{}", "═".repeat(60));
-    println!();
-    println!(
-        "{}",
-        "This will COMPLETELY remove Wenget from your system:".yellow()
-    );
-    println!();
-
-    let paths = WenPaths::new()?;
-
-    println!("  {} All Wenget directories and files:", "1.".bold());
-    println!("     {}", paths.root().display());
-    println!();
-    println!("  {} Wenget from PATH environment variable", "2.".bold());
-    println!();
-    println!("  {} The wenget executable itself", "3.".bold());
-
-    // Get current executable path
-    let exe_path = env::current_exe().context("Failed to get current executable path")?;
-    println!("     {}", exe_path.display());
-    println!();
+    let config = Config::new()?;
+    let plan = UninstallPlan::new(&config, false, false)?;
+    plan.print(config.paths());
 
     // Confirm deletion
     if !yes {
@@ -192,58 +202,14 @@ fn delete_self(yes: bool) -> Result<()> {
             println!("{}", "Deletion cancelled".green());
             return Ok(());
         }
+        println!();
     }
 
-    println!();
-    println!("{}", "Proceeding with uninstallation...".cyan());
-    println!();
-
-    // Step 1: Remove from PATH
-    println!("{} Removing from PATH...", "1.".bold());
-    match remove_from_path(&paths.bin_dir()) {
-        Ok(()) => println!("   {} PATH updated", "✓".green()),
-        Err(e) => println!("   {} Failed to update PATH: {}", "⚠".yellow(), e),
-    }
-    println!();
-
-    // Check if executable is inside .wenget directory
-    let exe_in_wenget = exe_path.starts_with(paths.root());
-
-    // Step 2: Delete Wenget directories
-    println!("{} Deleting Wenget directories...", "2.".bold());
-    if exe_in_wenget {
-        println!(
-            "   {} Scheduled for deletion (executable is inside .wenget)",
-            "✓".yellow()
-        );
-        println!("      Directory will be deleted after wenget exits");
-    } else if paths.root().exists() {
-        match fs::remove_dir_all(paths.root()) {
-            Ok(()) => println!("   {} Deleted: {}", "✓".green(), paths.root().display()),
-            Err(e) => println!("   {} Failed to delete directory: {}", "✗".red(), e),
-        }
-    } else {
-        println!("   {} Directory already removed", "✓".green());
-    }
-    println!();
-
-    // Step 3: Delete the executable
-    println!("{} Deleting wenget executable...", "3.".bold());
-    delete_executable(&exe_path, exe_in_wenget, paths.root())?;
-
-    println!();
-    println!("{}", "═".repeat(60));
-    println!();
-    println!("{}", "Wenget has been uninstalled.".green().bold());
-    println!();
-    println!("{}", "Thank you for using Wenget!".cyan());
-    println!();
-
-    Ok(())
+    plan.execute(config.paths())
 }
 
 /// Remove Wenget bin directory from PATH
-fn remove_from_path(bin_dir: &Path) -> Result<()> {
+pub(crate) fn remove_from_path(bin_dir: &Path) -> Result<()> {
     let bin_dir_str = bin_dir.to_string_lossy();
 
     #[cfg(windows)]
@@ -302,6 +268,7 @@ fn remove_from_path_unix(bin_dir: &str) -> Result<()> {
         home.join(".bash_profile"),
         home.join(".zshrc"),
         home.join(".profile"),
+        home.join(".config").join("fish").join("config.fish"),
     ];
 
     for config_path in shell_configs {
@@ -341,7 +308,11 @@ fn remove_from_shell_config(config_path: &Path, bin_dir: &str) -> Result<()> {
 }
 
 /// Delete the executable (platform-specific implementation)
-fn delete_executable(exe_path: &Path, exe_in_wenget: bool, wenget_root: &Path) -> Result<()> {
+pub(crate) fn delete_executable(
+    exe_path: &Path,
+    exe_in_wenget: bool,
+    wenget_root: &Path,
+) -> Result<()> {
     #[cfg(windows)]
     {
         delete_executable_windows(exe_path, exe_in_wenget, wenget_root)
@@ -0,0 +1,485 @@
+//! Doctor command implementation
+//!
+//! Looks for problems that keep installed commands from running: launchers
+//! that are missing or point nowhere, packages whose install directory was
+//! deleted by hand, and a launcher directory that is not on PATH. With
+//! `--fix`, each problem that can be repaired is fixed after confirmation.
+
+use super::add::create_launcher;
+use super::delete::delete_package;
+use super::refresh_shims::{launcher_is_current, launcher_target};
+use crate::core::manifest::PackageSource;
+use crate::core::paths::is_dir_in_path;
+use crate::core::{Config, InstalledManifest, ShimMode};
+use anyhow::Result;
+use colored::Colorize;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(not(windows))]
+use crate::utils::shell::{add_path_entry, RcUpdate, Shell};
+#[cfg(not(windows))]
+use anyhow::Context;
+
+/// A problem found by `wenget doctor`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// Recorded as installed, but the install directory is gone
+    MissingInstallDir { name: String, install_path: String },
+
+    /// The launcher is missing or does not run the package's executable
+    BrokenLauncher {
+        name: String,
+        launcher: PathBuf,
+        target: PathBuf,
+        command: String,
+        mode: ShimMode,
+    },
+
+    /// The executable the launcher should run is gone; only a reinstall helps
+    MissingExecutable {
+        name: String,
+        exe_path: Option<PathBuf>,
+    },
+
+    /// The launcher directory is not on PATH
+    BinNotInPath { dir: PathBuf },
+}
+
+impl Problem {
+    /// One-line description of the problem
+    pub fn describe(&self) -> String {
+        match self {
+            Self::MissingInstallDir { name, install_path } => {
+                format!("{}: install directory missing ({})", name, install_path)
+            }
+            Self::BrokenLauncher { name, launcher, .. }
+                if launcher.exists() || launcher.is_symlink() =>
+            {
+                format!(
+                    "{}: launcher {} does not run the installed executable",
+                    name,
+                    launcher.display()
+                )
+            }
+            Self::BrokenLauncher { name, launcher, .. } => {
+                format!("{}: launcher {} is missing", name, launcher.display())
+            }
+            Self::MissingExecutable { name, exe_path } => {
+                let location = exe_path
+                    .as_ref()
+                    .map(|p| format!(" at {}", p.display()))
+                    .unwrap_or_default();
+                format!("{}: executable not found{}", name, location)
+            }
+            Self::BinNotInPath { dir } => format!("{} is not in PATH", dir.display()),
+        }
+    }
+
+    /// What was done once the problem is fixed
+    pub fn fixed_message(&self) -> String {
+        match self {
+            Self::MissingInstallDir { name, .. } => {
+                format!("{}: removed from the installed packages", name)
+            }
+            Self::BrokenLauncher { name, launcher, .. } => {
+                format!("{}: launcher {} recreated", name, launcher.display())
+            }
+            Self::MissingExecutable { name, .. } => format!("{}: reinstalled", name),
+            Self::BinNotInPath { dir } => format!("{} added to PATH", dir.display()),
+        }
+    }
+
+    /// What the user has to do when `--fix` cannot repair the problem
+    pub fn manual_action(&self) -> String {
+        match self {
+            Self::MissingInstallDir { name, .. }
+            | Self::BrokenLauncher { name, .. }
+            | Self::MissingExecutable { name, .. } => format!("run 'wenget reinstall {}'", name),
+            Self::BinNotInPath { dir } => format!("add {} to your PATH", dir.display()),
+        }
+    }
+
+    /// Whether `--fix` can repair the problem
+    pub fn is_fixable(&self) -> bool {
+        !matches!(self, Self::MissingExecutable { .. })
+    }
+}
+
+/// Check the installation and, with `fix`, repair what can be repaired
+pub fn run(fix: bool, yes: bool) -> Result<()> {
+    let config = Config::new()?;
+    let settings = config.load_settings()?;
+    let bin_dir = settings
+        .global_bin
+        .unwrap_or_else(|| config.paths().bin_dir());
+
+    println!("{}", "Checking wenget installation...".bold());
+    println!();
+
+    let mut installed = config.get_or_create_installed()?;
+    let mut problems = check_packages(&config, &installed);
+    if !is_dir_in_path(&bin_dir) {
+        problems.push(Problem::BinNotInPath { dir: bin_dir });
+    }
+
+    if problems.is_empty() {
+        println!("{}", "No problems found.".green());
+        return Ok(());
+    }
+
+    for problem in &problems {
+        let symbol = if problem.is_fixable() {
+            "✗".red()
+        } else {
+            "⚠".yellow()
+        };
+        println!("  {} {}", symbol, problem.describe());
+    }
+    println!();
+
+    if !fix {
+        let fixable = problems.iter().filter(|p| p.is_fixable()).count();
+        if fixable > 0 {
+            println!(
+                "Run {} to repair {} problem(s).",
+                "wenget doctor --fix".cyan(),
+                fixable
+            );
+        }
+        for problem in problems.iter().filter(|p| !p.is_fixable()) {
+            println!("  {} {}", "•".cyan(), problem.manual_action());
+        }
+        return Ok(());
+    }
+
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut fixed = Vec::new();
+    let mut manual = Vec::new();
+
+    for problem in problems {
+        if !problem.is_fixable() {
+            manual.push(problem);
+            continue;
+        }
+        if !yes && !confirm(&fix_question(&problem, &home))? {
+            manual.push(problem);
+            continue;
+        }
+        match fix_problem(&config, &mut installed, &problem, &home) {
+            Ok(()) => {
+                println!("  {} {}", "✓".green(), problem.fixed_message());
+                fixed.push(problem);
+            }
+            Err(e) => {
+                println!("  {} {}: {:#}", "✗".red(), problem.describe(), e);
+                manual.push(problem);
+            }
+        }
+    }
+
+    config.save_installed(&installed)?;
+
+    println!();
+    println!("{}", "Summary:".bold());
+    println!("  {} problem(s) fixed", fixed.len().to_string().green());
+    if fixed
+        .iter()
+        .any(|p| matches!(p, Problem::BinNotInPath { .. }))
+    {
+        println!(
+            "  {} Restart your shell to pick up the PATH change",
+            "ℹ".cyan()
+        );
+    }
+    if !manual.is_empty() {
+        println!(
+            "  {} problem(s) need manual action:",
+            manual.len().to_string().yellow()
+        );
+        for problem in &manual {
+            println!(
+                "    {} {}: {}",
+                "•".cyan(),
+                problem.describe(),
+                problem.manual_action()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Find packages with a missing install directory, launcher or executable
+///
+/// Scripts and packages installed with `--no-shim` have no launcher to check.
+pub fn check_packages(config: &Config, installed: &InstalledManifest) -> Vec<Problem> {
+    let paths = config.paths();
+    let mut names: Vec<&String> = installed.packages.keys().collect();
+    names.sort();
+
+    let mut problems = Vec::new();
+    for name in names {
+        let pkg = &installed.packages[name];
+        if pkg.is_broken() {
+            problems.push(Problem::MissingInstallDir {
+                name: name.clone(),
+                install_path: pkg.install_path.clone(),
+            });
+            continue;
+        }
+        if pkg.no_shim || matches!(pkg.source, PackageSource::Script { .. }) {
+            continue;
+        }
+
+        let launcher = match pkg.shim_path {
+            Some(ref shim_path) => PathBuf::from(shim_path),
+            None => paths.bin_shim_path(&pkg.command_name),
+        };
+        // Packages installed before the executable path was recorded fall
+        // back to where their launcher points
+        let exe_path = pkg
+            .exe_path
+            .as_ref()
+            .map(PathBuf::from)
+            .or_else(|| launcher_target(&launcher));
+
+        match exe_path {
+            Some(target) if target.is_file() => {
+                if !launcher_is_current(&launcher, &target, pkg.shim_mode) {
+                    problems.push(Problem::BrokenLauncher {
+                        name: name.clone(),
+                        launcher,
+                        target,
+                        command: pkg.command_name.clone(),
+                        mode: pkg.shim_mode,
+                    });
+                }
+            }
+            exe_path => problems.push(Problem::MissingExecutable {
+                name: name.clone(),
+                exe_path,
+            }),
+        }
+    }
+
+    problems
+}
+
+/// Repair a single problem; `home` is where the shell startup files live
+pub fn fix_problem(
+    config: &Config,
+    installed: &mut InstalledManifest,
+    problem: &Problem,
+    home: &Path,
+) -> Result<()> {
+    match problem {
+        Problem::MissingInstallDir { name, .. } => {
+            delete_package(config, config.paths(), installed, name)
+        }
+        Problem::BrokenLauncher {
+            launcher,
+            target,
+            command,
+            mode,
+            ..
+        } => create_launcher(target, launcher, command, *mode),
+        Problem::MissingExecutable { name, .. } => {
+            anyhow::bail!(
+                "cannot be fixed automatically; run 'wenget reinstall {}'",
+                name
+            )
+        }
+        Problem::BinNotInPath { dir } => add_to_path(dir, home),
+    }
+}
+
+/// The question asked before fixing a problem
+fn fix_question(problem: &Problem, home: &Path) -> String {
+    match problem {
+        Problem::MissingInstallDir { name, .. } => {
+            format!("Remove {} from the installed packages?", name)
+        }
+        Problem::BrokenLauncher { launcher, .. } => {
+            format!("Recreate launcher {}?", launcher.display())
+        }
+        Problem::MissingExecutable { name, .. } => format!("Reinstall {}?", name),
+        #[cfg(not(windows))]
+        Problem::BinNotInPath { dir } => format!(
+            "Add {} to {}?",
+            dir.display(),
+            Shell::detect().rc_file(home).display()
+        ),
+        #[cfg(windows)]
+        Problem::BinNotInPath { dir } => {
+            let _ = home;
+            format!("Add {} to your user Path?", dir.display())
+        }
+    }
+}
+
+/// Put `dir` on PATH through the user's shell startup file
+#[cfg(not(windows))]
+fn add_to_path(dir: &Path, home: &Path) -> Result<()> {
+    let shell = Shell::detect();
+    let rc_file = shell.rc_file(home);
+    let update = add_path_entry(&rc_file, &shell.path_line(dir), dir)
+        .with_context(|| format!("Failed to update {}", rc_file.display()))?;
+    if let RcUpdate::Appended {
+        backup: Some(backup),
+    } = update
+    {
+        println!("    Previous version saved as {}", backup.display());
+    }
+    Ok(())
+}
+
+/// Put `dir` on the user Path
+#[cfg(windows)]
+fn add_to_path(dir: &Path, _home: &Path) -> Result<()> {
+    super::init::setup_path_windows(&dir.to_string_lossy())
+}
+
+/// Ask a yes/no question, defaulting to yes
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [Y/n] ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input.is_empty() || input == "y" || input == "yes")
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::core::{InstalledPackage, WenPaths};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn make_package(paths: &WenPaths, name: &str) -> InstalledPackage {
+        let app_dir = paths.app_dir(name);
+        InstalledPackage {
+            install_path: app_dir.to_string_lossy().into_owned(),
+            shim_path: Some(paths.bin_shim_path(name).to_string_lossy().into_owned()),
+            exe_path: Some(app_dir.join(name).to_string_lossy().into_owned()),
+            ..InstalledPackage::test_default(name)
+        }
+    }
+
+    /// Install `name` by hand: an executable in its app directory and a launcher
+    fn install(config: &Config, installed: &mut InstalledManifest, name: &str) {
+        let paths = config.paths();
+        let pkg = make_package(paths, name);
+        let exe = PathBuf::from(pkg.exe_path.as_ref().unwrap());
+        fs::create_dir_all(exe.parent().unwrap()).unwrap();
+        fs::write(&exe, "#!/bin/sh\n").unwrap();
+        create_launcher(&exe, &paths.bin_shim_path(name), name, ShimMode::Symlink).unwrap();
+        installed.upsert_package(name.to_string(), pkg);
+    }
+
+    fn setup() -> (TempDir, Config, InstalledManifest) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        let mut installed = InstalledManifest::new();
+        install(&config, &mut installed, "ok");
+        (temp_dir, config, installed)
+    }
+
+    #[test]
+    fn test_healthy_packages_have_no_problems() {
+        let (_temp_dir, config, installed) = setup();
+        assert!(check_packages(&config, &installed).is_empty());
+    }
+
+    #[test]
+    fn test_fix_missing_launcher() {
+        let (temp_dir, config, mut installed) = setup();
+        install(&config, &mut installed, "tool");
+        let launcher = config.paths().bin_shim_path("tool");
+        fs::remove_file(&launcher).unwrap();
+
+        let problems = check_packages(&config, &installed);
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(problems[0], Problem::BrokenLauncher { ref name, .. } if name == "tool"));
+        assert!(problems[0].describe().ends_with("is missing"));
+
+        fix_problem(&config, &mut installed, &problems[0], temp_dir.path()).unwrap();
+        assert_eq!(
+            fs::read_link(&launcher).unwrap(),
+            config.paths().app_dir("tool").join("tool")
+        );
+        assert!(check_packages(&config, &installed).is_empty());
+    }
+
+    #[test]
+    fn test_fix_dangling_launcher() {
+        let (temp_dir, config, mut installed) = setup();
+        install(&config, &mut installed, "tool");
+        let launcher = config.paths().bin_shim_path("tool");
+        fs::remove_file(&launcher).unwrap();
+        std::os::unix::fs::symlink(temp_dir.path().join("gone"), &launcher).unwrap();
+
+        let problems = check_packages(&config, &installed);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].describe().contains("does not run"));
+
+        fix_problem(&config, &mut installed, &problems[0], temp_dir.path()).unwrap();
+        assert!(check_packages(&config, &installed).is_empty());
+    }
+
+    #[test]
+    fn test_fix_missing_install_dir() {
+        let (temp_dir, config, mut installed) = setup();
+        install(&config, &mut installed, "tool");
+        fs::remove_dir_all(config.paths().app_dir("tool")).unwrap();
+
+        let problems = check_packages(&config, &installed);
+        assert_eq!(
+            problems,
+            vec![Problem::MissingInstallDir {
+                name: "tool".to_string(),
+                install_path: config
+                    .paths()
+                    .app_dir("tool")
+                    .to_string_lossy()
+                    .into_owned(),
+            }]
+        );
+
+        fix_problem(&config, &mut installed, &problems[0], temp_dir.path()).unwrap();
+        assert!(installed.get_package("tool").is_none());
+        assert!(installed.get_package("ok").is_some());
+        // The launcher that pointed into the deleted directory goes too
+        assert!(!config.paths().bin_shim_path("tool").is_symlink());
+    }
+
+    #[test]
+    fn test_missing_executable_needs_manual_action() {
+        let (temp_dir, config, mut installed) = setup();
+        install(&config, &mut installed, "tool");
+        fs::remove_file(config.paths().app_dir("tool").join("tool")).unwrap();
+
+        let problems = check_packages(&config, &installed);
+        assert_eq!(problems.len(), 1);
+        assert!(!problems[0].is_fixable());
+        assert_eq!(problems[0].manual_action(), "run 'wenget reinstall tool'");
+        assert!(fix_problem(&config, &mut installed, &problems[0], temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_fix_bin_not_in_path() {
+        let (temp_dir, config, mut installed) = setup();
+        let dir = config.paths().bin_dir();
+        let problem = Problem::BinNotInPath { dir: dir.clone() };
+
+        fix_problem(&config, &mut installed, &problem, temp_dir.path()).unwrap();
+        let rc_file = Shell::detect().rc_file(temp_dir.path());
+        let content = fs::read_to_string(&rc_file).unwrap();
+        assert!(content.contains(&*dir.to_string_lossy()));
+
+        // Fixing it again does not add a second entry
+        fix_problem(&config, &mut installed, &problem, temp_dir.path()).unwrap();
+        assert_eq!(fs::read_to_string(&rc_file).unwrap(), content);
+    }
+}
@@ -0,0 +1,266 @@
+//! Download command implementation
+//!
+//! Fetches release assets without installing anything, e.g. to prepare an
+//! offline mirror. Every file gets a `.sha256` sidecar next to it.
+
+use super::add::source_headers;
+use crate::core::{Channel, Config, Package, Platform, PlatformBinary};
+use crate::downloader::{self, checksum};
+use crate::error::WengetError;
+use crate::package_resolver::{PackageInput, PackageResolver};
+use crate::providers::GitHubProvider;
+use crate::utils::http::HostHeaders;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Manifest written next to an `--all-platforms` bundle
+pub const BUNDLE_MANIFEST: &str = "manifest.json";
+
+/// Contents of a bundle's `manifest.json`: the package with its bundled
+/// assets and checksums, plus the release version when it could be looked up
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(flatten)]
+    pub package: Package,
+}
+
+/// Download package assets into `dest` (the current directory by default)
+///
+/// Without `all_platforms`, the asset for `platform` (or the current
+/// platform) is saved as `<dest>/<file>`. With it, every platform in the
+/// package's manifest is saved as `<dest>/<name>/<platform>/<file>` along with
+/// a `manifest.json` recording the downloaded assets and their checksums.
+pub fn run(
+    names: Vec<String>,
+    platform: Option<String>,
+    all_platforms: bool,
+    dest: Option<PathBuf>,
+    retries: Option<u32>,
+) -> Result<()> {
+    let config = Config::new()?;
+
+    if names.is_empty() {
+        println!("{}", "No package names or URLs provided".yellow());
+        println!("Usage: wenget download <name|url>...");
+        println!();
+        println!("Examples:");
+        println!("  wenget download ripgrep                          # Current platform");
+        println!("  wenget download ripgrep --platform linux-x86_64  # Another platform");
+        println!("  wenget download ripgrep --all-platforms --dest mirror/");
+        return Ok(());
+    }
+
+    let settings = config.load_settings()?;
+    let retries = retries
+        .or(settings.download_retries)
+        .unwrap_or(downloader::DEFAULT_RETRIES);
+    let dest = match dest {
+        Some(dir) => dir,
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+    fs::create_dir_all(&dest)
+        .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
+
+    let cache = config.get_or_rebuild_cache()?;
+    let resolver = PackageResolver::new(&config, &cache)?;
+    let github = GitHubProvider::new()?;
+
+    let mut downloaded = 0;
+    let mut failed = 0;
+
+    for name in &names {
+        let packages = match resolver.resolve(&PackageInput::parse(name)) {
+            Ok(packages) => packages,
+            Err(e) => {
+                eprintln!("{} {}: {}", "Error".red().bold(), name, e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        for resolved in packages {
+            let pkg = &resolved.package;
+            println!("{} {}...", "Downloading".cyan(), pkg.name);
+            let headers = source_headers(&config, &resolved.source)?;
+
+            if all_platforms {
+                let (ok, errors) = download_all_platforms(&github, pkg, &dest, retries, &headers)?;
+                downloaded += ok;
+                failed += errors;
+                continue;
+            }
+
+            match download_platform(&github, pkg, platform.as_deref(), &dest, retries, &headers) {
+                Ok(()) => downloaded += 1,
+                Err(e) => {
+                    println!("  {} {}: {:#}", "✗".red(), pkg.name, e);
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    println!();
+    println!("{}", "Summary:".bold());
+    if downloaded > 0 {
+        println!(
+            "  {} {} asset(s) downloaded to {}",
+            "✓".green(),
+            downloaded,
+            dest.display()
+        );
+    }
+    if failed > 0 {
+        println!("  {} {} download(s) failed", "✗".red(), failed);
+    }
+
+    Ok(())
+}
+
+/// Download the asset for one platform straight into `dest`
+fn download_platform(
+    github: &GitHubProvider,
+    pkg: &Package,
+    platform: Option<&str>,
+    dest: &Path,
+    retries: u32,
+    headers: &HostHeaders,
+) -> Result<()> {
+    let platform_ids = match platform {
+        Some(id) => vec![id.to_string()],
+        None => Platform::current().possible_identifiers(),
+    };
+
+    let binary = platform_ids
+        .iter()
+        .find_map(|id| pkg.platforms.get(id))
+        .ok_or_else(|| WengetError::PlatformUnsupported {
+            name: pkg.name.clone(),
+            platform: platform_ids.first().cloned().unwrap_or_default(),
+        })?;
+    let binary = resolve_binary(github, pkg, binary)?;
+
+    download_with_checksum(&binary.url, dest, retries, headers)?;
+    Ok(())
+}
+
+/// Download every platform's asset into per-platform subdirectories
+///
+/// Platforms whose asset is gone (404) are skipped with a warning. Returns
+/// the number of downloaded and failed assets.
+fn download_all_platforms(
+    github: &GitHubProvider,
+    pkg: &Package,
+    dest: &Path,
+    retries: u32,
+    headers: &HostHeaders,
+) -> Result<(usize, usize)> {
+    let bundle_dir = dest.join(&pkg.name);
+    let mut platform_ids: Vec<&String> = pkg.platforms.keys().collect();
+    platform_ids.sort();
+
+    let mut bundled = HashMap::new();
+    let mut failed = 0;
+
+    for platform_id in platform_ids {
+        let result = resolve_binary(github, pkg, &pkg.platforms[platform_id]).and_then(|binary| {
+            let digest = download_with_checksum(
+                &binary.url,
+                &bundle_dir.join(platform_id),
+                retries,
+                headers,
+            )?;
+            Ok(PlatformBinary {
+                checksum: Some(digest),
+                ..binary
+            })
+        });
+
+        match result {
+            Ok(binary) => {
+                bundled.insert(platform_id.clone(), binary);
+            }
+            Err(e) if is_not_found(&e) => {
+                println!(
+                    "  {} {}: asset not found (404), skipping",
+                    "⚠".yellow(),
+                    platform_id
+                );
+            }
+            Err(e) => {
+                println!("  {} {}: {:#}", "✗".red(), platform_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if !bundled.is_empty() {
+        let downloaded = bundled.len();
+        let bundle = BundleManifest {
+            version: github
+                .fetch_latest_version(&pkg.repo, Channel::Stable, &pkg.yanked)
+                .ok(),
+            package: Package {
+                platforms: bundled,
+                ..pkg.clone()
+            },
+        };
+        let manifest_path = bundle_dir.join(BUNDLE_MANIFEST);
+        let json = serde_json::to_string_pretty(&bundle)?;
+        fs::write(&manifest_path, json)
+            .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+
+        return Ok((downloaded, failed));
+    }
+
+    Ok((0, failed))
+}
+
+/// Resolve a manifest asset pattern to a concrete download
+fn resolve_binary(
+    github: &GitHubProvider,
+    pkg: &Package,
+    binary: &PlatformBinary,
+) -> Result<PlatformBinary> {
+    match binary.asset_pattern {
+        Some(ref pattern) => {
+            github.resolve_asset_pattern(&pkg.repo, pattern, Channel::Stable, &pkg.yanked, None)
+        }
+        None => Ok(binary.clone()),
+    }
+}
+
+/// Download a URL into `dir`, record its SHA-256 sidecar and return the digest
+fn download_with_checksum(
+    url: &str,
+    dir: &Path,
+    retries: u32,
+    headers: &HostHeaders,
+) -> Result<String> {
+    let filename = url.split('/').next_back().context("Invalid download URL")?;
+
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    let path = dir.join(filename);
+
+    downloader::download_file_with_headers(url, &path, retries, headers)?;
+    let digest = checksum::sha256_file(&path)?;
+    checksum::write_sidecar(&path, &digest)?;
+
+    println!("  {} {} (sha256: {})", "✓".green(), path.display(), digest);
+    Ok(digest)
+}
+
+/// Whether a download failed because the asset doesn't exist
+fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        WengetError::find(err),
+        Some(WengetError::HttpStatus { status: 404, .. })
+    )
+}
@@ -0,0 +1,77 @@
+//! History command implementation
+
+use crate::core::history::HistoryAction;
+use crate::core::Config;
+use crate::utils::output::format_timestamp;
+use anyhow::Result;
+use colored::Colorize;
+
+/// Show recorded install, upgrade and remove events, newest first
+pub fn run(limit: Option<usize>, package: Option<String>) -> Result<()> {
+    let config = Config::new()?;
+    let mut events = config.load_history()?;
+
+    if let Some(ref name) = package {
+        events.retain(|e| &e.package == name);
+    }
+
+    if events.is_empty() {
+        match package {
+            Some(name) => println!("{}", format!("No history recorded for {}", name).yellow()),
+            None => println!("{}", "No history recorded".yellow()),
+        }
+        return Ok(());
+    }
+
+    events.reverse();
+    let total = events.len();
+    if let Some(limit) = limit {
+        events.truncate(limit);
+    }
+
+    // Print header
+    println!(
+        "{:<21} {:<8} {:<20} {:<24} {}",
+        "TIME".bold(),
+        "ACTION".bold(),
+        "PACKAGE".bold(),
+        "VERSION".bold(),
+        "SOURCE".bold()
+    );
+    println!("{}", "─".repeat(100));
+
+    for event in &events {
+        let time = format_timestamp(&event.timestamp);
+
+        let action = match event.action {
+            HistoryAction::Install => event.action.as_str().green(),
+            HistoryAction::Upgrade => event.action.as_str().yellow(),
+            HistoryAction::Remove => event.action.as_str().red(),
+        };
+
+        let version = match (&event.from_version, &event.to_version) {
+            (Some(from), Some(to)) if from != to => format!("{} → {}", from, to),
+            (_, Some(to)) => to.clone(),
+            (Some(from), None) => from.clone(),
+            (None, None) => "-".to_string(),
+        };
+
+        println!(
+            "{:<21} {:<8} {:<20} {:<24} {}",
+            time,
+            action,
+            event.package,
+            version,
+            event.source.as_deref().unwrap_or("-").dimmed()
+        );
+    }
+
+    println!();
+    if events.len() < total {
+        println!("Showing {} of {} event(s)", events.len(), total);
+    } else {
+        println!("Total: {} event(s)", total);
+    }
+
+    Ok(())
+}
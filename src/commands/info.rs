@@ -2,15 +2,127 @@
 //!
 //! Shows detailed package information from cache (with glob support) or GitHub URL
 
-use crate::core::Config;
+use crate::cache::{ReleaseSizeCache, ReleaseSizes};
+use crate::core::manifest::{PackageSource, ScriptItem};
+use crate::core::platform::emulation_allowed;
+use crate::core::{
+    AssetChange, BinarySelector, Config, InstalledManifest, InstalledPackage, Package, Platform,
+};
 use crate::package_resolver::{PackageInput, PackageResolver, ResolvedPackage};
+use crate::providers::GitHubProvider;
+use crate::utils::output::{format_timestamp, message_lines};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use serde::Serialize;
+
+/// Options for the info command
+#[derive(Debug, Clone, Default)]
+pub struct InfoOptions {
+    /// Refetch bucket manifests and take package details straight from
+    /// GitHub instead of the manifest cache (--remote)
+    pub remote: bool,
+
+    /// Diff the latest release assets against this version (--compare);
+    /// `Some(None)` means the installed version
+    pub compare: Option<Option<String>>,
+
+    /// Print the resolved manifest entries as JSON instead (--raw-manifest)
+    pub raw_manifest: bool,
+
+    /// Which platforms' assets to list (--platform)
+    pub platform: PlatformFilter,
+
+    /// Show download sizes from the latest release's assets instead of the
+    /// manifest (--latest-asset-size)
+    pub latest_asset_size: bool,
+
+    /// Buckets packages are looked up in (--sources); all when empty
+    pub sources: Vec<String>,
+
+    /// Show every installed package instead of the named ones (--installed)
+    pub installed: bool,
+
+    /// Print the installed packages as JSON (--json with --installed)
+    pub json: bool,
+}
+
+/// Platform selection for `info --platform <id|current|all>`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PlatformFilter {
+    /// Every platform the package supports
+    #[default]
+    All,
+    /// Only the asset that would be installed on this machine
+    Current,
+    /// One platform id; a partial id like `linux-x86_64` matches its variants
+    Id(String),
+}
+
+impl PlatformFilter {
+    /// Parse a `--platform` value
+    pub fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "all" => Self::All,
+            "current" => Self::Current,
+            id => Self::Id(id.to_string()),
+        }
+    }
+
+    /// Whether a platform id is selected
+    fn matches(&self, id: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Current => Platform::current()
+                .possible_identifiers()
+                .iter()
+                .any(|p| p == id),
+            Self::Id(wanted) => {
+                id == wanted
+                    || id
+                        .strip_prefix(wanted.as_str())
+                        .is_some_and(|rest| rest.starts_with('-'))
+            }
+        }
+    }
+
+    /// Selected platform ids of a package, sorted
+    ///
+    /// `Current` yields at most one id: the best match for this machine.
+    fn select<'a>(&self, pkg: &'a Package) -> Vec<&'a String> {
+        if *self == Self::Current {
+            return Platform::current()
+                .possible_identifiers()
+                .iter()
+                .find_map(|id| pkg.platforms.get_key_value(id).map(|(k, _)| k))
+                .into_iter()
+                .collect();
+        }
+
+        let mut ids: Vec<_> = pkg.platforms.keys().filter(|id| self.matches(id)).collect();
+        ids.sort();
+        ids
+    }
+}
 
 /// Show package and script information
-pub fn run(names: Vec<String>) -> Result<()> {
+pub fn run(names: Vec<String>, options: InfoOptions) -> Result<()> {
+    let InfoOptions {
+        remote,
+        compare,
+        raw_manifest,
+        platform,
+        latest_asset_size,
+        sources,
+        installed: all_installed,
+        json,
+    } = options;
     let config = Config::new()?;
 
+    if all_installed {
+        return show_installed(&config, &platform, json);
+    }
+
     if names.is_empty() {
         println!("{}", "No package names or URLs provided".yellow());
         println!("Usage: wenget info <name|url> [<name|url>...]");
@@ -19,6 +131,7 @@ pub fn run(names: Vec<String>) -> Result<()> {
         println!("  wenget info ripgrep              # Query from cache");
         println!("  wenget info 'rip*'               # Glob pattern (cache only)");
         println!("  wenget info https://github.com/BurntSushi/ripgrep  # Direct URL");
+        println!("  wenget info --installed          # Every installed package");
         return Ok(());
     }
 
@@ -26,12 +139,34 @@ pub fn run(names: Vec<String>) -> Result<()> {
     let installed = config.get_or_create_installed()?;
 
     // Load cache once for both script lookup and package resolution
-    let cache = config.get_or_rebuild_cache()?;
+    let cache = if remote {
+        println!(
+            "{} Showing live data from GitHub (cache bypassed)",
+            "ℹ".cyan()
+        );
+        println!();
+        config.refresh_buckets(&[])?
+    } else {
+        config.get_or_rebuild_cache()?
+    };
+    let github = if remote || compare.is_some() || latest_asset_size {
+        Some(GitHubProvider::new()?)
+    } else {
+        None
+    };
 
     // Create resolver with shared cache reference
-    let resolver = PackageResolver::new(&config, &cache)?;
+    let resolver = PackageResolver::new(&config, &cache)?.with_sources(&sources)?;
+
+    let release_cache_path = config.paths().release_cache_json();
+    let mut release_cache = ReleaseSizeCache::default();
+    if latest_asset_size {
+        release_cache = ReleaseSizeCache::load(&release_cache_path);
+    }
+    let ttl_seconds = config.cache_ttl()?;
 
     let mut total_found = 0;
+    let mut raw_entries = Vec::new();
 
     for name in &names {
         let input = PackageInput::parse(name);
@@ -39,19 +174,61 @@ pub fn run(names: Vec<String>) -> Result<()> {
         // First try to resolve as package
         match resolver.resolve(&input) {
             Ok(packages) => {
-                for resolved in packages {
+                for mut resolved in packages {
+                    if let Some(github) = github.as_ref().filter(|_| remote) {
+                        refresh_from_github(github, &mut resolved, &installed);
+                    }
+                    let latest_sizes = match &github {
+                        Some(github) if latest_asset_size && !raw_manifest => apply_latest_sizes(
+                            github,
+                            &mut resolved,
+                            &installed,
+                            &mut release_cache,
+                            ttl_seconds,
+                            remote,
+                        ),
+                        _ => None,
+                    };
+
+                    if raw_manifest {
+                        raw_entries.push(RawEntry::Package(resolved.package));
+                        total_found += 1;
+                        continue;
+                    }
+
                     if total_found > 0 {
                         println!();
                         println!("{}", "─".repeat(80));
                         println!();
                     }
-                    display_package_info(&resolved, &installed, &resolver)?;
+                    display_package_info(
+                        &resolved,
+                        &installed,
+                        &resolver,
+                        &platform,
+                        latest_sizes.as_ref(),
+                    )?;
+                    if let (Some(github), Some(version)) = (&github, &compare) {
+                        display_comparison(
+                            github,
+                            &resolved,
+                            &installed,
+                            version.as_deref(),
+                            &platform,
+                        );
+                    }
                     total_found += 1;
                 }
             }
             Err(_) => {
                 // If not found as package, try as script
-                if let Some(cached_script) = cache.find_script(name) {
+                if let Some(cached_script) = resolver.find_script(name) {
+                    if raw_manifest {
+                        raw_entries.push(RawEntry::Script(cached_script.script.clone()));
+                        total_found += 1;
+                        continue;
+                    }
+
                     if total_found > 0 {
                         println!();
                         println!("{}", "─".repeat(80));
@@ -66,6 +243,26 @@ pub fn run(names: Vec<String>) -> Result<()> {
         }
     }
 
+    if raw_manifest {
+        // One object for a single match, an array otherwise
+        let output = match raw_entries.len() {
+            0 => {
+                eprintln!("{}", "No packages or scripts found".yellow());
+                return Ok(());
+            }
+            1 => serde_json::to_string_pretty(&raw_entries[0])?,
+            _ => serde_json::to_string_pretty(&raw_entries)?,
+        };
+        println!("{}", output);
+        return Ok(());
+    }
+
+    if latest_asset_size {
+        if let Err(e) = release_cache.save(&release_cache_path) {
+            log::warn!("{:#}", e);
+        }
+    }
+
     if total_found == 0 {
         println!("{}", "No packages or scripts found".yellow());
     } else if total_found > 1 {
@@ -79,12 +276,303 @@ pub fn run(names: Vec<String>) -> Result<()> {
     Ok(())
 }
 
+/// One installed package as printed by `info --installed --json`
+#[derive(Serialize)]
+struct InstalledInfo<'a> {
+    name: &'a str,
+    version: &'a str,
+    /// Latest non-yanked version; None if it could not be looked up
+    latest: Option<String>,
+    outdated: Option<bool>,
+    source: String,
+    command_name: &'a str,
+    platform: &'a str,
+    channel: &'a str,
+    pin: Option<&'a str>,
+    install_path: &'a str,
+    /// Bytes on disk; None when the install directory is missing
+    installed_size: Option<u64>,
+    broken: bool,
+    installed_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Show every installed package, ending with a summary of what is outdated
+/// or broken (--installed)
+fn show_installed(config: &Config, platform: &PlatformFilter, json: bool) -> Result<()> {
+    let installed = config.get_or_create_installed()?;
+    if installed.packages.is_empty() {
+        if json {
+            println!("[]");
+        } else {
+            println!("{}", "No packages installed".yellow());
+        }
+        return Ok(());
+    }
+
+    let cache = config.get_or_rebuild_cache()?;
+    let resolver = PackageResolver::new(config, &cache)?;
+    let mut names: Vec<&String> = installed.packages.keys().collect();
+    names.sort();
+
+    let mut outdated = Vec::new();
+    let mut unchecked = Vec::new();
+    let mut entries = Vec::new();
+    for (i, name) in names.into_iter().enumerate() {
+        let inst_pkg = &installed.packages[name];
+        let is_script = matches!(inst_pkg.source, PackageSource::Script { .. });
+        // Scripts have no releases; other packages are looked up by name,
+        // which also covers ones installed from a URL
+        let resolved = if is_script {
+            None
+        } else {
+            resolver
+                .resolve(&PackageInput::CacheName(name.clone()))
+                .map(|mut found| found.remove(0))
+                .map_err(|e| log::debug!("Failed to resolve {}: {:#}", name, e))
+                .ok()
+        };
+
+        let latest = if json {
+            resolved.as_ref().and_then(|resolved| {
+                let pkg = &resolved.package;
+                resolver
+                    .fetch_latest_version(&pkg.repo, inst_pkg.channel, &pkg.yanked)
+                    .ok()
+            })
+        } else {
+            if i > 0 {
+                println!();
+                println!("{}", "─".repeat(80));
+                println!();
+            }
+            match resolved {
+                Some(ref resolved) => {
+                    display_package_info(resolved, &installed, &resolver, platform, None)?
+                }
+                None => {
+                    match cache.find_script(name).filter(|_| is_script) {
+                        Some(cached_script) => display_script_info(cached_script, &installed)?,
+                        None => display_installed_record(name, inst_pkg, is_script),
+                    }
+                    None
+                }
+            }
+        };
+
+        match latest {
+            Some(ref latest) if inst_pkg.has_update(latest) => outdated.push(name.as_str()),
+            None if !is_script => unchecked.push(name.as_str()),
+            _ => {}
+        }
+        if json {
+            entries.push(InstalledInfo {
+                name,
+                version: &inst_pkg.version,
+                outdated: latest.as_ref().map(|latest| inst_pkg.has_update(latest)),
+                latest,
+                source: inst_pkg.source.describe(),
+                command_name: &inst_pkg.command_name,
+                platform: &inst_pkg.platform,
+                channel: inst_pkg.channel.as_str(),
+                pin: inst_pkg.pin.as_deref(),
+                install_path: &inst_pkg.install_path,
+                installed_size: (!inst_pkg.is_broken()).then(|| inst_pkg.installed_size()),
+                broken: inst_pkg.is_broken(),
+                installed_at: inst_pkg.installed_at,
+                updated_at: inst_pkg.last_updated(),
+            });
+        }
+    }
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    let broken = installed.broken_packages();
+    println!();
+    println!("{}", "Summary:".bold());
+    println!("  {} package(s) installed", installed.packages.len());
+    if outdated.is_empty() && unchecked.is_empty() {
+        println!("  {} All up to date", "✓".green());
+    } else if !outdated.is_empty() {
+        println!(
+            "  {} {} outdated: {}",
+            "⚠".yellow(),
+            outdated.len(),
+            outdated.join(", ")
+        );
+    }
+    if !unchecked.is_empty() {
+        println!(
+            "  {} {} could not be checked: {}",
+            "ℹ".cyan(),
+            unchecked.len(),
+            unchecked.join(", ")
+        );
+    }
+    if !broken.is_empty() {
+        println!(
+            "  {} {} broken: {}",
+            "✗".red(),
+            broken.len(),
+            broken.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Display what was recorded at install time for a package that is no
+/// longer in any bucket (or a local script)
+fn display_installed_record(name: &str, inst_pkg: &InstalledPackage, is_script: bool) {
+    println!("{}", name.bold().cyan());
+    println!("{}", "─".repeat(60));
+    if !inst_pkg.description.is_empty() {
+        println!("{:<16} {}", "Description:".bold(), inst_pkg.description);
+    }
+    println!("{:<16} {}", "Source:".bold(), inst_pkg.source.describe());
+    if !is_script {
+        println!(
+            "{:<16} {}",
+            "",
+            "not found in any bucket; latest version unknown".dimmed()
+        );
+    }
+    display_install_status(inst_pkg, None);
+    for line in notes_lines(Some(inst_pkg), None) {
+        println!("{}", line);
+    }
+}
+
+/// The `Notes:` lines: the notes shown at install time, or the manifest's
+/// for a package that is not installed
+fn notes_lines(inst_pkg: Option<&InstalledPackage>, pkg: Option<&Package>) -> Vec<String> {
+    let notes = inst_pkg
+        .and_then(|p| p.post_install_message.as_deref())
+        .or(pkg.and_then(|p| p.post_install_message.as_deref()))
+        .map(message_lines)
+        .unwrap_or_default();
+    notes
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let label = if i == 0 { "Notes:" } else { "" };
+            format!("{:<16} {}", label.bold(), line)
+        })
+        .collect()
+}
+
+/// Manifest entry printed by `--raw-manifest`, serialized exactly as parsed
+#[derive(Serialize)]
+#[serde(untagged)]
+enum RawEntry {
+    Package(Package),
+    Script(ScriptItem),
+}
+
+/// Replace cached download links of a bucket package with the latest release
+///
+/// Direct URL packages were already fetched live by the resolver. Packages
+/// using asset patterns keep them, since those are resolved at install time.
+fn refresh_from_github(
+    github: &GitHubProvider,
+    resolved: &mut ResolvedPackage,
+    installed: &InstalledManifest,
+) {
+    let pkg = &mut resolved.package;
+    let uses_asset_patterns = pkg.platforms.values().any(|b| b.asset_pattern.is_some());
+    if !matches!(resolved.source, PackageSource::Bucket { .. }) || uses_asset_patterns {
+        return;
+    }
+
+    let channel = installed
+        .get_package(&pkg.name)
+        .map(|p| p.channel)
+        .unwrap_or_default();
+
+    match github.fetch_package_on(&pkg.repo, channel, &pkg.yanked) {
+        Ok(live) => {
+            pkg.platforms = live.platforms;
+            if pkg.license.is_none() {
+                pkg.license = live.license;
+            }
+        }
+        Err(e) => {
+            println!(
+                "{} Failed to fetch live data for {}, showing bucket data: {}",
+                "⚠".yellow(),
+                pkg.name,
+                e
+            );
+        }
+    }
+}
+
+/// Replace a package's download sizes with those of its latest release
+///
+/// The sizes are read from the release cache while it is fresh, unless
+/// `refresh` is set (`--remote`); otherwise they are fetched from the release
+/// JSON and cached. Returns the sizes applied, or None if they could not be
+/// looked up.
+fn apply_latest_sizes(
+    github: &GitHubProvider,
+    resolved: &mut ResolvedPackage,
+    installed: &InstalledManifest,
+    cache: &mut ReleaseSizeCache,
+    ttl_seconds: i64,
+    refresh: bool,
+) -> Option<ReleaseSizes> {
+    let pkg = &mut resolved.package;
+    let channel = installed
+        .get_package(&pkg.name)
+        .map(|p| p.channel)
+        .unwrap_or_default();
+
+    let cached = cache
+        .get_fresh(&pkg.repo, channel, ttl_seconds)
+        .filter(|_| !refresh)
+        .cloned();
+    let latest = match cached {
+        Some(latest) => latest,
+        None => match github.fetch_asset_sizes(pkg, channel) {
+            Ok((tag, sizes)) => {
+                cache.insert(&pkg.repo, channel, tag, sizes);
+                cache.repos[&pkg.repo].clone()
+            }
+            Err(e) => {
+                println!(
+                    "{} Failed to fetch release asset sizes for {}, showing manifest sizes: {}",
+                    "⚠".yellow(),
+                    pkg.name,
+                    e
+                );
+                return None;
+            }
+        },
+    };
+
+    for (id, size) in &latest.sizes {
+        if let Some(binary) = pkg.platforms.get_mut(id) {
+            binary.size = *size;
+        }
+    }
+    Some(latest)
+}
+
 /// Display detailed information for a single package
+///
+/// With `latest_sizes`, the sizes already applied by [`apply_latest_sizes`]
+/// are labeled with their release. Returns the latest (non-yanked) version,
+/// if it could be looked up.
 fn display_package_info(
     resolved: &ResolvedPackage,
     installed: &crate::core::InstalledManifest,
     resolver: &PackageResolver,
-) -> Result<()> {
+    platform_filter: &PlatformFilter,
+    latest_sizes: Option<&ReleaseSizes>,
+) -> Result<Option<String>> {
     let pkg = &resolved.package;
 
     // Header
@@ -104,6 +592,14 @@ fn display_package_info(
 
     println!("{:<16} {}", "Description:".bold(), pkg.description);
 
+    if !pkg.aliases.is_empty() {
+        println!("{:<16} {}", "Aliases:".bold(), pkg.aliases.join(", "));
+    }
+
+    if !pkg.tags.is_empty() {
+        println!("{:<16} {}", "Tags:".bold(), pkg.tags.join(", "));
+    }
+
     // Source
     match &resolved.source {
         crate::core::manifest::PackageSource::Bucket { name } => {
@@ -127,47 +623,305 @@ fn display_package_info(
     }
 
     // Latest version from GitHub
-    if let Ok(version) = resolver.fetch_latest_version(&pkg.repo) {
-        println!("{:<16} {}", "Latest version:".bold(), version.green());
+    let channel = installed
+        .get_package(&pkg.name)
+        .map(|p| p.channel)
+        .unwrap_or_default();
+    let mut latest = None;
+    if let Ok(version) = resolver.fetch_latest_version(&pkg.repo, channel, &[]) {
+        if pkg.is_yanked(&version) {
+            println!(
+                "{:<16} {} {}",
+                "Latest version:".bold(),
+                version.red(),
+                "(yanked)".red()
+            );
+            match resolver.fetch_latest_version(&pkg.repo, channel, &pkg.yanked) {
+                Ok(recommended) => {
+                    println!("{:<16} {}", "Recommended:".bold(), recommended.green());
+                    latest = Some(recommended);
+                }
+                Err(_) => println!("{:<16} {}", "Recommended:".bold(), "none".yellow()),
+            }
+        } else {
+            println!("{:<16} {}", "Latest version:".bold(), version.green());
+            latest = Some(version);
+        }
+    }
+    if !pkg.yanked.is_empty() {
+        println!(
+            "{:<16} {}",
+            "Yanked:".bold(),
+            pkg.yanked.join(", ").dimmed()
+        );
     }
 
     // Installation status
     if let Some(inst_pkg) = installed.get_package(&pkg.name) {
+        display_install_status(inst_pkg, latest.as_deref());
+    } else {
+        println!("{:<16} {}", "Status:".bold(), "Not installed".yellow());
+    }
+
+    display_installable(pkg);
+
+    for line in notes_lines(installed.get_package(&pkg.name), Some(pkg)) {
+        println!("{}", line);
+    }
+
+    // Supported platforms
+    println!();
+    let size_label = match latest_sizes {
+        Some(latest) => format!("(download size in {})", latest.tag),
+        None => "(download size)".to_string(),
+    };
+    println!(
+        "{} {} platform(s) {}",
+        "Supported platforms:".bold(),
+        pkg.platforms.len(),
+        size_label.dimmed()
+    );
+    let platforms = platform_filter.select(pkg);
+    match platform_filter {
+        PlatformFilter::All => {}
+        PlatformFilter::Current if platforms.is_empty() => println!(
+            "  {} No asset for the current platform ({})",
+            "⚠".yellow(),
+            Platform::current()
+        ),
+        PlatformFilter::Id(id) if platforms.is_empty() => {
+            println!("  {} No asset for platform '{}'", "⚠".yellow(), id)
+        }
+        _ => println!(
+            "  {}",
+            format!("Showing {} of them (--platform)", platforms.len()).dimmed()
+        ),
+    }
+
+    for platform in platforms {
+        let binary = &pkg.platforms[platform];
+        let size_mb = binary.size as f64 / 1024.0 / 1024.0;
+        let missing = latest_sizes
+            .filter(|latest| !latest.sizes.contains_key(platform))
+            .map(|latest| format!(" {}", format!("not in {}", latest.tag).yellow()))
+            .unwrap_or_default();
+        println!(
+            "  {} {:<25} ({:.2} MB){}",
+            "•".cyan(),
+            platform,
+            size_mb,
+            missing
+        );
+    }
+
+    Ok(latest)
+}
+
+/// Print whether `add` would find an asset for this machine, and which one
+fn display_installable(pkg: &Package) {
+    let platform = Platform::current().with_emulation(emulation_allowed());
+    let chosen = platform
+        .possible_identifiers()
+        .into_iter()
+        .find_map(|id| pkg.platforms.get_key_value(&id));
+
+    let Some((id, binary)) = chosen else {
         println!(
+            "{:<16} {} (no asset for {})",
+            "Installable:".bold(),
+            "no".red(),
+            platform
+        );
+        return;
+    };
+
+    let asset = match binary.asset_pattern {
+        Some(ref pattern) => format!("asset matching '{}'", pattern),
+        None => binary
+            .url
+            .rsplit('/')
+            .next()
+            .unwrap_or(&binary.url)
+            .to_string(),
+    };
+    let size = if binary.size > 0 {
+        format!(", {:.2} MB", binary.size as f64 / 1024.0 / 1024.0)
+    } else {
+        String::new()
+    };
+    println!(
+        "{:<16} {} ({}: {}{})",
+        "Installable:".bold(),
+        "yes".green(),
+        id,
+        asset,
+        size
+    );
+}
+
+/// Print the installation details of an installed package, flagging it as
+/// outdated when `latest` differs from the installed version
+fn display_install_status(inst_pkg: &InstalledPackage, latest: Option<&str>) {
+    match latest.filter(|latest| *latest != inst_pkg.version) {
+        Some(latest) if inst_pkg.is_ignored_update(latest) => println!(
+            "{:<16} {} (v{}, {} available but ignored)",
+            "Status:".bold(),
+            "Installed".green(),
+            inst_pkg.version,
+            latest
+        ),
+        Some(latest) => println!(
+            "{:<16} {} (v{}, {} available)",
+            "Status:".bold(),
+            "Outdated".yellow(),
+            inst_pkg.version,
+            latest.green()
+        ),
+        None => println!(
             "{:<16} {} (v{})",
             "Status:".bold(),
             "Installed".green(),
             inst_pkg.version
+        ),
+    }
+    if inst_pkg.alias.is_some() {
+        println!(
+            "{:<16} {} {}",
+            "Command name:".bold(),
+            inst_pkg.command_name.yellow(),
+            "(renamed)".dimmed()
         );
+    } else {
         println!(
             "{:<16} {}",
             "Command name:".bold(),
             inst_pkg.command_name.yellow()
         );
-        println!("{:<16} {}", "Installed at:".bold(), inst_pkg.installed_at);
-        println!("{:<16} {}", "Platform:".bold(), inst_pkg.platform);
-        println!("{:<16} {}", "Install path:".bold(), inst_pkg.install_path);
+    }
+    println!(
+        "{:<16} {}",
+        "Installed at:".bold(),
+        format_timestamp(&inst_pkg.installed_at)
+    );
+    println!(
+        "{:<16} {}",
+        "Updated at:".bold(),
+        format_timestamp(&inst_pkg.last_updated())
+    );
+    println!("{:<16} {}", "Platform:".bold(), inst_pkg.platform);
+    if !inst_pkg.channel.is_stable() {
+        println!("{:<16} {}", "Channel:".bold(), inst_pkg.channel.as_str());
+    }
+    if let Some(ref pin) = inst_pkg.pin {
+        println!("{:<16} {}", "Pinned to:".bold(), pin.yellow());
+    }
+    println!("{:<16} {}", "Install path:".bold(), inst_pkg.install_path);
+    if inst_pkg.is_broken() {
+        println!(
+            "{:<16} {}",
+            "",
+            "missing; run `wenget repair` to reinstall".red()
+        );
     } else {
-        println!("{:<16} {}", "Status:".bold(), "Not installed".yellow());
+        println!(
+            "{:<16} {:.2} MB on disk",
+            "Installed size:".bold(),
+            inst_pkg.installed_size() as f64 / 1024.0 / 1024.0
+        );
+    }
+    if inst_pkg.no_shim {
+        println!("{:<16} {}", "Launcher:".bold(), "none (--no-shim)".dimmed());
+    } else if let Some(ref shim_path) = inst_pkg.shim_path {
+        println!("{:<16} {}", "Launcher:".bold(), shim_path);
     }
+    for shim in &inst_pkg.extra_shims {
+        println!("{:<16} {}", "", shim);
+    }
+}
+
+/// Show how the latest release's per-platform assets differ from `version`
+///
+/// Without a version, the installed one is used. Problems are reported as
+/// warnings so the rest of the info output is unaffected.
+fn display_comparison(
+    github: &GitHubProvider,
+    resolved: &ResolvedPackage,
+    installed: &InstalledManifest,
+    version: Option<&str>,
+    platform_filter: &PlatformFilter,
+) {
+    let pkg = &resolved.package;
+    let inst_pkg = installed.get_package(&pkg.name);
 
-    // Supported platforms
     println!();
+    let Some(version) = version.or(inst_pkg.map(|p| p.version.as_str())) else {
+        println!(
+            "{} {} is not installed; pass a version to compare against (--compare <version>)",
+            "⚠".yellow(),
+            pkg.name
+        );
+        return;
+    };
+    let channel = inst_pkg.map(|p| p.channel).unwrap_or_default();
+
+    let releases = github
+        .fetch_release_assets(&pkg.repo, Some(version), channel, &[])
+        .and_then(|old| {
+            let new = github.fetch_release_assets(&pkg.repo, None, channel, &pkg.yanked)?;
+            Ok((old, new))
+        });
+    let ((old_tag, old_assets), (new_tag, new_assets)) = match releases {
+        Ok(releases) => releases,
+        Err(e) => {
+            println!("{} Failed to compare releases: {:#}", "⚠".yellow(), e);
+            return;
+        }
+    };
+
     println!(
-        "{} {} platform(s)",
-        "Supported platforms:".bold(),
-        pkg.platforms.len()
+        "{} {} → {}",
+        "Asset changes:".bold(),
+        old_tag,
+        new_tag.green()
     );
-    let mut platforms: Vec<_> = pkg.platforms.keys().collect();
-    platforms.sort();
 
-    for platform in platforms {
-        let binary = &pkg.platforms[platform];
-        let size_mb = binary.size as f64 / 1024.0 / 1024.0;
-        println!("  {} {:<25} ({:.2} MB)", "•".cyan(), platform, size_mb);
+    let mut changes = BinarySelector::diff_platforms(&old_assets, &new_assets);
+    changes.retain(|change| platform_filter.matches(change.platform()));
+    if changes.is_empty() {
+        println!("  {}", "No platform asset changes".dimmed());
+        return;
     }
 
-    Ok(())
+    let mb = |size: u64| size as f64 / 1024.0 / 1024.0;
+    for change in &changes {
+        match change {
+            AssetChange::Added { platform, asset } => println!(
+                "  {} {:<25} {} ({:.2} MB)",
+                "+".green(),
+                platform,
+                asset.name,
+                mb(asset.size)
+            ),
+            AssetChange::Removed { platform, asset } => println!(
+                "  {} {:<25} {} ({:.2} MB)",
+                "-".red(),
+                platform,
+                asset.name,
+                mb(asset.size)
+            ),
+            AssetChange::Resized { platform, old, new } => {
+                let delta = mb(new.size) - mb(old.size);
+                println!(
+                    "  {} {:<25} {:.2} MB → {:.2} MB ({:+.2} MB)",
+                    "~".yellow(),
+                    platform,
+                    mb(old.size),
+                    mb(new.size),
+                    delta
+                );
+            }
+        }
+    }
 }
 
 /// Display detailed information for a single script
@@ -230,8 +984,30 @@ fn display_script_info(
             "Command name:".bold(),
             inst_pkg.command_name.yellow()
         );
-        println!("{:<16} {}", "Installed at:".bold(), inst_pkg.installed_at);
+        println!(
+            "{:<16} {}",
+            "Installed at:".bold(),
+            format_timestamp(&inst_pkg.installed_at)
+        );
+        println!(
+            "{:<16} {}",
+            "Updated at:".bold(),
+            format_timestamp(&inst_pkg.last_updated())
+        );
         println!("{:<16} {}", "Install path:".bold(), inst_pkg.install_path);
+        if inst_pkg.is_broken() {
+            println!(
+                "{:<16} {}",
+                "",
+                "missing; run `wenget repair` to reinstall".red()
+            );
+        } else {
+            println!(
+                "{:<16} {:.2} MB on disk",
+                "Installed size:".bold(),
+                inst_pkg.installed_size() as f64 / 1024.0 / 1024.0
+            );
+        }
     } else {
         println!("{:<16} {}", "Status:".bold(), "Not installed".yellow());
     }
@@ -247,3 +1023,38 @@ fn display_script_info(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notes_lines() {
+        let pkg: Package = serde_json::from_str(
+            r#"{"name": "tool", "description": "", "repo": "", "platforms": {},
+                "post_install_message": "Run `tool init`\nthen restart your shell\n"}"#,
+        )
+        .unwrap();
+
+        // Not installed: the manifest's notes, labelled on the first line
+        let lines = notes_lines(None, Some(&pkg));
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("Notes:"));
+        assert!(lines[0].ends_with("Run `tool init`"));
+        assert!(!lines[1].contains("Notes:"));
+        assert!(lines[1].ends_with("then restart your shell"));
+
+        // Installed: the notes shown at install time, even once the package
+        // left its bucket
+        let mut inst_pkg = InstalledPackage::test_default("tool");
+        inst_pkg.post_install_message = Some("Run `tool setup`".to_string());
+        for pkg in [Some(&pkg), None] {
+            let lines = notes_lines(Some(&inst_pkg), pkg);
+            assert_eq!(lines.len(), 1);
+            assert!(lines[0].ends_with("Run `tool setup`"));
+        }
+
+        inst_pkg.post_install_message = None;
+        assert!(notes_lines(Some(&inst_pkg), None).is_empty());
+    }
+}
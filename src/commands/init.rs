@@ -1,18 +1,24 @@
 //! Initialize Wenget
 
-use crate::bucket::Bucket;
+use crate::bucket::{Bucket, DEFAULT_BUCKET_NAME, DEFAULT_BUCKET_URL};
 use crate::core::Config;
 use anyhow::{Context, Result};
 use colored::Colorize;
 use std::env;
 use std::io::{self, Write as IoWrite};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
+
+#[cfg(windows)]
+use std::path::Path;
 
 #[cfg(not(windows))]
-use std::fs::{self, OpenOptions};
+use crate::utils::shell::{add_path_entry, existing_rc_files, RcUpdate};
 
 /// Initialize Wenget (create directories and manifests)
-pub fn run(yes: bool) -> Result<()> {
+///
+/// A first run is seeded with the official bucket unless `no_default_bucket`
+/// is set.
+pub fn run(yes: bool, no_default_bucket: bool) -> Result<()> {
     println!("{}", "Initializing Wenget...".cyan());
 
     let config = Config::new()?;
@@ -34,19 +40,19 @@ pub fn run(yes: bool) -> Result<()> {
         }
 
         // Check if wenget bucket exists
-        if !has_wenget_bucket(&config)? {
+        if has_wenget_bucket(&config)? {
+            println!("{}", "✓ Wenget bucket is configured".green());
+        } else if !no_default_bucket {
             println!();
             if prompt_add_wenget_bucket(yes)? {
                 add_wenget_bucket(&config)?;
             }
-        } else {
-            println!("{}", "✓ Wenget bucket is configured".green());
         }
 
         return Ok(());
     }
 
-    config.init()?;
+    config.init_with_default_bucket(!no_default_bucket)?;
 
     println!("{}", "✓ Wenget initialized successfully!".green());
     println!();
@@ -67,10 +73,17 @@ pub fn run(yes: bool) -> Result<()> {
     // Set up PATH
     setup_path(&config)?;
 
-    // Ask about adding wenget bucket
+    // Report the seeded bucket and build its cache right away
     println!();
-    if prompt_add_wenget_bucket(yes)? {
-        add_wenget_bucket(&config)?;
+    let buckets = config.load_buckets()?;
+    match buckets.buckets.first() {
+        Some(bucket) => {
+            println!("{} Bucket '{}' added", "✓".green(), bucket.name);
+            println!("  URL: {}", bucket.url);
+            println!("  Remove it with: wenget bucket del {}", bucket.name);
+            build_bucket_cache(&config);
+        }
+        None => println!("No buckets added. Add one with: wenget bucket add <name> <url>"),
     }
 
     println!();
@@ -221,7 +234,7 @@ fn setup_path(config: &Config) -> Result<()> {
 
 /// Set up PATH on Windows (modify user environment variable)
 #[cfg(windows)]
-fn setup_path_windows(bin_dir: &str) -> Result<()> {
+pub(crate) fn setup_path_windows(bin_dir: &str) -> Result<()> {
     use std::process::Command;
 
     // Use PowerShell to add to user PATH
@@ -268,27 +281,15 @@ fn setup_path_windows(bin_dir: &str) -> Result<()> {
 #[cfg(not(windows))]
 fn setup_path_unix(bin_dir: &str) -> Result<()> {
     let home = dirs::home_dir().context("Failed to determine home directory")?;
-
-    // Determine which shell configs to update
-    let shell_configs = detect_shell_configs(&home);
-
-    if shell_configs.is_empty() {
-        println!("{}", "⚠ No shell configuration files found".yellow());
-        println!();
-        println!("Please manually add the following to your shell configuration:");
-        println!("  export PATH=\"{}:$PATH\"", bin_dir.cyan());
-        return Ok(());
-    }
-
-    let export_line = format!("\n# Wenget\nexport PATH=\"{}:$PATH\"\n", bin_dir);
+    let dir = std::path::Path::new(bin_dir);
 
     let mut updated_files = Vec::new();
     let mut skipped_files = Vec::new();
 
-    for config_path in shell_configs {
-        match update_shell_config(&config_path, &export_line, bin_dir) {
-            Ok(true) => updated_files.push(config_path),
-            Ok(false) => skipped_files.push(config_path),
+    for (shell, config_path) in existing_rc_files(&home) {
+        match add_path_entry(&config_path, &shell.path_line(dir), dir) {
+            Ok(RcUpdate::Appended { .. }) => updated_files.push(config_path),
+            Ok(RcUpdate::AlreadyPresent) => skipped_files.push(config_path),
             Err(e) => {
                 println!(
                     "  {} Failed to update {}: {}",
@@ -308,14 +309,7 @@ fn setup_path_unix(bin_dir: &str) -> Result<()> {
         println!();
         println!("{}", "IMPORTANT:".yellow().bold());
         println!("  Run the following command to apply changes:");
-        println!(
-            "  source ~/{}",
-            updated_files[0]
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .cyan()
-        );
+        println!("  source {}", updated_files[0].display().to_string().cyan());
         println!();
         println!("  Or restart your terminal");
     }
@@ -330,56 +324,6 @@ fn setup_path_unix(bin_dir: &str) -> Result<()> {
     Ok(())
 }
 
-/// Detect available shell configuration files
-#[cfg(not(windows))]
-fn detect_shell_configs(home: &PathBuf) -> Vec<PathBuf> {
-    let mut configs = Vec::new();
-
-    // Check for common shell configs
-    let candidates = vec![".bashrc", ".bash_profile", ".zshrc", ".profile"];
-
-    for candidate in candidates {
-        let path = home.join(candidate);
-        if path.exists() {
-            configs.push(path);
-        }
-    }
-
-    // If no configs found, try to create .profile
-    if configs.is_empty() {
-        let profile = home.join(".profile");
-        configs.push(profile);
-    }
-
-    configs
-}
-
-/// Update a shell configuration file
-#[cfg(not(windows))]
-fn update_shell_config(config_path: &PathBuf, export_line: &str, bin_dir: &str) -> Result<bool> {
-    // Check if already configured
-    if config_path.exists() {
-        let content = fs::read_to_string(config_path)
-            .with_context(|| format!("Failed to read {}", config_path.display()))?;
-
-        if content.contains(bin_dir) {
-            return Ok(false); // Already configured
-        }
-    }
-
-    // Append to file
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(config_path)
-        .with_context(|| format!("Failed to open {}", config_path.display()))?;
-
-    file.write_all(export_line.as_bytes())
-        .with_context(|| format!("Failed to write to {}", config_path.display()))?;
-
-    Ok(true)
-}
-
 /// Check if a directory is in PATH
 fn is_in_path(dir: PathBuf) -> Result<bool> {
     let path_var = env::var("PATH").unwrap_or_default();
@@ -418,16 +362,13 @@ fn prompt_add_wenget_bucket(yes: bool) -> Result<bool> {
 
 /// Check if wenget bucket is already configured
 fn has_wenget_bucket(config: &Config) -> Result<bool> {
-    const WENGET_BUCKET_URL: &str =
-        "https://raw.githubusercontent.com/superyngo/wenget-bucket/refs/heads/main/manifest.json";
-
     match config.get_or_create_buckets() {
         Ok(bucket_config) => {
             // Check if any bucket has the wenget URL
             Ok(bucket_config
                 .buckets
                 .iter()
-                .any(|b| b.url == WENGET_BUCKET_URL))
+                .any(|b| b.name == DEFAULT_BUCKET_NAME || b.url == DEFAULT_BUCKET_URL))
         }
         Err(_) => Ok(false),
     }
@@ -435,10 +376,6 @@ fn has_wenget_bucket(config: &Config) -> Result<bool> {
 
 /// Add wenget bucket
 fn add_wenget_bucket(config: &Config) -> Result<()> {
-    const WENGET_BUCKET_NAME: &str = "wenget";
-    const WENGET_BUCKET_URL: &str =
-        "https://raw.githubusercontent.com/superyngo/wenget-bucket/refs/heads/main/manifest.json";
-
     println!();
     println!("{} wenget bucket...", "Adding".cyan());
 
@@ -447,10 +384,11 @@ fn add_wenget_bucket(config: &Config) -> Result<()> {
 
     // Create bucket
     let bucket = Bucket {
-        name: WENGET_BUCKET_NAME.to_string(),
-        url: WENGET_BUCKET_URL.to_string(),
+        name: DEFAULT_BUCKET_NAME.to_string(),
+        url: DEFAULT_BUCKET_URL.to_string(),
         enabled: true,
         priority: 100,
+        headers: Default::default(),
     };
 
     // Try to add bucket
@@ -458,32 +396,35 @@ fn add_wenget_bucket(config: &Config) -> Result<()> {
         // Save config
         config.save_buckets(&bucket_config)?;
 
-        println!("{} Bucket '{}' added", "✓".green(), WENGET_BUCKET_NAME);
-        println!("  URL: {}", WENGET_BUCKET_URL);
-
-        // Build cache immediately
-        match config.rebuild_cache() {
-            Ok(cache) => {
-                println!();
-                println!(
-                    "{} {} package(s) available from wenget bucket",
-                    "✓".green(),
-                    cache.packages.len()
-                );
-            }
-            Err(e) => {
-                println!();
-                println!("{} Failed to build cache: {}", "⚠".yellow(), e);
-                println!("  You can rebuild it later with: wenget bucket refresh");
-            }
-        }
+        println!("{} Bucket '{}' added", "✓".green(), DEFAULT_BUCKET_NAME);
+        println!("  URL: {}", DEFAULT_BUCKET_URL);
+        build_bucket_cache(config);
     } else {
         println!(
             "{} Bucket '{}' already exists",
             "✗".yellow(),
-            WENGET_BUCKET_NAME
+            DEFAULT_BUCKET_NAME
         );
     }
 
     Ok(())
 }
+
+/// Build the manifest cache right after a bucket was added
+fn build_bucket_cache(config: &Config) {
+    match config.rebuild_cache() {
+        Ok(cache) => {
+            println!();
+            println!(
+                "{} {} package(s) available from buckets",
+                "✓".green(),
+                cache.packages.len()
+            );
+        }
+        Err(e) => {
+            println!();
+            println!("{} Failed to build cache: {}", "⚠".yellow(), e);
+            println!("  You can rebuild it later with: wenget bucket refresh");
+        }
+    }
+}
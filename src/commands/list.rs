@@ -1,12 +1,52 @@
 //! List command implementation
 
+use super::update::find_upgradeable;
 use crate::core::manifest::PackageSource;
-use crate::core::{Config, Platform};
+use crate::core::{Config, InstalledManifest, InstalledPackage, Platform};
+use crate::providers::GitHubProvider;
+use crate::utils::output::format_timestamp;
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Version of the `list --json` document, raised whenever a field is
+/// removed or changes meaning (new fields may be added without a bump)
+const LIST_SCHEMA_VERSION: u32 = 1;
+
+/// The document printed by `list --json`
+#[derive(Serialize)]
+struct ListDocument<'a> {
+    schema_version: u32,
+    packages: Vec<ListEntry<'a>>,
+}
+
+/// One installed package, as shown by `list` and `list --json`
+#[derive(Serialize)]
+struct ListEntry<'a> {
+    name: &'a str,
+    version: &'a str,
+    platform: &'a str,
+    /// Where it came from (see `PackageSource::describe`)
+    source: String,
+    installed_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    /// The `wenget pin` constraint; None when not pinned
+    pinned: Option<&'a str>,
+    /// None when the latest version was not looked up or could not be; an
+    /// update ignored with `update --ignore` does not count
+    outdated: Option<bool>,
+    latest: Option<&'a str>,
+    #[serde(skip)]
+    package: &'a InstalledPackage,
+}
 
 /// List installed packages or all available packages
-pub fn run(all: bool) -> Result<()> {
+///
+/// `outdated` keeps only packages with a newer release; it and `json` look
+/// up every package's latest version on GitHub.
+pub fn run(all: bool, outdated: bool, json: bool) -> Result<()> {
     let config = Config::new()?;
 
     if all {
@@ -14,42 +54,143 @@ pub fn run(all: bool) -> Result<()> {
         list_all_packages(&config)?;
     } else {
         // Show only installed packages
-        list_installed_packages(&config)?;
+        list_installed_packages(&config, outdated, json)?;
     }
 
     Ok(())
 }
 
+/// The installed packages sorted by name, compared against their latest
+/// versions when they were looked up
+fn list_entries<'a>(
+    manifest: &'a InstalledManifest,
+    latest: Option<&'a HashMap<String, String>>,
+) -> Vec<ListEntry<'a>> {
+    let mut entries: Vec<ListEntry> = manifest
+        .packages
+        .iter()
+        .map(|(name, pkg)| {
+            let latest = latest.and_then(|l| l.get(name)).map(String::as_str);
+            ListEntry {
+                name,
+                version: &pkg.version,
+                platform: &pkg.platform,
+                source: pkg.source.describe(),
+                installed_at: pkg.installed_at,
+                updated_at: pkg.last_updated(),
+                pinned: pkg.pin.as_deref(),
+                outdated: latest.map(|latest| pkg.has_update(latest)),
+                latest,
+                package: pkg,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.name.cmp(b.name));
+    entries
+}
+
+/// Look up the latest version of every installed package, keyed by name
+///
+/// Scripts and packages that could not be checked are left out.
+fn fetch_latest_versions(
+    config: &Config,
+    manifest: &InstalledManifest,
+) -> Result<HashMap<String, String>> {
+    let github = GitHubProvider::new()?;
+    let (upgradeable, up_to_date, ignored, rate_limited) =
+        find_upgradeable(config, manifest, &github, &[], &[], false)?;
+    if let Some((exceeded, skipped)) = rate_limited {
+        eprintln!(
+            "{} {} ({} package(s) not checked: {})",
+            "⚠".yellow(),
+            exceeded,
+            skipped.len(),
+            skipped.join(", ")
+        );
+    }
+
+    Ok(upgradeable
+        .into_iter()
+        .chain(ignored)
+        .map(|(name, _, latest)| (name, latest))
+        .chain(up_to_date)
+        .collect())
+}
+
 /// List only installed packages
-fn list_installed_packages(config: &Config) -> Result<()> {
+fn list_installed_packages(config: &Config, outdated: bool, json: bool) -> Result<()> {
     // Load installed manifest
     let manifest = config.get_or_create_installed()?;
 
+    let latest = if (outdated || json) && !manifest.packages.is_empty() {
+        Some(fetch_latest_versions(config, &manifest)?)
+    } else {
+        None
+    };
+    let mut entries = list_entries(&manifest, latest.as_ref());
+    let unchecked = entries
+        .iter()
+        .filter(|e| e.outdated.is_none())
+        .filter(|e| !matches!(e.package.source, PackageSource::Script { .. }))
+        .count();
+    if outdated {
+        entries.retain(|entry| entry.outdated == Some(true));
+    }
+
+    if json {
+        let document = ListDocument {
+            schema_version: LIST_SCHEMA_VERSION,
+            packages: entries,
+        };
+        println!("{}", serde_json::to_string_pretty(&document)?);
+        return Ok(());
+    }
+
     if manifest.packages.is_empty() {
         println!("{}", "No packages installed".yellow());
         println!("Install packages with: wenget add <name>");
         return Ok(());
     }
 
+    if outdated && entries.is_empty() {
+        if unchecked == 0 {
+            println!("{} All packages are up to date", "✓".green());
+        } else {
+            println!(
+                "{} No outdated packages found; {} could not be checked",
+                "ℹ".cyan(),
+                unchecked
+            );
+        }
+        return Ok(());
+    }
+
     // Print header
-    println!("{}", "Installed packages".bold());
+    println!(
+        "{}",
+        if outdated {
+            "Outdated packages"
+        } else {
+            "Installed packages"
+        }
+        .bold()
+    );
     println!();
     println!(
-        "{:<20} {:<15} {:<10} {:<12} {}",
+        "{:<20} {:<15} {:<10} {:<12} {:<21} {}",
         "NAME".bold(),
         "COMMAND".bold(),
         "VERSION".bold(),
         "SOURCE".bold(),
+        "UPDATED".bold(),
         "DESCRIPTION".bold()
     );
-    println!("{}", "─".repeat(100));
-
-    // Convert to sorted vector for consistent display
-    let mut packages: Vec<_> = manifest.packages.iter().collect();
-    packages.sort_by(|a, b| a.0.cmp(b.0));
+    println!("{}", "─".repeat(120));
 
     // Print packages
-    for (name, pkg) in packages {
+    for entry in &entries {
+        let pkg = entry.package;
+
         // Get source display
         let source_display = match &pkg.source {
             PackageSource::Bucket { name } => name.clone(),
@@ -65,20 +206,64 @@ fn list_installed_packages(config: &Config) -> Result<()> {
         } else {
             pkg.description.clone()
         };
+        let pin = match entry.pinned {
+            Some(pin) => format!(" (pinned {})", pin).dimmed().to_string(),
+            None => String::new(),
+        };
+        let latest = match entry.latest.filter(|_| entry.outdated == Some(true)) {
+            Some(latest) => format!(" (latest {})", latest).yellow().to_string(),
+            None => String::new(),
+        };
+        let broken = if pkg.is_broken() {
+            " (broken: install directory missing)".red().to_string()
+        } else {
+            String::new()
+        };
 
         println!(
-            "{:<20} {:<15} {:<10} {:<12} {}",
-            name.green(),
+            "{:<20} {:<15} {:<10} {:<12} {:<21} {}{}{}{}",
+            entry.name.green(),
             pkg.command_name.yellow(),
-            pkg.version,
+            entry.version,
             source_display.cyan(),
-            description
+            format_timestamp(&entry.updated_at),
+            description,
+            latest,
+            pin,
+            broken
         );
     }
 
     println!();
+    if outdated {
+        println!(
+            "Total: {} of {} package(s) outdated; run {} to upgrade them",
+            entries.len(),
+            manifest.packages.len(),
+            "wenget update".cyan()
+        );
+        if unchecked > 0 {
+            println!("{} {} could not be checked", "ℹ".cyan(), unchecked);
+        }
+        return Ok(());
+    }
     println!("Total: {} package(s) installed", manifest.packages.len());
 
+    let broken = manifest.broken_packages();
+    if !broken.is_empty() {
+        println!(
+            "{} {} package(s) have a missing install directory: {}",
+            "⚠".yellow(),
+            broken.len(),
+            broken.join(", ")
+        );
+        println!(
+            "  Run {} to reinstall them, or {} to remove them",
+            "wenget repair".cyan(),
+            "wenget repair --prune".cyan()
+        );
+    }
+
     Ok(())
 }
 
@@ -215,3 +400,58 @@ fn list_all_packages(config: &Config) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_json_shape() {
+        let package: InstalledPackage = serde_json::from_str(
+            r#"{
+                "version": "1.0.0",
+                "platform": "linux-x86_64",
+                "installed_at": "2025-01-01T00:00:00Z",
+                "updated_at": "2025-02-01T00:00:00Z",
+                "install_path": "/nonexistent/apps/tool",
+                "files": ["tool"],
+                "source": { "type": "bucket", "name": "main" },
+                "description": "A tool",
+                "command_name": "tool",
+                "pin": "1.x"
+            }"#,
+        )
+        .unwrap();
+        let mut manifest = InstalledManifest::new();
+        manifest.upsert_package("tool".to_string(), package.clone());
+        manifest.upsert_package("other".to_string(), package);
+        let latest = HashMap::from([("tool".to_string(), "1.2.0".to_string())]);
+
+        let document = ListDocument {
+            schema_version: LIST_SCHEMA_VERSION,
+            packages: list_entries(&manifest, Some(&latest)),
+        };
+        let value = serde_json::to_value(&document).unwrap();
+
+        assert_eq!(value["schema_version"], 1);
+        let packages = value["packages"].as_array().unwrap();
+        // Sorted by name; a package that could not be checked has nulls
+        assert_eq!(packages[0]["name"], "other");
+        assert!(packages[0]["latest"].is_null());
+        assert!(packages[0]["outdated"].is_null());
+        assert_eq!(
+            packages[1],
+            serde_json::json!({
+                "name": "tool",
+                "version": "1.0.0",
+                "platform": "linux-x86_64",
+                "source": "bucket:main",
+                "installed_at": "2025-01-01T00:00:00Z",
+                "updated_at": "2025-02-01T00:00:00Z",
+                "pinned": "1.x",
+                "outdated": true,
+                "latest": "1.2.0"
+            })
+        );
+    }
+}
@@ -1,25 +1,58 @@
 //! Command implementations for WenPM
 
 pub mod add;
+pub mod autoremove;
 pub mod bucket;
+pub mod cache;
+pub mod channel;
+pub mod config;
 pub mod delete;
+pub mod doctor;
+pub mod download;
+pub mod history;
 pub mod info;
 pub mod init;
 pub mod list;
+pub mod path;
+pub mod pin;
+pub mod ratelimit;
+pub mod refresh_shims;
+pub mod reinstall;
+pub mod rename;
 pub mod repair;
 pub mod search;
+pub mod self_uninstall;
+pub mod tree;
 pub mod update;
+pub mod verify;
 
 // Re-export command functions
 pub use add::run as run_add;
+pub use autoremove::run as run_autoremove;
 pub use bucket::run as run_bucket;
+pub use cache::run as run_cache;
+pub use channel::run as run_channel;
+pub use config::run as run_config;
 pub use delete::run as run_delete;
+pub use doctor::run as run_doctor;
+pub use download::run as run_download;
+pub use history::run as run_history;
 pub use info::run as run_info;
 pub use init::run as run_init;
 pub use list::run as run_list;
+pub use path::run as run_path;
+pub use pin::run as run_pin;
+pub use pin::unpin as run_unpin;
+pub use ratelimit::run as run_ratelimit;
+pub use refresh_shims::run as run_refresh_shims;
+pub use reinstall::run as run_reinstall;
+pub use rename::run as run_rename;
 pub use repair::run as run_repair;
 pub use search::run as run_search;
+pub use self_uninstall::run as run_self_uninstall;
+pub use tree::run as run_tree;
 pub use update::run as run_update;
+pub use verify::run as run_verify;
 
 // Placeholders for future commands
 // pub mod setup_path;
@@ -0,0 +1,58 @@
+//! Path command implementation
+
+use crate::core::{Config, WenPaths};
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Components `wenget path` can print, in display order
+pub const COMPONENTS: &[&str] = &[
+    "root",
+    "bin",
+    "apps",
+    "cache",
+    "downloads",
+    "installed",
+    "sources",
+];
+
+/// Print where Wenget keeps its files
+///
+/// With a component, prints just that path so it can be used in scripts
+/// (`export PATH="$(wenget path bin):$PATH"`); otherwise lists all of them.
+pub fn run(component: Option<String>) -> Result<()> {
+    let config = Config::new()?;
+    let settings = config.load_settings()?;
+    let paths = config.paths();
+
+    // Launchers go to the configured global_bin directory when one is set
+    let bin_dir = settings.global_bin.unwrap_or_else(|| paths.bin_dir());
+
+    match component {
+        Some(name) => println!("{}", resolve(paths, &bin_dir, &name)?.display()),
+        None => {
+            for name in COMPONENTS {
+                println!("{:<10} {}", name, resolve(paths, &bin_dir, name)?.display());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve a component name to its path
+fn resolve(paths: &WenPaths, bin_dir: &std::path::Path, name: &str) -> Result<PathBuf> {
+    Ok(match name {
+        "root" => paths.root().to_path_buf(),
+        "bin" => bin_dir.to_path_buf(),
+        "apps" => paths.apps_dir(),
+        "cache" => paths.cache_dir(),
+        "downloads" => paths.downloads_dir(),
+        "installed" => paths.installed_json(),
+        "sources" => paths.buckets_json(),
+        _ => anyhow::bail!(
+            "Unknown path '{}'. Available: {}",
+            name,
+            COMPONENTS.join(", ")
+        ),
+    })
+}
@@ -0,0 +1,72 @@
+//! Pin command implementation
+
+use crate::core::{Config, VersionConstraint};
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+/// Pin an installed package to a version constraint
+///
+/// Without a constraint the package is locked to its installed version.
+/// `update` then only offers the newest release satisfying the pin.
+pub fn run(name: String, version: Option<String>) -> Result<()> {
+    let config = Config::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    let pkg = installed
+        .packages
+        .get_mut(&name)
+        .with_context(|| format!("Package '{}' is not installed", name))?;
+
+    let requested = version.unwrap_or_else(|| pkg.version.clone());
+    let constraint = VersionConstraint::parse(&requested).with_context(|| {
+        format!(
+            "Invalid version constraint '{}'. Expected e.g. 13.0.5, 13.x, ^1.2 or ~1.2.3",
+            requested
+        )
+    })?;
+    let pin = constraint.to_string();
+
+    if pkg.pin.as_deref() == Some(pin.as_str()) {
+        println!("{} is already pinned to {}", name, pin);
+        return Ok(());
+    }
+
+    let in_range = constraint.matches(&pkg.version);
+    let current = pkg.version.clone();
+    pkg.pin = Some(pin.clone());
+    config.save_installed(&installed)?;
+
+    println!("{} {} pinned to {}", "✓".green(), name, pin);
+    if !in_range {
+        println!(
+            "{} Installed v{} does not match the pin; run 'wenget update {}' to switch to the newest matching release",
+            "ℹ".cyan(),
+            current,
+            name
+        );
+    }
+
+    Ok(())
+}
+
+/// Remove the version pin of an installed package
+pub fn unpin(name: String) -> Result<()> {
+    let config = Config::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    let pkg = installed
+        .packages
+        .get_mut(&name)
+        .with_context(|| format!("Package '{}' is not installed", name))?;
+
+    let Some(pin) = pkg.pin.take() else {
+        println!("{} is not pinned", name);
+        return Ok(());
+    };
+    config.save_installed(&installed)?;
+
+    println!("{} {} is no longer pinned to {}", "✓".green(), name, pin);
+    println!("Run 'wenget update {}' to pick up the latest release", name);
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! Rate limit command implementation
+
+use crate::providers::github::github_token_var;
+use crate::providers::GitHubProvider;
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use colored::Colorize;
+
+/// Show the GitHub API rate-limit status
+pub fn run() -> Result<()> {
+    let rate_limit = GitHubProvider::new()?.fetch_rate_limit()?;
+    let token = github_token_var();
+
+    println!("{}", "GitHub API rate limit".bold());
+    println!("{}", "─".repeat(60));
+
+    let remaining = format!("{} / {}", rate_limit.remaining, rate_limit.limit);
+    let remaining = if rate_limit.remaining == 0 {
+        remaining.red()
+    } else if rate_limit.is_low() {
+        remaining.yellow()
+    } else {
+        remaining.green()
+    };
+    println!("{:<16} {}", "Remaining:".bold(), remaining);
+
+    if let Some(reset) = DateTime::from_timestamp(rate_limit.reset as i64, 0) {
+        let minutes = (reset - Utc::now()).num_minutes().max(0);
+        println!(
+            "{:<16} {} (in {} min)",
+            "Resets at:".bold(),
+            reset.with_timezone(&Local).format("%H:%M:%S"),
+            minutes
+        );
+    }
+
+    match token {
+        Some(var) => println!("{:<16} {} ({})", "Token:".bold(), "configured".green(), var),
+        None => println!("{:<16} {}", "Token:".bold(), "not configured".yellow()),
+    }
+    println!();
+
+    if rate_limit.remaining == 0 {
+        println!(
+            "{} The limit is exhausted; `update` skips the remaining packages until it resets (or waits with --wait)",
+            "⚠".yellow()
+        );
+    }
+    if token.is_none() {
+        println!(
+            "{} Set GITHUB_TOKEN to raise the limit from 60 to 5,000 requests per hour",
+            "ℹ".cyan()
+        );
+    }
+
+    Ok(())
+}
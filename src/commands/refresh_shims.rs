@@ -0,0 +1,238 @@
+//! Refresh-shims command implementation
+//!
+//! Rewrites the launchers of installed packages so they point at the
+//! executable under the package's current install directory, e.g. after the
+//! wenget root was moved or a launcher was overwritten. No files are moved;
+//! only launchers and the recorded paths change.
+
+use super::add::create_launcher;
+use crate::core::manifest::PackageSource;
+use crate::core::paths::rebase_path;
+use crate::core::{Config, ShimMode, WenPaths};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use glob::Pattern;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(windows)]
+use crate::installer::shim_target;
+#[cfg(unix)]
+use crate::installer::wrapper_target;
+
+/// Refresh the launchers of the named installed packages (globs allowed), or all of them
+///
+/// Scripts and packages installed with `--no-shim` are skipped.
+pub fn run(names: Vec<String>, all: bool) -> Result<()> {
+    let config = Config::new()?;
+    let paths = WenPaths::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    if installed.packages.is_empty() {
+        println!("{}", "No packages installed".yellow());
+        return Ok(());
+    }
+
+    if names.is_empty() && !all {
+        println!("{}", "No package names provided".yellow());
+        println!("Usage: wenget refresh-shims <name>... | --all");
+        return Ok(());
+    }
+
+    let patterns: Vec<Pattern> = names
+        .iter()
+        .map(|p| Pattern::new(p))
+        .collect::<Result<_, _>>()?;
+    let mut targets: Vec<String> = installed
+        .packages
+        .iter()
+        .filter(|(name, _)| all || patterns.iter().any(|p| p.matches(name)))
+        .filter(|(_, pkg)| !pkg.no_shim && !matches!(pkg.source, PackageSource::Script { .. }))
+        .map(|(name, _)| name.clone())
+        .collect();
+    targets.sort();
+
+    if targets.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "No installed packages with launchers found matching: {:?}",
+                names
+            )
+            .yellow()
+        );
+        return Ok(());
+    }
+
+    let mut refreshed = 0;
+    let mut current = 0;
+    let mut failed = 0;
+
+    for name in &targets {
+        let original = installed.packages[name].clone();
+        let old_app_dir = PathBuf::from(&original.install_path);
+        let app_dir = paths.app_dir(name);
+        let old_shim = match original.shim_path {
+            Some(ref shim_path) => PathBuf::from(shim_path),
+            None => paths.bin_shim_path(&original.command_name),
+        };
+
+        let mut pkg = original.clone();
+        pkg.relocate(&app_dir, paths.root());
+        let new_shim = match pkg.shim_path {
+            Some(ref shim_path) => PathBuf::from(shim_path),
+            None => paths.bin_shim_path(&pkg.command_name),
+        };
+
+        // Packages installed before the executable path was recorded fall
+        // back to where their launcher pointed
+        let exe_path = match pkg.exe_path {
+            Some(ref exe_path) => Some(PathBuf::from(exe_path)),
+            None => launcher_target(&old_shim).map(|t| rebase_path(&t, &old_app_dir, &app_dir)),
+        };
+        let exe_path = match exe_path {
+            Some(exe_path) if exe_path.is_file() => exe_path,
+            other => {
+                let location = other
+                    .map(|p| format!(" at {}", p.display()))
+                    .unwrap_or_default();
+                println!(
+                    "  {} {}: executable not found{}; run 'wenget reinstall {}'",
+                    "✗".red(),
+                    name,
+                    location,
+                    name
+                );
+                failed += 1;
+                continue;
+            }
+        };
+
+        let mut launchers = vec![(
+            old_shim,
+            new_shim,
+            exe_path.clone(),
+            pkg.command_name.clone(),
+        )];
+        for (old, new) in original.extra_shims.iter().zip(&pkg.extra_shims) {
+            let (old, new) = (PathBuf::from(old), PathBuf::from(new));
+            let target = launcher_target(&old)
+                .or_else(|| launcher_target(&new))
+                .map(|t| rebase_path(&t, &old_app_dir, &app_dir));
+            let Some(target) = target else {
+                // A copy in place needs nothing; where it was copied from is unknown
+                if pkg.shim_mode == ShimMode::Copy && old == new && new.is_file() {
+                    continue;
+                }
+                println!(
+                    "  {} {}: cannot tell where {} points; run 'wenget reinstall {}'",
+                    "⚠".yellow(),
+                    name,
+                    new.display(),
+                    name
+                );
+                continue;
+            };
+            let command = new
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or(name)
+                .to_string();
+            launchers.push((old, new, target, command));
+        }
+
+        let mut fixed = 0;
+        let mut result = Ok(());
+        for (old, new, target, command) in &launchers {
+            if old == new && launcher_is_current(new, target, pkg.shim_mode) {
+                continue;
+            }
+            result = refresh_launcher(old, new, target, command, pkg.shim_mode);
+            if result.is_err() {
+                break;
+            }
+            fixed += 1;
+        }
+
+        if let Err(e) = result {
+            println!("  {} {}: {:#}", "✗".red(), name, e);
+            failed += 1;
+            continue;
+        }
+
+        pkg.exe_path = Some(exe_path.to_string_lossy().to_string());
+        installed.packages.insert(name.clone(), pkg);
+
+        if fixed == 0 {
+            println!("  {} {} (up to date)", "•".dimmed(), name.dimmed());
+            current += 1;
+        } else {
+            println!(
+                "  {} {}: {} launcher(s) refreshed",
+                "✓".green(),
+                name,
+                fixed
+            );
+            refreshed += fixed;
+        }
+    }
+
+    config.save_installed(&installed)?;
+
+    println!();
+    println!("{}", "Summary:".bold());
+    println!("  {} launcher(s) refreshed", refreshed.to_string().green());
+    if current > 0 {
+        println!("  {} package(s) already up to date", current);
+    }
+    if failed > 0 {
+        println!("  {} package(s) failed", failed.to_string().red());
+    }
+
+    Ok(())
+}
+
+/// Recreate a launcher at `new` for `target`, removing it from `old` if it moved
+fn refresh_launcher(
+    old: &Path,
+    new: &Path,
+    target: &Path,
+    command: &str,
+    mode: ShimMode,
+) -> Result<()> {
+    create_launcher(target, new, command, mode)?;
+    if old != new && (old.exists() || old.is_symlink()) {
+        fs::remove_file(old)
+            .with_context(|| format!("Failed to remove launcher: {}", old.display()))?;
+    }
+    Ok(())
+}
+
+/// Whether the launcher at `launcher` already runs `target`
+pub(super) fn launcher_is_current(launcher: &Path, target: &Path, mode: ShimMode) -> bool {
+    match mode {
+        ShimMode::Copy if cfg!(unix) => match (fs::read(launcher), fs::read(target)) {
+            (Ok(copy), Ok(original)) => copy == original,
+            _ => false,
+        },
+        _ => launcher_target(launcher).as_deref() == Some(target),
+    }
+}
+
+/// Where an existing launcher points: a symlink's or wrapper script's target
+/// on Unix, the executable a `.cmd` shim runs on Windows
+///
+/// Copies of the executable point nowhere, so they are always refreshed.
+pub(super) fn launcher_target(launcher: &Path) -> Option<PathBuf> {
+    #[cfg(unix)]
+    {
+        fs::read_link(launcher)
+            .ok()
+            .or_else(|| wrapper_target(launcher))
+    }
+
+    #[cfg(windows)]
+    {
+        shim_target(launcher)
+    }
+}
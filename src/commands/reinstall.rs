@@ -0,0 +1,95 @@
+//! Reinstall command implementation
+//!
+//! Reinstalls packages at the version they are currently at, e.g. to get
+//! fresh binaries and launchers after an OS upgrade. Each package goes
+//! through the regular install, which swaps the new files into place, so a
+//! failure leaves that package as it was and the others are unaffected.
+
+use super::add::{self, AddOptions, OutcomeAction};
+use crate::core::manifest::PackageSource;
+use crate::core::Config;
+use anyhow::Result;
+use colored::Colorize;
+use glob::Pattern;
+
+/// Reinstall the named installed packages (globs allowed), or all of them
+pub fn run(names: Vec<String>, all: bool, yes: bool) -> Result<()> {
+    let config = Config::new()?;
+    let installed = config.get_or_create_installed()?;
+
+    if installed.packages.is_empty() {
+        println!("{}", "No packages installed".yellow());
+        return Ok(());
+    }
+
+    if names.is_empty() && !all {
+        println!("{}", "No package names provided".yellow());
+        println!("Usage: wenget reinstall <name>... | --all");
+        return Ok(());
+    }
+
+    let patterns: Vec<Pattern> = names
+        .iter()
+        .map(|p| Pattern::new(p))
+        .collect::<Result<_, _>>()?;
+    let mut targets: Vec<&String> = installed
+        .packages
+        .keys()
+        .filter(|name| all || patterns.iter().any(|p| p.matches(name)))
+        .collect();
+    targets.sort();
+
+    if targets.is_empty() {
+        println!(
+            "{}",
+            format!("No installed packages found matching: {:?}", names).yellow()
+        );
+        return Ok(());
+    }
+
+    // Scripts are reinstalled from where they came from, packages by name
+    // (which also finds packages installed from a repository URL)
+    let inputs: Vec<String> = targets
+        .iter()
+        .map(|name| match installed.packages[*name].source {
+            PackageSource::Script { ref origin, .. } => origin.clone(),
+            _ => name.to_string(),
+        })
+        .collect();
+
+    let report = add::install(
+        inputs,
+        AddOptions {
+            yes,
+            upgrade: true,
+            reinstall: true,
+            ..Default::default()
+        },
+    )?;
+
+    if report.outcomes.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("{}", "Reinstalled packages:".bold());
+    for outcome in &report.outcomes {
+        let version = outcome.to.as_deref().unwrap_or("unknown");
+        match outcome.action {
+            OutcomeAction::Failed => println!(
+                "  {} {}: {}",
+                "✗".red(),
+                outcome.name,
+                outcome.error.as_deref().unwrap_or("failed")
+            ),
+            OutcomeAction::Skipped => {
+                println!("  {} {} v{}", "•".dimmed(), outcome.name, version.dimmed())
+            }
+            OutcomeAction::Installed | OutcomeAction::Upgraded => {
+                println!("  {} {} v{}", "✓".green(), outcome.name, version)
+            }
+        }
+    }
+
+    report.result()
+}
@@ -0,0 +1,133 @@
+//! Rename command implementation
+
+use super::add::create_launcher;
+use crate::core::manifest::PackageSource;
+use crate::core::{Config, WenPaths};
+use crate::installer::normalize_command_name;
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(unix)]
+use crate::installer::wrapper_target;
+
+/// Give an installed package's launcher a different command name
+pub fn run(name: String, new_name: String, force: bool) -> Result<()> {
+    let config = Config::new()?;
+    let paths = WenPaths::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    let pkg = installed
+        .get_package(&name)
+        .with_context(|| format!("Package '{}' is not installed", name))?
+        .clone();
+
+    if matches!(pkg.source, PackageSource::Script { .. }) {
+        anyhow::bail!(
+            "'{}' is a script; reinstall it with 'wenget add <script> --name {}' instead",
+            name,
+            new_name
+        );
+    }
+    if pkg.no_shim {
+        anyhow::bail!(
+            "'{}' was installed with --no-shim and has no launcher to rename",
+            name
+        );
+    }
+    if new_name.is_empty() || new_name.contains(['/', '\\']) {
+        anyhow::bail!("Invalid command name: '{}'", new_name);
+    }
+    if new_name == pkg.command_name {
+        println!("{} is already available as {}", name, new_name);
+        return Ok(());
+    }
+
+    let old_shim = match pkg.shim_path {
+        Some(ref shim_path) => PathBuf::from(shim_path),
+        None => paths.bin_shim_path(&pkg.command_name),
+    };
+    let bin_dir = old_shim
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| paths.bin_dir());
+    let new_shim = WenPaths::shim_path_in(&bin_dir, &new_name);
+
+    // Refuse to take over another launcher unless forced
+    if !force {
+        if let Some((owner, _)) = installed
+            .packages
+            .iter()
+            .find(|(other, p)| **other != name && p.command_name == new_name)
+        {
+            anyhow::bail!(
+                "'{}' is already the command of {}. Use --force to replace it",
+                new_name,
+                owner
+            );
+        }
+        if new_shim.exists() || new_shim.is_symlink() {
+            anyhow::bail!(
+                "{} already exists. Use --force to replace it",
+                new_shim.display()
+            );
+        }
+    }
+
+    let exe_path = installed_exe_path(pkg.exe_path.as_deref(), &old_shim)?;
+
+    create_launcher(&exe_path, &new_shim, &new_name, pkg.shim_mode)?;
+
+    if old_shim.exists() || old_shim.is_symlink() {
+        fs::remove_file(&old_shim)
+            .with_context(|| format!("Failed to remove launcher: {}", old_shim.display()))?;
+    }
+
+    // Renaming back to the detected name drops the alias
+    let detected = exe_path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .map(normalize_command_name);
+    let old_name = pkg.command_name.clone();
+
+    let entry = installed
+        .packages
+        .get_mut(&name)
+        .expect("package checked above");
+    entry.alias = (detected.as_deref() != Some(new_name.as_str())).then(|| new_name.clone());
+    entry.command_name = new_name.clone();
+    entry.shim_path = Some(new_shim.to_string_lossy().to_string());
+    entry.exe_path = Some(exe_path.to_string_lossy().to_string());
+    config.save_installed(&installed)?;
+
+    println!(
+        "{} {} is now available as {} (was {})",
+        "✓".green(),
+        name,
+        new_name.yellow(),
+        old_name
+    );
+
+    Ok(())
+}
+
+/// Locate the executable a package's launcher points at
+///
+/// Packages installed before the executable path was recorded fall back to
+/// the target of their symlink or wrapper script.
+fn installed_exe_path(recorded: Option<&str>, shim: &Path) -> Result<PathBuf> {
+    if let Some(path) = recorded {
+        return Ok(PathBuf::from(path));
+    }
+
+    #[cfg(unix)]
+    if let Some(target) = fs::read_link(shim).ok().or_else(|| wrapper_target(shim)) {
+        return Ok(target);
+    }
+
+    anyhow::bail!(
+        "Cannot find the executable behind {}; reinstall the package first",
+        shim.display()
+    )
+}
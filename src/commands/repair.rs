@@ -1,17 +1,25 @@
 //! Repair command for Wenget
 //!
-//! Checks and repairs corrupted configuration files.
+//! Checks and repairs corrupted configuration files, and packages whose
+//! install directory was deleted by hand.
 
+use super::add::{self, AddOptions};
+use super::delete::delete_package;
 use crate::bucket::BucketConfig;
 use crate::cache::ManifestCache;
-use crate::core::manifest::InstalledManifest;
+use crate::core::manifest::{InstalledManifest, PackageSource};
 use crate::core::repair::{check_json_file, create_backup, FileStatus};
 use crate::core::Config;
 use anyhow::Result;
 use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
 
 /// Run the repair command
-pub fn run(force: bool) -> Result<()> {
+///
+/// Broken packages are reinstalled at their recorded version, or with
+/// `prune` removed along with their launchers; either is asked for first
+/// unless `yes` is set.
+pub fn run(force: bool, prune: bool, yes: bool) -> Result<()> {
     println!("{}", "Checking Wenget configuration files...".cyan());
     println!();
 
@@ -48,7 +56,8 @@ pub fn run(force: bool) -> Result<()> {
 
     if issues == 0 && !force {
         println!("{}", "All configuration files are OK.".green());
-        return Ok(());
+        println!();
+        return repair_packages(&config, prune, &|question| Ok(yes || confirm(question)?));
     }
 
     if force {
@@ -80,6 +89,109 @@ pub fn run(force: bool) -> Result<()> {
 
     println!();
     println!("{}", "Repair complete.".green());
+    println!();
+
+    repair_packages(&config, prune, &|question| Ok(yes || confirm(question)?))
+}
+
+/// Ask a yes/no question, defaulting to yes; without a terminal the answer is no
+fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    print!("{} [Y/n] ", question);
+    io::stdout().flush()?;
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_lowercase();
+    Ok(input.is_empty() || input == "y" || input == "yes")
+}
+
+/// Reinstall or remove packages whose install directory is missing, once
+/// `confirm` agrees
+fn repair_packages(
+    config: &Config,
+    prune: bool,
+    confirm: &dyn Fn(&str) -> Result<bool>,
+) -> Result<()> {
+    let mut installed = config.get_or_create_installed()?;
+    let broken: Vec<String> = installed
+        .broken_packages()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    if broken.is_empty() {
+        println!("{}", "All installed packages are OK.".green());
+        return Ok(());
+    }
+
+    println!("{}", "Broken packages (install directory missing):".bold());
+    for name in &broken {
+        if let Some(pkg) = installed.get_package(name) {
+            println!("  {} {} ({})", "•".yellow(), name, pkg.install_path);
+        }
+    }
+    println!();
+
+    if prune {
+        if !confirm("Remove them and their launchers?")? {
+            println!("{}", "Nothing removed".yellow());
+            return Ok(());
+        }
+        for name in &broken {
+            match delete_package(config, config.paths(), &mut installed, name) {
+                Ok(()) => println!("  {} Removed {}", "✓".green(), name),
+                Err(e) => println!("  {} {}: {:#}", "✗".red(), name, e),
+            }
+        }
+        config.save_installed(&installed)?;
+        return Ok(());
+    }
+
+    // Scripts are reinstalled from where they came from, packages by name
+    let inputs: Vec<String> = broken
+        .iter()
+        .map(
+            |name| match installed.get_package(name).map(|p| &p.source) {
+                Some(PackageSource::Script { origin, .. }) => origin.clone(),
+                _ => name.clone(),
+            },
+        )
+        .collect();
+
+    if !confirm("Reinstall them at their recorded versions?")? {
+        println!(
+            "Run {} to reinstall them, or {} to remove them",
+            "wenget repair --yes".cyan(),
+            "wenget repair --prune".cyan()
+        );
+        return Ok(());
+    }
+
+    // The recorded version, like `wenget reinstall`, not the latest release
+    println!("{}", "Reinstalling broken packages...".cyan());
+    println!();
+    add::install(
+        inputs,
+        AddOptions {
+            yes: true,
+            upgrade: true,
+            reinstall: true,
+            ..Default::default()
+        },
+    )?;
+
+    let still_broken = config.get_or_create_installed()?.broken_packages().len();
+    if still_broken > 0 {
+        println!();
+        println!(
+            "{} {} package(s) could not be reinstalled; run {} to remove them",
+            "⚠".yellow(),
+            still_broken,
+            "wenget repair --prune".cyan()
+        );
+    }
 
     Ok(())
 }
@@ -196,3 +308,71 @@ fn repair_cache(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{InstalledPackage, WenPaths};
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    /// A config in a temp dir with "ok" installed and "gone" missing its install directory
+    fn setup() -> (TempDir, Config) {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        let paths = config.paths();
+        let mut installed = InstalledManifest::new();
+        for name in ["ok", "gone"] {
+            installed.upsert_package(
+                name.to_string(),
+                InstalledPackage {
+                    install_path: paths.app_dir(name).to_string_lossy().into_owned(),
+                    ..InstalledPackage::test_default(name)
+                },
+            );
+        }
+        std::fs::create_dir_all(paths.app_dir("ok")).unwrap();
+        std::fs::create_dir_all(paths.bin_dir()).unwrap();
+        std::fs::write(paths.bin_shim_path("gone"), "").unwrap();
+        config.save_installed(&installed).unwrap();
+        (temp_dir, config)
+    }
+
+    #[test]
+    fn test_repair_packages_asks_before_reinstalling() {
+        let (_temp_dir, config) = setup();
+        let asked = RefCell::new(Vec::new());
+
+        // Declining leaves everything as it was, without going to the network
+        repair_packages(&config, false, &|question| {
+            asked.borrow_mut().push(question.to_string());
+            Ok(false)
+        })
+        .unwrap();
+
+        assert_eq!(
+            *asked.borrow(),
+            vec!["Reinstall them at their recorded versions?"]
+        );
+        let installed = config.get_or_create_installed().unwrap();
+        assert_eq!(installed.broken_packages(), vec!["gone"]);
+        assert!(config.paths().bin_shim_path("gone").exists());
+    }
+
+    #[test]
+    fn test_repair_packages_prune() {
+        let (_temp_dir, config) = setup();
+
+        repair_packages(&config, true, &|_| Ok(false)).unwrap();
+        assert!(config
+            .get_or_create_installed()
+            .unwrap()
+            .is_installed("gone"));
+
+        repair_packages(&config, true, &|_| Ok(true)).unwrap();
+        let installed = config.get_or_create_installed().unwrap();
+        assert!(!installed.is_installed("gone"));
+        assert!(installed.is_installed("ok"));
+        assert!(!config.paths().bin_shim_path("gone").exists());
+    }
+}
@@ -1,13 +1,19 @@
 //! Search command implementation
 
 use crate::core::{Config, Platform};
+use crate::package_resolver::{in_sources, validate_sources};
 use anyhow::Result;
 use colored::Colorize;
 use glob::Pattern;
 
 /// Search for packages and scripts
-pub fn run(patterns: Vec<String>) -> Result<()> {
+///
+/// With `tags`, only packages carrying all of them are shown (scripts have no
+/// tags); the name patterns may then be omitted to list every tagged package.
+/// With `sources`, only entries from those buckets are searched.
+pub fn run(patterns: Vec<String>, tags: Vec<String>, sources: Vec<String>) -> Result<()> {
     let config = Config::new()?;
+    validate_sources(&config, &sources)?;
 
     // Load cache
     let cache = config.get_or_rebuild_cache()?;
@@ -18,11 +24,16 @@ pub fn run(patterns: Vec<String>) -> Result<()> {
         return Ok(());
     }
 
-    if patterns.is_empty() {
+    if patterns.is_empty() && tags.is_empty() {
         println!("{}", "No search pattern provided".yellow());
-        println!("Usage: wenget search <name>...");
+        println!("Usage: wenget search <name>... [--tag <tag>]");
         return Ok(());
     }
+    let patterns = if patterns.is_empty() {
+        vec!["*".to_string()]
+    } else {
+        patterns
+    };
 
     // Get current platform
     let platform = Platform::current();
@@ -48,7 +59,10 @@ pub fn run(patterns: Vec<String>) -> Result<()> {
             // Check if supports current platform
             let platform_matches = platform_ids.iter().any(|id| pkg.platforms.contains_key(id));
 
-            name_matches && platform_matches
+            name_matches
+                && platform_matches
+                && pkg.has_tags(&tags)
+                && in_sources(&sources, &cached_pkg.source)
         })
         .collect();
 
@@ -66,20 +80,29 @@ pub fn run(patterns: Vec<String>) -> Result<()> {
             // Check if supports current platform
             let platform_matches = script.script_type.is_supported_on_current_platform();
 
-            name_matches && platform_matches
+            name_matches
+                && platform_matches
+                && tags.is_empty()
+                && in_sources(&sources, &cached_script.source)
         })
         .collect();
 
+    let query = if tags.is_empty() {
+        format!("{:?}", patterns)
+    } else {
+        format!("{:?} tagged {}", patterns, tags.join(", "))
+    };
+
     if matching_packages.is_empty() && matching_scripts.is_empty() {
         println!(
             "{}",
-            format!("No packages or scripts found matching: {:?}", patterns).yellow()
+            format!("No packages or scripts found matching: {}", query).yellow()
         );
         return Ok(());
     }
 
     // Print header
-    println!("{}", format!("Search results for: {:?}", patterns).bold());
+    println!("{}", format!("Search results for: {}", query).bold());
     println!();
 
     // Print packages
@@ -0,0 +1,337 @@
+//! Self uninstall command implementation
+//!
+//! Removes launchers, the manifest cache and (unless `--keep-data`) installed
+//! apps and manifests, then the wenget executable itself. Because it is so
+//! destructive it only previews what would be removed unless `--yes` is given.
+
+use super::delete::{delete_executable, remove_from_path};
+use crate::core::{Config, WenPaths};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Uninstall Wenget
+///
+/// With `keep_data`, installed apps and the manifests describing them
+/// (installed.json, buckets, settings, history) are left in place so a later
+/// reinstall picks them up again. With `keep_binary`, the running executable
+/// is not removed.
+pub fn run(keep_data: bool, keep_binary: bool, yes: bool) -> Result<()> {
+    let config = Config::new()?;
+    let plan = UninstallPlan::new(&config, keep_data, keep_binary)?;
+    plan.print(config.paths());
+
+    if !yes {
+        println!("{}", "═".repeat(60));
+        println!();
+        println!(
+            "{} Nothing was removed. Re-run with {} to uninstall.",
+            "⚠".yellow(),
+            "--yes".bold()
+        );
+        return Ok(());
+    }
+
+    plan.execute(config.paths())
+}
+
+/// Everything an uninstall removes, worked out before anything is touched
+///
+/// Shared with `wenget del self`, which asks for confirmation instead of
+/// requiring `--yes`.
+pub(crate) struct UninstallPlan {
+    exe_path: PathBuf,
+    /// Launchers outside the bin directory, listed separately in the preview
+    shims: Vec<PathBuf>,
+    /// Files and directories to delete, apart from the executable
+    targets: Vec<PathBuf>,
+    keep_data: bool,
+    keep_binary: bool,
+}
+
+impl UninstallPlan {
+    pub(crate) fn new(config: &Config, keep_data: bool, keep_binary: bool) -> Result<Self> {
+        let exe_path = env::current_exe().context("Failed to get current executable path")?;
+        let paths = config.paths();
+        let shims = external_shims(config);
+        let mut targets = vec![paths.bin_dir()];
+        targets.extend(shims.iter().cloned());
+        if keep_data {
+            targets.push(paths.cache_dir());
+            targets.push(paths.manifest_cache_json());
+        } else {
+            targets.push(paths.root().to_path_buf());
+        }
+
+        Ok(Self {
+            exe_path,
+            shims,
+            targets,
+            keep_data,
+            keep_binary,
+        })
+    }
+
+    /// Show what would be removed
+    pub(crate) fn print(&self, paths: &WenPaths) {
+        println!("{}", "Wenget Uninstall".bold().red());
+        println!("{}", "═".repeat(60));
+        println!();
+        println!("{}", "This will remove:".yellow());
+        println!();
+        println!("  {} Package launchers:", "•".bold());
+        println!("     {}", paths.bin_dir().display());
+        for shim in &self.shims {
+            println!("     {}", shim.display());
+        }
+        if self.keep_data {
+            println!("  {} The manifest cache:", "•".bold());
+            println!("     {}", paths.cache_dir().display());
+            println!("     {}", paths.manifest_cache_json().display());
+        } else {
+            println!(
+                "  {} All installed apps, manifests and the cache:",
+                "•".bold()
+            );
+            println!("     {}", paths.root().display());
+        }
+        println!("  {} Wenget from PATH environment variable", "•".bold());
+        if !self.keep_binary {
+            println!("  {} The wenget executable itself:", "•".bold());
+            println!("     {}", self.exe_path.display());
+        }
+        if self.keep_data {
+            println!();
+            println!(
+                "{} Installed apps in {} are kept (--keep-data)",
+                "ℹ".cyan(),
+                paths.apps_dir().display()
+            );
+        }
+        println!();
+    }
+
+    /// Remove everything in the plan
+    pub(crate) fn execute(&self, paths: &WenPaths) -> Result<()> {
+        println!("{}", "Proceeding with uninstallation...".cyan());
+        println!();
+
+        // Step 1: Remove from PATH
+        println!("{} Removing from PATH...", "1.".bold());
+        match remove_from_path(&paths.bin_dir()) {
+            Ok(()) => println!("   {} PATH updated", "✓".green()),
+            Err(e) => println!("   {} Failed to update PATH: {}", "⚠".yellow(), e),
+        }
+        println!();
+
+        // Step 2: Delete files, leaving the running executable for step 3
+        println!("{} Deleting Wenget files...", "2.".bold());
+        let mut failed = 0;
+        for target in &self.targets {
+            if target.symlink_metadata().is_err() {
+                continue;
+            }
+            match remove_except(target, &self.exe_path) {
+                Ok(()) => println!("   {} Deleted: {}", "✓".green(), target.display()),
+                Err(e) => {
+                    println!("   {} {}: {:#}", "✗".red(), target.display(), e);
+                    failed += 1;
+                }
+            }
+        }
+        println!();
+
+        // Step 3: Delete the executable
+        let exe_in_wenget = self.exe_path.starts_with(paths.root());
+        if self.keep_binary {
+            println!(
+                "{} Keeping wenget executable: {}",
+                "3.".bold(),
+                self.exe_path.display()
+            );
+        } else {
+            println!("{} Deleting wenget executable...", "3.".bold());
+            // Whatever is left of the root only holds the executable, unless data is kept
+            delete_executable(
+                &self.exe_path,
+                exe_in_wenget && !self.keep_data,
+                paths.root(),
+            )?;
+        }
+
+        println!();
+        println!("{}", "═".repeat(60));
+        println!();
+        if failed > 0 {
+            println!(
+                "{} {} item(s) could not be removed; delete them manually",
+                "⚠".yellow(),
+                failed
+            );
+        } else {
+            println!("{}", "Wenget has been uninstalled.".green().bold());
+        }
+        println!();
+        print_path_hint(&paths.bin_dir());
+
+        Ok(())
+    }
+}
+
+/// Launchers placed outside the default bin directory (`--global-bin`)
+fn external_shims(config: &Config) -> Vec<PathBuf> {
+    let bin_dir = config.paths().bin_dir();
+    let installed = match config.load_installed() {
+        Ok(installed) => installed,
+        Err(e) => {
+            log::warn!("Failed to load installed packages: {:#}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut shims: Vec<PathBuf> = installed
+        .packages
+        .values()
+        .flat_map(|pkg| pkg.shim_path.iter().chain(&pkg.extra_shims))
+        .map(PathBuf::from)
+        .filter(|shim| !shim.starts_with(&bin_dir))
+        .collect();
+    shims.sort();
+    shims.dedup();
+    shims
+}
+
+/// Remove a file or directory tree, skipping `keep` if it lies inside it
+fn remove_except(path: &Path, keep: &Path) -> Result<()> {
+    if path == keep {
+        return Ok(());
+    }
+
+    let metadata =
+        fs::symlink_metadata(path).with_context(|| format!("Failed to read {}", path.display()))?;
+
+    if !metadata.is_dir() {
+        return fs::remove_file(path)
+            .with_context(|| format!("Failed to delete {}", path.display()));
+    }
+
+    if !keep.starts_with(path) {
+        return fs::remove_dir_all(path)
+            .with_context(|| format!("Failed to delete {}", path.display()));
+    }
+
+    for entry in fs::read_dir(path)
+        .with_context(|| format!("Failed to read directory: {}", path.display()))?
+    {
+        remove_except(&entry?.path(), keep)?;
+    }
+    Ok(())
+}
+
+/// Show the PATH entry the user may still have in their shell configuration
+fn print_path_hint(bin_dir: &Path) {
+    println!(
+        "{} If Wenget's bin directory is still on your PATH, remove this entry:",
+        "ℹ".cyan()
+    );
+
+    #[cfg(windows)]
+    println!(
+        "  {} (User PATH environment variable)",
+        bin_dir.display().to_string().cyan()
+    );
+
+    #[cfg(not(windows))]
+    println!(
+        "  export PATH=\"{}:$PATH\"",
+        bin_dir.display().to_string().cyan()
+    );
+
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{InstalledManifest, InstalledPackage};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_remove_except_spares_kept_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join(".wenget");
+        let exe = root.join("bin").join("wenget");
+        fs::create_dir_all(exe.parent().unwrap()).unwrap();
+        fs::write(&exe, "exe").unwrap();
+        fs::write(root.join("bin").join("rg"), "launcher").unwrap();
+        fs::create_dir_all(root.join("apps").join("rg")).unwrap();
+        fs::write(root.join("apps").join("rg").join("rg"), "app").unwrap();
+        fs::write(root.join("installed.json"), "{}").unwrap();
+
+        remove_except(&root, &exe).unwrap();
+
+        // Only the executable and the directories leading to it are left
+        assert!(exe.is_file());
+        assert!(!root.join("bin").join("rg").exists());
+        assert!(!root.join("apps").exists());
+        assert!(!root.join("installed.json").exists());
+
+        // A tree that does not contain it goes entirely
+        let other = temp_dir.path().join("other");
+        fs::create_dir_all(other.join("nested")).unwrap();
+        remove_except(&other, &exe).unwrap();
+        assert!(!other.exists());
+    }
+
+    #[test]
+    fn test_plan_targets() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+        config.init_with_default_bucket(false).unwrap();
+        let paths = config.paths();
+        let global_shim = temp_dir.path().join("usr-local-bin").join("rg");
+
+        let mut installed = InstalledManifest::new();
+        installed.upsert_package(
+            "rg".to_string(),
+            InstalledPackage {
+                shim_path: Some(global_shim.to_string_lossy().into_owned()),
+                ..InstalledPackage::test_default("rg")
+            },
+        );
+        installed.upsert_package(
+            "fd".to_string(),
+            InstalledPackage {
+                shim_path: Some(paths.bin_dir().join("fd").to_string_lossy().into_owned()),
+                ..InstalledPackage::test_default("fd")
+            },
+        );
+        config.save_installed(&installed).unwrap();
+
+        // --keep-data: launchers and the cache, but not apps or manifests
+        let plan = UninstallPlan::new(&config, true, false).unwrap();
+        assert_eq!(plan.shims, vec![global_shim.clone()]);
+        assert_eq!(
+            plan.targets,
+            vec![
+                paths.bin_dir(),
+                global_shim.clone(),
+                paths.cache_dir(),
+                paths.manifest_cache_json(),
+            ]
+        );
+        assert!(!plan
+            .targets
+            .iter()
+            .any(|t| paths.apps_dir().starts_with(t) || paths.installed_json().starts_with(t)));
+
+        // Otherwise the whole root goes
+        let plan = UninstallPlan::new(&config, false, false).unwrap();
+        assert_eq!(
+            plan.targets,
+            vec![paths.bin_dir(), global_shim, paths.root().to_path_buf()]
+        );
+    }
+}
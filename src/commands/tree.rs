@@ -0,0 +1,121 @@
+//! Tree command implementation
+
+use crate::core::{Config, InstalledManifest};
+use anyhow::Result;
+use colored::Colorize;
+
+/// Show installed packages with the packages they require nested below them
+pub fn run() -> Result<()> {
+    let config = Config::new()?;
+    let manifest = config.get_or_create_installed()?;
+
+    if manifest.packages.is_empty() {
+        println!("{}", "No packages installed".yellow());
+        println!("Install packages with: wenget add <name>");
+        return Ok(());
+    }
+
+    // Explicitly installed packages are the roots
+    let mut roots: Vec<&String> = manifest
+        .packages
+        .iter()
+        .filter(|(_, pkg)| !pkg.auto_installed)
+        .map(|(name, _)| name)
+        .collect();
+    roots.sort();
+
+    for name in roots {
+        print_node(&manifest, name, "", None, &mut Vec::new());
+    }
+
+    let orphans = manifest.orphaned_dependencies();
+    if !orphans.is_empty() {
+        println!();
+        println!("{}", "Orphaned dependencies:".bold());
+        for name in &orphans {
+            let version = &manifest.packages[*name].version;
+            println!(
+                "  {} {} v{} {}",
+                "⚠".yellow(),
+                name,
+                version,
+                "(no installed package requires it)".dimmed()
+            );
+        }
+        println!();
+        println!("Remove them with: wenget autoremove");
+    }
+
+    Ok(())
+}
+
+/// Print a package line and recurse into its requires
+///
+/// `branch` is `Some(is_last)` for nested entries and `None` for roots;
+/// `path` holds the packages above this one so cycles are cut short.
+fn print_node<'a>(
+    manifest: &'a InstalledManifest,
+    name: &'a str,
+    prefix: &str,
+    branch: Option<bool>,
+    path: &mut Vec<&'a str>,
+) {
+    let connector = match branch {
+        Some(true) => "└── ",
+        Some(false) => "├── ",
+        None => "",
+    };
+
+    let Some(pkg) = manifest.get_package(name) else {
+        println!(
+            "{}{}{} {}",
+            prefix,
+            connector,
+            name.red(),
+            "(not installed)".red()
+        );
+        return;
+    };
+
+    let label = if branch.is_some() {
+        name.normal()
+    } else {
+        name.green()
+    };
+    let marker = if pkg.auto_installed {
+        " (dependency)".dimmed().to_string()
+    } else {
+        String::new()
+    };
+
+    if path.contains(&name) {
+        println!(
+            "{}{}{} v{}{} {}",
+            prefix,
+            connector,
+            label,
+            pkg.version,
+            marker,
+            "(cycle)".yellow()
+        );
+        return;
+    }
+
+    println!(
+        "{}{}{} v{}{}",
+        prefix, connector, label, pkg.version, marker
+    );
+
+    let child_prefix = match branch {
+        Some(true) => format!("{}    ", prefix),
+        Some(false) => format!("{}│   ", prefix),
+        None => prefix.to_string(),
+    };
+
+    path.push(name);
+    for (i, dep) in pkg.requires.iter().enumerate() {
+        let is_last = i + 1 == pkg.requires.len();
+        print_node(manifest, dep, &child_prefix, Some(is_last), path);
+    }
+    path.pop();
+}
@@ -1,26 +1,111 @@
 //! Update (Upgrade) command implementation
 
-use crate::commands::add;
-use crate::core::manifest::PackageSource;
-use crate::core::Config;
+use crate::commands::add::{self, InstallReport, PackageOutcome};
+use crate::core::manifest::{expand_env, PackageSource};
+use crate::core::{Channel, Compiler, Config};
+use crate::error::WengetError;
+use crate::outln;
+use crate::package_resolver::{is_excluded, is_selected, split_version};
 use crate::providers::base::SourceProvider;
+use crate::providers::rate_limit::breaker;
 use crate::providers::GitHubProvider;
+use crate::utils::concurrency::parallel_map;
+use crate::utils::http::RateLimitExceeded;
 use anyhow::Result;
 use colored::Colorize;
+use std::io::{self, IsTerminal, Write};
+
+/// Options for the update command
+#[derive(Debug, Clone, Default)]
+pub struct UpdateOptions {
+    /// Skip confirmation prompts
+    pub yes: bool,
+
+    /// Wait for the GitHub rate limit to reset instead of skipping packages
+    pub wait: bool,
+
+    /// Preferred libc/compiler variant (--prefer-gnu / --prefer-musl)
+    pub prefer_variant: Option<Compiler>,
+
+    /// Glob patterns of package names to leave out (--exclude)
+    pub exclude: Vec<String>,
+
+    /// Glob patterns limiting the upgrade to matching packages (--only)
+    pub only: Vec<String>,
+
+    /// Download retries (--retries); None uses the configured default
+    pub retries: Option<u32>,
+
+    /// Previous versions to keep after upgrading (--keep-versions)
+    pub keep_versions: Option<usize>,
+
+    /// Print the per-package outcome as JSON instead (--json); implies `yes`
+    pub json: bool,
+
+    /// Choose which of the upgradeable packages to upgrade (--interactive)
+    pub interactive: bool,
+
+    /// `name@version` updates to stop offering (--ignore)
+    pub ignore: Vec<String>,
+
+    /// Forget the ignored updates of the named packages, or of all packages
+    /// when none are named (--reset-ignores)
+    pub reset_ignores: bool,
+
+    /// Offer ignored updates anyway (--include-ignored)
+    pub include_ignored: bool,
+}
 
 /// Upgrade installed packages
-pub fn run(names: Vec<String>, yes: bool) -> Result<()> {
+pub fn run(names: Vec<String>, options: UpdateOptions) -> Result<()> {
+    // Wait out GitHub rate limits instead of skipping the remaining packages
+    breaker().set_wait(options.wait);
+
     // Handle "wenget update self"
     if names.len() == 1 && names[0] == "self" {
-        return upgrade_self();
+        return upgrade_self(options.retries);
+    }
+
+    if !options.ignore.is_empty() {
+        return ignore_updates(&options.ignore);
+    }
+    if options.reset_ignores {
+        return reset_ignores(&names);
+    }
+
+    let json = options.json;
+    crate::utils::output::set_json(json);
+
+    let report = upgrade(names, options)?;
+    if json {
+        add::print_json_report(&report.outcomes)?;
     }
+    report.result()
+}
+
+/// Upgrade installed packages, returning what happened to each
+fn upgrade(names: Vec<String>, options: UpdateOptions) -> Result<InstallReport> {
+    let UpdateOptions {
+        yes,
+        prefer_variant,
+        exclude,
+        only,
+        retries,
+        keep_versions,
+        json,
+        interactive,
+        include_ignored,
+        ..
+    } = options;
+    let mut yes = yes;
+    let mut report = InstallReport::default();
 
     let config = Config::new()?;
     let installed = config.get_or_create_installed()?;
 
     if installed.packages.is_empty() {
-        println!("{}", "No packages installed".yellow());
-        return Ok(());
+        outln!("{}", "No packages installed".yellow());
+        return Ok(report);
     }
 
     // Create GitHub provider to fetch latest versions
@@ -28,40 +113,350 @@ pub fn run(names: Vec<String>, yes: bool) -> Result<()> {
 
     // Determine which packages to upgrade
     let to_upgrade: Vec<String> = if names.is_empty() || (names.len() == 1 && names[0] == "all") {
+        let mut excluded: Vec<&str> = installed
+            .packages
+            .keys()
+            .map(String::as_str)
+            .filter(|name| is_excluded(name, &exclude))
+            .collect();
+        if !excluded.is_empty() {
+            excluded.sort_unstable();
+            outln!("{} {}", "Excluded:".bold(), excluded.join(", ").dimmed());
+        }
+
+        if !only.is_empty() {
+            let mut selected: Vec<&str> = installed
+                .packages
+                .keys()
+                .map(String::as_str)
+                .filter(|name| is_selected(name, &only) && !is_excluded(name, &exclude))
+                .collect();
+            if selected.is_empty() {
+                outln!(
+                    "{}",
+                    format!("No installed packages match {}", only.join(", ")).yellow()
+                );
+                return Ok(report);
+            }
+            selected.sort_unstable();
+            outln!(
+                "{} {} ({})",
+                "Only:".bold(),
+                selected.join(", "),
+                only.join(", ").dimmed()
+            );
+        }
+
         // List upgradeable packages
-        let upgradeable = find_upgradeable(&config, &installed, &github)?;
+        let (upgradeable, up_to_date, ignored, rate_limited) = find_upgradeable(
+            &config,
+            &installed,
+            &github,
+            &exclude,
+            &only,
+            include_ignored,
+        )?;
+        report.outcomes.extend(
+            up_to_date
+                .iter()
+                .map(|(name, version)| PackageOutcome::skipped(name, version)),
+        );
+        report.outcomes.extend(
+            ignored
+                .iter()
+                .map(|(name, current, _)| PackageOutcome::skipped(name, current)),
+        );
+
+        if let Some((exceeded, skipped)) = rate_limited {
+            print_rate_limit_summary(&exceeded, &skipped);
+            report
+                .outcomes
+                .extend(skipped.iter().map(|name| PackageOutcome {
+                    error: Some(exceeded.to_string()),
+                    to: None,
+                    ..PackageOutcome::skipped(name, &installed.packages[name].version)
+                }));
+
+            // Upgrades found before the limit was hit still go ahead: their
+            // releases were fetched by the check, so installing them needs
+            // no further API requests
+            if upgradeable.is_empty() {
+                outln!("No packages were upgraded.");
+                return Ok(report);
+            }
+        } else if let Some(warning) = github.rate_limit().and_then(|r| r.warning_message()) {
+            // Warn before the next run stalls on the limit
+            outln!("{}", warning.yellow());
+        }
+
+        if !ignored.is_empty() {
+            let list: Vec<_> = ignored
+                .iter()
+                .map(|(name, _, latest)| format!("{} {}", name, latest))
+                .collect();
+            outln!(
+                "{} {} ignored update(s) not offered: {} (use --include-ignored)",
+                "ℹ".cyan(),
+                ignored.len(),
+                list.join(", ")
+            );
+        }
 
         if upgradeable.is_empty() {
-            println!("{}", "All packages are up to date".green());
-            return Ok(());
+            outln!("{}", "All packages are up to date".green());
+            return Ok(report);
         }
 
-        println!("{}", "Packages to upgrade:".bold());
+        outln!("{}", "Packages to upgrade:".bold());
         for (name, current, latest) in &upgradeable {
-            println!("  • {} {} -> {}", name, current.yellow(), latest.green());
+            let inst_pkg = &installed.packages[name];
+            let mut notes = Vec::new();
+            if let Some(ref pin) = inst_pkg.pin {
+                notes.push(format!("pinned {}", pin));
+            }
+            if inst_pkg.is_ignored_update(latest) {
+                notes.push("ignored".to_string());
+            }
+            if notes.is_empty() {
+                outln!("  • {} {} -> {}", name, current.yellow(), latest.green());
+            } else {
+                outln!(
+                    "  • {} {} -> {} {}",
+                    name,
+                    current.yellow(),
+                    latest.green(),
+                    format!("({})", notes.join(", ")).dimmed()
+                );
+            }
         }
-        println!();
+        outln!();
 
-        upgradeable.into_iter().map(|(name, _, _)| name).collect()
+        if interactive && io::stdin().is_terminal() {
+            let picked = pick_upgrades(&upgradeable)?;
+            if picked.is_empty() {
+                outln!("{}", "No packages selected".yellow());
+                return Ok(report);
+            }
+            // Choosing the packages already confirmed the upgrade
+            yes = true;
+            picked
+        } else {
+            if interactive {
+                outln!("{} Not a terminal, upgrading all packages", "ℹ".cyan());
+            }
+            upgradeable.into_iter().map(|(name, _, _)| name).collect()
+        }
     } else {
         names
     };
 
     // Use add command to upgrade (reinstall)
-    add::run(to_upgrade, yes, None)
+    report.merge(add::install(
+        to_upgrade,
+        add::AddOptions {
+            yes,
+            prefer_variant,
+            upgrade: true,
+            exclude,
+            retries,
+            keep_versions,
+            json,
+            ..Default::default()
+        },
+    )?);
+
+    Ok(report)
+}
+
+/// Record `name@version` entries as updates to stop offering
+///
+/// Nothing is recorded unless every entry names an installed package.
+fn ignore_updates(entries: &[String]) -> Result<()> {
+    let config = Config::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    let mut ignores = Vec::new();
+    for entry in entries {
+        match split_version(entry) {
+            (name, Some(version)) if installed.is_installed(name) => {
+                ignores.push((name, version.trim_start_matches('v')))
+            }
+            (name, Some(_)) => anyhow::bail!("Package '{}' is not installed", name),
+            _ => anyhow::bail!("Invalid --ignore '{}': expected <name>@<version>", entry),
+        }
+    }
+
+    for (name, version) in ignores {
+        let Some(pkg) = installed.packages.get_mut(name) else {
+            continue;
+        };
+
+        if pkg.is_ignored_update(version) {
+            println!("{} {} is already ignored", name, version);
+            continue;
+        }
+        pkg.ignored_updates.push(version.to_string());
+        println!(
+            "{} {} {} will no longer be offered by update (newer releases still are)",
+            "✓".green(),
+            name,
+            version
+        );
+    }
+
+    config.save_installed(&installed)
+}
+
+/// Forget the ignored updates of the named packages, or of every package
+fn reset_ignores(names: &[String]) -> Result<()> {
+    let config = Config::new()?;
+    let mut installed = config.get_or_create_installed()?;
+
+    let all = names.is_empty() || (names.len() == 1 && names[0] == "all");
+    if !all {
+        if let Some(name) = names.iter().find(|n| !installed.is_installed(n)) {
+            anyhow::bail!("Package '{}' is not installed", name);
+        }
+    }
+
+    let mut cleared = 0;
+    for (name, pkg) in installed.packages.iter_mut() {
+        if all || names.contains(name) {
+            cleared += pkg.ignored_updates.len();
+            pkg.ignored_updates.clear();
+        }
+    }
+
+    if cleared == 0 {
+        println!("{}", "No ignored updates".yellow());
+        return Ok(());
+    }
+    config.save_installed(&installed)?;
+    println!("{} Cleared {} ignored update(s)", "✓".green(), cleared);
+    Ok(())
+}
+
+/// Let the user tick which of the upgradeable packages to upgrade
+///
+/// Every package starts checked; the numbers entered toggle them, and an
+/// empty line accepts the current selection.
+fn pick_upgrades(upgradeable: &[Upgrade]) -> Result<Vec<String>> {
+    let mut checked = vec![true; upgradeable.len()];
+
+    loop {
+        for (i, (name, current, latest)) in upgradeable.iter().enumerate() {
+            let mark = if checked[i] {
+                "[x]".green()
+            } else {
+                "[ ]".dimmed()
+            };
+            outln!(
+                "  {} {}. {} {} -> {}",
+                mark,
+                i + 1,
+                name,
+                current.yellow(),
+                latest.green()
+            );
+        }
+
+        print!(
+            "\nToggle packages [1-{}, ranges like 2-4, a = all, n = none], Enter to upgrade: ",
+            upgradeable.len()
+        );
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim();
+        outln!();
+
+        match input {
+            "" => break,
+            "a" | "all" => checked.iter_mut().for_each(|c| *c = true),
+            "n" | "none" => checked.iter_mut().for_each(|c| *c = false),
+            _ => match parse_selection(input, upgradeable.len()) {
+                Some(indices) => indices.into_iter().for_each(|i| checked[i] = !checked[i]),
+                None => outln!("{} Invalid selection: {}", "⚠".yellow(), input),
+            },
+        }
+    }
+
+    Ok(upgradeable
+        .iter()
+        .zip(checked)
+        .filter(|(_, checked)| *checked)
+        .map(|((name, _, _), _)| name.clone())
+        .collect())
 }
 
+/// Parse a list of 1-based numbers and ranges (`1 3-4,6`) into indices
+fn parse_selection(input: &str, count: usize) -> Option<Vec<usize>> {
+    let number = |s: &str| {
+        s.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|n| (1..=count).contains(n))
+    };
+
+    let mut indices = Vec::new();
+    for part in input.split([',', ' ']).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (number(start)?, number(end)?),
+            None => (number(part)?, number(part)?),
+        };
+        indices.extend((start.min(end)..=start.max(end)).map(|n| n - 1));
+    }
+    Some(indices)
+}
+
+/// An available upgrade: (name, current version, latest version)
+pub(crate) type Upgrade = (String, String, String);
+
+/// A package already at its latest version: (name, version)
+pub(crate) type UpToDate = (String, String);
+
+/// Packages left unchecked after hitting the GitHub rate limit
+pub(crate) type RateLimited = (RateLimitExceeded, Vec<String>);
+
+/// Result of [`find_upgradeable`]: upgrades, up-to-date packages, ignored
+/// upgrades and the packages skipped for the rate limit
+pub(crate) type UpgradeCheck = (
+    Vec<Upgrade>,
+    Vec<UpToDate>,
+    Vec<Upgrade>,
+    Option<RateLimited>,
+);
+
 /// Find upgradeable packages by checking their sources
-fn find_upgradeable(
+///
+/// Only packages matching `only` (all when empty) and not `exclude` are
+/// checked. Packages already at the latest version are returned separately,
+/// and so are upgrades to a version ignored with `update --ignore` unless
+/// `include_ignored` is set. Once GitHub reports the rate limit is
+/// exhausted, the remaining packages are not checked and are returned as
+/// skipped instead.
+pub(crate) fn find_upgradeable(
     config: &Config,
     installed: &crate::core::InstalledManifest,
     github: &GitHubProvider,
-) -> Result<Vec<(String, String, String)>> {
+    exclude: &[String],
+    only: &[String],
+    include_ignored: bool,
+) -> Result<UpgradeCheck> {
     let mut upgradeable = Vec::new();
+    let mut up_to_date = Vec::new();
+    let mut ignored = Vec::new();
+    let mut rate_limited: Option<RateLimited> = None;
+    let mut to_check = Vec::new();
 
     for (name, inst_pkg) in &installed.packages {
-        // Determine repo URL based on source
-        let repo_url = match &inst_pkg.source {
+        if is_excluded(name, exclude) || !is_selected(name, only) {
+            continue;
+        }
+
+        // Determine repo URL (and the bucket's yanked versions) based on source
+        let (repo_url, yanked) = match &inst_pkg.source {
             PackageSource::Bucket { name: bucket_name } => {
                 // Get package info from cache for bucket packages
                 let cache = config.get_or_rebuild_cache()?;
@@ -73,7 +468,19 @@ fn find_upgradeable(
                     .find(|cached_pkg| cached_pkg.package.name == *name);
 
                 if let Some(cached_pkg) = found {
-                    cached_pkg.package.repo.clone()
+                    let repo_url = match expand_env(&cached_pkg.package.repo) {
+                        Ok(url) => url,
+                        Err(e) => {
+                            eprintln!(
+                                "{} {}: {:#}, skipping update check",
+                                "Warning:".yellow(),
+                                name,
+                                e
+                            );
+                            continue;
+                        }
+                    };
+                    (repo_url, cached_pkg.package.yanked.clone())
                 } else {
                     eprintln!(
                         "{} Package {} not found in bucket {} cache, skipping update check",
@@ -86,7 +493,7 @@ fn find_upgradeable(
             }
             PackageSource::DirectRepo { url } => {
                 // Use the stored repo URL directly
-                url.clone()
+                (url.clone(), Vec::new())
             }
             PackageSource::Script { .. } => {
                 // Scripts don't support updates
@@ -95,45 +502,93 @@ fn find_upgradeable(
             }
         };
 
-        // Fetch latest version from GitHub
-        if let Ok(latest_version) = github.fetch_latest_version(&repo_url) {
-            if inst_pkg.version != latest_version {
-                upgradeable.push((name.clone(), inst_pkg.version.clone(), latest_version));
+        to_check.push((name, inst_pkg, repo_url, yanked));
+    }
+
+    // Fetch latest versions from GitHub on the shared worker pool; once the
+    // rate limit is exhausted the breaker fails the remaining checks fast
+    // A pinned package is offered the newest release matching its pin
+    let latest = parallel_map(&to_check, |(_, inst_pkg, repo_url, yanked)| {
+        let pin = inst_pkg.version_constraint();
+        github.fetch_matching_version(repo_url, inst_pkg.channel, yanked, pin.as_ref())
+    });
+
+    for ((name, inst_pkg, _, _), result) in to_check.into_iter().zip(latest) {
+        match result {
+            Ok(latest_version) => {
+                if inst_pkg.version == latest_version {
+                    up_to_date.push((name.clone(), latest_version));
+                } else if inst_pkg.is_ignored_update(&latest_version) && !include_ignored {
+                    ignored.push((name.clone(), inst_pkg.version.clone(), latest_version));
+                } else {
+                    upgradeable.push((name.clone(), inst_pkg.version.clone(), latest_version));
+                }
+            }
+            Err(e) => {
+                if let Some(exceeded) = WengetError::rate_limit(&e) {
+                    match rate_limited {
+                        Some((_, ref mut skipped)) => skipped.push(name.clone()),
+                        None => rate_limited = Some((exceeded.clone(), vec![name.clone()])),
+                    }
+                } else {
+                    log::debug!("Failed to check {} for updates: {}", name, e);
+                }
             }
         }
     }
 
-    Ok(upgradeable)
+    Ok((upgradeable, up_to_date, ignored, rate_limited))
+}
+
+/// Explain which packages were skipped because of the rate limit
+fn print_rate_limit_summary(exceeded: &RateLimitExceeded, skipped: &[String]) {
+    outln!("{} {}", "⚠".yellow(), exceeded.to_string().yellow());
+    outln!();
+    outln!(
+        "{} {} package(s) could not be checked:",
+        "Skipped".yellow(),
+        skipped.len()
+    );
+    for name in skipped {
+        outln!("  • {}", name);
+    }
+    outln!();
+    outln!("Check them again after the reset, or run with --wait.");
+    outln!("Run `wenget ratelimit` to see the current limit.");
 }
 
 /// Upgrade wenget itself
-fn upgrade_self() -> Result<()> {
+fn upgrade_self(retries: Option<u32>) -> Result<()> {
     use crate::core::platform::Os;
     use crate::core::{Platform, WenPaths};
-    use crate::downloader::download_file;
+    use crate::downloader::{download_file, DEFAULT_RETRIES};
     use crate::installer::{extract_archive, find_executable};
     use colored::Colorize;
     use std::env;
     use std::fs;
 
-    println!("{}", "Upgrading wenget...".cyan());
+    outln!("{}", "Upgrading wenget...".cyan());
 
     // Get current version
     let current_version = env!("CARGO_PKG_VERSION");
-    println!("Current version: {}", current_version);
+    outln!("Current version: {}", current_version);
 
     // Fetch latest package info from GitHub
     let provider = GitHubProvider::new()?;
-    let latest_version = provider.fetch_latest_version("https://github.com/superyngo/wenget")?;
+    let latest_version = provider.fetch_latest_version(
+        "https://github.com/superyngo/wenget",
+        Channel::Stable,
+        &[],
+    )?;
 
-    println!("Latest version: {}", latest_version);
+    outln!("Latest version: {}", latest_version);
 
     if current_version == latest_version {
-        println!("{}", "✓ Already up to date".green());
+        outln!("{}", "✓ Already up to date".green());
         return Ok(());
     }
 
-    println!(
+    outln!(
         "{}",
         format!(
             "New version available: {} -> {}",
@@ -141,7 +596,7 @@ fn upgrade_self() -> Result<()> {
         )
         .yellow()
     );
-    println!();
+    outln!();
 
     // Get package information including binaries
     let package = provider.fetch_package("https://github.com/superyngo/wenget")?;
@@ -163,7 +618,7 @@ fn upgrade_self() -> Result<()> {
         })
         .ok_or_else(|| anyhow::anyhow!("No binary available for platform: {}", platform_id))?;
 
-    println!("Downloading: {}", binary.url);
+    outln!("Downloading: {}", binary.url);
 
     // Determine download file name from URL
     let filename = binary
@@ -178,13 +633,20 @@ fn upgrade_self() -> Result<()> {
     fs::create_dir_all(&temp_dir)?;
 
     let download_path = temp_dir.join(filename);
-    download_file(&binary.url, &download_path)?;
+    let retries = match retries {
+        Some(retries) => retries,
+        None => Config::new()?
+            .load_settings()?
+            .download_retries
+            .unwrap_or(DEFAULT_RETRIES),
+    };
+    download_file(&binary.url, &download_path, retries)?;
 
     // Extract archive
     let extract_dir = temp_dir.join("extracted");
     fs::create_dir_all(&extract_dir)?;
 
-    println!("{}", "Extracting...".cyan());
+    outln!("{}", "Extracting...".cyan());
     let extracted_files = extract_archive(&download_path, &extract_dir)?;
 
     // Find the wenget executable
@@ -200,7 +662,7 @@ fn upgrade_self() -> Result<()> {
     // Get current executable path
     let current_exe = env::current_exe()?;
 
-    println!("{}", "Installing new version...".cyan());
+    outln!("{}", "Installing new version...".cyan());
 
     // Platform-specific replacement logic
     #[cfg(windows)]
@@ -216,12 +678,12 @@ fn upgrade_self() -> Result<()> {
     // Clean up temporary files
     let _ = fs::remove_dir_all(&temp_dir);
 
-    println!();
-    println!(
+    outln!();
+    outln!(
         "{}",
         "✓ Successfully upgraded to the latest version!".green()
     );
-    println!("Please restart your terminal or run 'wenget --version' to verify.");
+    outln!("Please restart your terminal or run 'wenget --version' to verify.");
 
     Ok(())
 }
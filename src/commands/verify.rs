@@ -0,0 +1,277 @@
+//! Verify command implementation
+//!
+//! Re-hashes the files of installed packages and compares them with the
+//! SHA-256 digests recorded when they were installed, to catch binaries that
+//! were modified, truncated or deleted since. Files are hashed on the worker
+//! pool (`--concurrency`), behind one progress bar for the bytes hashed.
+
+use crate::core::{Config, InstalledPackage};
+use crate::downloader::checksum;
+use crate::outln;
+use anyhow::Result;
+use colored::Colorize;
+use glob::Pattern;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+
+/// What is wrong with a recorded file
+#[derive(Debug, PartialEq)]
+enum FileProblem {
+    /// The file no longer matches its recorded digest
+    Modified,
+    /// The file is gone
+    Missing,
+    /// The file could not be read
+    Unreadable(String),
+}
+
+/// Outcome of verifying one package
+#[derive(Debug)]
+struct VerifyResult {
+    name: String,
+    version: String,
+    /// Recorded files that were checked
+    checked: usize,
+    /// Files that failed, sorted by path
+    problems: Vec<(String, FileProblem)>,
+}
+
+impl VerifyResult {
+    /// Installed before digests were recorded, so nothing could be checked
+    fn is_unrecorded(&self) -> bool {
+        self.checked == 0
+    }
+}
+
+/// Verify the files of the named installed packages (globs allowed), or all of them
+pub fn run(names: Vec<String>, all: bool) -> Result<()> {
+    let config = Config::new()?;
+    let installed = config.get_or_create_installed()?;
+
+    if installed.packages.is_empty() {
+        println!("{}", "No packages installed".yellow());
+        return Ok(());
+    }
+
+    let patterns: Vec<Pattern> = names
+        .iter()
+        .map(|p| Pattern::new(p))
+        .collect::<Result<_, _>>()?;
+    let mut targets: Vec<(&String, &InstalledPackage)> = installed
+        .packages
+        .iter()
+        .filter(|(name, _)| all || patterns.iter().any(|p| p.matches(name)))
+        .collect();
+    targets.sort_by(|a, b| a.0.cmp(b.0));
+
+    if targets.is_empty() {
+        println!(
+            "{}",
+            format!("No installed packages found matching: {:?}", names).yellow()
+        );
+        return Ok(());
+    }
+
+    outln!("{} {} package(s)...\n", "Verifying".cyan(), targets.len());
+
+    let results = verify_packages(&targets);
+
+    let failed = results.iter().filter(|r| !r.problems.is_empty()).count();
+    let unrecorded = results.iter().filter(|r| r.is_unrecorded()).count();
+    let intact = results.len() - failed - unrecorded;
+
+    print_verify_results(&results);
+    println!();
+    println!("{}", "Summary:".bold());
+    println!("  {} {} package(s) intact", "✓".green(), intact);
+    if failed > 0 {
+        println!(
+            "  {} {} package(s) with modified or missing files",
+            "✗".red(),
+            failed
+        );
+    }
+    if unrecorded > 0 {
+        println!(
+            "  {} {} package(s) without recorded digests (reinstall them to record digests)",
+            "⚠".yellow(),
+            unrecorded
+        );
+    }
+
+    if failed > 0 {
+        anyhow::bail!("{} package(s) failed verification", failed);
+    }
+    Ok(())
+}
+
+/// Hash the recorded files of `packages` and compare them with their digests
+///
+/// The files of every package share one pool, so a single large package is
+/// hashed in parallel too. Results are in the order of `packages`, and the
+/// problems of each sorted by file path.
+fn verify_packages(packages: &[(&String, &InstalledPackage)]) -> Vec<VerifyResult> {
+    let mut files = Vec::new();
+    for (index, (_, pkg)) in packages.iter().enumerate() {
+        let install_path = Path::new(&pkg.install_path);
+        for (file, digest) in &pkg.file_digests {
+            files.push((index, file, digest, install_path.join(file)));
+        }
+    }
+
+    let paths: Vec<PathBuf> = files.iter().map(|(_, _, _, path)| path.clone()).collect();
+    let total_bytes = paths
+        .iter()
+        .filter_map(|path| path.metadata().ok())
+        .map(|metadata| metadata.len())
+        .sum();
+
+    let progress = hashing_progress(total_bytes);
+    let digests = checksum::sha256_files(&paths, &|n| progress.inc(n));
+    progress.finish_and_clear();
+
+    let mut results: Vec<VerifyResult> = packages
+        .iter()
+        .map(|(name, pkg)| VerifyResult {
+            name: name.to_string(),
+            version: pkg.version.clone(),
+            checked: pkg.file_digests.len(),
+            problems: Vec::new(),
+        })
+        .collect();
+
+    for ((index, file, expected, path), digest) in files.into_iter().zip(digests) {
+        let problem = match digest {
+            Ok(actual) if actual == *expected => continue,
+            Ok(_) => FileProblem::Modified,
+            Err(_) if !path.exists() => FileProblem::Missing,
+            Err(e) => FileProblem::Unreadable(format!("{:#}", e)),
+        };
+        results[index].problems.push((file.clone(), problem));
+    }
+
+    results
+}
+
+/// Print one line per package, with the files that failed
+fn print_verify_results(results: &[VerifyResult]) {
+    for result in results {
+        let version = result.version.dimmed();
+        if result.is_unrecorded() {
+            println!(
+                "  {} {} {} {}",
+                "⚠".yellow(),
+                result.name,
+                version,
+                "no digests recorded".yellow()
+            );
+        } else if result.problems.is_empty() {
+            println!(
+                "  {} {} {} ({} file(s))",
+                "✓".green(),
+                result.name,
+                version,
+                result.checked
+            );
+        } else {
+            println!("  {} {} {}", "✗".red(), result.name, version);
+            for (file, problem) in &result.problems {
+                let line = match problem {
+                    FileProblem::Modified => format!("modified: {}", file),
+                    FileProblem::Missing => format!("missing: {}", file),
+                    FileProblem::Unreadable(error) => format!("unreadable: {}", error),
+                };
+                println!("      {}", line.red());
+            }
+        }
+    }
+}
+
+/// Progress bar for `total` bytes to hash, hidden when quiet or not on a terminal
+fn hashing_progress(total: u64) -> ProgressBar {
+    if crate::utils::is_quiet() || !std::io::stdout().is_terminal() {
+        return ProgressBar::hidden();
+    }
+
+    let pb = ProgressBar::new(total);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "  {spinner:.green} [{bar:40.cyan/blue}] {bytes}/{total_bytes} hashed ({eta})",
+            )
+            .unwrap()
+            .progress_chars("#>-"),
+    );
+    pb
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// An installed package with `files` written under `dir` and their digests recorded
+    fn installed_with_files(dir: &Path, name: &str, files: &[(&str, &str)]) -> InstalledPackage {
+        let install_path = dir.join(name);
+        let mut file_digests = BTreeMap::new();
+        for (file, content) in files {
+            let path = install_path.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            file_digests.insert(file.to_string(), checksum::sha256_file(&path).unwrap());
+        }
+
+        InstalledPackage {
+            install_path: install_path.to_string_lossy().into_owned(),
+            files: files.iter().map(|(file, _)| file.to_string()).collect(),
+            file_digests,
+            ..InstalledPackage::test_default(name)
+        }
+    }
+
+    #[test]
+    fn test_verify_packages_reports_problems_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path();
+
+        let intact = installed_with_files(dir, "intact", &[("bin/intact", "a"), ("README", "b")]);
+        let broken = installed_with_files(
+            dir,
+            "broken",
+            &[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")],
+        );
+        fs::write(dir.join("broken").join("b"), "tampered").unwrap();
+        fs::remove_file(dir.join("broken").join("d")).unwrap();
+        let unrecorded = InstalledPackage::test_default("unrecorded");
+
+        let names = ["broken", "intact", "unrecorded"].map(String::from);
+        let packages = [
+            (&names[0], &broken),
+            (&names[1], &intact),
+            (&names[2], &unrecorded),
+        ];
+        let results = verify_packages(&packages);
+
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["broken", "intact", "unrecorded"]);
+
+        assert_eq!(results[0].checked, 4);
+        assert_eq!(
+            results[0].problems,
+            [
+                ("b".to_string(), FileProblem::Modified),
+                ("d".to_string(), FileProblem::Missing),
+            ]
+        );
+
+        assert_eq!(results[1].checked, 2);
+        assert!(results[1].problems.is_empty());
+        assert!(!results[1].is_unrecorded());
+
+        assert!(results[2].is_unrecorded());
+        assert!(results[2].problems.is_empty());
+    }
+}
@@ -0,0 +1,133 @@
+//! Progress of a multi-package `add` run
+//!
+//! `wenget add a b c` records its inputs in `~/.wenget/batch.json` before
+//! installing and adds every package as it completes. If the run is
+//! interrupted, `wenget add --resume` reads the file back and skips what was
+//! already done. The file is removed once every package installed.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A package installed by the batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompletedPackage {
+    /// Package name
+    pub name: String,
+
+    /// Where it came from (see `PackageSource::describe`), to match
+    /// URL and script inputs
+    pub source: String,
+}
+
+/// The state file of an `add` batch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BatchState {
+    /// When the batch was started
+    pub started: DateTime<Utc>,
+
+    /// Inputs as given on the command line
+    pub names: Vec<String>,
+
+    /// Packages installed so far
+    #[serde(default)]
+    pub completed: Vec<CompletedPackage>,
+}
+
+impl BatchState {
+    /// A new batch over `names`, with nothing completed yet
+    pub fn new(names: Vec<String>) -> Self {
+        Self {
+            started: Utc::now(),
+            names,
+            completed: Vec::new(),
+        }
+    }
+
+    /// Load the state file; None if there is no unfinished batch
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read batch state: {}", path.display()))?;
+        let state = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse batch state: {}", path.display()))?;
+        Ok(Some(state))
+    }
+
+    /// Write the state file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize batch state")?;
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write batch state: {}", path.display()))
+    }
+
+    /// Remove the state file, if any
+    pub fn clear(path: &Path) -> Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to remove batch state: {}", path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Record a completed package (again, after a retry, replaces it)
+    pub fn complete(&mut self, name: &str, source: &str) {
+        self.completed.retain(|p| p.name != name);
+        self.completed.push(CompletedPackage {
+            name: name.to_string(),
+            source: source.to_string(),
+        });
+    }
+
+    /// Whether a package with this name was installed by the batch
+    pub fn has_name(&self, name: &str) -> bool {
+        self.completed.iter().any(|p| p.name == name)
+    }
+
+    /// Whether a package from this source (URL or script origin) was
+    /// installed by the batch
+    pub fn has_source(&self, source: &str) -> bool {
+        self.completed.iter().any(|p| p.source == source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_batch_state_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("batch.json");
+        assert!(BatchState::load(&path).unwrap().is_none());
+
+        let mut state = BatchState::new(vec!["ripgrep".to_string(), "fd".to_string()]);
+        state.complete("ripgrep", "bucket:main");
+        state.complete("ripgrep", "bucket:main");
+        state.save(&path).unwrap();
+
+        let loaded = BatchState::load(&path).unwrap().unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(loaded.completed.len(), 1);
+        assert!(loaded.has_name("ripgrep"));
+        assert!(!loaded.has_name("fd"));
+        assert!(loaded.has_source("bucket:main"));
+
+        BatchState::clear(&path).unwrap();
+        assert!(!path.exists());
+        // Clearing twice is fine
+        BatchState::clear(&path).unwrap();
+    }
+}
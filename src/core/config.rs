@@ -4,15 +4,27 @@
 //! - Loading and saving installed.json
 //! - Loading and saving buckets.json
 //! - Loading and saving manifest-cache.json
+//! - Loading and saving settings.json
+//! - Appending to history.jsonl
+//! - Tracking the progress of an `add` batch in batch.json
 //! - Directory initialization
 
+use super::batch::BatchState;
+use super::history::{append_event, load_events, HistoryEvent};
 use super::manifest::{InstalledManifest, SourceManifest};
 use super::paths::WenPaths;
-use crate::bucket::BucketConfig;
+use super::settings::Settings;
+use crate::bucket::{default_bucket, BucketConfig};
 use crate::cache::ManifestCache;
+use crate::outln;
 use anyhow::{Context, Result};
+use colored::Colorize;
 use std::fs;
 use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Seconds to wait for a bucket source unless `bucket_timeout` is set
+pub const DEFAULT_BUCKET_TIMEOUT_SECS: u64 = 15;
 
 /// Configuration manager
 pub struct Config {
@@ -22,8 +34,13 @@ pub struct Config {
 impl Config {
     /// Create a new Config instance
     pub fn new() -> Result<Self> {
-        let paths = WenPaths::new()?;
-        Ok(Self { paths })
+        Ok(Self::with_paths(WenPaths::new()?))
+    }
+
+    /// Create a Config working under other paths, such as
+    /// [`WenPaths::with_root`] for a temporary root
+    pub fn with_paths(paths: WenPaths) -> Self {
+        Self { paths }
     }
 
     /// Get the paths manager
@@ -31,8 +48,15 @@ impl Config {
         &self.paths
     }
 
-    /// Initialize WenPM (create directories if needed)
+    /// Initialize WenPM (create directories if needed), seeding the
+    /// default bucket on first run
     pub fn init(&self) -> Result<()> {
+        self.init_with_default_bucket(true)
+    }
+
+    /// Initialize WenPM; `seed_bucket` controls whether a first run starts
+    /// with the default bucket (see [`default_bucket`]) or with none
+    pub fn init_with_default_bucket(&self, seed_bucket: bool) -> Result<()> {
         self.paths.init_dirs()?;
 
         // Create empty manifests if they don't exist
@@ -40,6 +64,15 @@ impl Config {
             self.save_installed(&InstalledManifest::new())?;
         }
 
+        // Only a first run is seeded; a removed default bucket stays removed
+        if !self.paths.buckets_json().exists() {
+            let mut buckets = BucketConfig::new();
+            if let Some(bucket) = default_bucket().filter(|_| seed_bucket) {
+                buckets.add_bucket(bucket);
+            }
+            self.save_buckets(&buckets)?;
+        }
+
         Ok(())
     }
 
@@ -67,7 +100,12 @@ impl Config {
 
         // Try to parse JSON
         match try_parse_json::<InstalledManifest>(&content, &path) {
-            Ok(manifest) => Ok(manifest),
+            Ok(mut manifest) => {
+                if manifest.migrate() {
+                    self.save_installed(&manifest)?;
+                }
+                Ok(manifest)
+            }
             Err(parse_error) => {
                 log::error!("CRITICAL: Failed to parse installed.json: {}", parse_error);
 
@@ -102,6 +140,40 @@ impl Config {
         Self::save_json(&path, manifest).context("Failed to save installed.json")
     }
 
+    /// Append an event to the install history
+    ///
+    /// History is informational, so failures are logged instead of aborting
+    /// the command that triggered them.
+    pub fn record_history(&self, event: HistoryEvent) {
+        let path = self.paths.history_jsonl();
+        if let Err(e) = append_event(&path, &event) {
+            log::warn!("Failed to record history: {:#}", e);
+        }
+    }
+
+    /// Mark a package as installed in the unfinished `add` batch, if there is one
+    ///
+    /// Like history, failures are only logged: at worst `--resume` checks
+    /// the package again.
+    pub fn record_batch_progress(&self, name: &str, source: &str) {
+        let path = self.paths.batch_json();
+        let result = BatchState::load(&path).and_then(|state| match state {
+            Some(mut state) => {
+                state.complete(name, source);
+                state.save(&path)
+            }
+            None => Ok(()),
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to record batch progress: {:#}", e);
+        }
+    }
+
+    /// Load the install history, oldest first
+    pub fn load_history(&self) -> Result<Vec<HistoryEvent>> {
+        load_events(&self.paths.history_jsonl())
+    }
+
     /// Generic JSON loader (without repair - for internal use)
     #[allow(dead_code)]
     fn load_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
@@ -113,11 +185,17 @@ impl Config {
     }
 
     /// Generic JSON saver
+    ///
+    /// Writes to a temporary file and renames it into place, so an interrupt
+    /// mid-write never leaves a truncated file behind.
     fn save_json<T: serde::Serialize>(path: &Path, data: &T) -> Result<()> {
         let json =
             serde_json::to_string_pretty(data).context("Failed to serialize data to JSON")?;
 
-        fs::write(path, json)
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, json)
+            .with_context(|| format!("Failed to write file: {}", tmp_path.display()))?;
+        fs::rename(&tmp_path, path)
             .with_context(|| format!("Failed to write file: {}", path.display()))?;
 
         Ok(())
@@ -151,6 +229,18 @@ impl Config {
         self.load_buckets()
     }
 
+    /// Load user settings
+    pub fn load_settings(&self) -> Result<Settings> {
+        let path = self.paths.settings_json();
+        Settings::load(&path)
+    }
+
+    /// Save user settings
+    pub fn save_settings(&self, settings: &Settings) -> Result<()> {
+        let path = self.paths.settings_json();
+        settings.save(&path)
+    }
+
     /// Load manifest cache
     pub fn load_cache(&self) -> Result<ManifestCache> {
         let path = self.paths.manifest_cache_json();
@@ -173,44 +263,219 @@ impl Config {
         Ok(())
     }
 
+    /// Seconds the manifest cache stays valid (the `cache_ttl` setting)
+    pub fn cache_ttl(&self) -> Result<i64> {
+        let ttl = self
+            .load_settings()?
+            .cache_ttl
+            .unwrap_or(crate::cache::DEFAULT_TTL_SECS);
+        Ok(i64::try_from(ttl).unwrap_or(i64::MAX))
+    }
+
     /// Get or rebuild manifest cache
     /// Returns the cache if valid, otherwise rebuilds it
     pub fn get_or_rebuild_cache(&self) -> Result<ManifestCache> {
-        let cache = self.load_cache()?;
+        let mut cache = self.load_cache()?;
+        cache.ttl_seconds = self.cache_ttl()?;
 
-        // Check if cache is valid
-        if cache.is_valid() && !cache.packages.is_empty() {
+        // Check if cache is valid (never reused with --no-cache)
+        if !crate::cache::is_bypassed() && cache.is_valid() && !cache.packages.is_empty() {
             return Ok(cache);
         }
 
-        // Rebuild cache
-        self.rebuild_cache()
+        // Rebuild cache, reporting progress since fetching buckets can take a while
+        let quiet = crate::utils::is_quiet();
+        let bucket_config = self.get_or_create_buckets()?;
+        let bucket_names: Vec<&str> = bucket_config
+            .enabled_buckets()
+            .iter()
+            .map(|b| b.name.as_str())
+            .collect();
+        let bucket_count = bucket_names.len();
+        if !quiet {
+            outln!(
+                "{} cache from {} bucket(s)...",
+                "Rebuilding".cyan(),
+                bucket_count
+            );
+        }
+
+        let started = Instant::now();
+        let cache = self.rebuild_cache()?;
+
+        // Every source was just fetched; later lookups in this run reuse it
+        crate::cache::set_bypass(false);
+
+        if !quiet {
+            outln!(
+                "{} Cached {} package(s) from {} bucket(s) in {:.1}s",
+                "✓".green(),
+                cache.packages.len(),
+                bucket_count,
+                started.elapsed().as_secs_f64()
+            );
+
+            let (stale, failed) = cache.bucket_health(&bucket_names);
+            if !stale.is_empty() {
+                outln!(
+                    "  {} Using stale data for: {}",
+                    "⚠".yellow(),
+                    stale.join(", ")
+                );
+            }
+            if !failed.is_empty() {
+                outln!("  {} Failed: {}", "✗".red(), failed.join(", "));
+            }
+        }
+
+        Ok(cache)
     }
 
-    /// Force rebuild manifest cache from buckets only
+    /// Rebuild manifest cache from buckets
+    ///
+    /// Only buckets whose per-bucket cache entry is missing, expired, or
+    /// points at an old URL are refetched; the rest are reused as-is.
     pub fn rebuild_cache(&self) -> Result<ManifestCache> {
-        use crate::cache::build_cache;
+        self.build_cache_from_buckets(|_| false)
+    }
+
+    /// Force refetch of the named buckets (all if empty) and rebuild the cache
+    pub fn refresh_buckets(&self, names: &[String]) -> Result<ManifestCache> {
+        self.build_cache_from_buckets(|bucket| {
+            names.is_empty() || names.iter().any(|n| n == &bucket.name)
+        })
+    }
+
+    /// Remove a bucket's cache entry (after the bucket is deleted)
+    pub fn remove_bucket_cache(&self, name: &str) -> Result<()> {
+        let path = self.paths.bucket_cache_json(name);
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove bucket cache: {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// Merge per-bucket cache entries into the manifest cache, refetching
+    /// stale buckets and those selected by `force`
+    ///
+    /// Each request for a bucket is bounded by the `bucket_timeout` setting.
+    /// A bucket that times out or fails keeps serving its last cached copy
+    /// (marked stale) so one unreachable source can't break the rebuild.
+    fn build_cache_from_buckets(
+        &self,
+        force: impl Fn(&crate::bucket::Bucket) -> bool + Sync,
+    ) -> Result<ManifestCache> {
+        use crate::cache::{build_cache, BucketCacheEntry};
+        use crate::error::WengetError;
+        use crate::utils::http::Conditional;
         use crate::utils::HttpClient;
 
         let bucket_config = self.get_or_create_buckets()?;
+        let ttl_seconds = self.cache_ttl()?;
+        let timeout_secs = self
+            .load_settings()?
+            .bucket_timeout
+            .unwrap_or(DEFAULT_BUCKET_TIMEOUT_SECS);
+        let http = HttpClient::with_timeout(Duration::from_secs(timeout_secs))?;
+
+        let bucket_entry = |bucket: &crate::bucket::Bucket| -> Result<BucketCacheEntry> {
+            // Buckets with custom headers get a client of their own
+            let bucket_http;
+            let http = if bucket.headers.is_empty() {
+                &http
+            } else {
+                bucket_http = HttpClient::with_headers(
+                    Duration::from_secs(timeout_secs),
+                    bucket.host_headers()?,
+                )?;
+                &bucket_http
+            };
+
+            let path = self.paths.bucket_cache_json(&bucket.name);
+            let cached = BucketCacheEntry::load_for(&path, bucket);
+
+            if let Some(ref entry) = cached {
+                if !force(bucket) && entry.is_fresh(bucket, ttl_seconds) {
+                    log::debug!("Using cached manifest for bucket '{}'", bucket.name);
+                    return Ok(entry.clone());
+                }
+            }
 
-        // Fetch bucket manifests
-        let fetch_bucket = |bucket: &crate::bucket::Bucket| -> Result<SourceManifest> {
             log::info!("Fetching bucket '{}' from {}", bucket.name, bucket.url);
 
-            let http = HttpClient::new()?;
-            let content = http
-                .get_text(&bucket.url)
-                .with_context(|| format!("Failed to fetch bucket from {}", bucket.url))?;
-
-            // Try to parse as SourceManifest
-            let manifest: SourceManifest = serde_json::from_str(&content)
-                .with_context(|| format!("Failed to parse bucket manifest from {}", bucket.url))?;
+            // Scoop bucket repositories are read through their directory listing
+            let scoop_listing = super::scoop::bucket_listing_url(&bucket.url);
+            let fetch_url = scoop_listing.as_deref().unwrap_or(&bucket.url);
+
+            let etag = cached.as_ref().and_then(|e| e.etag.as_deref());
+            let fetched = http
+                .get_text_conditional(fetch_url, etag)
+                .with_context(|| format!("Failed to fetch bucket from {}", bucket.url))
+                .and_then(|response| match response {
+                    Conditional::NotModified => Ok(None),
+                    Conditional::Modified { body, etag } => {
+                        let manifest = match scoop_listing {
+                            Some(_) => super::scoop::import_bucket_listing(http, &body),
+                            None => super::scoop::parse_bucket_document(&body, &bucket.url),
+                        }
+                        .with_context(|| {
+                            format!("Failed to parse bucket manifest from {}", bucket.url)
+                        })?;
+                        Ok(Some((manifest, etag)))
+                    }
+                })
+                .map_err(|e| {
+                    if WengetError::is_timeout(&e) {
+                        e.context(format!("Timed out after {}s", timeout_secs))
+                    } else {
+                        e
+                    }
+                });
+
+            let entry = match (fetched, cached) {
+                (Ok(None), Some(mut entry)) => {
+                    log::debug!("Bucket '{}' not modified", bucket.name);
+                    entry.fetched_at = chrono::Utc::now();
+                    entry
+                }
+                (Ok(None), None) => {
+                    anyhow::bail!(
+                        "Server returned 304 for {} without a cached copy",
+                        bucket.url
+                    )
+                }
+                (Ok(Some((manifest, etag))), _) => BucketCacheEntry {
+                    url: bucket.url.clone(),
+                    fetched_at: chrono::Utc::now(),
+                    etag,
+                    manifest,
+                    stale: None,
+                },
+                (Err(e), Some(entry)) => {
+                    // Keep serving the stale copy rather than dropping the bucket
+                    log::warn!(
+                        "Failed to refresh bucket '{}', using cached copy: {:#}",
+                        bucket.name,
+                        e
+                    );
+                    return Ok(BucketCacheEntry {
+                        stale: Some(e.to_string()),
+                        ..entry
+                    });
+                }
+                (Err(e), None) => return Err(e),
+            };
+
+            if let Err(e) = entry.save(&path) {
+                log::warn!("Failed to save cache for bucket '{}': {}", bucket.name, e);
+            }
 
-            Ok(manifest)
+            Ok(entry)
         };
 
-        let cache = build_cache(&bucket_config, fetch_bucket)?;
+        let mut cache = build_cache(&bucket_config, bucket_entry)?;
+        cache.ttl_seconds = ttl_seconds;
 
         // Save cache
         self.save_cache(&cache)?;
@@ -240,11 +505,7 @@ mod tests {
     #[allow(dead_code)]
     fn create_test_config() -> (Config, TempDir) {
         let temp_dir = TempDir::new().unwrap();
-        let _paths = WenPaths::new().unwrap();
-
-        // For testing, we would need to override the paths
-        // This is a simplified version
-        let config = Config::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
         (config, temp_dir)
     }
 
@@ -262,6 +523,28 @@ mod tests {
         assert!(config.paths().root().exists());
     }
 
+    #[test]
+    fn test_init_seeds_default_bucket() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join(".wenget")));
+
+        config.init().unwrap();
+        let buckets = config.load_buckets().unwrap();
+        assert!(!buckets.buckets.is_empty());
+        assert!(config.paths().buckets_json().exists());
+
+        // A removed default bucket is not seeded again
+        config.save_buckets(&BucketConfig::new()).unwrap();
+        config.init().unwrap();
+        assert!(config.load_buckets().unwrap().buckets.is_empty());
+
+        // Opting out records an empty bucket list
+        let config = Config::with_paths(WenPaths::with_root(temp_dir.path().join("bare")));
+        config.init_with_default_bucket(false).unwrap();
+        assert!(config.paths().buckets_json().exists());
+        assert!(config.load_buckets().unwrap().buckets.is_empty());
+    }
+
     #[test]
     fn test_manifest_round_trip() {
         let config = Config::new().unwrap();
@@ -0,0 +1,209 @@
+//! Install history log for WenPM
+//!
+//! Every install, upgrade and removal is appended as one JSON line to
+//! `~/.wenget/history.jsonl`. The file is never rewritten, so a crash can at
+//! worst leave a truncated last line, which readers skip.
+
+use super::manifest::PackageSource;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// What happened to a package
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryAction {
+    Install,
+    Upgrade,
+    Remove,
+}
+
+impl HistoryAction {
+    /// Convert to display string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryAction::Install => "install",
+            HistoryAction::Upgrade => "upgrade",
+            HistoryAction::Remove => "remove",
+        }
+    }
+}
+
+/// One entry of the history log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEvent {
+    /// When the action completed
+    pub timestamp: DateTime<Utc>,
+
+    /// What was done
+    pub action: HistoryAction,
+
+    /// Package name
+    pub package: String,
+
+    /// Version before the action (upgrades and removals)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_version: Option<String>,
+
+    /// Version after the action (installs and upgrades)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_version: Option<String>,
+
+    /// Where the package came from (e.g. `bucket:main` or a repository URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl HistoryEvent {
+    /// Create an event stamped with the current time
+    pub fn new(
+        action: HistoryAction,
+        package: &str,
+        from_version: Option<&str>,
+        to_version: Option<&str>,
+        source: &PackageSource,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now(),
+            action,
+            package: package.to_string(),
+            from_version: from_version.map(str::to_string),
+            to_version: to_version.map(str::to_string),
+            source: Some(source.describe()),
+        }
+    }
+}
+
+/// Append an event to the history file
+///
+/// The line is written with a single `write_all` on a file opened in append
+/// mode and synced before returning. A truncated last line left by a crash is
+/// terminated first, so the new event starts on a line of its own.
+pub fn append_event(path: &Path, event: &HistoryEvent) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    let mut line = serde_json::to_string(event).context("Failed to serialize history event")?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open history: {}", path.display()))?;
+
+    if !ends_with_newline(&mut file)
+        .with_context(|| format!("Failed to read history: {}", path.display()))?
+    {
+        line.insert(0, '\n');
+    }
+
+    file.write_all(line.as_bytes())
+        .with_context(|| format!("Failed to write history: {}", path.display()))?;
+    file.sync_data()
+        .with_context(|| format!("Failed to sync history: {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Whether a history file is empty or its last line is complete
+fn ends_with_newline(file: &mut fs::File) -> std::io::Result<bool> {
+    if file.metadata()?.len() == 0 {
+        return Ok(true);
+    }
+
+    let mut last = [0u8; 1];
+    file.seek(SeekFrom::End(-1))?;
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
+
+/// Load all events from the history file, oldest first
+///
+/// A missing file is an empty history; unreadable lines are skipped.
+pub fn load_events(path: &Path) -> Result<Vec<HistoryEvent>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read history: {}", path.display()))?;
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str(line) {
+            Ok(event) => Some(event),
+            Err(e) => {
+                log::warn!("Skipping history line {}: {}", index + 1, e);
+                None
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_history_append_and_load() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.jsonl");
+
+        assert!(load_events(&path).unwrap().is_empty());
+
+        let source = PackageSource::Bucket {
+            name: "main".to_string(),
+        };
+        let install =
+            HistoryEvent::new(HistoryAction::Install, "rg", None, Some("14.0.0"), &source);
+        let upgrade = HistoryEvent::new(
+            HistoryAction::Upgrade,
+            "rg",
+            Some("14.0.0"),
+            Some("14.1.0"),
+            &source,
+        );
+        append_event(&path, &install).unwrap();
+        append_event(&path, &upgrade).unwrap();
+
+        let events = load_events(&path).unwrap();
+        assert_eq!(events, vec![install, upgrade]);
+        assert_eq!(events[1].source.as_deref(), Some("bucket:main"));
+    }
+
+    #[test]
+    fn test_history_skips_truncated_line() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("history.jsonl");
+
+        let source = PackageSource::DirectRepo {
+            url: "https://github.com/user/tool".to_string(),
+        };
+        let event = HistoryEvent::new(HistoryAction::Remove, "tool", Some("1.0.0"), None, &source);
+        append_event(&path, &event).unwrap();
+
+        // Simulate a crash in the middle of writing the next line
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"timestamp\":\"2024-").unwrap();
+
+        let events = load_events(&path).unwrap();
+        assert_eq!(events, vec![event.clone()]);
+
+        // The next event goes on a line of its own rather than onto the partial one
+        let next = HistoryEvent::new(HistoryAction::Install, "tool", None, Some("1.1.0"), &source);
+        append_event(&path, &next).unwrap();
+
+        let events = load_events(&path).unwrap();
+        assert_eq!(events, vec![event, next]);
+    }
+}
@@ -6,9 +6,13 @@
 //! - `SourceManifest`: The sources.json structure
 //! - `InstalledManifest`: The installed.json structure
 
+use super::paths::rebase_path;
+use super::platform::{canonical_platform_id, Compiler};
+use super::version::VersionConstraint;
+use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 /// Script type enumeration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -122,14 +126,41 @@ impl ScriptType {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PlatformBinary {
     /// Download URL for the binary
+    /// May be empty when `asset_pattern` is set (resolved at install time)
+    #[serde(default)]
     pub url: String,
 
     /// File size in bytes
+    #[serde(default)]
     pub size: u64,
 
     /// Optional SHA256 checksum (for future use)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub checksum: Option<String>,
+
+    /// Optional asset name pattern, resolved against the latest release assets
+    /// Glob by default, or a regex when prefixed with `regex:`.
+    /// Supports `{version}` and `{tag}` placeholders.
+    /// Examples: "tool-{version}-x86_64-linux.tar.gz", "regex:^tool-.*-linux\\.zip$"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_pattern: Option<String>,
+
+    /// Executables the package exposes, like scoop's `bin`
+    /// The first entry is used as the package's executable instead of
+    /// auto-detecting one; every other entry gets a launcher of its own.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bin: Vec<BinEntry>,
+}
+
+/// Executable declared by a manifest `bin` list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BinEntry {
+    /// Path of the executable inside the extracted archive
+    pub path: String,
+
+    /// Command name for its launcher; defaults to the file name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
 /// Package metadata
@@ -138,6 +169,11 @@ pub struct Package {
     /// Package name (used as identifier)
     pub name: String,
 
+    /// Other names the package is known by (`rg` for ripgrep); lookups by
+    /// name match these too, but the package is installed under `name`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
     /// Short description
     pub description: String,
 
@@ -156,6 +192,131 @@ pub struct Package {
     /// Key format: "{os}-{arch}" or "{os}-{arch}-{variant}"
     /// Examples: "windows-x86_64", "linux-x86_64-musl", "macos-aarch64"
     pub platforms: HashMap<String, PlatformBinary>,
+
+    /// Names of other packages this package needs (installed alongside it)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<String>,
+
+    /// Release versions known to be broken; installs and updates pick the
+    /// newest release not listed here (a leading "v" is ignored)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub yanked: Vec<String>,
+
+    /// Categories for discovery (e.g. "cli", "rust", "search"); manifests
+    /// may also call them `categories`
+    #[serde(default, alias = "categories", skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Follow-up guidance shown after a successful install ("run `tool
+    /// init`"); only printed, never executed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_install_message: Option<String>,
+
+    /// Basename of the executable inside the archive when it differs from
+    /// the package name (`rg` for ripgrep); detection is used when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe_name: Option<String>,
+}
+
+impl Package {
+    /// Check whether a release version is yanked by the manifest
+    pub fn is_yanked(&self, version: &str) -> bool {
+        is_yanked(version, &self.yanked)
+    }
+
+    /// Check whether the package carries all of the given tags (ignoring case)
+    pub fn has_tags(&self, tags: &[String]) -> bool {
+        tags.iter()
+            .all(|tag| self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Rename platform keys that follow another naming convention
+    /// (`linux-amd64`, `x86_64-unknown-linux-gnu`) to wenget's identifiers
+    ///
+    /// An entry already under the identifier wins over its aliases.
+    pub fn normalize_platforms(&mut self) {
+        let mut aliased: Vec<(String, String)> = self
+            .platforms
+            .keys()
+            .filter_map(|key| {
+                let canonical = canonical_platform_id(key)?;
+                (canonical != *key).then(|| (key.clone(), canonical))
+            })
+            .collect();
+        aliased.sort();
+
+        for (alias, canonical) in aliased {
+            if let Some(binary) = self.platforms.remove(&alias) {
+                self.platforms.entry(canonical).or_insert(binary);
+            }
+        }
+    }
+
+    /// Expand `${VAR}` placeholders in the repo and download URLs from the
+    /// process environment
+    ///
+    /// Lets a shared manifest point at an internal mirror or carry a token
+    /// without committing it. Fails if a referenced variable is unset.
+    pub fn expand_env_vars(&mut self) -> Result<()> {
+        self.repo = expand_env(&self.repo)
+            .with_context(|| format!("Invalid repo URL for package '{}'", self.name))?;
+
+        for (platform, binary) in &mut self.platforms {
+            binary.url = expand_env(&binary.url).with_context(|| {
+                format!(
+                    "Invalid {} download URL for package '{}'",
+                    platform, self.name
+                )
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Expand `${VAR}` placeholders from the process environment
+pub fn expand_env(value: &str) -> Result<String> {
+    expand_vars(value, |name| std::env::var(name).ok())
+}
+
+/// Expand `${VAR}` placeholders using `lookup`; a `$` not followed by `{`
+/// is kept as-is
+fn expand_vars(value: &str, lookup: impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut expanded = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .with_context(|| format!("Unterminated '${{' in '{}'", value))?;
+
+        let name = &after[..end];
+        let valid = !name.is_empty()
+            && !name.starts_with(|c: char| c.is_ascii_digit())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            anyhow::bail!("Invalid variable name '{}' in '{}'", name, value);
+        }
+
+        let var = lookup(name).with_context(|| {
+            format!(
+                "Environment variable '{}' is not set (referenced by '{}')",
+                name, value
+            )
+        })?;
+        expanded.push_str(&var);
+        rest = &after[end + 1..];
+    }
+
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Check whether a release version or tag is in a yanked list
+pub fn is_yanked(version: &str, yanked: &[String]) -> bool {
+    let version = version.trim_start_matches('v');
+    yanked.iter().any(|y| y.trim_start_matches('v') == version)
 }
 
 /// Script item metadata (for bucket scripts)
@@ -251,6 +412,91 @@ pub enum PackageSource {
     },
 }
 
+impl PackageSource {
+    /// Short description of where a package came from (e.g. `bucket:main`)
+    pub fn describe(&self) -> String {
+        match self {
+            PackageSource::Bucket { name } => format!("bucket:{}", name),
+            PackageSource::DirectRepo { url } => url.clone(),
+            PackageSource::Script { origin, .. } => origin.clone(),
+        }
+    }
+}
+
+/// Release channel a package follows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    /// Only full releases
+    #[default]
+    Stable,
+    /// Prereleases as well as full releases
+    Prerelease,
+}
+
+impl Channel {
+    /// Channel name as used in the CLI and installed.json
+    pub fn as_str(&self) -> &str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Prerelease => "prerelease",
+        }
+    }
+
+    /// Parse a channel name ("stable", "prerelease")
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "stable" => Some(Channel::Stable),
+            "prerelease" | "pre" => Some(Channel::Prerelease),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the default channel
+    pub fn is_stable(&self) -> bool {
+        *self == Channel::Stable
+    }
+}
+
+/// How a package's launcher in the bin directory runs its executable
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShimMode {
+    /// A symlink into the package's install directory (Unix)
+    #[default]
+    Symlink,
+    /// A copy of the executable, refreshed on every upgrade (Unix)
+    Copy,
+    /// A small `sh` script that execs the executable (Unix)
+    Wrapper,
+}
+
+impl ShimMode {
+    /// Mode name as used in the CLI, settings and installed.json
+    pub fn as_str(&self) -> &str {
+        match self {
+            ShimMode::Symlink => "symlink",
+            ShimMode::Copy => "copy",
+            ShimMode::Wrapper => "wrapper",
+        }
+    }
+
+    /// Parse a mode name ("symlink", "copy", "wrapper")
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "symlink" => Some(ShimMode::Symlink),
+            "copy" => Some(ShimMode::Copy),
+            "wrapper" => Some(ShimMode::Wrapper),
+            _ => None,
+        }
+    }
+
+    /// Whether this is the default mode
+    pub fn is_symlink(&self) -> bool {
+        *self == ShimMode::Symlink
+    }
+}
+
 /// Installed package information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InstalledPackage {
@@ -260,15 +506,28 @@ pub struct InstalledPackage {
     /// Platform identifier
     pub platform: String,
 
-    /// Installation timestamp
+    /// First installation timestamp (preserved across upgrades)
     pub installed_at: DateTime<Utc>,
 
+    /// Timestamp of the most recent install or upgrade
+    ///
+    /// Missing in manifests written by older versions; filled from
+    /// `installed_at` by [`InstalledManifest::migrate`] on load.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime<Utc>>,
+
     /// Installation path
     pub install_path: String,
 
     /// List of installed files (relative to install_path)
     pub files: Vec<String>,
 
+    /// SHA-256 of each installed file, keyed like `files`; checked by `wenget verify`
+    ///
+    /// Empty for packages installed before digests were recorded.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_digests: BTreeMap<String, String>,
+
     /// Package source (where it was installed from)
     pub source: PackageSource,
 
@@ -277,6 +536,184 @@ pub struct InstalledPackage {
 
     /// Command name (the name used to invoke the tool)
     pub command_name: String,
+
+    /// Full path of the launcher (symlink/shim) created for this package
+    ///
+    /// Missing for packages installed before launcher locations were recorded;
+    /// those live in the default bin directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shim_path: Option<String>,
+
+    /// Full path of the executable the launcher points at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe_path: Option<String>,
+
+    /// Launchers created for the manifest's other `bin` entries
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_shims: Vec<String>,
+
+    /// Launcher name set with `wenget rename` (kept across upgrades)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// Installed without a launcher (--no-shim); run it from install_path
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub no_shim: bool,
+
+    /// How the launcher runs the executable (--shim-mode); reused on upgrade
+    #[serde(default, skip_serializing_if = "ShimMode::is_symlink")]
+    pub shim_mode: ShimMode,
+
+    /// Libc/compiler variant the user asked for (reused on upgrade)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_variant: Option<Compiler>,
+
+    /// Packages this package required when it was installed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub requires: Vec<String>,
+
+    /// Installed only to satisfy another package's `requires`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_installed: bool,
+
+    /// Release channel followed by `update`
+    #[serde(default, skip_serializing_if = "Channel::is_stable")]
+    pub channel: Channel,
+
+    /// Asset name pattern picked with `add --interactive` (reused on upgrade)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asset_pattern: Option<String>,
+
+    /// Previous versions to keep after an upgrade (--keep-versions)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_versions: Option<usize>,
+
+    /// Previous versions kept under ~/.wenget/backups/{name}/, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kept_versions: Vec<String>,
+
+    /// Version constraint set with `wenget pin` (`13.x`, `^1.2`, `13.0.5`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+
+    /// Release versions `wenget update --ignore` no longer offers
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored_updates: Vec<String>,
+
+    /// The manifest's post-install message, kept so `info` can show it again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_install_message: Option<String>,
+
+    /// Executable basename from the manifest or --exe-name, reused on upgrade
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exe_name: Option<String>,
+}
+
+impl InstalledPackage {
+    /// A bucket package named `name` at v1.0.0, for tests to override with `..`
+    #[cfg(test)]
+    pub fn test_default(name: &str) -> Self {
+        Self {
+            version: "1.0.0".to_string(),
+            platform: "linux-x86_64".to_string(),
+            installed_at: Utc::now(),
+            updated_at: None,
+            install_path: format!("/home/test/.wenget/apps/{}", name),
+            files: vec![],
+            file_digests: BTreeMap::new(),
+            source: PackageSource::Bucket {
+                name: "test-bucket".to_string(),
+            },
+            description: "Test package".to_string(),
+            command_name: name.to_string(),
+            shim_path: None,
+            exe_path: None,
+            extra_shims: Vec::new(),
+            alias: None,
+            no_shim: false,
+            prefer_variant: None,
+            requires: Vec::new(),
+            auto_installed: false,
+            channel: Channel::Stable,
+            asset_pattern: None,
+            keep_versions: None,
+            kept_versions: Vec::new(),
+            pin: None,
+            ignored_updates: Vec::new(),
+            post_install_message: None,
+            exe_name: None,
+            shim_mode: ShimMode::Symlink,
+        }
+    }
+
+    /// Timestamp of the most recent install or upgrade
+    pub fn last_updated(&self) -> DateTime<Utc> {
+        self.updated_at.unwrap_or(self.installed_at)
+    }
+
+    /// The version constraint this package is pinned to, if any
+    pub fn version_constraint(&self) -> Option<VersionConstraint> {
+        self.pin.as_deref().and_then(VersionConstraint::parse)
+    }
+
+    /// Check whether an available version was ignored with `update --ignore`
+    pub fn is_ignored_update(&self, version: &str) -> bool {
+        is_yanked(version, &self.ignored_updates)
+    }
+
+    /// Whether `latest` is an update to offer: a different version that was
+    /// not ignored
+    pub fn has_update(&self, latest: &str) -> bool {
+        latest != self.version && !self.is_ignored_update(latest)
+    }
+
+    /// Bytes the recorded files take up on disk
+    ///
+    /// Unlike the asset size in the manifest, which is the compressed
+    /// download. Files that are gone are not counted.
+    pub fn installed_size(&self) -> u64 {
+        let install_path = std::path::Path::new(&self.install_path);
+        self.files
+            .iter()
+            .filter_map(|file| std::fs::symlink_metadata(install_path.join(file)).ok())
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.len())
+            .sum()
+    }
+
+    /// Point the recorded paths at `app_dir`, e.g. after the wenget root moved
+    ///
+    /// The executable is rebased from the recorded install directory onto
+    /// `app_dir`, and launchers under the old root onto `root`. Launchers
+    /// outside it (`--global-bin`) keep their place.
+    pub fn relocate(&mut self, app_dir: &std::path::Path, root: &std::path::Path) {
+        let old_app_dir = std::path::PathBuf::from(&self.install_path);
+        let rebase = |path: &str, from: &std::path::Path, to: &std::path::Path| {
+            rebase_path(std::path::Path::new(path), from, to)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        if let Some(ref exe_path) = self.exe_path {
+            self.exe_path = Some(rebase(exe_path, &old_app_dir, app_dir));
+        }
+        // Install directories are <root>/apps/<name>
+        if let Some(old_root) = old_app_dir.parent().and_then(|apps| apps.parent()) {
+            if let Some(ref shim_path) = self.shim_path {
+                self.shim_path = Some(rebase(shim_path, old_root, root));
+            }
+            for shim in &mut self.extra_shims {
+                *shim = rebase(shim, old_root, root);
+            }
+        }
+        self.install_path = app_dir.to_string_lossy().into_owned();
+    }
+
+    /// Whether the install directory is gone (e.g. deleted by hand) while the
+    /// package is still recorded as installed
+    pub fn is_broken(&self) -> bool {
+        !std::path::Path::new(&self.install_path).exists()
+    }
 }
 
 /// Installed manifest (installed.json)
@@ -305,15 +742,104 @@ impl InstalledManifest {
     }
 
     /// Add or update an installed package
-    pub fn upsert_package(&mut self, name: String, package: InstalledPackage) {
+    ///
+    /// When the package is already installed, its original `installed_at` is
+    /// kept so upgrades only move `updated_at`.
+    pub fn upsert_package(&mut self, name: String, mut package: InstalledPackage) {
+        if let Some(existing) = self.packages.get(&name) {
+            package.installed_at = existing.installed_at;
+        }
         self.packages.insert(name, package);
     }
 
+    /// Upgrade records written by older versions in place
+    ///
+    /// Returns true if any record changed and the manifest should be saved.
+    pub fn migrate(&mut self) -> bool {
+        let mut changed = false;
+
+        for package in self.packages.values_mut() {
+            if package.updated_at.is_none() {
+                package.updated_at = Some(package.installed_at);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
     /// Remove an installed package
     pub fn remove_package(&mut self, name: &str) -> Option<InstalledPackage> {
         self.packages.remove(name)
     }
 
+    /// Mark a dependency-installed package as explicitly installed
+    ///
+    /// Returns true if the package was previously marked as a dependency.
+    pub fn mark_manual(&mut self, name: &str) -> bool {
+        match self.packages.get_mut(name) {
+            Some(pkg) if pkg.auto_installed => {
+                pkg.auto_installed = false;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Names of installed packages reachable from explicitly installed ones
+    /// through their `requires`
+    pub fn required_packages(&self) -> HashSet<&str> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<&str> = self
+            .packages
+            .iter()
+            .filter(|(_, pkg)| !pkg.auto_installed)
+            .map(|(name, _)| name.as_str())
+            .collect();
+
+        while let Some(name) = pending.pop() {
+            if !reachable.insert(name) {
+                continue;
+            }
+            if let Some(pkg) = self.packages.get(name) {
+                pending.extend(
+                    pkg.requires
+                        .iter()
+                        .map(|s| s.as_str())
+                        .filter(|dep| self.packages.contains_key(*dep)),
+                );
+            }
+        }
+
+        reachable
+    }
+
+    /// Dependency-installed packages no explicitly installed package needs
+    /// anymore, sorted by name
+    pub fn orphaned_dependencies(&self) -> Vec<&str> {
+        let required = self.required_packages();
+        let mut orphans: Vec<&str> = self
+            .packages
+            .iter()
+            .filter(|(name, pkg)| pkg.auto_installed && !required.contains(name.as_str()))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        orphans.sort();
+        orphans
+    }
+
+    /// Packages whose install directory no longer exists, sorted by name
+    pub fn broken_packages(&self) -> Vec<&str> {
+        let mut broken: Vec<&str> = self
+            .packages
+            .iter()
+            .filter(|(_, pkg)| pkg.is_broken())
+            .map(|(name, _)| name.as_str())
+            .collect();
+        broken.sort();
+        broken
+    }
+
     /// Get all installed package names
     #[allow(dead_code)]
     pub fn installed_names(&self) -> Vec<&str> {
@@ -342,16 +868,10 @@ mod tests {
         let mut manifest = InstalledManifest::new();
 
         let package = InstalledPackage {
-            version: "1.0.0".to_string(),
             platform: "windows-x86_64".to_string(),
-            installed_at: Utc::now(),
             install_path: "C:\\Users\\test\\.wenget\\apps\\test".to_string(),
             files: vec!["bin/test.exe".to_string()],
-            source: PackageSource::Bucket {
-                name: "test-bucket".to_string(),
-            },
-            description: "Test package".to_string(),
-            command_name: "test".to_string(),
+            ..InstalledPackage::test_default("test")
         };
 
         manifest.upsert_package("test".to_string(), package);
@@ -361,4 +881,416 @@ mod tests {
         manifest.remove_package("test");
         assert!(!manifest.is_installed("test"));
     }
+
+    #[test]
+    fn test_package_yanked_versions() {
+        let json = r#"{
+            "name": "tool",
+            "description": "A tool",
+            "repo": "https://github.com/test/tool",
+            "platforms": {},
+            "yanked": ["v2.0.0", "1.8.1"]
+        }"#;
+
+        let package: Package = serde_json::from_str(json).unwrap();
+        assert!(package.is_yanked("2.0.0"));
+        assert!(package.is_yanked("v1.8.1"));
+        assert!(!package.is_yanked("1.9.0"));
+    }
+
+    #[test]
+    fn test_package_tags() {
+        let json = r#"{
+            "name": "ripgrep",
+            "description": "Fast grep",
+            "repo": "https://github.com/BurntSushi/ripgrep",
+            "platforms": {},
+            "tags": ["cli", "Rust", "search"]
+        }"#;
+        let package: Package = serde_json::from_str(json).unwrap();
+        let tags = |t: &[&str]| t.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert!(package.has_tags(&tags(&["search"])));
+        assert!(package.has_tags(&tags(&["rust", "CLI"])));
+        assert!(package.has_tags(&[]));
+        assert!(!package.has_tags(&tags(&["search", "gui"])));
+
+        // `categories` is accepted too, and manifests without tags match no tag
+        let json = r#"{"name": "a", "description": "", "repo": "", "platforms": {},
+            "categories": ["gui"]}"#;
+        let package: Package = serde_json::from_str(json).unwrap();
+        assert!(package.has_tags(&tags(&["gui"])));
+
+        let json = r#"{"name": "b", "description": "", "repo": "", "platforms": {}}"#;
+        let package: Package = serde_json::from_str(json).unwrap();
+        assert!(package.tags.is_empty());
+        assert!(!package.has_tags(&tags(&["cli"])));
+        assert!(!serde_json::to_string(&package).unwrap().contains("tags"));
+    }
+
+    #[test]
+    fn test_post_install_message() {
+        let json = r#"{
+            "name": "zoxide",
+            "description": "Smarter cd",
+            "repo": "https://github.com/ajeetdsouza/zoxide",
+            "platforms": {},
+            "post_install_message": "Add `eval \"$(zoxide init bash)\"` to ~/.bashrc"
+        }"#;
+        let package: Package = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            package.post_install_message.as_deref(),
+            Some("Add `eval \"$(zoxide init bash)\"` to ~/.bashrc")
+        );
+
+        // Installed packages keep it for `info`; records without one omit it
+        let json = r#"{
+            "version": "0.9.4",
+            "platform": "linux-x86_64",
+            "installed_at": "2024-01-01T00:00:00Z",
+            "install_path": "/home/u/.wenget/apps/zoxide",
+            "files": [],
+            "source": {"type": "bucket", "name": "main"},
+            "description": "Smarter cd",
+            "command_name": "zoxide",
+            "post_install_message": "Run `zoxide init`"
+        }"#;
+        let installed: InstalledPackage = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            installed.post_install_message.as_deref(),
+            Some("Run `zoxide init`")
+        );
+
+        let mut installed = installed;
+        installed.post_install_message = None;
+        let json = serde_json::to_string(&installed).unwrap();
+        assert!(!json.contains("post_install_message"));
+        let installed: InstalledPackage = serde_json::from_str(&json).unwrap();
+        assert!(installed.post_install_message.is_none());
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let lookup = |name: &str| match name {
+            "MIRROR" => Some("https://mirror.internal".to_string()),
+            "TOKEN" => Some("s3cret".to_string()),
+            _ => None,
+        };
+
+        assert_eq!(
+            expand_vars("${MIRROR}/tool/v1/tool.tar.gz?token=${TOKEN}", lookup).unwrap(),
+            "https://mirror.internal/tool/v1/tool.tar.gz?token=s3cret"
+        );
+        assert_eq!(
+            expand_vars("https://example.com/$HOME/a", lookup).unwrap(),
+            "https://example.com/$HOME/a"
+        );
+
+        let err = expand_vars("${MISSING}/tool.zip", lookup).unwrap_err();
+        assert!(err.to_string().contains("'MISSING' is not set"));
+        assert!(expand_vars("${MIRROR/tool.zip", lookup).is_err());
+        assert!(expand_vars("${}/tool.zip", lookup).is_err());
+    }
+
+    #[test]
+    fn test_normalize_platforms() {
+        let json = r#"{
+            "name": "tool",
+            "description": "A tool",
+            "repo": "https://github.com/test/tool",
+            "platforms": {
+                "linux-amd64": { "url": "https://example.com/alias.tar.gz", "size": 1 },
+                "linux-x86_64": { "url": "https://example.com/linux.tar.gz", "size": 1 },
+                "darwin-arm64": { "url": "https://example.com/mac.tar.gz", "size": 1 },
+                "x86_64-pc-windows-msvc": { "url": "https://example.com/win.zip", "size": 1 },
+                "plan9-mips": { "url": "https://example.com/plan9.tar.gz", "size": 1 }
+            }
+        }"#;
+
+        let mut package: Package = serde_json::from_str(json).unwrap();
+        package.normalize_platforms();
+
+        let mut keys: Vec<&str> = package.platforms.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            vec![
+                "linux-x86_64",
+                "macos-aarch64",
+                "plan9-mips",
+                "windows-x86_64-msvc"
+            ]
+        );
+        // The entry under wenget's own identifier wins over its alias
+        assert_eq!(
+            package.platforms["linux-x86_64"].url,
+            "https://example.com/linux.tar.gz"
+        );
+    }
+
+    #[test]
+    fn test_package_expand_env_vars_missing() {
+        let json = r#"{
+            "name": "tool",
+            "description": "A tool",
+            "repo": "https://github.com/test/tool",
+            "platforms": {
+                "linux-x86_64": {
+                    "url": "${WENGET_TEST_UNSET_MIRROR}/tool.tar.gz",
+                    "size": 1
+                }
+            }
+        }"#;
+
+        let mut package: Package = serde_json::from_str(json).unwrap();
+        let err = package.expand_env_vars().unwrap_err();
+        assert!(format!("{:#}", err).contains("WENGET_TEST_UNSET_MIRROR"));
+    }
+
+    #[test]
+    fn test_installed_package_legacy_launcher_fields() {
+        // Records written before launcher tracking existed still load
+        let json = r#"{
+            "version": "1.0.0",
+            "platform": "linux-x86_64",
+            "installed_at": "2025-01-01T00:00:00Z",
+            "install_path": "/home/test/.wenget/apps/test",
+            "files": ["test"],
+            "source": { "type": "bucket", "name": "test-bucket" },
+            "description": "Test package",
+            "command_name": "test"
+        }"#;
+
+        let package: InstalledPackage = serde_json::from_str(json).unwrap();
+        assert_eq!(package.shim_path, None);
+        assert!(!package.no_shim);
+
+        let serialized = serde_json::to_string(&package).unwrap();
+        assert!(!serialized.contains("no_shim"));
+        assert!(!serialized.contains("shim_path"));
+    }
+
+    #[test]
+    fn test_ignored_updates() {
+        let json = r#"{
+            "version": "1.0.0",
+            "platform": "linux-x86_64",
+            "installed_at": "2025-01-01T00:00:00Z",
+            "install_path": "/home/test/.wenget/apps/test",
+            "files": ["test"],
+            "source": { "type": "bucket", "name": "test-bucket" },
+            "description": "Test package",
+            "command_name": "test"
+        }"#;
+        let mut package: InstalledPackage = serde_json::from_str(json).unwrap();
+        assert!(package.ignored_updates.is_empty());
+        assert!(!serde_json::to_string(&package)
+            .unwrap()
+            .contains("ignored_updates"));
+        assert!(package.has_update("2.0.0"));
+        assert!(!package.has_update("1.0.0"));
+
+        // Only the ignored version stops counting, with or without a `v`
+        package.ignored_updates.push("2.0.0".to_string());
+        assert!(package.is_ignored_update("v2.0.0"));
+        assert!(!package.has_update("2.0.0"));
+        assert!(package.has_update("2.0.1"));
+    }
+
+    #[test]
+    fn test_channel_parse_and_default() {
+        assert_eq!(Channel::parse("stable"), Some(Channel::Stable));
+        assert_eq!(Channel::parse("Prerelease"), Some(Channel::Prerelease));
+        assert_eq!(Channel::parse("nightly"), None);
+
+        let json = r#"{
+            "version": "1.0.0",
+            "platform": "linux-x86_64",
+            "installed_at": "2025-01-01T00:00:00Z",
+            "install_path": "/home/test/.wenget/apps/test",
+            "files": [],
+            "source": { "type": "bucket", "name": "test-bucket" },
+            "description": "Test package",
+            "command_name": "test"
+        }"#;
+        let mut package: InstalledPackage = serde_json::from_str(json).unwrap();
+        assert_eq!(package.channel, Channel::Stable);
+        assert!(!serde_json::to_string(&package).unwrap().contains("channel"));
+
+        package.channel = Channel::Prerelease;
+        let serialized = serde_json::to_string(&package).unwrap();
+        assert!(serialized.contains(r#""channel":"prerelease""#));
+    }
+
+    #[test]
+    fn test_upsert_preserves_installed_at() {
+        let first = Utc::now() - chrono::Duration::days(30);
+        let make_package = |version: &str, at: DateTime<Utc>| InstalledPackage {
+            version: version.to_string(),
+            installed_at: at,
+            updated_at: Some(at),
+            ..InstalledPackage::test_default("test")
+        };
+
+        let mut manifest = InstalledManifest::new();
+        manifest.upsert_package("test".to_string(), make_package("1.0.0", first));
+
+        let now = Utc::now();
+        manifest.upsert_package("test".to_string(), make_package("2.0.0", now));
+
+        let package = manifest.get_package("test").unwrap();
+        assert_eq!(package.version, "2.0.0");
+        assert_eq!(package.installed_at, first);
+        assert_eq!(package.last_updated(), now);
+    }
+
+    #[test]
+    fn test_migrate_fills_updated_at() {
+        let mut manifest: InstalledManifest = serde_json::from_str(
+            r#"{"packages": {"test": {
+                "version": "1.0.0",
+                "platform": "linux-x86_64",
+                "installed_at": "2025-01-01T00:00:00Z",
+                "install_path": "/home/test/.wenget/apps/test",
+                "files": [],
+                "source": { "type": "bucket", "name": "test-bucket" },
+                "description": "Test package",
+                "command_name": "test"
+            }}}"#,
+        )
+        .unwrap();
+
+        assert!(manifest.migrate());
+        let package = manifest.get_package("test").unwrap();
+        assert_eq!(package.updated_at, Some(package.installed_at));
+
+        // Already migrated
+        assert!(!manifest.migrate());
+    }
+
+    #[test]
+    fn test_orphaned_dependencies() {
+        let make_package = |requires: &[&str], auto_installed: bool| InstalledPackage {
+            requires: requires.iter().map(|s| s.to_string()).collect(),
+            auto_installed,
+            ..InstalledPackage::test_default("test")
+        };
+
+        let mut manifest = InstalledManifest::new();
+        manifest.upsert_package("app".to_string(), make_package(&["lib", "missing"], false));
+        manifest.upsert_package("lib".to_string(), make_package(&["core"], true));
+        manifest.upsert_package("core".to_string(), make_package(&[], true));
+        manifest.upsert_package("stray".to_string(), make_package(&[], true));
+        manifest.upsert_package("tool".to_string(), make_package(&[], false));
+
+        assert_eq!(manifest.orphaned_dependencies(), vec!["stray"]);
+
+        // Removing the explicitly installed package orphans its whole chain
+        manifest.remove_package("app");
+        assert_eq!(
+            manifest.orphaned_dependencies(),
+            vec!["core", "lib", "stray"]
+        );
+    }
+
+    #[test]
+    fn test_autoremove_candidates() {
+        let make_package = |requires: &[&str], auto_installed: bool| InstalledPackage {
+            requires: requires.iter().map(|s| s.to_string()).collect(),
+            auto_installed,
+            ..InstalledPackage::test_default("test")
+        };
+
+        // A pulls in B; C is added on its own
+        let mut manifest = InstalledManifest::new();
+        manifest.upsert_package("a".to_string(), make_package(&["b"], false));
+        manifest.upsert_package("b".to_string(), make_package(&[], true));
+        manifest.upsert_package("c".to_string(), make_package(&[], false));
+        assert!(manifest.orphaned_dependencies().is_empty());
+
+        manifest.remove_package("a");
+        assert_eq!(manifest.orphaned_dependencies(), vec!["b"]);
+
+        // Adding B directly keeps it around
+        assert!(manifest.mark_manual("b"));
+        assert!(!manifest.mark_manual("b"));
+        assert!(manifest.orphaned_dependencies().is_empty());
+    }
+
+    #[test]
+    fn test_installed_size() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join("doc")).unwrap();
+        std::fs::write(temp_dir.path().join("tool"), vec![0u8; 1000]).unwrap();
+        std::fs::write(temp_dir.path().join("doc").join("README"), "hello").unwrap();
+
+        let pkg = InstalledPackage {
+            install_path: temp_dir.path().to_string_lossy().into_owned(),
+            files: vec![
+                "tool".to_string(),
+                "doc/README".to_string(),
+                "doc".to_string(),
+                "deleted".to_string(),
+            ],
+            ..InstalledPackage::test_default("tool")
+        };
+
+        // Directories and missing files don't count
+        assert_eq!(pkg.installed_size(), 1005);
+    }
+
+    #[test]
+    fn test_relocate() {
+        let old_root = std::path::Path::new("old-home").join(".wenget");
+        let new_root = std::path::Path::new("new-home").join(".wenget");
+        let global_bin = std::path::Path::new("usr").join("local").join("bin");
+        let path = |p: std::path::PathBuf| p.to_string_lossy().into_owned();
+
+        let mut pkg = InstalledPackage {
+            install_path: path(old_root.join("apps").join("rg")),
+            shim_path: Some(path(old_root.join("bin").join("rg"))),
+            exe_path: Some(path(old_root.join("apps").join("rg").join("rg"))),
+            extra_shims: vec![path(global_bin.join("rg-extra"))],
+            ..InstalledPackage::test_default("rg")
+        };
+
+        pkg.relocate(&new_root.join("apps").join("rg"), &new_root);
+        assert_eq!(pkg.install_path, path(new_root.join("apps").join("rg")));
+        assert_eq!(
+            pkg.exe_path,
+            Some(path(new_root.join("apps").join("rg").join("rg")))
+        );
+        assert_eq!(pkg.shim_path, Some(path(new_root.join("bin").join("rg"))));
+        // Launchers outside the root are left where they are
+        assert_eq!(pkg.extra_shims, vec![path(global_bin.join("rg-extra"))]);
+    }
+
+    #[test]
+    fn test_broken_packages() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let make_package = |install_path: &std::path::Path| InstalledPackage {
+            install_path: install_path.to_string_lossy().into_owned(),
+            ..InstalledPackage::test_default("test")
+        };
+
+        let present = temp_dir.path().join("present");
+        std::fs::create_dir(&present).unwrap();
+        let deleted = temp_dir.path().join("deleted");
+        std::fs::create_dir(&deleted).unwrap();
+
+        let mut manifest = InstalledManifest::new();
+        manifest.upsert_package("present".to_string(), make_package(&present));
+        manifest.upsert_package("deleted".to_string(), make_package(&deleted));
+        manifest.upsert_package(
+            "never".to_string(),
+            make_package(&temp_dir.path().join("never")),
+        );
+        assert_eq!(manifest.broken_packages(), vec!["never"]);
+
+        // Removing an app directory by hand leaves a broken entry behind
+        std::fs::remove_dir_all(&deleted).unwrap();
+        assert!(manifest.get_package("deleted").unwrap().is_broken());
+        assert!(!manifest.get_package("present").unwrap().is_broken());
+        assert_eq!(manifest.broken_packages(), vec!["deleted", "never"]);
+    }
 }
@@ -1,14 +1,26 @@
 //! Core modules for WenPM
 
+pub mod batch;
 pub mod config;
+pub mod history;
 pub mod manifest;
 pub mod paths;
 pub mod platform;
 pub mod repair;
+pub mod scoop;
+pub mod settings;
+pub mod version;
 
 // Re-export commonly used items
 pub use config::Config;
-pub use manifest::{InstalledManifest, InstalledPackage, Package, PlatformBinary};
+pub use manifest::{
+    Channel, InstalledManifest, InstalledPackage, Package, PlatformBinary, ShimMode,
+};
 pub use paths::WenPaths;
 #[allow(unused_imports)]
-pub use platform::{Arch, BinaryAsset, BinarySelector, Compiler, FileExtension, Os, Platform};
+pub use platform::{
+    Arch, AssetChange, AssetPattern, BinaryAsset, BinarySelector, Compiler, FileExtension, Os,
+    Platform,
+};
+pub use settings::Settings;
+pub use version::VersionConstraint;
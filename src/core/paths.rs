@@ -4,12 +4,15 @@
 //! - Root directory: ~/.wenget/
 //! - Installed manifest: ~/.wenget/installed.json
 //! - Buckets config: ~/.wenget/buckets.json
+//! - User settings: ~/.wenget/settings.json
 //! - Manifest cache: ~/.wenget/manifest-cache.json
 //! - Apps directory: ~/.wenget/apps/
 //! - Bin directory: ~/.wenget/bin/
 //! - Cache directory: ~/.wenget/cache/
+//! - Staging directory: ~/.wenget/cache/staging/
 
 use anyhow::{Context, Result};
+use std::fs;
 use std::path::{Path, PathBuf};
 
 /// Wenget paths manager
@@ -26,8 +29,15 @@ impl WenPaths {
     /// Returns an error if the home directory cannot be determined
     pub fn new() -> Result<Self> {
         let home = dirs::home_dir().context("Failed to determine home directory")?;
-        let root = home.join(".wenget");
-        Ok(Self { root })
+        Ok(Self::with_root(home.join(".wenget")))
+    }
+
+    /// Use another root directory instead of ~/.wenget/
+    ///
+    /// Everything derived from these paths (apps, launchers, cache, staging
+    /// and manifests) then lives under `root`, e.g. a temporary directory.
+    pub fn with_root(root: PathBuf) -> Self {
+        Self { root }
     }
 
     /// Get the root directory (~/.wenget/)
@@ -45,11 +55,26 @@ impl WenPaths {
         self.root.join("buckets.json")
     }
 
+    /// Get the state file of an unfinished `add` batch (~/.wenget/batch.json)
+    pub fn batch_json(&self) -> PathBuf {
+        self.root.join("batch.json")
+    }
+
+    /// Get the user settings path (~/.wenget/settings.json)
+    pub fn settings_json(&self) -> PathBuf {
+        self.root.join("settings.json")
+    }
+
     /// Get the manifest cache path (~/.wenget/manifest-cache.json)
     pub fn manifest_cache_json(&self) -> PathBuf {
         self.root.join("manifest-cache.json")
     }
 
+    /// Get the install history path (~/.wenget/history.jsonl)
+    pub fn history_jsonl(&self) -> PathBuf {
+        self.root.join("history.jsonl")
+    }
+
     /// Get the apps directory (~/.wenget/apps/)
     pub fn apps_dir(&self) -> PathBuf {
         self.root.join("apps")
@@ -66,6 +91,11 @@ impl WenPaths {
         self.app_dir(name).join("bin")
     }
 
+    /// Get a package's retained previous versions (~/.wenget/backups/{name}/)
+    pub fn backups_dir(&self, name: &str) -> PathBuf {
+        self.root.join("backups").join(name)
+    }
+
     /// Get the bin directory (~/.wenget/bin/)
     pub fn bin_dir(&self) -> PathBuf {
         self.root.join("bin")
@@ -76,11 +106,34 @@ impl WenPaths {
         self.root.join("cache")
     }
 
+    /// Get the per-bucket cache directory (~/.wenget/cache/buckets/)
+    pub fn bucket_cache_dir(&self) -> PathBuf {
+        self.cache_dir().join("buckets")
+    }
+
+    /// Get a bucket's cache entry path (~/.wenget/cache/buckets/{name}.json)
+    pub fn bucket_cache_json(&self, name: &str) -> PathBuf {
+        self.bucket_cache_dir().join(format!("{}.json", name))
+    }
+
+    /// Get the release asset size cache path (~/.wenget/cache/releases.json)
+    pub fn release_cache_json(&self) -> PathBuf {
+        self.cache_dir().join("releases.json")
+    }
+
     /// Get the downloads directory (~/.wenget/cache/downloads/)
     pub fn downloads_dir(&self) -> PathBuf {
         self.cache_dir().join("downloads")
     }
 
+    /// Get the staging directory for installs in progress (~/.wenget/cache/staging/)
+    ///
+    /// Kept under the Wenget root so it shares a filesystem with the apps
+    /// directory and staged installs can be renamed into place.
+    pub fn staging_dir(&self) -> PathBuf {
+        self.cache_dir().join("staging")
+    }
+
     /// Initialize all required directories
     ///
     /// Creates the following directories if they don't exist:
@@ -111,14 +164,22 @@ impl WenPaths {
     /// On Unix: ~/.wenget/bin/{name}
     /// On Windows: ~/.wenget/bin/{name}.cmd
     pub fn bin_shim_path(&self, name: &str) -> PathBuf {
+        Self::shim_path_in(&self.bin_dir(), name)
+    }
+
+    /// Get the symlink/shim path for an app in an arbitrary directory
+    ///
+    /// On Unix: {dir}/{name}
+    /// On Windows: {dir}/{name}.cmd
+    pub fn shim_path_in(dir: &Path, name: &str) -> PathBuf {
         #[cfg(windows)]
         {
-            self.bin_dir().join(format!("{}.cmd", name))
+            dir.join(format!("{}.cmd", name))
         }
 
         #[cfg(not(windows))]
         {
-            self.bin_dir().join(name)
+            dir.join(name)
         }
     }
 
@@ -140,6 +201,77 @@ impl WenPaths {
     }
 }
 
+/// Check whether a directory is listed in the PATH environment variable
+pub fn is_dir_in_path(dir: &Path) -> bool {
+    let Some(path_var) = std::env::var_os("PATH") else {
+        return false;
+    };
+
+    std::env::split_paths(&path_var).any(|p| p == dir)
+}
+
+/// Position of a directory in the PATH environment variable
+pub fn path_index(dir: &Path) -> Option<usize> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var).position(|p| p == dir)
+}
+
+/// Move `path` from under `from` to under `to`, leaving other paths as they are
+pub fn rebase_path(path: &Path, from: &Path, to: &Path) -> PathBuf {
+    match path.strip_prefix(from) {
+        Ok(rest) if rest.as_os_str().is_empty() => to.to_path_buf(),
+        Ok(rest) => to.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Find executables named `name` in the PATH directories, in PATH order
+pub fn find_in_path(name: &str) -> Vec<PathBuf> {
+    match std::env::var_os("PATH") {
+        Some(path_var) => find_in_dirs(std::env::split_paths(&path_var), name),
+        None => Vec::new(),
+    }
+}
+
+/// Find executables named `name` in the given directories, in order
+fn find_in_dirs(dirs: impl Iterator<Item = PathBuf>, name: &str) -> Vec<PathBuf> {
+    #[cfg(windows)]
+    let file_names: Vec<String> = ["exe", "cmd", "bat", "com"]
+        .iter()
+        .map(|ext| format!("{}.{}", name, ext))
+        .collect();
+
+    #[cfg(not(windows))]
+    let file_names = [name.to_string()];
+
+    dirs.flat_map(|dir| {
+        file_names
+            .iter()
+            .map(|file_name| dir.join(file_name))
+            .collect::<Vec<_>>()
+    })
+    .filter(|path| is_executable_file(path))
+    .collect()
+}
+
+/// Check whether a path is a file the current user could run
+fn is_executable_file(path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
 impl Default for WenPaths {
     fn default() -> Self {
         Self::new().expect("Failed to initialize WenPaths")
@@ -150,6 +282,34 @@ impl Default for WenPaths {
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_find_in_dirs() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let first = temp_dir.path().join("first");
+        let second = temp_dir.path().join("second");
+        let empty = temp_dir.path().join("empty");
+        for dir in [&first, &second, &empty] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        for dir in [&first, &second] {
+            let exe = dir.join("fd");
+            fs::write(&exe, b"#!/bin/sh\n").unwrap();
+            fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        // Not executable, so never run from PATH
+        fs::write(empty.join("fd"), b"data").unwrap();
+
+        let dirs = vec![empty.clone(), second.clone(), first.clone()];
+        let found = find_in_dirs(dirs.into_iter(), "fd");
+        assert_eq!(found, vec![second.join("fd"), first.join("fd")]);
+
+        assert!(find_in_dirs(vec![first].into_iter(), "rg").is_empty());
+    }
+
     #[test]
     fn test_paths_creation() {
         let paths = WenPaths::new().unwrap();
@@ -197,4 +357,29 @@ mod tests {
             assert!(shim.ends_with("bin/test"));
         }
     }
+
+    #[test]
+    fn test_rebase_path() {
+        let old = Path::new("old-home").join(".wenget");
+        let new = Path::new("new-home").join(".wenget");
+
+        let exe = old.join("apps").join("rg").join("rg");
+        assert_eq!(
+            rebase_path(&exe, &old, &new),
+            new.join("apps").join("rg").join("rg")
+        );
+        assert_eq!(rebase_path(&old, &old, &new), new);
+
+        // Launchers placed outside the root (--global-bin) stay put
+        let global = Path::new("usr").join("local").join("bin").join("rg");
+        assert_eq!(rebase_path(&global, &old, &new), global);
+    }
+
+    #[test]
+    fn test_shim_path_in() {
+        let dir = Path::new("custom-bin");
+        let shim = WenPaths::shim_path_in(dir, "test");
+        assert!(shim.starts_with(dir));
+        assert_eq!(shim.parent(), Some(dir));
+    }
 }
@@ -5,7 +5,112 @@
 //! - Binary selection from release assets based on platform
 //! - Platform string normalization
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static ALLOW_EMULATION: AtomicBool = AtomicBool::new(false);
+
+/// Platform names other tools use in manifests, and the identifier they
+/// stand for
+const PLATFORM_ALIASES: &[(&str, &str)] = &[
+    ("linux64", "linux-x86_64"),
+    ("linux32", "linux-i686"),
+    ("linux-amd64", "linux-x86_64"),
+    ("linux-x64", "linux-x86_64"),
+    ("linux-386", "linux-i686"),
+    ("linux-x86", "linux-i686"),
+    ("linux-arm64", "linux-aarch64"),
+    ("linux-arm", "linux-armv7"),
+    ("x86_64-unknown-linux-gnu", "linux-x86_64-gnu"),
+    ("x86_64-unknown-linux-musl", "linux-x86_64-musl"),
+    ("i686-unknown-linux-gnu", "linux-i686-gnu"),
+    ("i686-unknown-linux-musl", "linux-i686-musl"),
+    ("aarch64-unknown-linux-gnu", "linux-aarch64-gnu"),
+    ("aarch64-unknown-linux-musl", "linux-aarch64-musl"),
+    ("armv7-unknown-linux-gnueabihf", "linux-armv7-gnu"),
+    ("armv7-unknown-linux-musleabihf", "linux-armv7-musl"),
+    ("darwin-amd64", "macos-x86_64"),
+    ("darwin-x64", "macos-x86_64"),
+    ("darwin-arm64", "macos-aarch64"),
+    ("macos-amd64", "macos-x86_64"),
+    ("macos-x64", "macos-x86_64"),
+    ("macos-arm64", "macos-aarch64"),
+    ("osx-x64", "macos-x86_64"),
+    ("osx-arm64", "macos-aarch64"),
+    ("x86_64-apple-darwin", "macos-x86_64"),
+    ("aarch64-apple-darwin", "macos-aarch64"),
+    ("win64", "windows-x86_64"),
+    ("win32", "windows-i686"),
+    ("windows-amd64", "windows-x86_64"),
+    ("windows-x64", "windows-x86_64"),
+    ("windows-386", "windows-i686"),
+    ("windows-x86", "windows-i686"),
+    ("windows-arm64", "windows-aarch64"),
+    ("x86_64-pc-windows-msvc", "windows-x86_64-msvc"),
+    ("x86_64-pc-windows-gnu", "windows-x86_64-gnu"),
+    ("i686-pc-windows-msvc", "windows-i686-msvc"),
+    ("aarch64-pc-windows-msvc", "windows-aarch64-msvc"),
+    ("freebsd-amd64", "freebsd-x86_64"),
+    ("x86_64-unknown-freebsd", "freebsd-x86_64"),
+];
+
+/// Aliases added with the `platform_aliases` setting
+static CUSTOM_ALIASES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Allow x86_64 builds on ARM64 Windows when no native build exists
+pub fn set_allow_emulation(allow: bool) {
+    ALLOW_EMULATION.store(allow, Ordering::Relaxed);
+}
+
+/// Whether x86_64 builds may be used under emulation (`allow_emulation`)
+pub fn emulation_allowed() -> bool {
+    ALLOW_EMULATION.load(Ordering::Relaxed)
+}
+
+/// Add platform aliases on top of the built-in table (`platform_aliases`)
+pub fn set_platform_aliases(aliases: Vec<(String, String)>) {
+    *CUSTOM_ALIASES.lock().unwrap_or_else(|e| e.into_inner()) = aliases;
+}
+
+/// Whether `id` is one of wenget's own platform identifiers
+/// (`linux-x86_64`, `windows-x86_64-msvc`)
+pub fn is_canonical_id(id: &str) -> bool {
+    let mut parts = id.split('-');
+    let (Some(os), Some(arch)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    let compiler = parts.next();
+
+    ["windows", "linux", "macos", "freebsd"].contains(&os)
+        && Arch::parse(arch).is_some_and(|a| a.as_str() == arch)
+        && compiler.is_none_or(|c| Compiler::parse(c).is_some_and(|p| p.as_str() == c))
+        && parts.next().is_none()
+}
+
+/// The identifier a manifest platform key stands for
+///
+/// Identifiers are returned as they are; aliases from the setting take
+/// precedence over the built-in table. Unknown keys give None.
+pub fn canonical_platform_id(id: &str) -> Option<String> {
+    if is_canonical_id(id) {
+        return Some(id.to_string());
+    }
+
+    let custom = CUSTOM_ALIASES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(id))
+        .map(|(_, canonical)| canonical.clone());
+    custom.or_else(|| {
+        PLATFORM_ALIASES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(id))
+            .map(|(_, canonical)| canonical.to_string())
+    })
+}
 
 /// Supported operating systems
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -111,6 +216,17 @@ impl Arch {
         }
     }
 
+    /// Parse an architecture name as accepted by `--arch`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "x86_64" | "x64" | "amd64" => Some(Arch::X86_64),
+            "i686" | "i386" | "x86" => Some(Arch::I686),
+            "aarch64" | "arm64" => Some(Arch::Aarch64),
+            "armv7" | "armhf" => Some(Arch::Armv7),
+            _ => None,
+        }
+    }
+
     /// Resolve the "x86" keyword based on OS context
     /// Darwin: x86 -> x86_64 (32-bit Mac is obsolete)
     /// Others: x86 -> i686
@@ -123,7 +239,8 @@ impl Arch {
 }
 
 /// Compiler/libc variant for platform-specific binaries
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Compiler {
     /// GNU libc (glibc)
     Gnu,
@@ -169,6 +286,25 @@ impl Compiler {
             Compiler::Msvc => "msvc",
         }
     }
+
+    /// Parse a compiler name ("gnu", "musl", "msvc")
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "gnu" | "glibc" => Some(Compiler::Gnu),
+            "musl" => Some(Compiler::Musl),
+            "msvc" => Some(Compiler::Msvc),
+            _ => None,
+        }
+    }
+
+    /// Variants worth distinguishing for an OS (in default priority order)
+    fn variants_for(os: Os) -> &'static [Compiler] {
+        match os {
+            Os::Linux => &[Compiler::Musl, Compiler::Gnu],
+            Os::Windows => &[Compiler::Msvc, Compiler::Gnu],
+            Os::MacOS | Os::FreeBSD => &[],
+        }
+    }
 }
 
 /// Supported file extensions for binary assets
@@ -180,6 +316,12 @@ pub enum FileExtension {
     TarXz,
     TarBz2,
     SevenZ,
+    /// Plain .gz/.bz2/.xz, usually a single compressed binary
+    Compressed,
+    /// macOS disk image, mounted with hdiutil
+    Dmg,
+    /// Debian or RPM package, only its payload is extracted
+    LinuxPackage,
     Unsupported,
 }
 
@@ -199,6 +341,12 @@ impl FileExtension {
             FileExtension::TarBz2
         } else if lower.ends_with(".7z") {
             FileExtension::SevenZ
+        } else if lower.ends_with(".gz") || lower.ends_with(".bz2") || lower.ends_with(".xz") {
+            FileExtension::Compressed
+        } else if lower.ends_with(".dmg") {
+            FileExtension::Dmg
+        } else if lower.ends_with(".deb") || lower.ends_with(".rpm") {
+            FileExtension::LinuxPackage
         } else {
             FileExtension::Unsupported
         }
@@ -213,6 +361,9 @@ impl FileExtension {
             FileExtension::TarBz2 => 3,
             FileExtension::SevenZ => 2,
             FileExtension::Exe => 2,
+            FileExtension::Compressed => 1,
+            FileExtension::Dmg => 1,
+            FileExtension::LinuxPackage => 1,
             FileExtension::Unsupported => 0,
         }
     }
@@ -280,6 +431,11 @@ impl ParsedAsset {
             return (Some(Os::Windows), true);
         }
 
+        // .deb/.rpm imply Linux
+        if ext == FileExtension::LinuxPackage {
+            return (Some(Os::Linux), true);
+        }
+
         (None, false)
     }
 
@@ -333,16 +489,14 @@ pub struct Platform {
     pub os: Os,
     pub arch: Arch,
     pub compiler: Option<Compiler>,
+    /// Fall back to builds for an architecture the OS can emulate
+    pub emulation: bool,
 }
 
 impl Platform {
     /// Get the current platform
     pub fn current() -> Self {
-        Self {
-            os: Os::current(),
-            arch: Arch::current(),
-            compiler: None,
-        }
+        Self::new(Os::current(), Arch::current()).with_emulation(emulation_allowed())
     }
 
     /// Create a platform from components
@@ -351,6 +505,7 @@ impl Platform {
             os,
             arch,
             compiler: None,
+            emulation: false,
         }
     }
 
@@ -358,9 +513,36 @@ impl Platform {
     #[allow(dead_code)]
     pub fn with_compiler(os: Os, arch: Arch, compiler: Compiler) -> Self {
         Self {
-            os,
-            arch,
             compiler: Some(compiler),
+            ..Self::new(os, arch)
+        }
+    }
+
+    /// Use a preferred compiler/libc variant when selecting binaries
+    pub fn prefer_compiler(mut self, compiler: Option<Compiler>) -> Self {
+        self.compiler = compiler;
+        self
+    }
+
+    /// Also accept builds for the architecture this one can emulate
+    pub fn with_emulation(mut self, emulation: bool) -> Self {
+        self.emulation = emulation;
+        self
+    }
+
+    /// The platform whose builds run under emulation here, if any
+    ///
+    /// Windows 11 on ARM64 runs x86_64 executables through its built-in
+    /// emulation layer. (macOS needs no fallback: Rosetta 2 is covered by
+    /// Darwin assets defaulting to aarch64.)
+    pub fn emulated(&self) -> Option<Platform> {
+        match (self.os, self.arch) {
+            (Os::Windows, Arch::Aarch64) => Some(Self {
+                arch: Arch::X86_64,
+                emulation: false,
+                ..*self
+            }),
+            _ => None,
         }
     }
 
@@ -372,21 +554,30 @@ impl Platform {
     /// - "linux-x86_64-gnu"
     /// - "windows-x86_64-msvc"
     /// - "windows-x86_64-gnu"
+    ///
+    /// Identifiers are in preference order. When a compiler is set, its
+    /// variant comes first so it wins over the default heuristic. With
+    /// emulation enabled, the emulated platform's identifiers follow the
+    /// native ones (`windows-aarch64-*`, then `windows-x86_64-*`).
     pub fn possible_identifiers(&self) -> Vec<String> {
-        let base = format!("{}", self);
-        let mut identifiers = vec![base.clone()];
+        let base = format!("{}-{}", self.os.as_str(), self.arch.as_str());
+        let mut identifiers = Vec::new();
+
+        if let Some(compiler) = self.compiler {
+            identifiers.push(format!("{}-{}", base, compiler.as_str()));
+        }
+        identifiers.push(base.clone());
 
         // Add compiler variants
-        match self.os {
-            Os::Linux => {
-                identifiers.push(format!("{}-musl", base));
-                identifiers.push(format!("{}-gnu", base));
+        for compiler in Compiler::variants_for(self.os) {
+            let id = format!("{}-{}", base, compiler.as_str());
+            if !identifiers.contains(&id) {
+                identifiers.push(id);
             }
-            Os::Windows => {
-                identifiers.push(format!("{}-msvc", base));
-                identifiers.push(format!("{}-gnu", base));
-            }
-            _ => {}
+        }
+
+        if let Some(emulated) = self.emulated().filter(|_| self.emulation) {
+            identifiers.extend(emulated.possible_identifiers());
         }
 
         identifiers
@@ -429,6 +620,13 @@ impl BinarySelector {
     /// # Returns
     /// The best matching asset, or None if no suitable asset found
     pub fn select_for_platform(assets: &[BinaryAsset], platform: Platform) -> Option<BinaryAsset> {
+        Self::candidates_for_platform(assets, platform)
+            .into_iter()
+            .next()
+    }
+
+    /// All assets usable on a platform, best match first
+    pub fn candidates_for_platform(assets: &[BinaryAsset], platform: Platform) -> Vec<BinaryAsset> {
         let mut scored_assets: Vec<(usize, &BinaryAsset)> = assets
             .iter()
             .filter_map(|asset| {
@@ -437,10 +635,13 @@ impl BinarySelector {
             })
             .collect();
 
-        // Sort by score (highest first)
-        scored_assets.sort_by(|a, b| b.0.cmp(&a.0));
+        // Sort by score (highest first); the sort is stable so ties keep release order
+        scored_assets.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
 
-        scored_assets.first().map(|(_, asset)| (*asset).clone())
+        scored_assets
+            .into_iter()
+            .map(|(_, asset)| asset.clone())
+            .collect()
     }
 
     /// Score an asset filename based on how well it matches the platform
@@ -516,6 +717,11 @@ impl BinarySelector {
         if let Some(compiler) = parsed.compiler {
             let priority = compiler.priority(platform.os);
             score += (priority as usize) * 10;
+
+            // An explicitly preferred variant outranks the OS default order
+            if platform.compiler == Some(compiler) {
+                score += 40;
+            }
         }
 
         // File format preference
@@ -528,10 +734,7 @@ impl BinarySelector {
     fn should_exclude(filename: &str) -> bool {
         let excludes = [
             "source",
-            ".deb",
-            ".rpm",
             ".apk",
-            ".dmg",
             ".pkg",
             ".msi",
             ".sha256",
@@ -595,16 +798,261 @@ impl BinarySelector {
 
                 platforms.insert(platform_id, asset);
             }
+
+            // Also keep the best build of each libc/compiler variant so a
+            // preferred variant can be selected at install time
+            for &variant in Compiler::variants_for(platform.os) {
+                let platform_id = format!("{}-{}", platform, variant.as_str());
+                if platforms.contains_key(&platform_id) {
+                    continue;
+                }
+
+                let variant_assets: Vec<BinaryAsset> = assets
+                    .iter()
+                    .filter(|a| ParsedAsset::from_filename(&a.name).compiler == Some(variant))
+                    .cloned()
+                    .collect();
+
+                if let Some(asset) = Self::select_for_platform(&variant_assets, platform) {
+                    platforms.insert(platform_id, asset);
+                }
+            }
         }
 
         platforms
     }
+
+    /// Compare the per-platform assets of two releases
+    ///
+    /// Platforms are matched with [`Self::extract_platforms`]; assets whose
+    /// size is unchanged are left out. Changes are sorted by platform id.
+    pub fn diff_platforms(old: &[BinaryAsset], new: &[BinaryAsset]) -> Vec<AssetChange> {
+        let mut old = Self::extract_platforms(old);
+        let new = Self::extract_platforms(new);
+
+        let mut changes: Vec<AssetChange> = new
+            .into_iter()
+            .filter_map(|(platform, asset)| match old.remove(&platform) {
+                None => Some(AssetChange::Added { platform, asset }),
+                Some(previous) if previous.size != asset.size => Some(AssetChange::Resized {
+                    platform,
+                    old: previous,
+                    new: asset,
+                }),
+                Some(_) => None,
+            })
+            .collect();
+        changes.extend(
+            old.into_iter()
+                .map(|(platform, asset)| AssetChange::Removed { platform, asset }),
+        );
+
+        changes.sort_by(|a, b| a.platform().cmp(b.platform()));
+        changes
+    }
+}
+
+/// Difference in one platform's asset between two releases
+#[derive(Debug, Clone)]
+pub enum AssetChange {
+    /// Only the newer release has an asset for this platform
+    Added {
+        platform: String,
+        asset: BinaryAsset,
+    },
+    /// Only the older release has an asset for this platform
+    Removed {
+        platform: String,
+        asset: BinaryAsset,
+    },
+    /// Both releases have an asset, with different sizes
+    Resized {
+        platform: String,
+        old: BinaryAsset,
+        new: BinaryAsset,
+    },
+}
+
+impl AssetChange {
+    /// Platform id the change applies to
+    pub fn platform(&self) -> &str {
+        match self {
+            AssetChange::Added { platform, .. }
+            | AssetChange::Removed { platform, .. }
+            | AssetChange::Resized { platform, .. } => platform,
+        }
+    }
+}
+
+/// Asset name pattern from a manifest platform entry
+///
+/// Patterns are globs by default, or regexes when prefixed with `regex:`.
+/// `{version}` expands to the release tag without a leading `v`,
+/// `{tag}` expands to the raw release tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetPattern {
+    raw: String,
+}
+
+impl AssetPattern {
+    /// Prefix that marks a pattern as a regular expression
+    const REGEX_PREFIX: &'static str = "regex:";
+
+    /// Create a new asset pattern
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            raw: pattern.to_string(),
+        }
+    }
+
+    /// Build a glob matching the same asset in later releases
+    ///
+    /// The release version inside `name` becomes `{version}`, so
+    /// `tool-1.2.3-linux-gnu.tar.gz` picked from `v1.2.3` yields
+    /// `tool-{version}-linux-gnu.tar.gz`.
+    pub fn from_asset_name(name: &str, tag: &str) -> Self {
+        let version = tag.trim_start_matches('v');
+        let raw = if version.is_empty() {
+            glob::Pattern::escape(name)
+        } else {
+            name.split(version)
+                .map(glob::Pattern::escape)
+                .collect::<Vec<_>>()
+                .join("{version}")
+        };
+
+        Self { raw }
+    }
+
+    /// Check if this pattern is a regular expression
+    pub fn is_regex(&self) -> bool {
+        self.raw.starts_with(Self::REGEX_PREFIX)
+    }
+
+    /// Expand placeholders for a release tag and build a matcher
+    fn matcher(&self, tag: &str) -> anyhow::Result<AssetMatcher> {
+        let version = tag.trim_start_matches('v');
+
+        if self.is_regex() {
+            let body = self.raw[Self::REGEX_PREFIX.len()..]
+                .replace("{version}", &regex::escape(version))
+                .replace("{tag}", &regex::escape(tag));
+            regex::Regex::new(&body)
+                .map(AssetMatcher::Regex)
+                .map_err(|e| anyhow::anyhow!("Invalid asset regex '{}': {}", self.raw, e))
+        } else {
+            let body = self
+                .raw
+                .replace("{version}", &glob::Pattern::escape(version))
+                .replace("{tag}", &glob::Pattern::escape(tag));
+            glob::Pattern::new(&body)
+                .map(AssetMatcher::Glob)
+                .map_err(|e| anyhow::anyhow!("Invalid asset glob '{}': {}", self.raw, e))
+        }
+    }
+
+    /// Select the single asset matching this pattern for a release tag
+    ///
+    /// Returns an error if no asset or more than one asset matches.
+    pub fn select<'a>(
+        &self,
+        assets: &'a [BinaryAsset],
+        tag: &str,
+    ) -> anyhow::Result<&'a BinaryAsset> {
+        let matcher = self.matcher(tag)?;
+        let matches: Vec<&BinaryAsset> = assets
+            .iter()
+            .filter(|a| matcher.is_match(&a.name))
+            .collect();
+
+        match matches.len() {
+            1 => Ok(matches[0]),
+            0 => Err(crate::error::WengetError::NotFound(format!(
+                "No release asset matches pattern '{}' (tag {}). Available assets:\n  {}",
+                self.raw,
+                tag,
+                assets
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n  ")
+            ))
+            .into()),
+            n => anyhow::bail!(
+                "Pattern '{}' is ambiguous: {} assets match (tag {}):\n  {}",
+                self.raw,
+                n,
+                tag,
+                matches
+                    .iter()
+                    .map(|a| a.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n  ")
+            ),
+        }
+    }
+}
+
+/// Compiled asset pattern with placeholders expanded
+enum AssetMatcher {
+    Glob(glob::Pattern),
+    Regex(regex::Regex),
+}
+
+impl AssetMatcher {
+    fn is_match(&self, name: &str) -> bool {
+        match self {
+            AssetMatcher::Glob(pattern) => pattern.matches(name),
+            AssetMatcher::Regex(re) => re.is_match(name),
+        }
+    }
+}
+
+impl std::fmt::Display for AssetPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.raw)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_canonical_platform_id() {
+        let canonical = |id| canonical_platform_id(id);
+        assert_eq!(canonical("linux-amd64").as_deref(), Some("linux-x86_64"));
+        assert_eq!(canonical("linux64").as_deref(), Some("linux-x86_64"));
+        assert_eq!(
+            canonical("x86_64-unknown-linux-gnu").as_deref(),
+            Some("linux-x86_64-gnu")
+        );
+        assert_eq!(canonical("Darwin-ARM64").as_deref(), Some("macos-aarch64"));
+        assert_eq!(
+            canonical("x86_64-pc-windows-msvc").as_deref(),
+            Some("windows-x86_64-msvc")
+        );
+        assert_eq!(canonical("win32").as_deref(), Some("windows-i686"));
+
+        // Wenget's own identifiers are kept, unknown names are not guessed at
+        assert_eq!(
+            canonical("linux-x86_64-musl").as_deref(),
+            Some("linux-x86_64-musl")
+        );
+        assert_eq!(canonical("solaris-sparc"), None);
+        assert!(!is_canonical_id("linux-amd64"));
+        assert!(!is_canonical_id("linux-x86_64-gnu-extra"));
+
+        // Configured aliases extend the table
+        assert_eq!(canonical("test-penguin64"), None);
+        set_platform_aliases(vec![(
+            "test-penguin64".to_string(),
+            "linux-x86_64".to_string(),
+        )]);
+        assert_eq!(canonical("test-penguin64").as_deref(), Some("linux-x86_64"));
+        set_platform_aliases(Vec::new());
+    }
+
     #[test]
     fn test_current_platform() {
         let platform = Platform::current();
@@ -619,6 +1067,18 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_arch_parse() {
+        assert_eq!(Arch::parse("arm64"), Some(Arch::Aarch64));
+        assert_eq!(Arch::parse("AMD64"), Some(Arch::X86_64));
+        assert_eq!(Arch::parse("x86"), Some(Arch::I686));
+        assert_eq!(Arch::parse("armhf"), Some(Arch::Armv7));
+        assert_eq!(Arch::parse("sparc"), None);
+
+        let platform = Platform::new(Os::MacOS, Arch::parse("arm64").unwrap());
+        assert_eq!(platform.possible_identifiers()[0], "macos-aarch64");
+    }
+
     #[test]
     fn test_platform_string() {
         let platform = Platform::new(Os::Windows, Arch::X86_64);
@@ -663,10 +1123,90 @@ mod tests {
         assert!(selected.unwrap().name.contains("linux"));
     }
 
+    #[test]
+    fn test_compressed_single_binary_selection() {
+        let assets = vec![
+            BinaryAsset {
+                name: "tool-x86_64-linux.gz".to_string(),
+                url: "https://example.com/tool.gz".to_string(),
+                size: 1000000,
+            },
+            BinaryAsset {
+                name: "tool-aarch64-linux.gz".to_string(),
+                url: "https://example.com/tool-arm.gz".to_string(),
+                size: 1000000,
+            },
+        ];
+
+        let linux_platform = Platform::new(Os::Linux, Arch::X86_64);
+        let selected = BinarySelector::select_for_platform(&assets, linux_platform).unwrap();
+        assert_eq!(selected.name, "tool-x86_64-linux.gz");
+
+        // Real archives still win over bare compressed binaries
+        assert_eq!(
+            FileExtension::from_filename("tool.tar.gz"),
+            FileExtension::TarGz
+        );
+        assert!(FileExtension::Compressed.format_score() < FileExtension::TarGz.format_score());
+    }
+
+    #[test]
+    fn test_dmg_selection() {
+        let dmg = BinaryAsset {
+            name: "tool-macos-arm64.dmg".to_string(),
+            url: "https://example.com/tool.dmg".to_string(),
+            size: 1000000,
+        };
+        let tarball = BinaryAsset {
+            name: "tool-macos-arm64.tar.gz".to_string(),
+            url: "https://example.com/tool.tar.gz".to_string(),
+            size: 1000000,
+        };
+        let mac = Platform::new(Os::MacOS, Arch::Aarch64);
+
+        // A disk image is used when nothing else is published
+        let selected =
+            BinarySelector::select_for_platform(std::slice::from_ref(&dmg), mac).unwrap();
+        assert_eq!(selected.name, "tool-macos-arm64.dmg");
+
+        // but a plain archive is preferred
+        let selected = BinarySelector::select_for_platform(&[dmg, tarball], mac).unwrap();
+        assert_eq!(selected.name, "tool-macos-arm64.tar.gz");
+    }
+
+    #[test]
+    fn test_linux_package_selection() {
+        let deb = BinaryAsset {
+            name: "tool_1.2.0_amd64.deb".to_string(),
+            url: "https://example.com/tool.deb".to_string(),
+            size: 1000000,
+        };
+        let rpm = BinaryAsset {
+            name: "tool-1.2.0-1.aarch64.rpm".to_string(),
+            url: "https://example.com/tool.rpm".to_string(),
+            size: 1000000,
+        };
+        let tarball = BinaryAsset {
+            name: "tool-linux-x86_64.tar.gz".to_string(),
+            url: "https://example.com/tool.tar.gz".to_string(),
+            size: 1000000,
+        };
+        let linux = Platform::new(Os::Linux, Arch::X86_64);
+
+        // Packages are picked up when they are the only Linux build
+        let selected =
+            BinarySelector::select_for_platform(&[deb.clone(), rpm.clone()], linux).unwrap();
+        assert_eq!(selected.name, "tool_1.2.0_amd64.deb");
+
+        // but a plain archive is preferred
+        let selected = BinarySelector::select_for_platform(&[deb, rpm, tarball], linux).unwrap();
+        assert_eq!(selected.name, "tool-linux-x86_64.tar.gz");
+    }
+
     #[test]
     fn test_should_exclude() {
         assert!(BinarySelector::should_exclude("source.tar.gz"));
-        assert!(BinarySelector::should_exclude("app.deb"));
+        assert!(BinarySelector::should_exclude("app.msi"));
         assert!(BinarySelector::should_exclude("checksums.txt"));
         assert!(!BinarySelector::should_exclude("app-linux-x86_64.tar.gz"));
     }
@@ -953,10 +1493,237 @@ mod tests {
         assert_eq!(Os::FreeBSD.default_arch(), None);
     }
 
+    fn libc_variant_assets() -> Vec<BinaryAsset> {
+        ["gnu", "musl"]
+            .iter()
+            .map(|libc| BinaryAsset {
+                name: format!("app-x86_64-unknown-linux-{}.tar.gz", libc),
+                url: format!("https://example.com/{}.tar.gz", libc),
+                size: 1000,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_prefer_compiler_identifier_order() {
+        let platform = Platform::new(Os::Linux, Arch::X86_64);
+        assert_eq!(
+            platform.possible_identifiers(),
+            vec!["linux-x86_64", "linux-x86_64-musl", "linux-x86_64-gnu"]
+        );
+
+        let preferred = platform.prefer_compiler(Some(Compiler::Gnu));
+        assert_eq!(
+            preferred.possible_identifiers(),
+            vec!["linux-x86_64-gnu", "linux-x86_64", "linux-x86_64-musl"]
+        );
+    }
+
+    #[test]
+    fn test_windows_arm64_identifiers() {
+        let platform = Platform::new(Os::Windows, Arch::Aarch64);
+        assert_eq!(
+            platform.possible_identifiers(),
+            vec![
+                "windows-aarch64",
+                "windows-aarch64-msvc",
+                "windows-aarch64-gnu"
+            ]
+        );
+
+        // x86_64 builds are only a fallback, after every native identifier
+        assert_eq!(
+            platform.with_emulation(true).possible_identifiers(),
+            vec![
+                "windows-aarch64",
+                "windows-aarch64-msvc",
+                "windows-aarch64-gnu",
+                "windows-x86_64",
+                "windows-x86_64-msvc",
+                "windows-x86_64-gnu"
+            ]
+        );
+        let preferred = platform
+            .with_emulation(true)
+            .prefer_compiler(Some(Compiler::Gnu))
+            .possible_identifiers();
+        assert_eq!(preferred[0], "windows-aarch64-gnu");
+        assert_eq!(preferred[3], "windows-x86_64-gnu");
+
+        // Nothing to emulate elsewhere
+        let linux = Platform::new(Os::Linux, Arch::Aarch64).with_emulation(true);
+        assert_eq!(linux.possible_identifiers().len(), 3);
+        assert!(linux.emulated().is_none());
+    }
+
+    #[test]
+    fn test_windows_arm64_asset_selection() {
+        let assets: Vec<BinaryAsset> = [
+            "tool-x86_64-pc-windows-msvc.zip",
+            "tool-aarch64-pc-windows-msvc.zip",
+            "tool-x86_64-unknown-linux-musl.tar.gz",
+        ]
+        .iter()
+        .map(|name| BinaryAsset {
+            name: name.to_string(),
+            url: format!("https://example.com/{}", name),
+            size: 1000,
+        })
+        .collect();
+
+        let selected =
+            BinarySelector::select_for_platform(&assets, Platform::new(Os::Windows, Arch::Aarch64));
+        assert_eq!(selected.unwrap().name, "tool-aarch64-pc-windows-msvc.zip");
+
+        let platforms = BinarySelector::extract_platforms(&assets);
+        assert_eq!(
+            platforms["windows-aarch64-msvc"].name,
+            "tool-aarch64-pc-windows-msvc.zip"
+        );
+        assert_eq!(
+            platforms["windows-x86_64-msvc"].name,
+            "tool-x86_64-pc-windows-msvc.zip"
+        );
+    }
+
+    #[test]
+    fn test_extract_platforms_keeps_libc_variants() {
+        let platforms = BinarySelector::extract_platforms(&libc_variant_assets());
+
+        assert!(platforms["linux-x86_64-musl"].url.contains("musl"));
+        assert!(platforms["linux-x86_64-gnu"].url.contains("gnu"));
+    }
+
+    fn pattern_assets() -> Vec<BinaryAsset> {
+        [
+            "tool-1.2.3-x86_64-unknown-linux-musl.tar.gz",
+            "tool-1.2.3-x86_64-unknown-linux-gnu.tar.gz",
+            "tool-1.2.3-x86_64-pc-windows-msvc.zip",
+            "tool-1.2.3.sha256",
+        ]
+        .iter()
+        .map(|name| BinaryAsset {
+            name: name.to_string(),
+            url: format!("https://example.com/{}", name),
+            size: 1000,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_asset_pattern_glob_with_version() {
+        let assets = pattern_assets();
+        let pattern = AssetPattern::new("tool-{version}-x86_64-*-linux-musl.tar.gz");
+        let selected = pattern.select(&assets, "v1.2.3").unwrap();
+        assert_eq!(selected.name, "tool-1.2.3-x86_64-unknown-linux-musl.tar.gz");
+    }
+
+    #[test]
+    fn test_asset_pattern_regex_with_tag() {
+        let assets = pattern_assets();
+        let pattern = AssetPattern::new(r"regex:^tool-{version}-.*windows.*\.zip$");
+        assert!(pattern.is_regex());
+        let selected = pattern.select(&assets, "1.2.3").unwrap();
+        assert_eq!(selected.name, "tool-1.2.3-x86_64-pc-windows-msvc.zip");
+
+        // {tag} keeps the raw tag (including "v")
+        let pattern = AssetPattern::new("regex:^{tag}$");
+        assert!(pattern.select(&assets, "v1.2.3").is_err());
+    }
+
+    #[test]
+    fn test_asset_pattern_from_asset_name() {
+        let pattern =
+            AssetPattern::from_asset_name("tool-1.2.3-x86_64-unknown-linux-gnu.tar.gz", "v1.2.3");
+        assert_eq!(
+            pattern.to_string(),
+            "tool-{version}-x86_64-unknown-linux-gnu.tar.gz"
+        );
+        let assets = pattern_assets();
+        let selected = pattern.select(&assets, "v1.2.3").unwrap();
+        assert_eq!(selected.name, "tool-1.2.3-x86_64-unknown-linux-gnu.tar.gz");
+
+        // Names without the version are matched literally
+        let pattern = AssetPattern::from_asset_name("tool[linux].zip", "v2.0.0");
+        assert_eq!(pattern.to_string(), "tool[[]linux[]].zip");
+    }
+
+    #[test]
+    fn test_candidates_for_platform() {
+        let linux = Platform::new(Os::Linux, Arch::X86_64);
+        let candidates = BinarySelector::candidates_for_platform(&pattern_assets(), linux);
+        let names: Vec<&str> = candidates.iter().map(|a| a.name.as_str()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "tool-1.2.3-x86_64-unknown-linux-musl.tar.gz",
+                "tool-1.2.3-x86_64-unknown-linux-gnu.tar.gz",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_asset_pattern_no_match_or_ambiguous() {
+        let assets = pattern_assets();
+
+        let none = AssetPattern::new("tool-{version}-aarch64-*");
+        let err = none.select(&assets, "v1.2.3").unwrap_err().to_string();
+        assert!(err.contains("No release asset matches"));
+
+        let many = AssetPattern::new("tool-{version}-x86_64-*-linux-*");
+        let err = many.select(&assets, "v1.2.3").unwrap_err().to_string();
+        assert!(err.contains("ambiguous"));
+
+        // Version from a different release must not match
+        let stale = AssetPattern::new("tool-{version}-x86_64-pc-windows-msvc.zip");
+        assert!(stale.select(&assets, "v1.2.4").is_err());
+    }
+
     #[test]
     fn test_arch_resolve_x86_keyword() {
         assert_eq!(Arch::resolve_x86_keyword(Os::MacOS), Arch::X86_64);
         assert_eq!(Arch::resolve_x86_keyword(Os::Linux), Arch::I686);
         assert_eq!(Arch::resolve_x86_keyword(Os::Windows), Arch::I686);
     }
+
+    #[test]
+    fn test_diff_platforms() {
+        let asset = |name: &str, size| BinaryAsset {
+            name: name.to_string(),
+            url: format!("https://example.com/{}", name),
+            size,
+        };
+        let old = vec![
+            asset("tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz", 100),
+            asset("tool-1.0.0-x86_64-pc-windows-msvc.zip", 200),
+            asset("tool-1.0.0-i686-pc-windows-msvc.zip", 150),
+        ];
+        let new = vec![
+            asset("tool-1.1.0-x86_64-unknown-linux-gnu.tar.gz", 120),
+            asset("tool-1.1.0-x86_64-pc-windows-msvc.zip", 200),
+            asset("tool-1.1.0-aarch64-apple-darwin.tar.gz", 90),
+        ];
+
+        let changes = BinarySelector::diff_platforms(&old, &new);
+        let summary: Vec<String> = changes
+            .iter()
+            .map(|c| match c {
+                AssetChange::Added { platform, .. } => format!("+{}", platform),
+                AssetChange::Removed { platform, .. } => format!("-{}", platform),
+                AssetChange::Resized { platform, old, new } => {
+                    format!("~{} {}->{}", platform, old.size, new.size)
+                }
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            vec![
+                "~linux-x86_64-gnu 100->120",
+                "+macos-aarch64",
+                "-windows-i686-msvc",
+            ]
+        );
+    }
 }
@@ -0,0 +1,374 @@
+//! Scoop manifest import
+//!
+//! Scoop buckets keep one JSON manifest per app. This maps the fields Wenget
+//! understands (`url`, `hash`, `bin`, `architecture` and the descriptive
+//! fields) onto a [`Package`], so a scoop bucket can be used like any other
+//! bucket. Scoop only targets Windows, so every platform is a `windows-*` one.
+//!
+//! A bucket URL may point at a single scoop manifest, or at a scoop bucket's
+//! GitHub repository, whose `bucket/` directory is listed and imported.
+
+use super::manifest::{BinEntry, Package, PlatformBinary, SourceManifest};
+use crate::utils::concurrency::parallel_map;
+use crate::utils::HttpClient;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Scoop architecture keys and the Wenget platforms they map to
+const ARCHITECTURES: &[(&str, &str)] = &[
+    ("64bit", "windows-x86_64"),
+    ("32bit", "windows-i686"),
+    ("arm64", "windows-aarch64"),
+];
+
+/// Platform used for architecture-independent downloads
+const DEFAULT_PLATFORM: &str = "windows-x86_64";
+
+/// The subset of a scoop app manifest Wenget uses
+#[derive(Debug, Deserialize)]
+pub struct ScoopManifest {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    homepage: Option<String>,
+    #[serde(default)]
+    license: Option<Value>,
+    #[serde(flatten)]
+    download: ScoopDownload,
+    #[serde(default)]
+    architecture: HashMap<String, ScoopDownload>,
+}
+
+/// Download fields, found at the top level or per architecture
+#[derive(Debug, Default, Deserialize)]
+struct ScoopDownload {
+    #[serde(default)]
+    url: Option<Value>,
+    #[serde(default)]
+    hash: Option<Value>,
+    #[serde(default)]
+    bin: Option<Value>,
+}
+
+impl ScoopManifest {
+    /// Check whether a JSON document looks like a scoop app manifest
+    pub fn detect(value: &Value) -> bool {
+        value.get("version").is_some()
+            && (value.get("url").is_some() || value.get("architecture").is_some())
+    }
+
+    /// Convert to a Wenget package called `name`
+    ///
+    /// Returns `None` when the manifest has no download for any platform.
+    pub fn into_package(self, name: &str) -> Option<Package> {
+        let mut platforms = HashMap::new();
+
+        if let Some(binary) = self.download.to_binary(None) {
+            platforms.insert(DEFAULT_PLATFORM.to_string(), binary);
+        }
+        for (arch, platform) in ARCHITECTURES {
+            let binary = self
+                .architecture
+                .get(*arch)
+                .and_then(|d| d.to_binary(Some(&self.download)));
+            if let Some(binary) = binary {
+                platforms.insert(platform.to_string(), binary);
+            }
+        }
+
+        if platforms.is_empty() {
+            return None;
+        }
+
+        // Release downloads point at their GitHub repo; otherwise use the homepage
+        let repo = platforms
+            .values()
+            .find_map(|b| github_repo(&b.url))
+            .or_else(|| self.homepage.clone())
+            .unwrap_or_default();
+
+        Some(Package {
+            name: name.to_string(),
+            description: self.description.unwrap_or_else(|| name.to_string()),
+            repo,
+            homepage: self.homepage,
+            license: self.license.as_ref().and_then(license_name),
+            platforms,
+            requires: Vec::new(),
+            yanked: Vec::new(),
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            post_install_message: None,
+            exe_name: None,
+        })
+    }
+}
+
+impl ScoopDownload {
+    /// Build a platform binary, taking missing fields from `fallback`
+    fn to_binary(&self, fallback: Option<&ScoopDownload>) -> Option<PlatformBinary> {
+        let url = first_string(self.url.as_ref())?;
+        let hash = first_string(self.hash.as_ref());
+        let bin = self
+            .bin
+            .as_ref()
+            .or(fallback.and_then(|f| f.bin.as_ref()))
+            .map(bin_entries)
+            .unwrap_or_default();
+
+        Some(PlatformBinary {
+            url,
+            size: 0,
+            checksum: hash.and_then(|h| sha256_hash(&h)),
+            asset_pattern: None,
+            bin,
+        })
+    }
+}
+
+/// Parse a bucket document that is either a Wenget manifest or a single scoop
+/// app manifest (named after the file in `url`)
+pub fn parse_bucket_document(body: &str, url: &str) -> Result<SourceManifest> {
+    let value: Value = serde_json::from_str(body).context("Invalid JSON")?;
+
+    if !ScoopManifest::detect(&value) {
+        return serde_json::from_value(value).context("Not a Wenget or scoop manifest");
+    }
+
+    let name = app_name(url);
+    let manifest: ScoopManifest = serde_json::from_value(value)
+        .with_context(|| format!("Invalid scoop manifest for {}", name))?;
+    let package = manifest
+        .into_package(&name)
+        .with_context(|| format!("Scoop manifest for {} has no download URL", name))?;
+
+    Ok(SourceManifest {
+        packages: vec![package],
+        scripts: Vec::new(),
+    })
+}
+
+/// GitHub API URL listing the app manifests of a scoop bucket repository
+///
+/// Returns `None` unless `url` is a bare `https://github.com/<owner>/<repo>`.
+pub fn bucket_listing_url(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("https://github.com/")?
+        .trim_end_matches('/')
+        .trim_end_matches(".git");
+    let (owner, repo) = rest.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+
+    Some(format!(
+        "https://api.github.com/repos/{}/{}/contents/bucket",
+        owner, repo
+    ))
+}
+
+/// Entry of a GitHub directory listing
+#[derive(Debug, Deserialize)]
+struct ListingEntry {
+    name: String,
+    download_url: Option<String>,
+}
+
+/// Import every app manifest from a scoop bucket directory listing
+///
+/// Apps whose manifest can't be fetched or has no usable download are
+/// skipped with a warning rather than failing the whole bucket.
+pub fn import_bucket_listing(http: &HttpClient, listing: &str) -> Result<SourceManifest> {
+    let entries: Vec<ListingEntry> =
+        serde_json::from_str(listing).context("Invalid scoop bucket listing")?;
+    let apps: Vec<(String, String)> = entries
+        .into_iter()
+        .filter(|e| e.name.ends_with(".json"))
+        .filter_map(|e| Some((app_name(&e.name), e.download_url?)))
+        .collect();
+
+    let packages = parallel_map(&apps, |(name, url)| {
+        let result = http.get_text(url).and_then(|body| {
+            let manifest: ScoopManifest = serde_json::from_str(&body)?;
+            manifest
+                .into_package(name)
+                .context("no download URL for Windows")
+        });
+        match result {
+            Ok(package) => Some(package),
+            Err(e) => {
+                log::warn!("Skipping scoop app '{}': {:#}", name, e);
+                None
+            }
+        }
+    });
+
+    Ok(SourceManifest {
+        packages: packages.into_iter().flatten().collect(),
+        scripts: Vec::new(),
+    })
+}
+
+/// Name of a scoop app from its manifest file name (`.../ripgrep.json`)
+pub fn app_name(url: &str) -> String {
+    let file = url.rsplit('/').next().unwrap_or(url);
+    file.split(['?', '#'])
+        .next()
+        .unwrap_or(file)
+        .trim_end_matches(".json")
+        .to_string()
+}
+
+/// First string of a value that is a string or an array of strings
+///
+/// Multi-file scoop apps list several URLs; the first one is the main archive.
+fn first_string(value: Option<&Value>) -> Option<String> {
+    match value? {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(items) => items.first()?.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Convert a scoop `bin` value: `"a.exe"`, `["a.exe", "b.exe"]` or
+/// `[["a.exe", "alias", "--args"]]`
+fn bin_entries(value: &Value) -> Vec<BinEntry> {
+    let entry = |value: &Value| match value {
+        Value::String(path) => Some(BinEntry {
+            path: path.clone(),
+            name: None,
+        }),
+        Value::Array(parts) => Some(BinEntry {
+            path: parts.first()?.as_str()?.to_string(),
+            name: parts.get(1).and_then(Value::as_str).map(str::to_string),
+        }),
+        _ => None,
+    };
+
+    match value {
+        Value::Array(items) => items.iter().filter_map(entry).collect(),
+        other => entry(other).into_iter().collect(),
+    }
+}
+
+/// Extract a SHA-256 digest from a scoop hash (`"<hex>"` or `"sha256:<hex>"`)
+fn sha256_hash(hash: &str) -> Option<String> {
+    let hex = match hash.split_once(':') {
+        Some((algorithm, hex)) if algorithm.eq_ignore_ascii_case("sha256") => hex,
+        Some(_) => return None,
+        None => hash,
+    };
+
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+        .then(|| hex.to_ascii_lowercase())
+}
+
+/// License as a string or `{ "identifier": ... }`
+fn license_name(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Object(map) => map
+            .get("identifier")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// GitHub repository of a release download URL
+fn github_repo(url: &str) -> Option<String> {
+    let rest = url.strip_prefix("https://github.com/")?;
+    let mut parts = rest.splitn(4, '/');
+    let (owner, repo, kind) = (parts.next()?, parts.next()?, parts.next()?);
+
+    (kind == "releases").then(|| format!("https://github.com/{}/{}", owner, repo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RIPGREP: &str = r#"{
+        "version": "14.1.0",
+        "description": "Recursively search directories for a regex pattern",
+        "homepage": "https://github.com/BurntSushi/ripgrep",
+        "license": { "identifier": "MIT" },
+        "architecture": {
+            "64bit": {
+                "url": "https://github.com/BurntSushi/ripgrep/releases/download/14.1.0/ripgrep-14.1.0-x86_64-pc-windows-msvc.zip",
+                "hash": "D0F534024C42AFD6CB4D38907C25CD2B249B79BBE6CC1DBEE8E3E37C2B6E25A1",
+                "extract_dir": "ripgrep-14.1.0-x86_64-pc-windows-msvc"
+            },
+            "32bit": {
+                "url": "https://github.com/BurntSushi/ripgrep/releases/download/14.1.0/ripgrep-14.1.0-i686-pc-windows-msvc.zip",
+                "hash": "sha512:abcd"
+            }
+        },
+        "bin": "rg.exe"
+    }"#;
+
+    #[test]
+    fn test_scoop_architecture_mapping() {
+        let manifest =
+            parse_bucket_document(RIPGREP, "https://example.com/bucket/ripgrep.json").unwrap();
+        let pkg = &manifest.packages[0];
+
+        assert_eq!(pkg.name, "ripgrep");
+        assert_eq!(pkg.repo, "https://github.com/BurntSushi/ripgrep");
+        assert_eq!(pkg.license.as_deref(), Some("MIT"));
+        assert_eq!(pkg.platforms.len(), 2);
+
+        let x64 = &pkg.platforms["windows-x86_64"];
+        assert!(x64.url.ends_with("x86_64-pc-windows-msvc.zip"));
+        assert_eq!(
+            x64.checksum.as_deref(),
+            Some("d0f534024c42afd6cb4d38907c25cd2b249b79bbe6cc1dbee8e3e37c2b6e25a1")
+        );
+        assert_eq!(x64.bin[0].path, "rg.exe");
+
+        // Only SHA-256 hashes are kept
+        assert_eq!(pkg.platforms["windows-i686"].checksum, None);
+    }
+
+    #[test]
+    fn test_scoop_bin_aliases() {
+        let body = r#"{
+            "version": "1.0",
+            "url": "https://example.com/tool.zip#/tool.zip",
+            "bin": [["bin\\tool.exe", "tl", "--flag"], "helper.exe"]
+        }"#;
+        let manifest = parse_bucket_document(body, "https://example.com/tool.json").unwrap();
+        let binary = &manifest.packages[0].platforms[DEFAULT_PLATFORM];
+
+        assert_eq!(binary.bin[0].path, "bin\\tool.exe");
+        assert_eq!(binary.bin[0].name.as_deref(), Some("tl"));
+        assert_eq!(binary.bin[1].path, "helper.exe");
+        assert_eq!(binary.bin[1].name, None);
+        assert_eq!(manifest.packages[0].repo, "");
+    }
+
+    #[test]
+    fn test_bucket_listing_url() {
+        assert_eq!(
+            bucket_listing_url("https://github.com/ScoopInstaller/Main").as_deref(),
+            Some("https://api.github.com/repos/ScoopInstaller/Main/contents/bucket")
+        );
+        assert_eq!(
+            bucket_listing_url("https://github.com/o/r/raw/main/x.json"),
+            None
+        );
+        assert_eq!(
+            bucket_listing_url("https://example.com/manifest.json"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_wenget_manifest_passes_through() {
+        let body = r#"{ "packages": [] }"#;
+        let manifest = parse_bucket_document(body, "https://example.com/manifest.json").unwrap();
+        assert!(manifest.packages.is_empty());
+    }
+}
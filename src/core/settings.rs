@@ -0,0 +1,643 @@
+//! User settings for Wenget (settings.json)
+//!
+//! Settings hold user-level defaults that apply to every command, such as
+//! where package launchers are placed. Keys are exposed to the CLI through
+//! `wenget config get/set/unset` using their snake_case names.
+
+use super::manifest::ShimMode;
+use super::platform::{is_canonical_id, Compiler};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// User settings (settings.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct Settings {
+    /// Directory for package launchers instead of ~/.wenget/bin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub global_bin: Option<PathBuf>,
+
+    /// Preferred libc/compiler variant when a release ships several
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefer_variant: Option<Compiler>,
+
+    /// How many times a failed download is retried
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_retries: Option<u32>,
+
+    /// Parallel network requests (bucket fetches, update checks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+
+    /// Previous versions kept after an upgrade, unless set per package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_versions: Option<usize>,
+
+    /// Seconds to wait for a bucket before falling back to its cached copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bucket_timeout: Option<u64>,
+
+    /// Seconds the manifest cache is used before commands rebuild it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
+
+    /// Hosts downloads may be redirected to (`*.example.com` for
+    /// subdomains); any host when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_hosts: Option<Vec<String>>,
+
+    /// Bytes an archive may expand to in total before extraction is aborted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_extract_size: Option<u64>,
+
+    /// Bytes any single extracted file may take
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+
+    /// Install x86_64 builds on ARM64 Windows when a package has no native
+    /// build (they run under emulation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_emulation: Option<bool>,
+
+    /// Extra manifest platform names and the identifier each stands for
+    /// (`linux64` → `linux-x86_64`), on top of the built-in aliases
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_aliases: Option<BTreeMap<String, String>>,
+
+    /// How new launchers run their executable on Unix (symlink, copy or
+    /// wrapper)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shim_mode: Option<ShimMode>,
+}
+
+impl Settings {
+    /// Names of all configurable keys
+    pub const KEYS: &'static [&'static str] = &[
+        "global_bin",
+        "prefer_variant",
+        "download_retries",
+        "concurrency",
+        "keep_versions",
+        "bucket_timeout",
+        "cache_ttl",
+        "redirect_hosts",
+        "max_extract_size",
+        "max_file_size",
+        "allow_emulation",
+        "platform_aliases",
+        "shim_mode",
+    ];
+
+    /// Create default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load settings from file with automatic repair on parse errors
+    pub fn load(path: &Path) -> Result<Self> {
+        use super::repair::{
+            create_backup, print_repair_warning, try_parse_json, RepairAction, RepairSeverity,
+        };
+
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings: {}", path.display()))?;
+
+        match try_parse_json::<Self>(&content, path) {
+            Ok(settings) => Ok(settings),
+            Err(parse_error) => {
+                log::warn!("Failed to parse settings.json: {}", parse_error);
+
+                let backup_path = create_backup(path).ok();
+
+                let new_settings = Self::new();
+                new_settings.save(path)?;
+
+                let action = RepairAction::ResetToEmpty { backup_path };
+                print_repair_warning(
+                    "settings.json",
+                    &action,
+                    RepairSeverity::Warning,
+                    Some("Your settings were reset to defaults. Re-apply them with 'wenget config set'."),
+                );
+
+                Ok(new_settings)
+            }
+        }
+    }
+
+    /// Save settings to file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize settings")?;
+
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write settings: {}", path.display()))
+    }
+
+    /// Get a setting value as a display string (None if unset)
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        match key {
+            "global_bin" => Ok(self.global_bin.as_ref().map(|p| p.display().to_string())),
+            "prefer_variant" => Ok(self.prefer_variant.map(|c| c.as_str().to_string())),
+            "download_retries" => Ok(self.download_retries.map(|n| n.to_string())),
+            "concurrency" => Ok(self.concurrency.map(|n| n.to_string())),
+            "keep_versions" => Ok(self.keep_versions.map(|n| n.to_string())),
+            "bucket_timeout" => Ok(self.bucket_timeout.map(|n| n.to_string())),
+            "cache_ttl" => Ok(self.cache_ttl.map(format_duration)),
+            "redirect_hosts" => Ok(self.redirect_hosts.as_ref().map(|hosts| hosts.join(","))),
+            "max_extract_size" => Ok(self.max_extract_size.map(format_size)),
+            "max_file_size" => Ok(self.max_file_size.map(format_size)),
+            "allow_emulation" => Ok(self.allow_emulation.map(|b| b.to_string())),
+            "platform_aliases" => Ok(self.platform_aliases.as_ref().map(|aliases| {
+                aliases
+                    .iter()
+                    .map(|(alias, id)| format!("{}={}", alias, id))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })),
+            "shim_mode" => Ok(self.shim_mode.map(|m| m.as_str().to_string())),
+            _ => anyhow::bail!(unknown_key(key)),
+        }
+    }
+
+    /// Set a setting from its string representation
+    pub fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "global_bin" => self.global_bin = Some(PathBuf::from(value)),
+            "prefer_variant" => {
+                let compiler = Compiler::parse(value).with_context(|| {
+                    format!(
+                        "Invalid variant '{}'. Expected one of: gnu, musl, msvc",
+                        value
+                    )
+                })?;
+                self.prefer_variant = Some(compiler);
+            }
+            "download_retries" => {
+                let retries = value.parse().with_context(|| {
+                    format!("Invalid retry count '{}'. Expected a number", value)
+                })?;
+                self.download_retries = Some(retries);
+            }
+            "concurrency" => {
+                let concurrency = value.parse().ok().filter(|n| *n > 0).with_context(|| {
+                    format!(
+                        "Invalid concurrency '{}'. Expected a number of at least 1",
+                        value
+                    )
+                })?;
+                self.concurrency = Some(concurrency);
+            }
+            "keep_versions" => {
+                let keep = value.parse().with_context(|| {
+                    format!("Invalid version count '{}'. Expected a number", value)
+                })?;
+                self.keep_versions = Some(keep);
+            }
+            "bucket_timeout" => {
+                let timeout = value.parse().ok().filter(|n| *n > 0).with_context(|| {
+                    format!(
+                        "Invalid timeout '{}'. Expected a number of seconds of at least 1",
+                        value
+                    )
+                })?;
+                self.bucket_timeout = Some(timeout);
+            }
+            "cache_ttl" => {
+                let ttl = parse_duration(value).filter(|n| *n > 0).with_context(|| {
+                    format!(
+                        "Invalid duration '{}'. Expected seconds or a number with s, m, h or d (e.g. 6h)",
+                        value
+                    )
+                })?;
+                self.cache_ttl = Some(ttl);
+            }
+            "redirect_hosts" => {
+                let hosts: Vec<String> = value
+                    .split(',')
+                    .map(|h| h.trim().to_lowercase())
+                    .filter(|h| !h.is_empty())
+                    .collect();
+                if hosts.is_empty() || hosts.iter().any(|h| h.contains(['/', ':', ' '])) {
+                    anyhow::bail!(
+                        "Invalid host list '{}'. Expected comma-separated host names (e.g. github.com,*.githubusercontent.com)",
+                        value
+                    );
+                }
+                self.redirect_hosts = Some(hosts);
+            }
+            "max_extract_size" | "max_file_size" => {
+                let size = parse_size(value).filter(|n| *n > 0).with_context(|| {
+                    format!(
+                        "Invalid size '{}'. Expected bytes or a number with K, M or G (e.g. 2G)",
+                        value
+                    )
+                })?;
+                if key == "max_extract_size" {
+                    self.max_extract_size = Some(size);
+                } else {
+                    self.max_file_size = Some(size);
+                }
+            }
+            "allow_emulation" => {
+                let allow = parse_bool(value).with_context(|| {
+                    format!("Invalid value '{}'. Expected true or false", value)
+                })?;
+                self.allow_emulation = Some(allow);
+            }
+            "platform_aliases" => {
+                let mut aliases = BTreeMap::new();
+                for entry in value.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                    let (alias, id) = entry
+                        .split_once('=')
+                        .map(|(alias, id)| (alias.trim().to_lowercase(), id.trim()))
+                        .filter(|(alias, id)| !alias.is_empty() && is_canonical_id(id))
+                        .with_context(|| {
+                            format!(
+                                "Invalid alias '{}'. Expected <name>=<platform>, e.g. linux64=linux-x86_64",
+                                entry
+                            )
+                        })?;
+                    aliases.insert(alias, id.to_string());
+                }
+                if aliases.is_empty() {
+                    anyhow::bail!(
+                        "Invalid alias list '{}'. Expected comma-separated <name>=<platform> pairs",
+                        value
+                    );
+                }
+                self.platform_aliases = Some(aliases);
+            }
+            "shim_mode" => {
+                let mode = ShimMode::parse(value).with_context(|| {
+                    format!(
+                        "Invalid shim mode '{}'. Expected one of: symlink, copy, wrapper",
+                        value
+                    )
+                })?;
+                self.shim_mode = Some(mode);
+            }
+            _ => anyhow::bail!(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    /// Reset a setting to its default
+    pub fn unset(&mut self, key: &str) -> Result<()> {
+        match key {
+            "global_bin" => self.global_bin = None,
+            "prefer_variant" => self.prefer_variant = None,
+            "download_retries" => self.download_retries = None,
+            "concurrency" => self.concurrency = None,
+            "keep_versions" => self.keep_versions = None,
+            "bucket_timeout" => self.bucket_timeout = None,
+            "cache_ttl" => self.cache_ttl = None,
+            "redirect_hosts" => self.redirect_hosts = None,
+            "max_extract_size" => self.max_extract_size = None,
+            "max_file_size" => self.max_file_size = None,
+            "allow_emulation" => self.allow_emulation = None,
+            "platform_aliases" => self.platform_aliases = None,
+            "shim_mode" => self.shim_mode = None,
+            _ => anyhow::bail!(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+/// Parse a duration such as `90`, `30m`, `6h` or `1d` into seconds
+pub fn parse_duration(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Format seconds in the largest unit that divides them evenly (`21600` → `6h`)
+pub fn format_duration(seconds: u64) -> String {
+    [(24 * 60 * 60, "d"), (60 * 60, "h"), (60, "m")]
+        .iter()
+        .find(|(unit, _)| seconds > 0 && seconds.is_multiple_of(*unit))
+        .map(|(unit, suffix)| format!("{}{}", seconds / unit, suffix))
+        .unwrap_or_else(|| format!("{}s", seconds))
+}
+
+/// Parse a size such as `1048576`, `512K`, `500M` or `2G` into bytes
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, unit) = match value.find(|c: char| !c.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, ""),
+    };
+
+    let multiplier: u64 = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" => 1024,
+        "M" | "MB" => 1024 * 1024,
+        "G" | "GB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    number.parse::<u64>().ok()?.checked_mul(multiplier)
+}
+
+/// Format bytes in the largest unit that divides them evenly (`2147483648` → `2G`)
+pub fn format_size(bytes: u64) -> String {
+    [(1024 * 1024 * 1024, "G"), (1024 * 1024, "M"), (1024, "K")]
+        .iter()
+        .find(|(unit, _)| bytes > 0 && bytes.is_multiple_of(*unit))
+        .map(|(unit, suffix)| format!("{}{}", bytes / unit, suffix))
+        .unwrap_or_else(|| bytes.to_string())
+}
+
+/// Parse a yes/no setting (`true`/`false`, `yes`/`no`, `on`/`off`, `1`/`0`)
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn unknown_key(key: &str) -> String {
+    format!(
+        "Unknown setting '{}'. Available settings: {}",
+        key,
+        Settings::KEYS.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_settings_get_set_unset() {
+        let mut settings = Settings::new();
+        assert_eq!(settings.get("global_bin").unwrap(), None);
+
+        settings.set("global_bin", "/tmp/bin").unwrap();
+        assert_eq!(settings.global_bin, Some(PathBuf::from("/tmp/bin")));
+        assert_eq!(
+            settings.get("global_bin").unwrap(),
+            Some("/tmp/bin".to_string())
+        );
+
+        settings.unset("global_bin").unwrap();
+        assert_eq!(settings.global_bin, None);
+
+        assert!(settings.set("no_such_key", "x").is_err());
+    }
+
+    #[test]
+    fn test_settings_prefer_variant() {
+        let mut settings = Settings::new();
+
+        settings.set("prefer_variant", "gnu").unwrap();
+        assert_eq!(settings.prefer_variant, Some(Compiler::Gnu));
+        assert_eq!(
+            settings.get("prefer_variant").unwrap(),
+            Some("gnu".to_string())
+        );
+
+        assert!(settings.set("prefer_variant", "bionic").is_err());
+        assert_eq!(settings.prefer_variant, Some(Compiler::Gnu));
+    }
+
+    #[test]
+    fn test_settings_download_retries() {
+        let mut settings = Settings::new();
+
+        settings.set("download_retries", "5").unwrap();
+        assert_eq!(settings.download_retries, Some(5));
+        assert_eq!(
+            settings.get("download_retries").unwrap(),
+            Some("5".to_string())
+        );
+
+        assert!(settings.set("download_retries", "-1").is_err());
+        assert!(settings.set("download_retries", "many").is_err());
+        assert_eq!(settings.download_retries, Some(5));
+    }
+
+    #[test]
+    fn test_settings_concurrency() {
+        let mut settings = Settings::new();
+
+        settings.set("concurrency", "8").unwrap();
+        assert_eq!(settings.concurrency, Some(8));
+
+        assert!(settings.set("concurrency", "0").is_err());
+        assert_eq!(settings.concurrency, Some(8));
+    }
+
+    #[test]
+    fn test_settings_bucket_timeout() {
+        let mut settings = Settings::new();
+
+        settings.set("bucket_timeout", "5").unwrap();
+        assert_eq!(settings.bucket_timeout, Some(5));
+        assert_eq!(
+            settings.get("bucket_timeout").unwrap(),
+            Some("5".to_string())
+        );
+
+        assert!(settings.set("bucket_timeout", "0").is_err());
+        assert!(settings.set("bucket_timeout", "soon").is_err());
+        assert_eq!(settings.bucket_timeout, Some(5));
+    }
+
+    #[test]
+    fn test_settings_cache_ttl() {
+        let mut settings = Settings::new();
+
+        settings.set("cache_ttl", "6h").unwrap();
+        assert_eq!(settings.cache_ttl, Some(6 * 60 * 60));
+        assert_eq!(settings.get("cache_ttl").unwrap(), Some("6h".to_string()));
+
+        settings.set("cache_ttl", "90").unwrap();
+        assert_eq!(settings.get("cache_ttl").unwrap(), Some("90s".to_string()));
+
+        assert!(settings.set("cache_ttl", "0").is_err());
+        assert!(settings.set("cache_ttl", "6w").is_err());
+        assert!(settings.set("cache_ttl", "h").is_err());
+        assert_eq!(settings.cache_ttl, Some(90));
+    }
+
+    #[test]
+    fn test_settings_redirect_hosts() {
+        let mut settings = Settings::new();
+
+        settings
+            .set("redirect_hosts", "GitHub.com, *.githubusercontent.com")
+            .unwrap();
+        assert_eq!(
+            settings.redirect_hosts,
+            Some(vec![
+                "github.com".to_string(),
+                "*.githubusercontent.com".to_string()
+            ])
+        );
+        assert_eq!(
+            settings.get("redirect_hosts").unwrap(),
+            Some("github.com,*.githubusercontent.com".to_string())
+        );
+
+        assert!(settings.set("redirect_hosts", " , ").is_err());
+        assert!(settings
+            .set("redirect_hosts", "https://github.com")
+            .is_err());
+
+        settings.unset("redirect_hosts").unwrap();
+        assert_eq!(settings.redirect_hosts, None);
+    }
+
+    #[test]
+    fn test_settings_extract_limits() {
+        let mut settings = Settings::new();
+
+        settings.set("max_extract_size", "4G").unwrap();
+        assert_eq!(settings.max_extract_size, Some(4 * 1024 * 1024 * 1024));
+        assert_eq!(
+            settings.get("max_extract_size").unwrap(),
+            Some("4G".to_string())
+        );
+
+        settings.set("max_file_size", "1536m").unwrap();
+        assert_eq!(settings.max_file_size, Some(1536 * 1024 * 1024));
+        assert_eq!(
+            settings.get("max_file_size").unwrap(),
+            Some("1536M".to_string())
+        );
+
+        assert!(settings.set("max_extract_size", "0").is_err());
+        assert!(settings.set("max_file_size", "2T").is_err());
+        assert_eq!(settings.max_file_size, Some(1536 * 1024 * 1024));
+
+        settings.unset("max_extract_size").unwrap();
+        assert_eq!(settings.max_extract_size, None);
+    }
+
+    #[test]
+    fn test_settings_allow_emulation() {
+        let mut settings = Settings::new();
+        assert_eq!(settings.get("allow_emulation").unwrap(), None);
+
+        settings.set("allow_emulation", "yes").unwrap();
+        assert_eq!(settings.allow_emulation, Some(true));
+        assert_eq!(
+            settings.get("allow_emulation").unwrap(),
+            Some("true".to_string())
+        );
+
+        settings.set("allow_emulation", "OFF").unwrap();
+        assert_eq!(settings.allow_emulation, Some(false));
+        assert!(settings.set("allow_emulation", "maybe").is_err());
+        assert_eq!(settings.allow_emulation, Some(false));
+
+        settings.unset("allow_emulation").unwrap();
+        assert_eq!(settings.allow_emulation, None);
+    }
+
+    #[test]
+    fn test_settings_platform_aliases() {
+        let mut settings = Settings::new();
+
+        settings
+            .set(
+                "platform_aliases",
+                "Lin64=linux-x86_64, mac-m1=macos-aarch64",
+            )
+            .unwrap();
+        assert_eq!(
+            settings.platform_aliases,
+            Some(BTreeMap::from([
+                ("lin64".to_string(), "linux-x86_64".to_string()),
+                ("mac-m1".to_string(), "macos-aarch64".to_string()),
+            ]))
+        );
+        assert_eq!(
+            settings.get("platform_aliases").unwrap(),
+            Some("lin64=linux-x86_64,mac-m1=macos-aarch64".to_string())
+        );
+
+        // The target must be one of wenget's identifiers
+        assert!(settings
+            .set("platform_aliases", "lin64=linux-amd64")
+            .is_err());
+        assert!(settings.set("platform_aliases", "lin64").is_err());
+        assert!(settings.set("platform_aliases", " , ").is_err());
+
+        settings.unset("platform_aliases").unwrap();
+        assert_eq!(settings.platform_aliases, None);
+    }
+
+    #[test]
+    fn test_settings_shim_mode() {
+        let mut settings = Settings::new();
+
+        settings.set("shim_mode", "Copy").unwrap();
+        assert_eq!(settings.shim_mode, Some(ShimMode::Copy));
+        assert_eq!(settings.get("shim_mode").unwrap(), Some("copy".to_string()));
+
+        assert!(settings.set("shim_mode", "hardlink").is_err());
+        assert_eq!(settings.shim_mode, Some(ShimMode::Copy));
+
+        settings.unset("shim_mode").unwrap();
+        assert_eq!(settings.shim_mode, None);
+    }
+
+    #[test]
+    fn test_parse_and_format_size() {
+        assert_eq!(parse_size("1000"), Some(1000));
+        assert_eq!(parse_size("512K"), Some(512 * 1024));
+        assert_eq!(parse_size("2gb"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1.5G"), None);
+
+        assert_eq!(format_size(2 * 1024 * 1024 * 1024), "2G");
+        assert_eq!(format_size(1536 * 1024), "1536K");
+        assert_eq!(format_size(1000), "1000");
+    }
+
+    #[test]
+    fn test_parse_and_format_duration() {
+        assert_eq!(parse_duration("45"), Some(45));
+        assert_eq!(parse_duration("30m"), Some(1800));
+        assert_eq!(parse_duration("1d"), Some(86400));
+        assert_eq!(parse_duration("1.5h"), None);
+
+        assert_eq!(format_duration(86400), "1d");
+        assert_eq!(format_duration(5400), "90m");
+        assert_eq!(format_duration(61), "61s");
+        assert_eq!(format_duration(0), "0s");
+    }
+
+    #[test]
+    fn test_settings_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("settings.json");
+
+        // Missing file yields defaults
+        assert_eq!(Settings::load(&path).unwrap(), Settings::new());
+
+        let mut settings = Settings::new();
+        settings.set("global_bin", "/usr/local/bin").unwrap();
+        settings.save(&path).unwrap();
+
+        assert_eq!(Settings::load(&path).unwrap(), settings);
+    }
+}
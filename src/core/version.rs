@@ -0,0 +1,369 @@
+//! Version parsing, ordering and constraints
+//!
+//! Release tags are loosely semver: an optional `v` prefix, dot-separated
+//! numbers and an optional `-prerelease`/`+build` suffix. Constraints cover
+//! the common pinning forms: an exact version, `x`/`*` wildcards (`13.x`),
+//! caret (`^1.2`) and tilde (`~1.2.3`) ranges, plus free-form tag globs
+//! (`*-beta`).
+
+use std::cmp::Ordering;
+use std::fmt;
+
+/// A parsed release version
+///
+/// Missing components compare as 0, so `1.2` equals `1.2.0`.
+#[derive(Debug, Clone)]
+pub struct Version {
+    /// Numeric components (`1.2.3` → `[1, 2, 3]`)
+    parts: Vec<u64>,
+    /// Prerelease suffix, if any (`1.0.0-rc1` → `rc1`)
+    pre: Option<String>,
+}
+
+impl Version {
+    /// Parse a version or release tag, ignoring a leading `v` and any build
+    /// metadata; returns `None` if it is not numeric
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches('v');
+        let s = s.split('+').next().unwrap_or(s);
+        let (numbers, pre) = match s.split_once('-') {
+            Some((numbers, pre)) => (numbers, Some(pre.to_string())),
+            None => (s, None),
+        };
+
+        let parts = numbers
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        Some(Self { parts, pre })
+    }
+
+    /// Numeric component at `index`, missing components counting as 0
+    fn part(&self, index: usize) -> u64 {
+        self.parts.get(index).copied().unwrap_or(0)
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Version {}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.parts.len().max(other.parts.len());
+        (0..len)
+            .map(|i| self.part(i).cmp(&other.part(i)))
+            .find(|o| o.is_ne())
+            .unwrap_or_else(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self.parts.iter().map(u64::to_string).collect();
+        write!(f, "{}", parts.join("."))?;
+        if let Some(ref pre) = self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether replacing `installed` with `requested` goes back to an older
+/// version
+///
+/// Versions that don't parse (`unknown`, `nightly`) are never considered a
+/// downgrade, since their order can't be told.
+pub fn is_downgrade(installed: &str, requested: &str) -> bool {
+    match (Version::parse(installed), Version::parse(requested)) {
+        (Some(installed), Some(requested)) => requested < installed,
+        _ => false,
+    }
+}
+
+/// A constraint on the versions a package may be upgraded to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionConstraint {
+    /// Exactly this version (`13.0.5`)
+    Exact(String),
+    /// Any version starting with these components (`13.x`, `13.0.*`)
+    Wildcard(Vec<u64>),
+    /// Compatible versions: same leftmost non-zero component (`^1.2`)
+    Caret(Version),
+    /// Patch-level changes: same major and minor when given (`~1.2.3`)
+    Tilde(Version),
+    /// Any tag matching a glob (`*-beta`, `2024.*-nightly`), with or without
+    /// its `v` prefix
+    Glob(String),
+}
+
+impl VersionConstraint {
+    /// Parse a constraint, returning `None` if it is not understood
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        if let Some(rest) = s.strip_prefix('^') {
+            return Version::parse(rest).map(Self::Caret);
+        }
+        if let Some(rest) = s.strip_prefix('~') {
+            return Version::parse(rest).map(Self::Tilde);
+        }
+
+        let numbers = s.trim_start_matches('v');
+        let parts: Vec<&str> = numbers.split('.').collect();
+        if let Some(wildcard) = parts.iter().position(|p| matches!(*p, "x" | "X" | "*")) {
+            // Only trailing wildcards make sense (`13.x`, not `x.2`)
+            let prefix = parts[wildcard..]
+                .iter()
+                .all(|p| matches!(*p, "x" | "X" | "*"))
+                .then(|| {
+                    parts[..wildcard]
+                        .iter()
+                        .map(|p| p.parse().ok())
+                        .collect::<Option<Vec<u64>>>()
+                })
+                .flatten();
+            if let Some(prefix) = prefix {
+                return Some(Self::Wildcard(prefix));
+            }
+        }
+
+        // Anything else with glob characters is matched against the tag text
+        if s.contains(['*', '?']) {
+            return glob::Pattern::new(s)
+                .ok()
+                .map(|_| Self::Glob(s.to_string()));
+        }
+
+        Version::parse(s).map(|_| Self::Exact(numbers.to_string()))
+    }
+
+    /// Whether this is a tag glob, which may also select prereleases
+    pub fn is_glob(&self) -> bool {
+        matches!(self, Self::Glob(_))
+    }
+
+    /// The exact version this constraint locks to, if any
+    pub fn exact(&self) -> Option<&str> {
+        match self {
+            Self::Exact(version) => Some(version),
+            _ => None,
+        }
+    }
+
+    /// Whether a version or release tag satisfies the constraint
+    pub fn matches(&self, version: &str) -> bool {
+        match self {
+            Self::Exact(exact) => version.trim_start_matches('v') == exact,
+            Self::Glob(glob) => glob::Pattern::new(glob).is_ok_and(|pattern| {
+                pattern.matches(version) || pattern.matches(version.trim_start_matches('v'))
+            }),
+            _ => Version::parse(version).is_some_and(|v| self.matches_range(&v)),
+        }
+    }
+
+    fn matches_range(&self, version: &Version) -> bool {
+        match self {
+            Self::Exact(exact) => version.to_string() == *exact,
+            Self::Glob(_) => self.matches(&version.to_string()),
+            Self::Wildcard(prefix) => prefix
+                .iter()
+                .enumerate()
+                .all(|(i, part)| version.part(i) == *part),
+            Self::Caret(base) => {
+                // The leftmost non-zero component must stay the same
+                let fixed = base
+                    .parts
+                    .iter()
+                    .position(|p| *p != 0)
+                    .unwrap_or(base.parts.len().saturating_sub(1));
+                version >= base && (0..=fixed).all(|i| version.part(i) == base.part(i))
+            }
+            Self::Tilde(base) => {
+                // `~1` allows minor changes, `~1.2` and `~1.2.3` only patches
+                let fixed = base.parts.len().clamp(1, 2);
+                version >= base && (0..fixed).all(|i| version.part(i) == base.part(i))
+            }
+        }
+    }
+}
+
+impl fmt::Display for VersionConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exact(version) => write!(f, "{}", version),
+            Self::Wildcard(prefix) if prefix.is_empty() => write!(f, "*"),
+            Self::Wildcard(prefix) => {
+                let parts: Vec<String> = prefix.iter().map(u64::to_string).collect();
+                write!(f, "{}.x", parts.join("."))
+            }
+            Self::Caret(base) => write!(f, "^{}", base),
+            Self::Tilde(base) => write!(f, "~{}", base),
+            Self::Glob(glob) => write!(f, "{}", glob),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint(s: &str) -> VersionConstraint {
+        VersionConstraint::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        let v = |s| Version::parse(s).unwrap();
+        assert!(v("13.0.10") > v("13.0.9"));
+        assert!(v("v2.0") > v("1.99.99"));
+        assert_eq!(v("1.2"), v("1.2.0"));
+        assert!(v("1.0.0-rc1") < v("1.0.0"));
+        assert!(v("1.0.0-beta") < v("1.0.0-rc1"));
+        assert_eq!(v("v1.2.3+build5").to_string(), "1.2.3");
+        assert!(Version::parse("nightly").is_none());
+    }
+
+    #[test]
+    fn test_is_downgrade() {
+        assert!(is_downgrade("13.0.0", "12.0.0"));
+        assert!(is_downgrade("v13.0.0", "12.1"));
+        assert!(is_downgrade("1.0.0", "1.0.0-rc1"));
+        assert!(!is_downgrade("12.0.0", "13.0.0"));
+        assert!(!is_downgrade("13.0.0", "v13.0"));
+        assert!(!is_downgrade("1.0.0-rc1", "1.0.0"));
+        assert!(!is_downgrade("unknown", "12.0.0"));
+        assert!(!is_downgrade("13.0.0", "unknown"));
+    }
+
+    #[test]
+    fn test_parse_constraints() {
+        assert_eq!(
+            constraint("13.0.5"),
+            VersionConstraint::Exact("13.0.5".into())
+        );
+        assert_eq!(
+            constraint("v13.0.5"),
+            VersionConstraint::Exact("13.0.5".into())
+        );
+        assert_eq!(constraint("13.x"), VersionConstraint::Wildcard(vec![13]));
+        assert_eq!(
+            constraint("13.0.*"),
+            VersionConstraint::Wildcard(vec![13, 0])
+        );
+        assert_eq!(constraint("*"), VersionConstraint::Wildcard(vec![]));
+        assert!(matches!(constraint("^1.2"), VersionConstraint::Caret(_)));
+        assert!(matches!(constraint("~1.2.3"), VersionConstraint::Tilde(_)));
+        assert_eq!(constraint("13.0.5").exact(), Some("13.0.5"));
+        assert_eq!(constraint("13.x").exact(), None);
+        assert_eq!(constraint("13.0.*").to_string(), "13.0.x");
+        assert_eq!(constraint("^1.2").to_string(), "^1.2");
+
+        assert_eq!(
+            constraint("*-beta"),
+            VersionConstraint::Glob("*-beta".into())
+        );
+        assert_eq!(constraint("1.*.3").to_string(), "1.*.3");
+        assert!(constraint("*-beta").is_glob());
+        assert!(!constraint("13.*").is_glob());
+
+        assert!(VersionConstraint::parse("x.2").is_none());
+        assert!(VersionConstraint::parse("[*").is_none());
+        assert!(VersionConstraint::parse("^latest").is_none());
+        assert!(VersionConstraint::parse("nightly").is_none());
+    }
+
+    #[test]
+    fn test_wildcard_matches() {
+        let c = constraint("13.x");
+        assert!(c.matches("13.0.0"));
+        assert!(c.matches("v13.4.1"));
+        assert!(!c.matches("14.0.0"));
+        assert!(!c.matches("1.3.0"));
+
+        let c = constraint("13.0.*");
+        assert!(c.matches("13.0.7"));
+        assert!(!c.matches("13.1.0"));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        let c = constraint("*-beta");
+        assert!(c.matches("v2.0.0-beta"));
+        assert!(c.matches("1.4-beta"));
+        assert!(!c.matches("v2.0.0-beta.2"));
+        assert!(!c.matches("v2.0.0"));
+
+        let c = constraint("v1.*-rc?");
+        assert!(c.matches("v1.2.0-rc1"));
+        assert!(!c.matches("v2.0.0-rc1"));
+    }
+
+    #[test]
+    fn test_exact_matches() {
+        let c = constraint("13.0.5");
+        assert!(c.matches("13.0.5"));
+        assert!(c.matches("v13.0.5"));
+        assert!(!c.matches("13.0.6"));
+    }
+
+    #[test]
+    fn test_caret_matches() {
+        let c = constraint("^1.2");
+        assert!(c.matches("1.2.0"));
+        assert!(c.matches("1.9.3"));
+        assert!(!c.matches("1.1.9"));
+        assert!(!c.matches("2.0.0"));
+
+        let c = constraint("^0.2.3");
+        assert!(c.matches("0.2.9"));
+        assert!(!c.matches("0.3.0"));
+        assert!(!c.matches("0.2.2"));
+    }
+
+    #[test]
+    fn test_tilde_matches() {
+        let c = constraint("~1.2.3");
+        assert!(c.matches("1.2.3"));
+        assert!(c.matches("1.2.10"));
+        assert!(!c.matches("1.3.0"));
+        assert!(!c.matches("1.2.2"));
+
+        let c = constraint("~1");
+        assert!(c.matches("1.5.0"));
+        assert!(!c.matches("2.0.0"));
+    }
+
+    #[test]
+    fn test_matching_tags() {
+        let tags = ["v14.1.0", "v13.0.10", "v13.0.9", "v12.1.1", "v13.0.0"];
+        let matching = |c: &str| {
+            let c = constraint(c);
+            tags.iter().filter(|t| c.matches(t)).count()
+        };
+
+        assert_eq!(matching("13.x"), 3);
+        assert_eq!(matching("13.*"), 3);
+        assert_eq!(matching("*.0.?"), 2);
+        assert_eq!(matching("^12"), 1);
+        assert_eq!(matching("~13.0.9"), 2);
+        assert_eq!(matching("13.0.9"), 1);
+        assert_eq!(matching("15.x"), 0);
+    }
+}
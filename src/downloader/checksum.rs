@@ -0,0 +1,347 @@
+//! Checksums of downloaded files
+
+use crate::error::WengetError;
+use crate::utils::concurrency::parallel_map;
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Hash algorithms accepted for expected digests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Length of the hex-encoded digest
+    fn hex_len(self) -> usize {
+        match self {
+            Self::Sha256 => 64,
+            Self::Sha512 => 128,
+        }
+    }
+}
+
+/// An expected digest given as `sha256:<hex>` or `sha512:<hex>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedDigest {
+    pub algorithm: Algorithm,
+    /// Lowercase hex digest
+    pub hex: String,
+}
+
+impl ExpectedDigest {
+    /// Parse `<algorithm>:<hex>`; a bare hex digest is taken as SHA-256 or
+    /// SHA-512 by its length
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (algorithm, hex) = match s.split_once(':') {
+            Some((name, hex)) => {
+                let algorithm = match name.to_ascii_lowercase().as_str() {
+                    "sha256" => Algorithm::Sha256,
+                    "sha512" => Algorithm::Sha512,
+                    _ => {
+                        return Err(format!(
+                            "unsupported algorithm '{}' (expected sha256 or sha512)",
+                            name
+                        ))
+                    }
+                };
+                (algorithm, hex)
+            }
+            None if s.len() == Algorithm::Sha512.hex_len() => (Algorithm::Sha512, s),
+            None => (Algorithm::Sha256, s),
+        };
+
+        if hex.len() != algorithm.hex_len() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "expected {} hex digits for {}",
+                algorithm.hex_len(),
+                algorithm.as_str()
+            ));
+        }
+
+        Ok(Self {
+            algorithm,
+            hex: hex.to_ascii_lowercase(),
+        })
+    }
+}
+
+impl fmt::Display for ExpectedDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.algorithm.as_str(), self.hex)
+    }
+}
+
+/// Compute the hex-encoded SHA-256 digest of a file
+pub fn sha256_file(path: &Path) -> Result<String> {
+    digest_file(path, Algorithm::Sha256)
+}
+
+/// Compute the hex-encoded SHA-256 digest of a file, passing the size of
+/// every chunk read to `on_read`
+pub fn sha256_file_counting(path: &Path, on_read: &dyn Fn(u64)) -> Result<String> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut reader = CountingReader {
+        inner: file,
+        on_read,
+    };
+
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Compute the SHA-256 digests of `paths` on the worker pool
+///
+/// Results are in the order of `paths`. `on_read` is called from the worker
+/// threads with the size of every chunk read, for a bytes-hashed display.
+pub fn sha256_files(paths: &[PathBuf], on_read: &(dyn Fn(u64) + Sync)) -> Vec<Result<String>> {
+    parallel_map(paths, |path| sha256_file_counting(path, on_read))
+}
+
+/// Reader that reports how many bytes pass through it
+struct CountingReader<'a, R> {
+    inner: R,
+    on_read: &'a dyn Fn(u64),
+}
+
+impl<R: Read> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        (self.on_read)(n as u64);
+        Ok(n)
+    }
+}
+
+/// Compute the hex-encoded digest of a file
+pub fn digest_file(path: &Path, algorithm: Algorithm) -> Result<String> {
+    let mut file =
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let read_error = || format!("Failed to read {}", path.display());
+
+    Ok(match algorithm {
+        Algorithm::Sha256 => {
+            let mut hasher = Sha256::new();
+            io::copy(&mut file, &mut hasher).with_context(read_error)?;
+            format!("{:x}", hasher.finalize())
+        }
+        Algorithm::Sha512 => {
+            let mut hasher = Sha512::new();
+            io::copy(&mut file, &mut hasher).with_context(read_error)?;
+            format!("{:x}", hasher.finalize())
+        }
+    })
+}
+
+/// Check a file against an expected SHA-256 digest (case-insensitive hex)
+pub fn verify_sha256(path: &Path, expected: &str) -> Result<()> {
+    verify_digest(
+        path,
+        &ExpectedDigest {
+            algorithm: Algorithm::Sha256,
+            hex: expected.trim().to_lowercase(),
+        },
+    )
+}
+
+/// Check a file against an expected digest
+pub fn verify_digest(path: &Path, expected: &ExpectedDigest) -> Result<()> {
+    let actual = digest_file(path, expected.algorithm)?;
+    if actual != expected.hex {
+        return Err(WengetError::ChecksumMismatch {
+            file: path.display().to_string(),
+            expected: expected.hex.clone(),
+            actual,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Path of the `.sha256` sidecar recorded next to a file
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".sha256");
+    PathBuf::from(name)
+}
+
+/// Write a `sha256sum`-compatible sidecar (`<digest>  <file name>`)
+pub fn write_sidecar(path: &Path, digest: &str) -> Result<()> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let sidecar = sidecar_path(path);
+
+    std::fs::write(&sidecar, format!("{}  {}\n", digest, name))
+        .with_context(|| format!("Failed to write {}", sidecar.display()))
+}
+
+/// The digest recorded in a file's `.sha256` sidecar; None without a sidecar
+pub fn read_sidecar(path: &Path) -> Result<Option<String>> {
+    let sidecar = sidecar_path(path);
+    if !sidecar.is_file() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&sidecar)
+        .with_context(|| format!("Failed to read {}", sidecar.display()))?;
+    match content.split_whitespace().next() {
+        Some(digest) => Ok(Some(digest.to_lowercase())),
+        None => anyhow::bail!("Empty checksum file: {}", sidecar.display()),
+    }
+}
+
+/// Check a file against the digest in its `.sha256` sidecar
+///
+/// Returns false, without reading the file, when there is no sidecar.
+pub fn verify_sidecar(path: &Path) -> Result<bool> {
+    match read_sidecar(path)? {
+        Some(digest) => verify_sha256(path, &digest).map(|()| true),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sha256_file_and_sidecar() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tool.tar.gz");
+        std::fs::write(&path, b"hello").unwrap();
+
+        let digest = sha256_file(&path).unwrap();
+        assert_eq!(
+            digest,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+
+        write_sidecar(&path, &digest).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(sidecar_path(&path)).unwrap(),
+            format!("{}  tool.tar.gz\n", digest)
+        );
+    }
+
+    #[test]
+    fn test_verify_sidecar_rejects_tampered_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tool.tar.gz");
+        std::fs::write(&path, b"hello").unwrap();
+
+        // No sidecar: nothing to check against
+        assert!(!verify_sidecar(&path).unwrap());
+
+        write_sidecar(&path, &sha256_file(&path).unwrap()).unwrap();
+        assert!(verify_sidecar(&path).unwrap());
+
+        std::fs::write(&path, b"hello, tampered").unwrap();
+        let err = verify_sidecar(&path).unwrap_err();
+        assert!(matches!(
+            WengetError::find(&err),
+            Some(WengetError::ChecksumMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_expected_digest() {
+        let sha256 = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        let digest = ExpectedDigest::parse(&format!("SHA256:{}", sha256.to_uppercase())).unwrap();
+        assert_eq!(digest.algorithm, Algorithm::Sha256);
+        assert_eq!(digest.hex, sha256);
+        assert_eq!(digest.to_string(), format!("sha256:{}", sha256));
+
+        // Bare digests are recognized by length
+        assert_eq!(
+            ExpectedDigest::parse(sha256).unwrap().algorithm,
+            Algorithm::Sha256
+        );
+        let sha512 = "ab".repeat(64);
+        assert_eq!(
+            ExpectedDigest::parse(&sha512).unwrap().algorithm,
+            Algorithm::Sha512
+        );
+
+        assert!(ExpectedDigest::parse("md5:d41d8cd98f00b204e9800998ecf8427e").is_err());
+        assert!(ExpectedDigest::parse("sha256:abc").is_err());
+        assert!(ExpectedDigest::parse(&format!("sha512:{}", sha256)).is_err());
+        assert!(ExpectedDigest::parse(&"zz".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_verify_sha512() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tool.tar.gz");
+        std::fs::write(&path, b"hello").unwrap();
+
+        let expected = ExpectedDigest::parse(
+            "sha512:9b71d224bd62f3785d96d46ad3ea3d73319bfbc2890caadae2dff72519673ca7\
+             2323c3d99ba5c11d7c7acc6e14b8c5da0c4663475c2e5c3adef46f73bcdec043",
+        )
+        .unwrap();
+        verify_digest(&path, &expected).unwrap();
+
+        let wrong = ExpectedDigest::parse(&format!("sha512:{}", "0".repeat(128))).unwrap();
+        assert!(verify_digest(&path, &wrong).is_err());
+    }
+
+    #[test]
+    fn test_sha256_files_in_order_counting_bytes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut paths = Vec::new();
+        for i in 0..10 {
+            let path = temp_dir.path().join(format!("file{}", i));
+            std::fs::write(&path, vec![b'x'; 1000 * i]).unwrap();
+            paths.push(path);
+        }
+        paths.push(temp_dir.path().join("missing"));
+
+        let hashed = std::sync::atomic::AtomicU64::new(0);
+        let results = sha256_files(&paths, &|n| {
+            hashed.fetch_add(n, std::sync::atomic::Ordering::Relaxed);
+        });
+
+        assert_eq!(results.len(), paths.len());
+        for (path, result) in paths.iter().zip(&results).take(10) {
+            assert_eq!(result.as_ref().unwrap(), &sha256_file(path).unwrap());
+        }
+        assert!(results[10].is_err());
+        assert_eq!(hashed.into_inner(), (0..10).map(|i| 1000 * i).sum::<u64>());
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("tool.tar.gz");
+        std::fs::write(&path, b"hello").unwrap();
+
+        verify_sha256(
+            &path,
+            "2CF24DBA5FB0A30E26E83B2AC5B9E29E1B161E5C1FA7425E73043362938B9824",
+        )
+        .unwrap();
+
+        let err = verify_sha256(&path, "00").unwrap_err();
+        assert!(matches!(
+            WengetError::find(&err),
+            Some(WengetError::ChecksumMismatch { .. })
+        ));
+    }
+}
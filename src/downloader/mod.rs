@@ -1,34 +1,265 @@
 //! Downloader module for WenPM
 
+pub mod checksum;
+
+use crate::providers::github;
+use crate::utils::backoff;
+use crate::utils::concurrency::parallel_map;
+use crate::utils::deadline::Deadline;
+use crate::utils::http::{
+    check_status, is_retryable, redirect_hosts, send_get, send_request, HostHeaders,
+};
 use anyhow::{Context, Result};
+use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::blocking::Client;
 use std::fs::File;
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Retries after a failed download when none are configured
+pub const DEFAULT_RETRIES: u32 = 3;
+
+/// Temporary path a download is written to until it completes
+pub fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.as_os_str().to_os_string();
+    name.push(".part");
+    PathBuf::from(name)
+}
 
 /// Download a file from URL to a local path with progress bar
-pub fn download_file(url: &str, dest: &Path) -> Result<()> {
+///
+/// Data is written to `<dest>.part` and renamed once complete, so an
+/// interrupted download never looks like a finished one. Server errors,
+/// timeouts and dropped connections are retried up to `retries` times;
+/// other failures (such as a 404) are returned immediately. A `url` without
+/// a scheme is taken as a local file path and copied. Release assets of
+/// private GitHub repositories are fetched through the API when a token is
+/// set.
+pub fn download_file(url: &str, dest: &Path, retries: u32) -> Result<()> {
+    download_file_with_headers(url, dest, retries, &HostHeaders::default())
+}
+
+/// Download a file like [`download_file`], adding a source's custom headers
+/// to requests for its host
+pub fn download_file_with_headers(
+    url: &str,
+    dest: &Path,
+    retries: u32,
+    headers: &HostHeaders,
+) -> Result<()> {
+    download_file_within(url, dest, retries, headers, None)
+}
+
+/// Download a file like [`download_file_with_headers`], giving up (retries
+/// included) once `deadline` passes
+pub fn download_file_within(
+    url: &str,
+    dest: &Path,
+    retries: u32,
+    headers: &HostHeaders,
+    deadline: Option<&Deadline>,
+) -> Result<()> {
     log::info!("Downloading: {}", url);
     log::debug!("Destination: {}", dest.display());
 
+    if let Some(deadline) = deadline {
+        deadline.check()?;
+    }
+
+    if !url.contains("://") {
+        std::fs::copy(url, dest)
+            .with_context(|| format!("Failed to copy {} to {}", url, dest.display()))?;
+        return Ok(());
+    }
+
+    // Private release assets are only served by the API asset endpoint
+    let private = github::private_asset(url)?;
+    let (url, headers) = match private {
+        Some(ref asset) => (asset.url.as_str(), &asset.headers),
+        None => (url, headers),
+    };
+
     // Create HTTP client
-    let client = reqwest::blocking::Client::builder()
+    let client = Client::builder()
         .user_agent(format!("WenPM/{}", env!("CARGO_PKG_VERSION")))
+        .redirect(reqwest::redirect::Policy::none())
         .build()
         .context("Failed to create HTTP client")?;
 
-    // Send GET request
-    let response = client
-        .get(url)
-        .send()
-        .with_context(|| format!("Failed to download from {}", url))?;
+    let mut last_status = None;
+    let mut attempt = 0;
+
+    loop {
+        let err = match download_once(&client, url, dest, headers, deadline, &mut last_status) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+
+        if let Some(deadline) = deadline.filter(|d| d.expired()) {
+            let _ = std::fs::remove_file(part_path(dest));
+            return Err(deadline.error()).with_context(|| format!("Download failed: {}", url));
+        }
+
+        if attempt >= retries || !is_retryable(&err) {
+            let _ = std::fs::remove_file(part_path(dest));
+            let status = last_status.map_or_else(|| "none".to_string(), |s: u16| s.to_string());
+            return Err(err).with_context(|| {
+                format!(
+                    "Download failed after {} attempt(s) (last HTTP status: {}): {}",
+                    attempt + 1,
+                    status,
+                    url
+                )
+            });
+        }
+
+        attempt += 1;
+        eprintln!(
+            "  {} {:#}, retrying ({}/{})...",
+            "⚠".yellow(),
+            err,
+            attempt,
+            retries
+        );
+        std::thread::sleep(retry_delay(attempt));
+    }
+}
+
+/// Check that downloads are reachable without fetching them
+///
+/// Sends a HEAD request for every URL (local paths are checked on disk) and
+/// returns, in order, each one's size when the server reports it. Servers
+/// that don't support HEAD count as reachable with an unknown size.
+pub fn probe_all(targets: &[(String, HostHeaders)]) -> Result<Vec<Result<Option<u64>>>> {
+    let client = Client::builder()
+        .user_agent(format!("WenPM/{}", env!("CARGO_PKG_VERSION")))
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .context("Failed to create HTTP client")?;
+    let allowed_hosts = redirect_hosts();
+
+    Ok(parallel_map(targets, |(url, headers)| {
+        if !url.contains("://") {
+            return std::fs::metadata(url)
+                .map(|m| Some(m.len()))
+                .with_context(|| format!("Failed to read {}", url));
+        }
+
+        // Listed in the release, which is as much as can be checked without
+        // following the API's signed redirect
+        if let Some(asset) = github::private_asset(url)? {
+            return Ok(Some(asset.size));
+        }
+
+        let response = send_request(
+            &client,
+            reqwest::Method::HEAD,
+            url,
+            headers,
+            &allowed_hosts,
+            |r| r,
+        )?;
+        if matches!(response.status().as_u16(), 405 | 501) {
+            return Ok(None);
+        }
+        check_status(&response, url)?;
+
+        Ok(response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok()))
+    }))
+}
+
+/// How long a reachability check may take per URL
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
 
-    if !response.status().is_success() {
-        anyhow::bail!("HTTP {} for {}", response.status(), url);
+/// Fail when a response body is a web page or an API error rather than the
+/// binary or archive that was asked for
+///
+/// Judged by the `Content-Type` (HTML or JSON) and by an HTML document
+/// start in the first bytes, since error pages are often served as
+/// `application/octet-stream` too.
+fn check_not_error_page(content_type: Option<&str>, head: &[u8]) -> Result<()> {
+    let mime = content_type
+        .and_then(|t| t.split(';').next())
+        .map(|t| t.trim().to_ascii_lowercase())
+        .unwrap_or_default();
+    let text = String::from_utf8_lossy(&head[..head.len().min(512)]);
+    let start = text.trim_start_matches('\u{feff}').trim_start();
+    let lower = start.to_ascii_lowercase();
+
+    let is_page = matches!(
+        mime.as_str(),
+        "text/html" | "application/xhtml+xml" | "application/json"
+    ) || lower.starts_with("<!doctype html")
+        || lower.starts_with("<html");
+    if !is_page {
+        return Ok(());
     }
 
+    let first_line: String = start
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(120)
+        .collect();
+    anyhow::bail!(
+        "Server returned an error page, not the expected asset ({}): {}",
+        if mime.is_empty() {
+            "no content type"
+        } else {
+            mime.as_str()
+        },
+        first_line
+    )
+}
+
+/// Delay before the given (one-based) retry attempt
+fn retry_delay(attempt: u32) -> Duration {
+    backoff::jittered(attempt.saturating_sub(1))
+}
+
+/// Make a single download attempt, recording the HTTP status it received
+fn download_once(
+    client: &Client,
+    url: &str,
+    dest: &Path,
+    headers: &HostHeaders,
+    deadline: Option<&Deadline>,
+    last_status: &mut Option<u16>,
+) -> Result<()> {
+    // Bound the request, body included, by the time left
+    let timeout = deadline.map(Deadline::remaining).transpose()?;
+
+    // Send GET request
+    let response = send_get(client, url, headers, &redirect_hosts(), |r| match timeout {
+        Some(timeout) => r.timeout(timeout),
+        None => r,
+    })
+    .with_context(|| format!("Failed to download from {}", url))?;
+
+    *last_status = Some(response.status().as_u16());
+    check_status(&response, url)?;
+
     // Get file size for progress bar
     let total_size = response.content_length().unwrap_or(0);
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    // A CDN may answer 200 with an HTML/JSON error page; catch it before it
+    // is handed to the extractor
+    let mut reader = std::io::BufReader::new(response);
+    let head = reader.fill_buf().context("Failed to read response")?;
+    check_not_error_page(content_type.as_deref(), head)?;
 
     // Create progress bar
     let pb = if total_size > 0 {
@@ -44,15 +275,15 @@ pub fn download_file(url: &str, dest: &Path) -> Result<()> {
         None
     };
 
-    // Create destination file
-    let mut file =
-        File::create(dest).with_context(|| format!("Failed to create file: {}", dest.display()))?;
+    // Create temporary destination file
+    let part = part_path(dest);
+    let mut file = File::create(&part)
+        .with_context(|| format!("Failed to create file: {}", part.display()))?;
 
     // Download and write with progress
     let mut downloaded = 0u64;
     let mut buffer = vec![0; 8192];
 
-    let mut reader = std::io::BufReader::new(response);
     loop {
         let n = std::io::Read::read(&mut reader, &mut buffer).context("Failed to read response")?;
 
@@ -65,6 +296,10 @@ pub fn download_file(url: &str, dest: &Path) -> Result<()> {
 
         downloaded += n as u64;
 
+        if let Some(deadline) = deadline {
+            deadline.check()?;
+        }
+
         if let Some(pb) = &pb {
             pb.set_position(downloaded);
         }
@@ -74,6 +309,10 @@ pub fn download_file(url: &str, dest: &Path) -> Result<()> {
         pb.finish_with_message("Download complete");
     }
 
+    drop(file);
+    std::fs::rename(&part, dest)
+        .with_context(|| format!("Failed to move download to {}", dest.display()))?;
+
     log::info!("Downloaded {} bytes", downloaded);
 
     Ok(())
@@ -82,6 +321,9 @@ pub fn download_file(url: &str, dest: &Path) -> Result<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::http::{testing, MAX_REDIRECTS};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
     use tempfile::TempDir;
 
     #[test]
@@ -91,8 +333,237 @@ mod tests {
         let dest = temp_dir.path().join("test.txt");
 
         // Download a small file
-        let result = download_file("https://httpbin.org/bytes/1024", &dest);
+        let result = download_file("https://httpbin.org/bytes/1024", &dest, DEFAULT_RETRIES);
         assert!(result.is_ok());
         assert!(dest.exists());
     }
+
+    fn serve(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let (base, requests) = testing::serve(responses);
+        (format!("{}/tool.tar.gz", base), requests)
+    }
+
+    const UNAVAILABLE: &str =
+        "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+    #[test]
+    fn test_download_retries_server_errors() {
+        let (url, requests) = serve(vec![
+            UNAVAILABLE,
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello",
+        ]);
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        download_file(&url, &dest, 2).unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_download_gives_up_with_last_status() {
+        let (url, requests) = serve(vec![UNAVAILABLE, UNAVAILABLE]);
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        let err = download_file(&url, &dest, 1).unwrap_err();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        let message = format!("{:#}", err);
+        assert!(message.contains("after 2 attempt(s)"));
+        assert!(message.contains("last HTTP status: 503"));
+        assert!(message.contains(&url));
+        assert!(!part_path(&dest).exists());
+    }
+
+    #[test]
+    fn test_download_rejects_html_error_page() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: 60\r\nConnection: close\r\n\r\n<!DOCTYPE html>\n<html><body>Bandwidth exceeded</body></html>",
+        ]);
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        let err = download_file(&url, &dest, 3).unwrap_err();
+
+        // Not retried, and nothing is left behind for the extractor
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        let message = format!("{:#}", err);
+        assert!(message.contains("error page, not the expected asset (text/html)"));
+        assert!(message.contains("<!DOCTYPE html>"));
+        assert!(!dest.exists());
+        assert!(!part_path(&dest).exists());
+    }
+
+    #[test]
+    fn test_check_not_error_page() {
+        let gzip = [0x1f, 0x8b, 0x08, 0x00];
+        assert!(check_not_error_page(Some("application/octet-stream"), &gzip).is_ok());
+        assert!(check_not_error_page(None, b"#!/bin/sh\necho hi\n").is_ok());
+        assert!(check_not_error_page(Some("text/plain"), b"").is_ok());
+
+        // HTML sniffed from the body even when labelled as binary
+        let err = check_not_error_page(
+            Some("application/octet-stream"),
+            b"\n  <html><head><title>404</title>",
+        )
+        .unwrap_err();
+        assert!(err.to_string().ends_with("<html><head><title>404</title>"));
+
+        let err = check_not_error_page(
+            Some("application/json"),
+            b"{\"message\": \"Not Found\"}\n{}",
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("(application/json): {\"message\": \"Not Found\"}"));
+    }
+
+    #[test]
+    fn test_download_does_not_retry_not_found() {
+        let (url, requests) = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        let err = download_file(&url, &dest, 3).unwrap_err();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert!(format!("{:#}", err).contains("last HTTP status: 404"));
+    }
+
+    #[test]
+    fn test_download_strips_auth_on_cross_host_redirect() {
+        let (cdn, cdn_requests) = testing::serve_recording(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello".to_string(),
+        ]);
+        let (origin, origin_requests) = testing::serve_recording(vec![format!(
+            "HTTP/1.1 302 Found\r\nLocation: {}/tool.tar.gz\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            cdn
+        )]);
+        let headers = HostHeaders::new(
+            &origin,
+            &std::collections::BTreeMap::from([(
+                "Authorization".to_string(),
+                "Bearer s3cret".to_string(),
+            )]),
+        )
+        .unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        download_file_with_headers(&format!("{}/tool.tar.gz", origin), &dest, 0, &headers).unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello");
+        assert!(origin_requests.lock().unwrap()[0].contains("authorization: bearer s3cret"));
+        assert!(!cdn_requests.lock().unwrap()[0].contains("authorization"));
+    }
+
+    #[test]
+    fn test_download_keeps_compressed_bytes() {
+        // A server labelling a .tar.gz as gzip-encoded must not get it unpacked
+        let archive: &[u8] = &[0x1f, 0x8b, 0x08, 0x00, 0x01, 0x02, 0x03];
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/gzip\r\nContent-Encoding: gzip\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            archive.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(archive);
+        let (url, requests) = testing::serve_recording(vec![response]);
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        download_file(&format!("{}/tool.tar.gz", url), &dest, 0).unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), archive);
+        assert!(!requests.lock().unwrap()[0].contains("accept-encoding"));
+    }
+
+    #[test]
+    fn test_download_redirect_cap() {
+        const REDIRECT: &str = "HTTP/1.1 302 Found\r\nLocation: /tool.tar.gz\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let (url, requests) = serve(vec![REDIRECT; MAX_REDIRECTS + 1]);
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        let err = download_file(&url, &dest, 3).unwrap_err();
+
+        assert_eq!(requests.load(Ordering::SeqCst), MAX_REDIRECTS + 1);
+        assert!(format!("{:#}", err).contains("Too many redirects"));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_probe_all() {
+        let (found, _) = testing::serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 1234\r\nConnection: close\r\n\r\n",
+        ]);
+        let (missing, _) = testing::serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let (no_head, _) = testing::serve(vec![
+            "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let targets: Vec<(String, HostHeaders)> = [found, missing, no_head]
+            .into_iter()
+            .map(|base| (format!("{}/tool.tar.gz", base), HostHeaders::default()))
+            .collect();
+
+        let results = probe_all(&targets).unwrap();
+
+        assert_eq!(results[0].as_ref().unwrap(), &Some(1234));
+        assert!(matches!(
+            results[1].as_ref().map_err(crate::error::WengetError::find),
+            Err(Some(crate::error::WengetError::HttpStatus {
+                status: 404,
+                ..
+            }))
+        ));
+        assert_eq!(results[2].as_ref().unwrap(), &None);
+    }
+
+    #[test]
+    fn test_download_stops_at_deadline() {
+        // Sends half the body, then stalls without closing the connection
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tool.tar.gz", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            use std::io::Read;
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0u8; 1024]);
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nhello")
+                .unwrap();
+            std::thread::sleep(Duration::from_secs(10));
+        });
+        let temp_dir = TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        let started = std::time::Instant::now();
+        let deadline = Deadline::after(Duration::from_secs(1));
+        let err = download_file_within(&url, &dest, 3, &HostHeaders::default(), Some(&deadline))
+            .unwrap_err();
+
+        // Not retried past the deadline, and nothing is left behind
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(format!("{:#}", err).contains("Timed out after 1s"));
+        assert!(!dest.exists());
+        assert!(!part_path(&dest).exists());
+    }
+
+    #[test]
+    fn test_download_copies_local_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = temp_dir.path().join("source.tar.gz");
+        std::fs::write(&source, b"hello").unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+
+        download_file(source.to_str().unwrap(), &dest, 0).unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello");
+    }
 }
@@ -0,0 +1,140 @@
+//! Structured error types for Wenget
+//!
+//! Library code (resolver, providers, downloader, installer) returns these
+//! wrapped in `anyhow::Error`, so callers can tell failure kinds apart with
+//! [`WengetError::find`] while the CLI keeps displaying the full context
+//! chain. Each kind maps to a distinct process exit code.
+
+use crate::utils::http::RateLimitExceeded;
+
+/// Categorized Wenget failure
+#[derive(Debug, thiserror::Error)]
+pub enum WengetError {
+    /// A package, release, or asset does not exist
+    #[error("{0}")]
+    NotFound(String),
+
+    /// The package ships no binary for this platform
+    #[error("{name} has no binary for this platform ({platform})")]
+    PlatformUnsupported { name: String, platform: String },
+
+    /// The GitHub API rate limit is exhausted
+    #[error(transparent)]
+    RateLimited(#[from] RateLimitExceeded),
+
+    /// The request could not be sent or the connection failed
+    #[error("Network error for {url}: {source}")]
+    Network {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    /// The server answered with an error status
+    #[error("HTTP {status} for {url}")]
+    HttpStatus { url: String, status: u16 },
+
+    /// A downloaded file does not match its expected checksum
+    #[error("Checksum mismatch for {file}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// The downloaded file is not in a format Wenget can unpack
+    #[error("Unsupported archive format: {0}")]
+    UnsupportedFormat(String),
+
+    /// Unpacking an archive failed
+    #[error("Failed to extract {archive}: {reason}")]
+    ExtractFailed { archive: String, reason: String },
+
+    /// The release ships only source code, no downloadable assets
+    #[error("Latest release {tag} of {repo} has no downloadable assets; it may be source-only. Pin an older release that shipped binaries (see https://github.com/{repo}/releases)")]
+    NoReleaseAssets { repo: String, tag: String },
+
+    /// A repository URL could not be parsed
+    #[error("Invalid GitHub URL: {0}")]
+    InvalidUrl(String),
+
+    /// Packages of an `add`/`update` run failed; `code` is the exit code of
+    /// their failures (1 when they failed in different ways)
+    #[error("{count} package(s) failed")]
+    PackagesFailed { count: usize, code: i32 },
+}
+
+impl WengetError {
+    /// Find the first Wenget error in an error's context chain
+    pub fn find(err: &anyhow::Error) -> Option<&WengetError> {
+        err.chain().find_map(|e| e.downcast_ref::<WengetError>())
+    }
+
+    /// Find a rate limit error in an error's context chain
+    pub fn rate_limit(err: &anyhow::Error) -> Option<&RateLimitExceeded> {
+        match Self::find(err) {
+            Some(WengetError::RateLimited(exceeded)) => Some(exceeded),
+            _ => None,
+        }
+    }
+
+    /// Whether an error was caused by a request timing out
+    pub fn is_timeout(err: &anyhow::Error) -> bool {
+        matches!(
+            Self::find(err),
+            Some(WengetError::Network { source, .. }) if source.is_timeout()
+        )
+    }
+
+    /// Process exit code for this kind of failure
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WengetError::NotFound(_) => 2,
+            WengetError::PlatformUnsupported { .. } | WengetError::NoReleaseAssets { .. } => 3,
+            WengetError::RateLimited(_) => 4,
+            WengetError::Network { .. } | WengetError::HttpStatus { .. } => 5,
+            WengetError::ChecksumMismatch { .. } => 6,
+            WengetError::UnsupportedFormat(_) | WengetError::ExtractFailed { .. } => 7,
+            WengetError::InvalidUrl(_) => 8,
+            WengetError::PackagesFailed { code, .. } => *code,
+        }
+    }
+}
+
+/// Exit code for an error reaching the CLI (1 when it has no known kind)
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    WengetError::find(err).map_or(1, WengetError::exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_find_through_context() {
+        let err = Err::<(), _>(WengetError::NotFound("Package 'x' not found".to_string()))
+            .context("Failed to resolve x")
+            .unwrap_err();
+
+        assert!(matches!(
+            WengetError::find(&err),
+            Some(WengetError::NotFound(_))
+        ));
+        assert_eq!(exit_code(&err), 2);
+        assert_eq!(exit_code(&anyhow::anyhow!("plain")), 1);
+    }
+
+    #[test]
+    fn test_rate_limit_lookup() {
+        let err: anyhow::Error = WengetError::RateLimited(RateLimitExceeded {
+            reset: None,
+            retry_after: None,
+        })
+        .into();
+        let err = err.context("Failed to fetch latest release");
+
+        assert!(WengetError::rate_limit(&err).is_some());
+        assert_eq!(exit_code(&err), 4);
+    }
+}
@@ -0,0 +1,153 @@
+//! Disk image (.dmg) extraction for macOS
+//!
+//! The image is mounted read-only with `hdiutil`, its `.app` bundles and
+//! top-level files are copied out, and it is detached again even when the
+//! copy fails.
+
+use super::extractor::{ExtractBudget, ExtractProgress};
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A mounted disk image, detached on drop
+struct MountedImage {
+    mount_point: PathBuf,
+}
+
+impl MountedImage {
+    /// Attach `image` at `mount_point` without showing it in Finder
+    fn attach(image: &Path, mount_point: &Path) -> Result<Self> {
+        fs::create_dir_all(mount_point)
+            .with_context(|| format!("Failed to create mount point: {}", mount_point.display()))?;
+
+        let output = Command::new("hdiutil")
+            .arg("attach")
+            .arg(image)
+            .args(["-nobrowse", "-readonly", "-noautoopen", "-mountpoint"])
+            .arg(mount_point)
+            .output()
+            .context("Failed to run hdiutil")?;
+
+        if !output.status.success() {
+            let _ = fs::remove_dir(mount_point);
+            anyhow::bail!(
+                "hdiutil attach failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(Self {
+            mount_point: mount_point.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for MountedImage {
+    fn drop(&mut self) {
+        let detached = Command::new("hdiutil")
+            .arg("detach")
+            .arg(&self.mount_point)
+            .arg("-force")
+            .output();
+
+        match detached {
+            Ok(output) if output.status.success() => {
+                let _ = fs::remove_dir(&self.mount_point);
+            }
+            Ok(output) => log::warn!(
+                "Failed to detach {}: {}",
+                self.mount_point.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Err(e) => log::warn!("Failed to detach {}: {}", self.mount_point.display(), e),
+        }
+    }
+}
+
+/// Mount a disk image and copy its contents into `dest_dir`
+///
+/// Copies `.app` bundles and plain top-level files; symlinks such as the
+/// usual `Applications` shortcut and hidden volume metadata are skipped.
+pub(super) fn extract_dmg(
+    image: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let mount_point = dest_dir.with_extension("mnt");
+    let mounted = MountedImage::attach(image, &mount_point)?;
+
+    let mut extracted_files = Vec::new();
+    let entries = fs::read_dir(&mounted.mount_point)
+        .with_context(|| format!("Failed to read disk image: {}", image.display()))?;
+
+    for entry in entries {
+        let entry = entry.context("Failed to read disk image entry")?;
+        let name = entry.file_name();
+        let file_type = entry.file_type()?;
+
+        if name.to_string_lossy().starts_with('.') || file_type.is_symlink() {
+            continue;
+        }
+
+        copy_entry(
+            &entry.path(),
+            &dest_dir.join(&name),
+            Path::new(&name),
+            budget,
+            &mut extracted_files,
+        )?;
+        progress(ExtractProgress {
+            entries: extracted_files.len(),
+            total: None,
+        });
+    }
+
+    drop(mounted);
+
+    Ok(extracted_files)
+}
+
+/// Copy a file or directory tree, recording copied files relative to the
+/// destination root
+///
+/// Symlinks inside bundles (common in frameworks) are recreated as links.
+fn copy_entry(
+    src: &Path,
+    dest: &Path,
+    relative: &Path,
+    budget: &mut ExtractBudget,
+    extracted_files: &mut Vec<String>,
+) -> Result<()> {
+    let metadata =
+        fs::symlink_metadata(src).with_context(|| format!("Failed to read: {}", src.display()))?;
+
+    if metadata.file_type().is_symlink() {
+        let target = fs::read_link(src)?;
+        std::os::unix::fs::symlink(&target, dest)
+            .with_context(|| format!("Failed to create symlink: {}", dest.display()))?;
+    } else if metadata.is_dir() {
+        fs::create_dir_all(dest)
+            .with_context(|| format!("Failed to create directory: {}", dest.display()))?;
+
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            copy_entry(
+                &entry.path(),
+                &dest.join(&name),
+                &relative.join(&name),
+                budget,
+                extracted_files,
+            )?;
+        }
+    } else {
+        budget.reserve(&relative.to_string_lossy(), metadata.len())?;
+        // fs::copy keeps the permission bits, so executables stay executable
+        fs::copy(src, dest).with_context(|| format!("Failed to copy: {}", src.display()))?;
+        extracted_files.push(relative.to_string_lossy().to_string());
+    }
+
+    Ok(())
+}
@@ -1,20 +1,194 @@
 //! Archive extraction utilities
 
+use crate::error::WengetError;
+use crate::utils::deadline::Deadline;
 use anyhow::{Context, Result};
+use bzip2::read::BzDecoder;
 use flate2::read::GzDecoder;
 use std::fs::{self, File};
+use std::io::{Read, Write};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tar::Archive;
 use xz2::read::XzDecoder;
 use zip::ZipArchive;
 
+/// Levels of archives extracted when an archive's only member is itself an
+/// archive (a `.tar.gz` shipped inside a `.zip`)
+const MAX_ARCHIVE_DEPTH: usize = 2;
+
+/// Largest nested archive that is extracted in turn
+const MAX_NESTED_ARCHIVE_SIZE: u64 = 1024 * 1024 * 1024;
+
+/// Total bytes an archive may expand to unless `max_extract_size` is set
+pub const DEFAULT_MAX_EXTRACT_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Bytes a single extracted file may take unless `max_file_size` is set
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+
+static MAX_EXTRACT_SIZE: AtomicU64 = AtomicU64::new(DEFAULT_MAX_EXTRACT_SIZE);
+static MAX_FILE_SIZE: AtomicU64 = AtomicU64::new(DEFAULT_MAX_FILE_SIZE);
+
+/// Set how much an archive may expand to in total and per file
+pub fn set_size_limits(total: u64, file: u64) {
+    MAX_EXTRACT_SIZE.store(total, Ordering::Relaxed);
+    MAX_FILE_SIZE.store(file, Ordering::Relaxed);
+}
+
+/// Bytes written by one extraction, checked against the size limits so a
+/// decompression bomb is refused before it fills the disk
+pub(super) struct ExtractBudget {
+    max_total: u64,
+    max_file: u64,
+    written: u64,
+    deadline: Option<Deadline>,
+}
+
+impl ExtractBudget {
+    /// Budget using the configured limits
+    pub(super) fn new() -> Self {
+        Self::with_limits(
+            MAX_EXTRACT_SIZE.load(Ordering::Relaxed),
+            MAX_FILE_SIZE.load(Ordering::Relaxed),
+        )
+    }
+
+    pub(super) fn with_limits(max_total: u64, max_file: u64) -> Self {
+        Self {
+            max_total,
+            max_file,
+            written: 0,
+            deadline: None,
+        }
+    }
+
+    /// Also abort the extraction once `deadline` passes
+    pub(super) fn with_deadline(mut self, deadline: Option<Deadline>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    fn check_deadline(&self, name: &str) -> Result<()> {
+        match self.deadline {
+            Some(deadline) => deadline
+                .check()
+                .with_context(|| format!("Extraction aborted at {}", name)),
+            None => Ok(()),
+        }
+    }
+
+    /// Account for a file whose size is known before it is written
+    pub(super) fn reserve(&mut self, name: &str, size: u64) -> Result<()> {
+        self.check_deadline(name)?;
+        if size > self.max_file {
+            return Err(self.file_too_large(name));
+        }
+        if self.written.saturating_add(size) > self.max_total {
+            return Err(self.archive_too_large(name));
+        }
+        self.written += size;
+        Ok(())
+    }
+
+    /// Copy a file's data, stopping as soon as it goes over a limit
+    pub(super) fn copy(
+        &mut self,
+        name: &str,
+        reader: &mut dyn Read,
+        writer: &mut dyn Write,
+    ) -> Result<u64> {
+        self.check_deadline(name)?;
+        let remaining = self.max_total.saturating_sub(self.written);
+        let allowed = self.max_file.min(remaining);
+        let copied = std::io::copy(&mut reader.take(allowed.saturating_add(1)), writer)
+            .with_context(|| format!("Failed to extract: {}", name))?;
+
+        if copied > allowed {
+            return Err(if allowed == self.max_file {
+                self.file_too_large(name)
+            } else {
+                self.archive_too_large(name)
+            });
+        }
+        self.written += copied;
+        Ok(copied)
+    }
+
+    /// Give back the bytes of a file that was removed again
+    fn release(&mut self, size: u64) {
+        self.written = self.written.saturating_sub(size);
+    }
+
+    fn file_too_large(&self, name: &str) -> anyhow::Error {
+        anyhow::anyhow!(
+            "{} is larger than the {} bytes allowed per extracted file (max_file_size); extraction aborted",
+            name,
+            self.max_file
+        )
+    }
+
+    fn archive_too_large(&self, name: &str) -> anyhow::Error {
+        anyhow::anyhow!(
+            "Archive expands to more than the {} bytes allowed (max_extract_size), at {}; extraction aborted",
+            self.max_total,
+            name
+        )
+    }
+}
+
+/// Progress of an extraction, reported after each archive entry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractProgress {
+    /// Number of entries processed so far
+    pub entries: usize,
+    /// Total number of entries, when the format records it up front (zip)
+    pub total: Option<usize>,
+}
+
 /// Extract an archive file to a destination directory
 /// For standalone executables, copies them directly to the destination
 pub fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
+    extract_archive_with_progress(archive_path, dest_dir, &mut |_| {})
+}
+
+/// Extract an archive, calling `progress` after every entry
+///
+/// Tar streams do not know their entry count up front, so `total` is only
+/// set for zip archives. When the only extracted member is itself an
+/// archive, it is extracted in its place (up to [`MAX_ARCHIVE_DEPTH`] levels).
+pub fn extract_archive_with_progress(
+    archive_path: &Path,
+    dest_dir: &Path,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    extract_archive_until(archive_path, dest_dir, None, progress)
+}
+
+/// Extract an archive like [`extract_archive_with_progress`], aborting once
+/// `deadline` passes (checked before every file is written)
+pub fn extract_archive_until(
+    archive_path: &Path,
+    dest_dir: &Path,
+    deadline: Option<Deadline>,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let mut budget = ExtractBudget::new().with_deadline(deadline);
+    extract_within(archive_path, dest_dir, &mut budget, progress)
+}
+
+/// Extract an archive, aborting once it writes more than `budget` allows
+fn extract_within(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
     log::info!("Extracting: {}", archive_path.display());
     log::debug!("Destination: {}", dest_dir.display());
 
-    // Create destination directory
+    // Create destination directory; one created here is removed again if
+    // extraction fails, so an aborted archive leaves nothing behind
+    let created = !dest_dir.exists();
     fs::create_dir_all(dest_dir)
         .with_context(|| format!("Failed to create directory: {}", dest_dir.display()))?;
 
@@ -24,22 +198,117 @@ pub fn extract_archive(archive_path: &Path, dest_dir: &Path) -> Result<Vec<Strin
         .and_then(|s| s.to_str())
         .context("Invalid file name")?;
 
-    let extracted_files = if is_standalone_executable(filename) {
+    let extracted =
+        extract_any(archive_path, filename, dest_dir, budget, progress).and_then(|mut files| {
+            for _ in 1..MAX_ARCHIVE_DEPTH {
+                match files.as_slice() {
+                    [inner] if is_nested_archive(inner) => {
+                        files = extract_nested(dest_dir, inner, budget, progress)?;
+                    }
+                    _ => break,
+                }
+            }
+            Ok(files)
+        });
+
+    if extracted.is_err() && created {
+        let _ = fs::remove_dir_all(dest_dir);
+    }
+
+    let extracted_files = extracted.map_err(|e| match WengetError::find(&e) {
+        Some(WengetError::UnsupportedFormat(_)) => e,
+        _ => WengetError::ExtractFailed {
+            archive: filename.to_string(),
+            reason: format!("{:#}", e),
+        }
+        .into(),
+    })?;
+
+    log::info!("Extracted {} file(s)", extracted_files.len());
+
+    Ok(extracted_files)
+}
+
+/// Extract an archive of any supported format by its file name
+fn extract_any(
+    archive_path: &Path,
+    filename: &str,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    if is_standalone_executable(filename) {
         // Handle standalone executable
-        extract_standalone_executable(archive_path, dest_dir)?
+        extract_standalone_executable(archive_path, dest_dir, budget)
     } else if filename.ends_with(".tar.gz") || filename.ends_with(".tgz") {
-        extract_tar_gz(archive_path, dest_dir)?
+        extract_tar_gz(archive_path, dest_dir, budget, progress)
     } else if filename.ends_with(".tar.xz") {
-        extract_tar_xz(archive_path, dest_dir)?
+        extract_tar_xz(archive_path, dest_dir, budget, progress)
     } else if filename.ends_with(".zip") {
-        extract_zip(archive_path, dest_dir)?
+        extract_zip(archive_path, dest_dir, budget, progress)
+    } else if filename.ends_with(".7z") {
+        extract_7z(archive_path, dest_dir, budget, progress)
+    } else if filename.ends_with(".dmg") {
+        extract_dmg(archive_path, dest_dir, budget, progress)
+    } else if filename.ends_with(".deb") {
+        super::linux_package::extract_deb(archive_path, dest_dir, budget, progress)
+    } else if filename.ends_with(".rpm") {
+        super::linux_package::extract_rpm(archive_path, dest_dir, budget, progress)
+    } else if let Some((compression, stem)) = Compression::from_filename(filename) {
+        extract_compressed(archive_path, dest_dir, compression, stem, budget, progress)
     } else {
-        anyhow::bail!("Unsupported archive format: {}", filename);
-    };
+        Err(WengetError::UnsupportedFormat(filename.to_string()).into())
+    }
+}
 
-    log::info!("Extracted {} file(s)", extracted_files.len());
+/// Check whether an extracted file is an archive worth unpacking in turn
+///
+/// System packages and disk images are left alone; they are never shipped
+/// inside another archive.
+fn is_nested_archive(path: &str) -> bool {
+    let filename = path.rsplit('/').next().unwrap_or(path);
+    !is_standalone_executable(filename)
+        && ([".tar.gz", ".tgz", ".tar.xz", ".zip", ".7z"]
+            .iter()
+            .any(|ext| filename.ends_with(ext))
+            || Compression::from_filename(filename).is_some())
+}
 
-    Ok(extracted_files)
+/// Replace an archive found inside the extracted files with its contents
+fn extract_nested(
+    dest_dir: &Path,
+    inner: &str,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let inner_path = dest_dir.join(inner);
+    let size = fs::metadata(&inner_path)
+        .with_context(|| format!("Failed to read {}", inner_path.display()))?
+        .len();
+    if size > MAX_NESTED_ARCHIVE_SIZE {
+        anyhow::bail!(
+            "Nested archive {} is too large to extract ({} bytes, limit {})",
+            inner,
+            size,
+            MAX_NESTED_ARCHIVE_SIZE
+        );
+    }
+    log::debug!("{} is a nested archive, extracting it", inner);
+
+    // Move it aside so its contents can't collide with it
+    let filename = inner.rsplit('/').next().unwrap_or(inner);
+    let holding_dir = dest_dir.join(".wenget-nested");
+    fs::create_dir_all(&holding_dir)
+        .with_context(|| format!("Failed to create directory: {}", holding_dir.display()))?;
+    let moved = holding_dir.join(filename);
+    fs::rename(&inner_path, &moved)
+        .with_context(|| format!("Failed to move {}", inner_path.display()))?;
+
+    // Only its contents remain on disk afterwards
+    budget.release(size);
+    let result = extract_any(&moved, filename, dest_dir, budget, progress);
+    let _ = fs::remove_dir_all(&holding_dir);
+    result
 }
 
 /// Check if a file is a standalone executable (not an archive)
@@ -55,7 +324,9 @@ fn is_standalone_executable(filename: &str) -> bool {
             return true;
         }
         // Check if it has no common archive extension
-        let archive_extensions = [".zip", ".tar", ".gz", ".xz", ".bz2", ".7z", ".rar"];
+        let archive_extensions = [
+            ".zip", ".tar", ".gz", ".xz", ".bz2", ".7z", ".rar", ".dmg", ".deb", ".rpm",
+        ];
         if !archive_extensions.iter().any(|ext| filename.contains(ext)) {
             // Could be a standalone binary
             return true;
@@ -66,10 +337,18 @@ fn is_standalone_executable(filename: &str) -> bool {
 }
 
 /// "Extract" a standalone executable by copying it to the destination directory
-fn extract_standalone_executable(executable_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
+fn extract_standalone_executable(
+    executable_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+) -> Result<Vec<String>> {
     let filename = executable_path
         .file_name()
         .context("Invalid executable filename")?;
+    let size = fs::metadata(executable_path)
+        .with_context(|| format!("Failed to read {}", executable_path.display()))?
+        .len();
+    budget.reserve(&filename.to_string_lossy(), size)?;
 
     let dest_path = dest_dir.join(filename);
 
@@ -96,48 +375,192 @@ fn extract_standalone_executable(executable_path: &Path, dest_dir: &Path) -> Res
 }
 
 /// Extract a .tar.gz file
-fn extract_tar_gz(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
+fn extract_tar_gz(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
     let file = File::open(archive_path)
         .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
 
     let decoder = GzDecoder::new(file);
     let mut archive = Archive::new(decoder);
 
-    extract_tar_archive(&mut archive, dest_dir)
+    extract_tar_archive(&mut archive, dest_dir, budget, progress)
 }
 
 /// Extract a .tar.xz file
-fn extract_tar_xz(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
+fn extract_tar_xz(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
     let file = File::open(archive_path)
         .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
 
     let decoder = XzDecoder::new(file);
     let mut archive = Archive::new(decoder);
 
-    extract_tar_archive(&mut archive, dest_dir)
+    extract_tar_archive(&mut archive, dest_dir, budget, progress)
+}
+
+/// Mount a .dmg disk image and copy out its contents
+#[cfg(target_os = "macos")]
+fn extract_dmg(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    super::dmg::extract_dmg(archive_path, dest_dir, budget, progress)
+}
+
+/// Disk images need `hdiutil`, which only exists on macOS
+#[cfg(not(target_os = "macos"))]
+fn extract_dmg(
+    _archive_path: &Path,
+    _dest_dir: &Path,
+    _budget: &mut ExtractBudget,
+    _progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    anyhow::bail!("Disk images (.dmg) can only be installed on macOS")
+}
+
+/// Compression formats that may wrap either a tar archive or a single file
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Compression {
+    Gzip,
+    Bzip2,
+    Xz,
+}
+
+impl Compression {
+    /// Detect the compression from a file name, returning it with the name
+    /// stripped of the compression extension
+    fn from_filename(filename: &str) -> Option<(Self, &str)> {
+        [
+            (".gz", Self::Gzip),
+            (".bz2", Self::Bzip2),
+            (".xz", Self::Xz),
+        ]
+        .into_iter()
+        .find_map(|(ext, compression)| {
+            filename
+                .strip_suffix(ext)
+                .filter(|stem| !stem.is_empty())
+                .map(|stem| (compression, stem))
+        })
+    }
+
+    /// Open a decompressing reader over the file
+    fn open(self, path: &Path) -> Result<Box<dyn Read>> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open archive: {}", path.display()))?;
+
+        Ok(match self {
+            Self::Gzip => Box::new(GzDecoder::new(file)),
+            Self::Bzip2 => Box::new(BzDecoder::new(file)),
+            Self::Xz => Box::new(XzDecoder::new(file)),
+        })
+    }
+}
+
+/// Extract a compressed file that holds either a tar archive or a bare binary
+///
+/// Releases sometimes ship `tool.gz` containing just the executable; if the
+/// decompressed data has no tar header it is written to `dest_dir/<stem>`.
+fn extract_compressed(
+    archive_path: &Path,
+    dest_dir: &Path,
+    compression: Compression,
+    stem: &str,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    if is_tar_stream(compression.open(archive_path)?)? {
+        let mut archive = Archive::new(compression.open(archive_path)?);
+        return extract_tar_archive(&mut archive, dest_dir, budget, progress);
+    }
+
+    log::debug!("{} is a compressed single file", archive_path.display());
+
+    let dest_path = dest_dir.join(stem);
+    let mut dest_file = File::create(&dest_path)
+        .with_context(|| format!("Failed to create file: {}", dest_path.display()))?;
+
+    budget.copy(stem, &mut compression.open(archive_path)?, &mut dest_file)?;
+
+    // Set executable permission on Unix
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&dest_path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&dest_path, perms)?;
+    }
+
+    progress(ExtractProgress {
+        entries: 1,
+        total: Some(1),
+    });
+
+    Ok(vec![stem.to_string()])
+}
+
+/// Check whether a decompressed stream starts with a tar header
+fn is_tar_stream(mut reader: impl Read) -> Result<bool> {
+    let mut header = [0u8; 512];
+    let mut filled = 0;
+
+    while filled < header.len() {
+        let n = reader
+            .read(&mut header[filled..])
+            .context("Failed to decompress file")?;
+        if n == 0 {
+            break;
+        }
+        filled += n;
+    }
+
+    // POSIX and GNU tar both put "ustar" at offset 257
+    Ok(filled == header.len() && &header[257..262] == b"ustar")
 }
 
 /// Extract a tar archive (common logic for .tar.gz and .tar.xz)
-fn extract_tar_archive<R: std::io::Read>(
+pub(super) fn extract_tar_archive<R: std::io::Read>(
     archive: &mut Archive<R>,
     dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
 ) -> Result<Vec<String>> {
     let mut extracted_files = Vec::new();
 
-    for entry_result in archive
+    for (index, entry_result) in archive
         .entries()
         .context("Failed to read archive entries")?
+        .enumerate()
     {
         let mut entry = entry_result.context("Failed to read entry")?;
 
         let path = entry.path().context("Failed to get entry path")?;
         let path_str = path.to_string_lossy().to_string();
 
+        let entries = index + 1;
+
         // Skip directories
         if path_str.ends_with('/') {
+            progress(ExtractProgress {
+                entries,
+                total: None,
+            });
             continue;
         }
 
+        // Tar entries carry their size, so oversized files are never written
+        budget.reserve(&path_str, entry.size())?;
+
         // Extract file
         let dest_path = dest_dir.join(&path);
 
@@ -162,6 +585,10 @@ fn extract_tar_archive<R: std::io::Read>(
         }
 
         extracted_files.push(path_str);
+        progress(ExtractProgress {
+            entries,
+            total: None,
+        });
     }
 
     Ok(extracted_files)
@@ -170,21 +597,26 @@ fn extract_tar_archive<R: std::io::Read>(
 /// Check if a tar entry is executable
 #[cfg(unix)]
 fn is_executable<R: std::io::Read>(entry: &mut tar::Entry<R>) -> Result<bool> {
-    use std::os::unix::fs::PermissionsExt;
     let mode = entry.header().mode()?;
     Ok(mode & 0o111 != 0)
 }
 
 /// Extract a .zip file
-fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
+fn extract_zip(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
     let file = File::open(archive_path)
         .with_context(|| format!("Failed to open archive: {}", archive_path.display()))?;
 
     let mut archive = ZipArchive::new(file).context("Failed to read ZIP archive")?;
 
     let mut extracted_files = Vec::new();
+    let total = archive.len();
 
-    for i in 0..archive.len() {
+    for i in 0..total {
         let mut file = archive.by_index(i).context("Failed to read ZIP entry")?;
 
         let file_path = file
@@ -193,9 +625,14 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
             .to_owned();
 
         let dest_path = dest_dir.join(&file_path);
+        let entry_progress = ExtractProgress {
+            entries: i + 1,
+            total: Some(total),
+        };
 
         if file.is_dir() {
             fs::create_dir_all(&dest_path)?;
+            progress(entry_progress);
             continue;
         }
 
@@ -208,7 +645,8 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
         let mut dest_file = File::create(&dest_path)
             .with_context(|| format!("Failed to create file: {}", dest_path.display()))?;
 
-        std::io::copy(&mut file, &mut dest_file).context("Failed to extract file")?;
+        // The recorded size may lie, so the data itself is counted
+        budget.copy(&file_path.to_string_lossy(), &mut file, &mut dest_file)?;
 
         // Set executable permission on Unix
         #[cfg(unix)]
@@ -224,11 +662,129 @@ fn extract_zip(archive_path: &Path, dest_dir: &Path) -> Result<Vec<String>> {
         }
 
         extracted_files.push(file_path.to_string_lossy().to_string());
+        progress(entry_progress);
     }
 
     Ok(extracted_files)
 }
 
+/// Extract a .7z file
+///
+/// Only archives without a password are supported; encrypted ones are
+/// refused with a clear message rather than a decoder error.
+fn extract_7z(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let seven_z_error = |e: sevenz_rust::Error| -> anyhow::Error {
+        let encrypted = match e {
+            sevenz_rust::Error::PasswordRequired | sevenz_rust::Error::MaybeBadPassword(_) => true,
+            sevenz_rust::Error::UnsupportedCompressionMethod(ref method) => {
+                method.starts_with("AES")
+            }
+            _ => false,
+        };
+        let reason = if encrypted {
+            "the archive is password-protected; only unencrypted .7z archives are supported"
+                .to_string()
+        } else {
+            e.to_string()
+        };
+        WengetError::ExtractFailed {
+            archive: archive_path.display().to_string(),
+            reason,
+        }
+        .into()
+    };
+
+    let mut reader = sevenz_rust::SevenZReader::open(archive_path, sevenz_rust::Password::empty())
+        .map_err(seven_z_error)?;
+    let total = reader.archive().files.len();
+
+    let mut extracted_files = Vec::new();
+    let mut entries = 0;
+    // Errors of our own (size limits, bad paths) stop the walk and are
+    // reported as they are
+    let mut failed = None;
+    let walked = reader.for_each_entries(|entry, data| {
+        entries += 1;
+        match extract_7z_entry(entry, data, dest_dir, budget) {
+            Ok(Some(file)) => extracted_files.push(file),
+            Ok(None) => {}
+            Err(e) => {
+                failed = Some(e);
+                return Ok(false);
+            }
+        }
+        progress(ExtractProgress {
+            entries,
+            total: Some(total),
+        });
+        Ok(true)
+    });
+    if let Some(e) = failed {
+        return Err(e);
+    }
+    walked.map_err(seven_z_error)?;
+
+    Ok(extracted_files)
+}
+
+/// Write one .7z entry below `dest_dir`, returning its path unless it is a
+/// directory
+fn extract_7z_entry(
+    entry: &sevenz_rust::SevenZArchiveEntry,
+    data: &mut dyn Read,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+) -> Result<Option<String>> {
+    let name = entry.name().replace('\\', "/");
+    let relative = Path::new(&name);
+    if relative.components().any(|c| {
+        matches!(
+            c,
+            std::path::Component::ParentDir
+                | std::path::Component::RootDir
+                | std::path::Component::Prefix(_)
+        )
+    }) {
+        anyhow::bail!("Invalid file path in 7z archive: {}", name);
+    }
+    let dest_path = dest_dir.join(relative);
+
+    if entry.is_directory() {
+        fs::create_dir_all(&dest_path)?;
+        return Ok(None);
+    }
+
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut dest_file = File::create(&dest_path)
+        .with_context(|| format!("Failed to create file: {}", dest_path.display()))?;
+    budget.copy(&name, data, &mut dest_file)?;
+
+    // 7-Zip on Unix keeps the file mode in the high 16 bits of the attributes
+    #[cfg(unix)]
+    {
+        const UNIX_EXTENSION: u32 = 0x8000;
+        let attributes = entry.windows_attributes();
+        if entry.has_windows_attributes
+            && attributes & UNIX_EXTENSION != 0
+            && (attributes >> 16) & 0o111 != 0
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = fs::metadata(&dest_path)?.permissions();
+            perms.set_mode(0o755);
+            fs::set_permissions(&dest_path, perms)?;
+        }
+    }
+
+    Ok(Some(name))
+}
+
 /// Candidate executable with priority score
 #[derive(Debug, Clone)]
 pub struct ExecutableCandidate {
@@ -435,6 +991,12 @@ pub fn find_executable_candidates(
             reasons.push("in bin/ directory");
         }
 
+        // Rule 4b: Main executable of a macOS app bundle
+        if file.contains(".app/Contents/MacOS/") {
+            score += 40;
+            reasons.push("in app bundle");
+        }
+
         // Rule 5: Located in target/release/ (Rust projects)
         if file.contains("target/release/") {
             score += 25;
@@ -475,7 +1037,7 @@ pub fn find_executable_candidates(
     }
 
     // Sort by score (highest first)
-    candidates.sort_by(|a, b| b.score.cmp(&a.score));
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.score));
 
     candidates
 }
@@ -508,6 +1070,35 @@ pub fn find_executable(extracted_files: &[String], package_name: &str) -> Option
     candidates.first().map(|c| c.path.clone())
 }
 
+/// Find the file whose name is exactly `exe_name` (a `.exe` suffix is
+/// ignored), preferring the one closest to the archive root
+///
+/// Used when the manifest or `--exe-name` names the executable, so archives
+/// whose binary differs from the package name need no guessing.
+pub fn find_named_executable(extracted_files: &[String], exe_name: &str) -> Option<String> {
+    let exe_name = exe_name.trim_end_matches(".exe");
+    extracted_files
+        .iter()
+        .filter(|file| {
+            Path::new(file)
+                .file_name()
+                .and_then(|s| s.to_str())
+                .is_some_and(|name| {
+                    let stem = name
+                        .len()
+                        .checked_sub(4)
+                        .filter(|&i| {
+                            name.get(i..)
+                                .is_some_and(|ext| ext.eq_ignore_ascii_case(".exe"))
+                        })
+                        .map_or(name, |i| &name[..i]);
+                    stem == exe_name
+                })
+        })
+        .min_by_key(|file| file.matches(['/', '\\']).count())
+        .cloned()
+}
+
 /// Normalize a command name by removing platform-specific suffixes
 ///
 /// Strategy: Check if filename contains platform keywords. If yes, remove everything
@@ -549,6 +1140,333 @@ pub fn normalize_command_name(name: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression as GzLevel;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Write `data` gzip-compressed to `dir/name`
+    fn write_gz_fixture(dir: &Path, name: &str, data: &[u8]) -> std::path::PathBuf {
+        let path = dir.join(name);
+        let mut encoder = GzEncoder::new(File::create(&path).unwrap(), GzLevel::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap();
+        path
+    }
+
+    /// A gzipped tarball holding an executable at `tool/bin/tool`
+    fn tar_gz_bytes() -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let contents = b"#!/bin/sh\necho hi\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "tool/bin/tool", &contents[..])
+            .unwrap();
+
+        let mut encoder = GzEncoder::new(Vec::new(), GzLevel::default());
+        encoder.write_all(&builder.into_inner().unwrap()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    /// A zip archive holding `data` as its only member, `name`
+    fn zip_bytes(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        writer
+            .start_file(name, zip::write::FileOptions::default())
+            .unwrap();
+        writer.write_all(data).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_compression_from_filename() {
+        assert_eq!(
+            Compression::from_filename("tool-linux.gz"),
+            Some((Compression::Gzip, "tool-linux"))
+        );
+        assert_eq!(
+            Compression::from_filename("tool.exe.bz2"),
+            Some((Compression::Bzip2, "tool.exe"))
+        );
+        assert_eq!(
+            Compression::from_filename("tool.xz"),
+            Some((Compression::Xz, "tool"))
+        );
+        assert_eq!(Compression::from_filename("tool.zip"), None);
+        assert_eq!(Compression::from_filename(".gz"), None);
+    }
+
+    #[test]
+    fn test_extract_gzip_bare_binary() {
+        let temp_dir = TempDir::new().unwrap();
+        let binary = b"\x7fELF fake executable contents";
+        let archive = write_gz_fixture(temp_dir.path(), "tool-x86_64-linux.gz", binary);
+        let dest = temp_dir.path().join("app");
+
+        let files = extract_archive(&archive, &dest).unwrap();
+
+        assert_eq!(files, vec!["tool-x86_64-linux".to_string()]);
+        let extracted = dest.join("tool-x86_64-linux");
+        assert_eq!(fs::read(&extracted).unwrap(), binary);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&extracted).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    #[test]
+    fn test_extract_gzip_wrapped_tar() {
+        let temp_dir = TempDir::new().unwrap();
+
+        // A tarball that was named with a bare .gz extension
+        let mut builder = tar::Builder::new(Vec::new());
+        let contents = b"#!/bin/sh\necho hi\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "tool/bin/tool", &contents[..])
+            .unwrap();
+        let tar_bytes = builder.into_inner().unwrap();
+
+        let archive = write_gz_fixture(temp_dir.path(), "tool.gz", &tar_bytes);
+        let dest = temp_dir.path().join("app");
+
+        let files = extract_archive(&archive, &dest).unwrap();
+
+        assert_eq!(files, vec!["tool/bin/tool".to_string()]);
+        assert_eq!(fs::read(dest.join("tool/bin/tool")).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_extract_zip_reports_progress() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("tool.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::FileOptions::default();
+        writer.add_directory("tool/", options).unwrap();
+        for name in ["tool/tool", "tool/README.md"] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(b"contents").unwrap();
+        }
+        writer.finish().unwrap();
+
+        let mut reports = Vec::new();
+        let files =
+            extract_archive_with_progress(&archive, &temp_dir.path().join("app"), &mut |p| {
+                reports.push(p)
+            })
+            .unwrap();
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(reports.len(), 3);
+        assert_eq!(
+            reports.last(),
+            Some(&ExtractProgress {
+                entries: 3,
+                total: Some(3)
+            })
+        );
+    }
+
+    /// A 7z archive holding `data` as its only member, `name`, encrypted
+    /// with `password` if given
+    fn seven_z_bytes(name: &str, data: &[u8], password: Option<&str>) -> Vec<u8> {
+        let mut writer = sevenz_rust::SevenZWriter::new(std::io::Cursor::new(Vec::new())).unwrap();
+        if let Some(password) = password {
+            writer.set_content_methods(vec![
+                sevenz_rust::AesEncoderOptions::new(password.into()).into(),
+                sevenz_rust::SevenZMethod::LZMA2.into(),
+            ]);
+        }
+        let mut entry = sevenz_rust::SevenZArchiveEntry::new();
+        entry.name = name.to_string();
+        entry.has_stream = true;
+        writer.push_archive_entry(entry, Some(data)).unwrap();
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_extract_7z_single_binary() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("tool-x86_64.7z");
+        let binary = b"\x7fELF fake executable contents";
+        fs::write(&archive, seven_z_bytes("tool/bin/tool", binary, None)).unwrap();
+        let dest = temp_dir.path().join("app");
+
+        let mut reported = Vec::new();
+        let files =
+            extract_archive_with_progress(&archive, &dest, &mut |p| reported.push(p)).unwrap();
+
+        assert_eq!(files, vec!["tool/bin/tool".to_string()]);
+        assert_eq!(find_executable(&files, "tool").unwrap(), "tool/bin/tool");
+        assert_eq!(fs::read(dest.join("tool/bin/tool")).unwrap(), binary);
+        assert_eq!(
+            reported,
+            vec![ExtractProgress {
+                entries: 1,
+                total: Some(1)
+            }]
+        );
+    }
+
+    #[test]
+    fn test_extract_7z_refuses_encrypted() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("tool.7z");
+        fs::write(&archive, seven_z_bytes("tool.exe", b"MZ", Some("secret"))).unwrap();
+        let dest = temp_dir.path().join("app");
+
+        let err = extract_archive(&archive, &dest).unwrap_err();
+        assert!(format!("{:#}", err).contains("password-protected"));
+        assert!(matches!(
+            WengetError::find(&err),
+            Some(WengetError::ExtractFailed { .. })
+        ));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_extract_tar_in_zip() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("tool-windows.zip");
+        fs::write(&archive, zip_bytes("tool-1.0.tar.gz", &tar_gz_bytes())).unwrap();
+        let dest = temp_dir.path().join("app");
+
+        let files = extract_archive(&archive, &dest).unwrap();
+
+        assert_eq!(files, vec!["tool/bin/tool".to_string()]);
+        assert_eq!(find_executable(&files, "tool").unwrap(), "tool/bin/tool");
+        assert!(dest.join("tool/bin/tool").is_file());
+        // The inner archive and its holding directory are gone
+        assert!(!dest.join("tool-1.0.tar.gz").exists());
+        assert!(!dest.join(".wenget-nested").exists());
+    }
+
+    #[test]
+    fn test_nested_archives_stop_at_depth_limit() {
+        let temp_dir = TempDir::new().unwrap();
+        let inner = zip_bytes("tool-1.0.tar.gz", &tar_gz_bytes());
+        let archive = temp_dir.path().join("tool.zip");
+        fs::write(&archive, zip_bytes("inner.zip", &inner)).unwrap();
+        let dest = temp_dir.path().join("app");
+
+        let files = extract_archive(&archive, &dest).unwrap();
+
+        // Two levels are unpacked; the third is left as a file
+        assert_eq!(files, vec!["tool-1.0.tar.gz".to_string()]);
+        assert!(dest.join("tool-1.0.tar.gz").is_file());
+    }
+
+    #[test]
+    fn test_is_nested_archive() {
+        assert!(is_nested_archive("tool-1.0.tar.gz"));
+        assert!(is_nested_archive("dist/tool.zip"));
+        assert!(is_nested_archive("tool.xz"));
+        assert!(!is_nested_archive("tool.deb"));
+        assert!(!is_nested_archive("bin/tool"));
+    }
+
+    #[test]
+    fn test_compressed_bomb_is_refused() {
+        let temp_dir = TempDir::new().unwrap();
+        // 8 MiB of zeros compress to a few KiB
+        let archive = write_gz_fixture(temp_dir.path(), "tool.gz", &vec![0u8; 8 << 20]);
+        let dest = temp_dir.path().join("app");
+
+        let mut budget = ExtractBudget::with_limits(1 << 20, 4 << 20);
+        let err = extract_within(&archive, &dest, &mut budget, &mut |_| {}).unwrap_err();
+
+        assert!(format!("{:#}", err).contains("max_extract_size"));
+        // Nothing is left behind
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_size_limits_per_file_and_total() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("tool.zip");
+        let mut writer = zip::ZipWriter::new(File::create(&archive).unwrap());
+        for name in ["a", "b"] {
+            writer
+                .start_file(name, zip::write::FileOptions::default())
+                .unwrap();
+            writer.write_all(&vec![0u8; 600 << 10]).unwrap();
+        }
+        writer.finish().unwrap();
+        let dest = temp_dir.path().join("app");
+
+        // Each file fits, but together they are too much
+        let mut budget = ExtractBudget::with_limits(1 << 20, 1 << 20);
+        let err = extract_within(&archive, &dest, &mut budget, &mut |_| {}).unwrap_err();
+        assert!(format!("{:#}", err).contains("max_extract_size"));
+        assert!(!dest.exists());
+
+        let mut budget = ExtractBudget::with_limits(4 << 20, 512 << 10);
+        let err = extract_within(&archive, &dest, &mut budget, &mut |_| {}).unwrap_err();
+        assert!(format!("{:#}", err).contains("a is larger than"));
+
+        let mut budget = ExtractBudget::with_limits(4 << 20, 1 << 20);
+        let files = extract_within(&archive, &dest, &mut budget, &mut |_| {}).unwrap();
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn test_budget_reserve() {
+        let mut budget = ExtractBudget::with_limits(1000, 100);
+        assert!(budget.reserve("small", 100).is_ok());
+        assert!(budget.reserve("big", 101).is_err());
+        for _ in 0..8 {
+            budget.reserve("more", 100).unwrap();
+        }
+        assert!(budget.reserve("last", 100).is_ok());
+        assert!(budget.reserve("over", 1).is_err());
+    }
+
+    #[test]
+    fn test_extract_stops_at_deadline() {
+        let temp_dir = TempDir::new().unwrap();
+        let archive = temp_dir.path().join("tool.tar.gz");
+        fs::write(&archive, tar_gz_bytes()).unwrap();
+        let dest = temp_dir.path().join("app");
+
+        let deadline = Deadline::after(std::time::Duration::ZERO);
+        let err = extract_archive_until(&archive, &dest, Some(deadline), &mut |_| {}).unwrap_err();
+        assert!(format!("{:#}", err).contains("Timed out after 0s"));
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn test_find_executable_in_app_bundle() {
+        let files = vec![
+            "Tool.app/Contents/Info.plist".to_string(),
+            "Tool.app/Contents/Frameworks/Helper.framework/Helper".to_string(),
+            "Tool.app/Contents/MacOS/tool".to_string(),
+            "Tool.app/Contents/Resources/icon.icns".to_string(),
+        ];
+
+        let candidates = find_executable_candidates(&files, "tool", None);
+        assert_eq!(candidates[0].path, "Tool.app/Contents/MacOS/tool");
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_dmg_rejected_off_macos() {
+        let temp_dir = TempDir::new().unwrap();
+        let image = temp_dir.path().join("tool-macos.dmg");
+        fs::write(&image, b"not really a disk image").unwrap();
+
+        let err = extract_archive(&image, &temp_dir.path().join("app")).unwrap_err();
+        assert!(format!("{:#}", err).contains("only be installed on macOS"));
+    }
 
     #[test]
     fn test_find_executable() {
@@ -562,6 +1480,26 @@ mod tests {
         assert_eq!(exe, Some("ripgrep-15.1.0/bin/rg.exe".to_string()));
     }
 
+    #[test]
+    fn test_find_named_executable() {
+        let files = vec![
+            "tool-1.0/complete/tool-cli.bash".to_string(),
+            "tool-1.0/extras/bin/tool-cli".to_string(),
+            "tool-1.0/tool-cli.EXE".to_string(),
+            "tool-1.0/tool".to_string(),
+        ];
+
+        assert_eq!(
+            find_named_executable(&files, "tool-cli"),
+            Some("tool-1.0/tool-cli.EXE".to_string())
+        );
+        assert_eq!(
+            find_named_executable(&files, "tool-cli.exe"),
+            Some("tool-1.0/tool-cli.EXE".to_string())
+        );
+        assert_eq!(find_named_executable(&files, "cli"), None);
+    }
+
     #[test]
     #[cfg(unix)]
     fn test_find_executable_ripgrep_linux() {
@@ -0,0 +1,148 @@
+//! Launchers for Unix that don't rely on a symlink into the apps directory
+//!
+//! `copy` places a copy of the executable in the bin directory, so it keeps
+//! working when the apps directory is unavailable. `wrapper` writes a small
+//! `sh` script that execs the executable, much like the Windows `.cmd` shim.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// First line of every wrapper script after the shebang, used to recognize them
+const WRAPPER_MARKER: &str = "# Launcher created by wenget";
+
+/// Remove whatever is at `launcher` and make sure its directory exists
+///
+/// A previous symlink must go first, or writing to the path would write
+/// through it into the installed executable.
+fn prepare(launcher: &Path) -> Result<()> {
+    if launcher.exists() || launcher.is_symlink() {
+        fs::remove_file(launcher).with_context(|| {
+            format!("Failed to remove existing launcher: {}", launcher.display())
+        })?;
+    }
+    if let Some(parent) = launcher.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(())
+}
+
+/// Make a file executable by everyone who can read it
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(path)?.permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+/// Place a copy of `target` at `launcher`
+pub fn copy_executable(target: &Path, launcher: &Path) -> Result<()> {
+    log::debug!(
+        "Copying launcher: {} -> {}",
+        target.display(),
+        launcher.display()
+    );
+
+    prepare(launcher)?;
+    fs::copy(target, launcher).with_context(|| {
+        format!(
+            "Failed to copy {} to {}",
+            target.display(),
+            launcher.display()
+        )
+    })?;
+    make_executable(launcher)
+}
+
+/// Write an `sh` script at `launcher` that runs `target` with its arguments
+pub fn create_wrapper(target: &Path, launcher: &Path) -> Result<()> {
+    log::debug!(
+        "Creating wrapper: {} -> {}",
+        launcher.display(),
+        target.display()
+    );
+
+    // Inside double quotes only these characters are special to sh
+    let mut quoted = String::new();
+    for c in target.to_string_lossy().chars() {
+        if matches!(c, '"' | '\\' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+
+    prepare(launcher)?;
+    fs::write(
+        launcher,
+        format!(
+            "#!/bin/sh\n{}\nexec \"{}\" \"$@\"\n",
+            WRAPPER_MARKER, quoted
+        ),
+    )
+    .with_context(|| format!("Failed to create wrapper: {}", launcher.display()))?;
+    make_executable(launcher)
+}
+
+/// The executable a wrapper script created by [`create_wrapper`] runs
+pub fn wrapper_target(launcher: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(launcher).ok()?;
+    if !content.contains(WRAPPER_MARKER) {
+        return None;
+    }
+
+    let quoted = content
+        .lines()
+        .find_map(|line| line.strip_prefix("exec \""))?
+        .strip_suffix("\" \"$@\"")?;
+    let mut target = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        target.push(if c == '\\' { chars.next()? } else { c });
+    }
+    Some(PathBuf::from(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_copy_executable_replaces_symlink() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("apps").join("tool");
+        fs::create_dir_all(target.parent().unwrap()).unwrap();
+        fs::write(&target, "v1").unwrap();
+
+        // The copy must not write through an existing symlink to the target
+        let launcher = temp_dir.path().join("bin").join("tool");
+        crate::installer::create_symlink(&target, &launcher).unwrap();
+        copy_executable(&target, &launcher).unwrap();
+
+        assert!(!launcher.is_symlink());
+        assert_eq!(fs::read_to_string(&launcher).unwrap(), "v1");
+        assert_eq!(fs::read_to_string(&target).unwrap(), "v1");
+        let mode = fs::metadata(&launcher).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+        assert_eq!(wrapper_target(&launcher), None);
+    }
+
+    #[test]
+    fn test_create_wrapper() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("my \"apps\"").join("$tool");
+        let launcher = temp_dir.path().join("bin").join("tool");
+
+        create_wrapper(&target, &launcher).unwrap();
+        let content = fs::read_to_string(&launcher).unwrap();
+        assert!(content.starts_with("#!/bin/sh\n"));
+        assert!(content.contains("\\\"apps\\\"/\\$tool\" \"$@\""));
+        assert_eq!(wrapper_target(&launcher), Some(target));
+
+        let mode = fs::metadata(&launcher).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+}
@@ -0,0 +1,399 @@
+//! Payload extraction for .deb and .rpm packages
+//!
+//! Some projects only publish Linux packages. Their files are unpacked into
+//! the app directory like any other archive; maintainer scripts, declared
+//! dependencies and the system package database are ignored.
+
+use super::extractor::{extract_tar_archive, ExtractBudget, ExtractProgress};
+use anyhow::{Context, Result};
+use bzip2::read::BzDecoder;
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Component, Path};
+use tar::Archive;
+use xz2::read::XzDecoder;
+
+/// Check whether a file is a .deb or .rpm package
+pub fn is_linux_package(filename: &str) -> bool {
+    let lower = filename.to_lowercase();
+    lower.ends_with(".deb") || lower.ends_with(".rpm")
+}
+
+/// Extract the `data.tar` member of a .deb package
+pub(super) fn extract_deb(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let file = File::open(archive_path)
+        .with_context(|| format!("Failed to open package: {}", archive_path.display()))?;
+    let mut archive = ar::Archive::new(file);
+
+    while let Some(entry) = archive.next_entry() {
+        let entry = entry.context("Failed to read .deb member")?;
+        let name = String::from_utf8_lossy(entry.header().identifier()).to_string();
+
+        if !name.starts_with("data.tar") {
+            continue;
+        }
+
+        let reader: Box<dyn Read + '_> = match name.rsplit_once('.') {
+            Some((_, "gz")) => Box::new(GzDecoder::new(entry)),
+            Some((_, "xz")) => Box::new(XzDecoder::new(entry)),
+            Some((_, "bz2")) => Box::new(BzDecoder::new(entry)),
+            Some((_, "zst")) => Box::new(zstd::Decoder::new(entry)?),
+            Some((_, "tar")) => Box::new(entry),
+            _ => anyhow::bail!("Unsupported .deb data member: {}", name),
+        };
+
+        return extract_tar_archive(&mut Archive::new(reader), dest_dir, budget, progress);
+    }
+
+    anyhow::bail!("No data.tar member found in {}", archive_path.display())
+}
+
+/// Magic bytes at the start of an RPM file
+const RPM_MAGIC: [u8; 4] = [0xed, 0xab, 0xee, 0xdb];
+
+/// Size of the legacy lead before the signature header
+const RPM_LEAD_SIZE: usize = 96;
+
+/// Magic bytes at the start of each RPM header structure
+const RPM_HEADER_MAGIC: [u8; 3] = [0x8e, 0xad, 0xe8];
+
+/// Extract the cpio payload of an .rpm package
+pub(super) fn extract_rpm(
+    archive_path: &Path,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let file = File::open(archive_path)
+        .with_context(|| format!("Failed to open package: {}", archive_path.display()))?;
+    let mut reader = BufReader::new(file);
+
+    let mut lead = [0u8; RPM_LEAD_SIZE];
+    reader
+        .read_exact(&mut lead)
+        .context("Failed to read RPM lead")?;
+    if lead[..4] != RPM_MAGIC {
+        anyhow::bail!("Not an RPM package");
+    }
+
+    // The signature header is padded to a multiple of 8 bytes
+    let signature_len = skip_rpm_header(&mut reader)?;
+    skip_bytes(&mut reader, ((8 - signature_len % 8) % 8) as u64)?;
+    skip_rpm_header(&mut reader)?;
+
+    // The rest is a (usually compressed) cpio archive
+    let magic = reader.fill_buf().context("Failed to read RPM payload")?;
+    let payload: Box<dyn Read> = if magic.starts_with(&[0x1f, 0x8b]) {
+        Box::new(GzDecoder::new(reader))
+    } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z']) {
+        Box::new(XzDecoder::new(reader))
+    } else if magic.starts_with(b"BZh") {
+        Box::new(BzDecoder::new(reader))
+    } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Box::new(zstd::Decoder::new(reader)?)
+    } else if magic.starts_with(b"0707") {
+        Box::new(reader)
+    } else {
+        anyhow::bail!("Unsupported RPM payload compression");
+    };
+
+    extract_cpio(payload, dest_dir, budget, progress)
+}
+
+/// Skip one RPM header structure, returning its size in bytes
+fn skip_rpm_header(reader: &mut impl Read) -> Result<usize> {
+    let mut intro = [0u8; 16];
+    reader
+        .read_exact(&mut intro)
+        .context("Failed to read RPM header")?;
+    if intro[..3] != RPM_HEADER_MAGIC {
+        anyhow::bail!("Corrupted RPM header");
+    }
+
+    let index_count = u32::from_be_bytes([intro[8], intro[9], intro[10], intro[11]]) as u64;
+    let data_size = u32::from_be_bytes([intro[12], intro[13], intro[14], intro[15]]) as u64;
+    let len = index_count * 16 + data_size;
+    skip_bytes(reader, len)?;
+
+    Ok(16 + len as usize)
+}
+
+/// Read and discard exactly `count` bytes
+fn skip_bytes(reader: &mut impl Read, count: u64) -> Result<()> {
+    let skipped = std::io::copy(&mut reader.take(count), &mut std::io::sink())?;
+    if skipped != count {
+        anyhow::bail!("Unexpected end of package");
+    }
+    Ok(())
+}
+
+/// Extract a cpio archive in the "newc" format used by RPM payloads
+fn extract_cpio(
+    mut reader: impl Read,
+    dest_dir: &Path,
+    budget: &mut ExtractBudget,
+    progress: &mut dyn FnMut(ExtractProgress),
+) -> Result<Vec<String>> {
+    let mut extracted_files = Vec::new();
+    let mut entries = 0;
+
+    loop {
+        let mut header = [0u8; 110];
+        reader
+            .read_exact(&mut header)
+            .context("Failed to read cpio header")?;
+        if &header[..6] != b"070701" && &header[..6] != b"070702" {
+            anyhow::bail!("Unsupported cpio format in RPM payload");
+        }
+
+        // Header fields are 8-digit hex numbers following the magic
+        let field = |index: usize| -> Result<u64> {
+            let start = 6 + index * 8;
+            let text = std::str::from_utf8(&header[start..start + 8])?;
+            Ok(u64::from_str_radix(text, 16)?)
+        };
+        let mode = field(1)? as u32;
+        let size = field(6)?;
+        let name_size = field(11)? as usize;
+
+        let mut name = vec![0u8; name_size];
+        reader
+            .read_exact(&mut name)
+            .context("Failed to read cpio entry name")?;
+        skip_bytes(&mut reader, ((4 - (110 + name_size) % 4) % 4) as u64)?;
+
+        let name = String::from_utf8_lossy(name.strip_suffix(&[0]).unwrap_or(&name)).to_string();
+        if name == "TRAILER!!!" {
+            break;
+        }
+
+        let relative = name.trim_start_matches("./").trim_start_matches('/');
+        let relative_path = Path::new(relative);
+        if relative_path
+            .components()
+            .any(|c| matches!(c, Component::ParentDir))
+        {
+            anyhow::bail!(
+                "Refusing to extract path outside the app directory: {}",
+                name
+            );
+        }
+        let dest_path = dest_dir.join(relative_path);
+
+        match mode & 0o170000 {
+            // Regular file
+            0o100000 => {
+                budget.reserve(relative, size)?;
+                if let Some(parent) = dest_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut dest_file = File::create(&dest_path)
+                    .with_context(|| format!("Failed to create file: {}", dest_path.display()))?;
+                let copied = std::io::copy(&mut (&mut reader).take(size), &mut dest_file)
+                    .with_context(|| format!("Failed to extract: {}", relative))?;
+                if copied != size {
+                    anyhow::bail!("Unexpected end of package while extracting {}", relative);
+                }
+
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&dest_path, fs::Permissions::from_mode(mode & 0o777))?;
+                }
+
+                extracted_files.push(relative.to_string());
+            }
+            // Symbolic link, the target is stored as the file data
+            #[cfg(unix)]
+            0o120000 => {
+                let mut target = vec![0u8; size as usize];
+                reader.read_exact(&mut target)?;
+                if let Some(parent) = dest_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let target = String::from_utf8_lossy(&target).to_string();
+                std::os::unix::fs::symlink(&target, &dest_path).with_context(|| {
+                    format!("Failed to create symlink: {}", dest_path.display())
+                })?;
+            }
+            // Directories are created with their files; skip anything else
+            _ => skip_bytes(&mut reader, size)?,
+        }
+        skip_bytes(&mut reader, (4 - size % 4) % 4)?;
+
+        entries += 1;
+        progress(ExtractProgress {
+            entries,
+            total: None,
+        });
+    }
+
+    Ok(extracted_files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    /// Build a gzip-compressed tarball holding `./usr/bin/tool`
+    fn data_tar_gz(contents: &[u8]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_mode(0o755);
+        header.set_size(0);
+        builder
+            .append_data(&mut header, "./usr/bin/", std::io::empty())
+            .unwrap();
+
+        let mut header = tar::Header::new_gnu();
+        header.set_mode(0o755);
+        header.set_size(contents.len() as u64);
+        builder
+            .append_data(&mut header, "./usr/bin/tool", contents)
+            .unwrap();
+
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    /// Write a minimal .deb: debian-binary, control.tar.gz and data.tar.gz
+    fn write_deb_fixture(path: &Path, contents: &[u8]) {
+        let control = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()))
+            .into_inner()
+            .unwrap()
+            .finish()
+            .unwrap();
+
+        let mut builder = ar::Builder::new(File::create(path).unwrap());
+        for (name, data) in [
+            ("debian-binary", b"2.0\n".to_vec()),
+            ("control.tar.gz", control),
+            ("data.tar.gz", data_tar_gz(contents)),
+        ] {
+            let header = ar::Header::new(name.as_bytes().to_vec(), data.len() as u64);
+            builder.append(&header, data.as_slice()).unwrap();
+        }
+    }
+
+    /// Append one newc cpio entry
+    fn cpio_entry(out: &mut Vec<u8>, name: &str, mode: u32, data: &[u8]) {
+        let name_size = name.len() + 1;
+        let fields = [
+            0,
+            mode,
+            0,
+            0,
+            1,
+            0,
+            data.len() as u32,
+            0,
+            0,
+            0,
+            0,
+            name_size as u32,
+            0,
+        ];
+        out.extend_from_slice(b"070701");
+        for value in fields {
+            out.extend_from_slice(format!("{:08x}", value).as_bytes());
+        }
+        out.extend_from_slice(name.as_bytes());
+        out.push(0);
+        out.resize(out.len() + (4 - (110 + name_size) % 4) % 4, 0);
+        out.extend_from_slice(data);
+        out.resize(out.len() + (4 - data.len() % 4) % 4, 0);
+    }
+
+    /// Write a minimal .rpm with empty headers and a gzip cpio payload
+    fn write_rpm_fixture(path: &Path, contents: &[u8]) {
+        let mut cpio = Vec::new();
+        cpio_entry(&mut cpio, "./usr/bin", 0o040755, b"");
+        cpio_entry(&mut cpio, "./usr/bin/tool", 0o100755, contents);
+        cpio_entry(&mut cpio, "TRAILER!!!", 0, b"");
+
+        let mut payload = GzEncoder::new(Vec::new(), Compression::default());
+        payload.write_all(&cpio).unwrap();
+
+        let mut rpm = vec![0u8; RPM_LEAD_SIZE];
+        rpm[..4].copy_from_slice(&RPM_MAGIC);
+        for _ in 0..2 {
+            // Header with no index entries and no data
+            rpm.extend_from_slice(&[0x8e, 0xad, 0xe8, 0x01, 0, 0, 0, 0]);
+            rpm.extend_from_slice(&[0u8; 8]);
+        }
+        rpm.extend_from_slice(&payload.finish().unwrap());
+
+        fs::write(path, rpm).unwrap();
+    }
+
+    #[test]
+    fn test_is_linux_package() {
+        assert!(is_linux_package("tool_1.0.0_amd64.deb"));
+        assert!(is_linux_package("tool-1.0.0-1.x86_64.RPM"));
+        assert!(!is_linux_package("tool-linux.tar.gz"));
+    }
+
+    #[test]
+    fn test_extract_deb_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let deb = temp_dir.path().join("tool_1.0.0_amd64.deb");
+        write_deb_fixture(&deb, b"#!/bin/sh\necho tool\n");
+        let dest = temp_dir.path().join("app");
+
+        let files = extract_deb(&deb, &dest, &mut ExtractBudget::new(), &mut |_| {}).unwrap();
+
+        assert_eq!(files, vec!["usr/bin/tool".to_string()]);
+        let tool = dest.join("usr/bin/tool");
+        assert_eq!(fs::read(&tool).unwrap(), b"#!/bin/sh\necho tool\n");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&tool).unwrap().permissions().mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+    }
+
+    #[test]
+    fn test_extract_rpm_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let rpm = temp_dir.path().join("tool-1.0.0-1.x86_64.rpm");
+        write_rpm_fixture(&rpm, b"\x7fELF tool");
+        let dest = temp_dir.path().join("app");
+
+        let files = extract_rpm(&rpm, &dest, &mut ExtractBudget::new(), &mut |_| {}).unwrap();
+
+        assert_eq!(files, vec!["usr/bin/tool".to_string()]);
+        assert_eq!(
+            fs::read(dest.join("usr/bin/tool")).unwrap(),
+            b"\x7fELF tool"
+        );
+    }
+
+    #[test]
+    fn test_extract_rpm_rejects_other_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fake.rpm");
+        fs::write(&path, vec![0u8; 200]).unwrap();
+
+        let err = extract_rpm(
+            &path,
+            &temp_dir.path().join("app"),
+            &mut ExtractBudget::new(),
+            &mut |_| {},
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Not an RPM package"));
+    }
+}
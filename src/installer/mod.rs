@@ -1,19 +1,31 @@
 //! Installer module for WenPM
 
+#[cfg(target_os = "macos")]
+pub mod dmg;
 pub mod extractor;
+#[cfg(unix)]
+pub mod launcher;
+pub mod linux_package;
 pub mod script;
 pub mod shim;
+pub mod staging;
 pub mod symlink;
 
 // Re-export commonly used items
 pub use extractor::{
-    extract_archive, find_executable, find_executable_candidates, normalize_command_name,
+    extract_archive, extract_archive_until, find_executable, find_executable_candidates,
+    find_named_executable, normalize_command_name,
 };
 pub use script::{
     create_script_shim, detect_script_type, download_script, extract_script_name, install_script,
     is_script_input, read_local_script,
 };
-pub use shim::create_shim;
+pub use staging::{prune_versions, same_filesystem, swap_into_place};
+
+#[cfg(windows)]
+pub use shim::{create_shim, shim_target};
 
+#[cfg(unix)]
+pub use launcher::{copy_executable, create_wrapper, wrapper_target};
 #[cfg(unix)]
 pub use symlink::create_symlink;
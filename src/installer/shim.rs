@@ -1,9 +1,13 @@
 //! Shim creation for Windows
 
-use anyhow::{Context, Result};
-use std::fs;
+use anyhow::Result;
 use std::path::Path;
 
+#[cfg(windows)]
+use anyhow::Context;
+#[cfg(windows)]
+use std::fs;
+
 /// Create a .cmd shim (Windows only)
 #[cfg(windows)]
 pub fn create_shim(target: &Path, shim: &Path, _name: &str) -> Result<()> {
@@ -30,8 +34,31 @@ pub fn create_shim(target: &Path, shim: &Path, _name: &str) -> Result<()> {
     Ok(())
 }
 
+/// The executable an existing .cmd shim runs (Windows only)
+#[cfg(windows)]
+pub fn shim_target(shim: &Path) -> Option<std::path::PathBuf> {
+    use std::path::Component;
+
+    let content = fs::read_to_string(shim).ok()?;
+    let relative = content.split("\"%~dp0").nth(1)?.split('"').next()?;
+
+    // Resolve the `..` segments so the result compares equal to real paths
+    let mut target = shim.parent()?.to_path_buf();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::ParentDir => {
+                target.pop();
+            }
+            Component::CurDir => {}
+            other => target.push(other),
+        }
+    }
+    Some(target)
+}
+
 /// Placeholder for Unix (uses symlink instead)
 #[cfg(not(windows))]
+#[allow(dead_code)]
 pub fn create_shim(_target: &Path, _shim: &Path, _name: &str) -> Result<()> {
     // On Unix, we use symlinks instead of shims
     Ok(())
@@ -68,5 +95,6 @@ mod tests {
         let content = fs::read_to_string(&shim).unwrap();
         assert!(content.contains("@echo off"));
         assert!(content.contains("test.exe"));
+        assert_eq!(shim_target(&shim), Some(target));
     }
 }
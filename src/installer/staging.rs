@@ -0,0 +1,359 @@
+//! Staged installs
+//!
+//! Packages are extracted into a staging directory first and only moved into
+//! `apps/<name>` once they look usable, so a failed extraction never destroys
+//! a working install. Staging lives under `~/.wenget/cache/staging/`, on the
+//! same filesystem as the apps directory, so each move is a single rename.
+//! A staging directory elsewhere (`--temp-dir`) may sit on another
+//! filesystem, in which case the staged files are copied instead.
+
+use crate::utils::CleanupGuard;
+use anyhow::{Context, Result};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Replace `target` with the staged directory
+///
+/// The current `target` (if any) is moved aside first: to `retain` when
+/// given (to keep it for a rollback), otherwise to `backup`. Until `cleanup`
+/// is committed, dropping it or interrupting wenget puts the previous install
+/// back (or removes a fresh `target`), even half way through copying the new
+/// one in; committing it deletes `backup`. Returns whether the previous
+/// install was kept at `retain`.
+pub fn swap_into_place(
+    staged: &Path,
+    target: &Path,
+    backup: &Path,
+    retain: Option<&Path>,
+    cleanup: &mut CleanupGuard,
+) -> Result<bool> {
+    if backup.exists() {
+        fs::remove_dir_all(backup)
+            .with_context(|| format!("Failed to remove stale backup: {}", backup.display()))?;
+    }
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+
+    let mut retained = false;
+    if target.exists() {
+        if let Some(retain) = retain {
+            match retain_previous(target, retain) {
+                Ok(()) => {
+                    cleanup.track_replaced(target, retain, false);
+                    retained = true;
+                }
+                Err(e) => log::warn!(
+                    "Failed to keep previous install at {}: {:#}",
+                    retain.display(),
+                    e
+                ),
+            }
+        }
+        if !retained {
+            fs::rename(target, backup).with_context(|| {
+                format!(
+                    "Failed to move previous install aside: {}",
+                    target.display()
+                )
+            })?;
+            cleanup.track_replaced(target, backup, true);
+        }
+    } else {
+        cleanup.track(target);
+    }
+
+    move_dir(staged, target)
+        .with_context(|| format!("Failed to move new install into {}", target.display()))?;
+
+    Ok(retained)
+}
+
+/// Move an install that is about to be replaced to its retention directory
+fn retain_previous(target: &Path, retain: &Path) -> Result<()> {
+    if retain.exists() {
+        fs::remove_dir_all(retain)
+            .with_context(|| format!("Failed to remove old copy: {}", retain.display()))?;
+    }
+    if let Some(parent) = retain.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+    }
+    fs::rename(target, retain)?;
+    Ok(())
+}
+
+/// Move a directory, copying it when a rename can't cross filesystems
+fn move_dir(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            log::debug!(
+                "{} is on another filesystem, copying instead of renaming",
+                from.display()
+            );
+            if let Err(e) = copy_dir(from, to) {
+                let _ = fs::remove_dir_all(to);
+                return Err(e);
+            }
+            fs::remove_dir_all(from)
+        }
+        result => result,
+    }
+}
+
+/// Recursively copy a directory, keeping symlinks as symlinks on Unix
+fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let source = entry.path();
+        let dest = to.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            copy_dir(&source, &dest)?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(&source)?, &dest)?;
+            #[cfg(not(unix))]
+            fs::copy(&source, &dest).map(|_| ())?;
+        } else {
+            // fs::copy keeps permission bits, so executables stay executable
+            fs::copy(&source, &dest)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check whether two existing paths live on the same filesystem
+///
+/// Only then is moving between them a cheap, atomic rename.
+pub fn same_filesystem(a: &Path, b: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (fs::metadata(a), fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev(),
+            _ => true,
+        }
+    }
+    #[cfg(windows)]
+    {
+        // Compare drive letters / UNC prefixes
+        let prefix = |p: &Path| {
+            p.canonicalize()
+                .ok()
+                .and_then(|p| p.components().next().map(|c| c.as_os_str().to_owned()))
+        };
+        prefix(a) == prefix(b)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (a, b);
+        true
+    }
+}
+
+/// Drop the oldest retained versions so at most `keep` remain
+///
+/// `versions` lists the versions kept under `dir` (one subdirectory each),
+/// oldest first. The dropped ones are deleted when `cleanup` is committed, so
+/// an install that fails still has them. Returns the versions that are still
+/// kept.
+pub fn prune_versions(
+    dir: &Path,
+    versions: &[String],
+    keep: usize,
+    cleanup: &mut CleanupGuard,
+) -> Vec<String> {
+    let excess = versions.len().saturating_sub(keep);
+    let (pruned, kept) = versions.split_at(excess);
+
+    if kept.is_empty() {
+        // Drop the directory once nothing is retained
+        cleanup.remove_on_commit(dir);
+    } else {
+        for version in pruned {
+            cleanup.remove_on_commit(dir.join(version));
+        }
+    }
+
+    kept.to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_swap_replaces_previous_install() {
+        let temp_dir = TempDir::new().unwrap();
+        let staged = temp_dir.path().join("staging/tool");
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("old"), b"1").unwrap();
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("new"), b"2").unwrap();
+
+        let mut cleanup = CleanupGuard::new();
+        let retained = swap_into_place(&staged, &target, &backup, None, &mut cleanup).unwrap();
+
+        // The previous install is only deleted once the new one is kept
+        assert!(!retained);
+        assert!(target.join("new").exists());
+        assert!(!staged.exists());
+        assert!(backup.join("old").exists());
+        cleanup.commit();
+        assert!(!target.join("old").exists());
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn test_swap_rolled_back_until_committed() {
+        let temp_dir = TempDir::new().unwrap();
+        let staged = temp_dir.path().join("staging/tool");
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+        let retain = temp_dir.path().join("backups/tool/1.0.0");
+
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("old"), b"1").unwrap();
+
+        // Say creating the launchers failed after each swap
+        for retain in [None, Some(retain.as_path())] {
+            fs::create_dir_all(&staged).unwrap();
+            fs::write(staged.join("new"), b"2").unwrap();
+            let mut cleanup = CleanupGuard::new();
+            swap_into_place(&staged, &target, &backup, retain, &mut cleanup).unwrap();
+            assert!(target.join("new").exists());
+            drop(cleanup);
+
+            assert!(target.join("old").exists());
+            assert!(!target.join("new").exists());
+            assert!(!backup.exists());
+            assert!(!retain.is_some_and(Path::exists));
+        }
+    }
+
+    #[test]
+    fn test_swap_failure_keeps_previous_install() {
+        let temp_dir = TempDir::new().unwrap();
+        let staged = temp_dir.path().join("staging/missing");
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("old"), b"1").unwrap();
+
+        let mut cleanup = CleanupGuard::new();
+        assert!(swap_into_place(&staged, &target, &backup, None, &mut cleanup).is_err());
+        drop(cleanup);
+        assert!(target.join("old").exists());
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn test_swap_retains_previous_install() {
+        let temp_dir = TempDir::new().unwrap();
+        let staged = temp_dir.path().join("staging/tool");
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+        let retain = temp_dir.path().join("backups/tool/1.0.0");
+
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("old"), b"1").unwrap();
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("new"), b"2").unwrap();
+
+        let mut cleanup = CleanupGuard::new();
+        let retained =
+            swap_into_place(&staged, &target, &backup, Some(&retain), &mut cleanup).unwrap();
+        cleanup.commit();
+
+        assert!(retained);
+        assert!(target.join("new").exists());
+        assert!(retain.join("old").exists());
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn test_copy_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let from = temp_dir.path().join("staging/tool");
+        let to = temp_dir.path().join("apps/tool");
+
+        fs::create_dir_all(from.join("bin")).unwrap();
+        fs::write(from.join("bin/tool"), b"exe").unwrap();
+        fs::write(from.join("README"), b"docs").unwrap();
+
+        copy_dir(&from, &to).unwrap();
+
+        assert_eq!(fs::read(to.join("bin/tool")).unwrap(), b"exe");
+        assert_eq!(fs::read(to.join("README")).unwrap(), b"docs");
+        assert!(same_filesystem(&from, &to));
+    }
+
+    #[test]
+    fn test_prune_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("backups/tool");
+        let versions: Vec<String> = ["1.0.0", "1.1.0", "1.2.0"]
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        for version in &versions {
+            fs::create_dir_all(dir.join(version)).unwrap();
+        }
+
+        // Nothing is deleted before the install is kept
+        let kept = prune_versions(&dir, &versions, 1, &mut CleanupGuard::new());
+        assert_eq!(kept, vec!["1.2.0".to_string()]);
+        assert!(dir.join("1.0.0").exists());
+
+        // Keeping one leaves only the most recent previous version
+        let mut cleanup = CleanupGuard::new();
+        let kept = prune_versions(&dir, &versions, 1, &mut cleanup);
+        cleanup.commit();
+        assert_eq!(kept, vec!["1.2.0".to_string()]);
+        assert!(!dir.join("1.0.0").exists());
+        assert!(!dir.join("1.1.0").exists());
+        assert!(dir.join("1.2.0").exists());
+
+        // Keeping more than exist changes nothing
+        let mut cleanup = CleanupGuard::new();
+        assert_eq!(prune_versions(&dir, &kept, 3, &mut cleanup), kept);
+        cleanup.commit();
+        assert!(dir.join("1.2.0").exists());
+
+        // Keeping none removes the directory entirely
+        let mut cleanup = CleanupGuard::new();
+        assert!(prune_versions(&dir, &kept, 0, &mut cleanup).is_empty());
+        cleanup.commit();
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_swap_fresh_install() {
+        let temp_dir = TempDir::new().unwrap();
+        let staged = temp_dir.path().join("staging/tool");
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+
+        fs::create_dir_all(&staged).unwrap();
+        fs::write(staged.join("bin"), b"2").unwrap();
+
+        let mut cleanup = CleanupGuard::new();
+        swap_into_place(&staged, &target, &backup, None, &mut cleanup).unwrap();
+        assert!(target.join("bin").exists());
+        cleanup.commit();
+        assert!(target.join("bin").exists());
+    }
+}
@@ -6,13 +6,14 @@ mod cli;
 mod commands;
 mod core;
 mod downloader;
+mod error;
 mod installer;
 mod package_resolver;
 mod providers;
 mod utils;
 
 use clap::CommandFactory;
-use cli::{BucketCommands, Cli, Commands};
+use cli::{BucketCommands, CacheCommands, Cli, Commands, ConfigCommands, SelfCommands};
 use colored::Colorize;
 
 fn main() {
@@ -29,6 +30,64 @@ fn main() {
         log::set_max_level(log::LevelFilter::Debug);
     }
 
+    // Quiet mode hides progress output and informational logs
+    if cli.quiet {
+        utils::output::set_quiet(true);
+        log::set_max_level(log::LevelFilter::Warn);
+    }
+    utils::output::set_utc(cli.utc);
+
+    let settings = core::Config::new()
+        .and_then(|config| config.load_settings())
+        .ok();
+
+    // Worker count for parallel network requests (--concurrency or setting)
+    let concurrency = cli
+        .concurrency
+        .or_else(|| settings.as_ref().and_then(|s| s.concurrency));
+    if let Some(requested) = concurrency {
+        let used = utils::concurrency::set_concurrency(requested);
+        if used != requested {
+            log::warn!("Concurrency {} is out of range, using {}", requested, used);
+        }
+    }
+
+    // Downloads may only be redirected to these hosts, if configured
+    if let Some(hosts) = settings.as_ref().and_then(|s| s.redirect_hosts.clone()) {
+        utils::http::set_redirect_hosts(hosts);
+    }
+
+    // Archives may only expand this far before extraction is aborted
+    if let Some(ref settings) = settings {
+        installer::extractor::set_size_limits(
+            settings
+                .max_extract_size
+                .unwrap_or(installer::extractor::DEFAULT_MAX_EXTRACT_SIZE),
+            settings
+                .max_file_size
+                .unwrap_or(installer::extractor::DEFAULT_MAX_FILE_SIZE),
+        );
+    }
+
+    // ARM64 Windows may fall back to x86_64 builds run under emulation
+    core::platform::set_allow_emulation(
+        settings
+            .as_ref()
+            .and_then(|s| s.allow_emulation)
+            .unwrap_or(false),
+    );
+
+    // Manifest platform names to treat as wenget's identifiers
+    if let Some(aliases) = settings.as_ref().and_then(|s| s.platform_aliases.clone()) {
+        core::platform::set_platform_aliases(aliases.into_iter().collect());
+    }
+
+    // Bucket manifests come straight from their sources with --no-cache
+    cache::set_bypass(cli.no_cache);
+
+    // Clean up partial installs on Ctrl-C
+    utils::interrupt::install_handler();
+
     // Handle no command (show help and exit 0)
     let Some(command) = cli.command else {
         let _ = Cli::command().print_help();
@@ -38,42 +97,241 @@ fn main() {
 
     // Run the appropriate command
     let result = match command {
-        Commands::Init { yes } => commands::run_init(yes),
+        Commands::Init {
+            yes,
+            no_default_bucket,
+        } => commands::run_init(yes, no_default_bucket),
 
         Commands::Bucket { command } => {
             let bucket_cmd = match command {
-                BucketCommands::Add { name, url } => {
-                    commands::bucket::BucketCommand::Add { name, url }
+                BucketCommands::Add { name, url, headers } => {
+                    commands::bucket::BucketCommand::Add { name, url, headers }
                 }
                 BucketCommands::Del { names } => commands::bucket::BucketCommand::Del { names },
                 BucketCommands::List => commands::bucket::BucketCommand::List,
                 BucketCommands::Refresh => commands::bucket::BucketCommand::Refresh,
+                BucketCommands::Update { names } => {
+                    commands::bucket::BucketCommand::Update { names }
+                }
+                BucketCommands::Verify { name, json } => {
+                    commands::bucket::BucketCommand::Verify { name, json }
+                }
             };
             commands::run_bucket(bucket_cmd)
         }
 
+        Commands::Cache { command } => {
+            let cache_cmd = match command {
+                CacheCommands::Info => commands::cache::CacheCommand::Info,
+                CacheCommands::Refresh => commands::cache::CacheCommand::Refresh,
+                CacheCommands::Clear => commands::cache::CacheCommand::Clear,
+            };
+            commands::run_cache(cache_cmd)
+        }
+
         Commands::Add {
             names,
             yes,
             script_name,
-        } => commands::run_add(names, yes, script_name),
+            global_bin,
+            no_shim,
+            shim_mode,
+            asset,
+            exe_name,
+            force_platform_match,
+            checksum,
+            keep_versions,
+            temp_dir,
+            interactive,
+            arch,
+            pre,
+            exclude,
+            sources,
+            retries,
+            timeout,
+            from_file,
+            if_not_installed,
+            allow_downgrade,
+            no_preflight,
+            offline_bundle,
+            json,
+            print_url,
+            resume,
+            dest,
+            platform,
+            variant,
+        } => commands::run_add(
+            names,
+            commands::add::AddOptions {
+                yes,
+                script_name,
+                global_bin,
+                no_shim,
+                shim_mode,
+                prefer_variant: variant.preference(),
+                asset,
+                exe_name,
+                force_platform: force_platform_match,
+                checksum,
+                channel: pre.then_some(core::Channel::Prerelease),
+                upgrade: false,
+                reinstall: false,
+                version: None,
+                from_file,
+                if_not_installed,
+                allow_downgrade,
+                no_preflight,
+                offline_bundle,
+                arch,
+                exclude,
+                sources,
+                retries,
+                timeout: timeout.map(std::time::Duration::from_secs),
+                interactive,
+                keep_versions,
+                temp_dir,
+                json,
+                print_url,
+                platform,
+                resume,
+                dest,
+            },
+        ),
+
+        Commands::Download {
+            names,
+            platform,
+            all_platforms,
+            dest,
+            retries,
+        } => commands::run_download(names, platform, all_platforms, dest, retries),
 
-        Commands::List { all } => commands::run_list(all),
+        Commands::List {
+            all,
+            outdated,
+            json,
+        } => commands::run_list(all, outdated, json),
 
-        Commands::Info { names } => commands::run_info(names),
+        Commands::Tree => commands::run_tree(),
 
-        Commands::Search { names } => commands::run_search(names),
+        Commands::History { limit, package } => commands::run_history(limit, package),
 
-        Commands::Update { names, yes } => commands::run_update(names, yes),
+        Commands::Info {
+            names,
+            remote,
+            compare,
+            raw_manifest,
+            platform,
+            latest_asset_size,
+            sources,
+            installed,
+            json,
+        } => commands::run_info(
+            names,
+            commands::info::InfoOptions {
+                remote,
+                compare,
+                raw_manifest,
+                platform: commands::info::PlatformFilter::parse(&platform),
+                latest_asset_size,
+                sources,
+                installed,
+                json,
+            },
+        ),
+
+        Commands::Search {
+            names,
+            tag,
+            sources,
+        } => commands::run_search(names, tag, sources),
+
+        Commands::Update {
+            names,
+            yes,
+            wait,
+            exclude,
+            only,
+            keep_versions,
+            retries,
+            json,
+            interactive,
+            ignore,
+            reset_ignores,
+            include_ignored,
+            variant,
+        } => commands::run_update(
+            names,
+            commands::update::UpdateOptions {
+                yes,
+                wait,
+                prefer_variant: variant.preference(),
+                exclude,
+                only,
+                retries,
+                keep_versions,
+                json,
+                interactive,
+                ignore,
+                reset_ignores,
+                include_ignored,
+            },
+        ),
 
         Commands::Del { names, yes, force } => commands::run_delete(names, yes, force),
 
-        Commands::Repair { force } => commands::run_repair(force),
+        Commands::Autoremove { yes } => commands::run_autoremove(yes),
+
+        Commands::SelfCmd { command } => match command {
+            SelfCommands::Uninstall {
+                keep_data,
+                keep_binary,
+                yes,
+            } => commands::run_self_uninstall(keep_data, keep_binary, yes),
+        },
+
+        Commands::Channel { name, channel } => commands::run_channel(name, channel),
+
+        Commands::Pin { name, version } => commands::run_pin(name, version),
+
+        Commands::Unpin { name } => commands::run_unpin(name),
+
+        Commands::Rename {
+            name,
+            new_name,
+            force,
+        } => commands::run_rename(name, new_name, force),
+
+        Commands::Path { component } => commands::run_path(component),
+
+        Commands::Config { command } => {
+            let config_cmd = match command {
+                ConfigCommands::Get { key } => commands::config::ConfigCommand::Get { key },
+                ConfigCommands::Set { key, value } => {
+                    commands::config::ConfigCommand::Set { key, value }
+                }
+                ConfigCommands::Unset { key } => commands::config::ConfigCommand::Unset { key },
+                ConfigCommands::List => commands::config::ConfigCommand::List,
+            };
+            commands::run_config(config_cmd)
+        }
+
+        Commands::Repair { force, prune, yes } => commands::run_repair(force, prune, yes),
+
+        Commands::Doctor { fix, yes } => commands::run_doctor(fix, yes),
+
+        Commands::Reinstall { names, all, yes } => commands::run_reinstall(names, all, yes),
+
+        Commands::RefreshShims { names, all } => commands::run_refresh_shims(names, all),
+
+        Commands::Verify { names, all } => commands::run_verify(names, all),
+
+        Commands::Ratelimit => commands::run_ratelimit(),
     };
 
     // Handle errors
     if let Err(e) = result {
         eprintln!("{} {}", "Error:".red().bold(), e);
-        std::process::exit(1);
+        std::process::exit(error::exit_code(&e));
     }
 }
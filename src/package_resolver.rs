@@ -5,11 +5,12 @@
 //! - Fetching package information from cache or GitHub
 //! - Determining the bucket source of cached packages
 
-use crate::cache::ManifestCache;
-use crate::core::manifest::{Package, PackageSource};
+use crate::cache::{CachedScript, ManifestCache};
+use crate::core::manifest::{Channel, InstalledPackage, Package, PackageSource};
 use crate::core::Config;
+use crate::error::WengetError;
 use crate::providers::{GitHubProvider, SourceProvider};
-use anyhow::{anyhow, Context, Result};
+use anyhow::{Context, Result};
 
 /// Represents the type of package input
 #[derive(Debug, Clone)]
@@ -22,7 +23,12 @@ pub enum PackageInput {
 
 impl PackageInput {
     /// Parse an input string and detect if it's a URL or package name
+    ///
+    /// A `@version` suffix (`ripgrep@13.*`) is dropped; use [`split_version`]
+    /// to get at it.
     pub fn parse(input: &str) -> Self {
+        let (input, _) = split_version(input);
+
         // Check if input looks like a URL
         if input.starts_with("http://")
             || input.starts_with("https://")
@@ -35,6 +41,20 @@ impl PackageInput {
     }
 }
 
+/// Split a `name@version` entry into its name and version
+///
+/// An `@` inside a URL (before a `/`) is part of the URL, not a version.
+pub fn split_version(entry: &str) -> (&str, Option<&str>) {
+    match entry.rsplit_once('@') {
+        Some((name, version))
+            if !name.is_empty() && !version.is_empty() && !version.contains('/') =>
+        {
+            (name, Some(version))
+        }
+        _ => (entry, None),
+    }
+}
+
 /// Normalize GitHub URL to standard format
 fn normalize_github_url(url: &str) -> String {
     let mut url = url.trim().to_string();
@@ -62,6 +82,41 @@ fn normalize_github_url(url: &str) -> String {
     url
 }
 
+/// One entry of a package list file (`wenget add --from-file`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListEntry {
+    /// 1-based line number in the file
+    pub line: usize,
+    /// Package name or URL
+    pub name: String,
+    /// Required version from a `name@version` entry
+    pub version: Option<String>,
+}
+
+/// Parse a package list: one name or URL per line, `#` starts a comment
+///
+/// Entries may pin a version as `name@version`.
+pub fn parse_package_list(content: &str) -> Vec<ListEntry> {
+    content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let entry = line.split('#').next().unwrap_or("").trim();
+            if entry.is_empty() {
+                return None;
+            }
+
+            let (name, version) = split_version(entry);
+
+            Some(ListEntry {
+                line: index + 1,
+                name: name.to_string(),
+                version: version.map(|v| v.trim_start_matches('v').to_string()),
+            })
+        })
+        .collect()
+}
+
 /// Result of package resolution with source information
 #[derive(Debug, Clone)]
 pub struct ResolvedPackage {
@@ -83,6 +138,8 @@ pub struct PackageResolver<'a> {
     config: &'a Config,
     cache: &'a ManifestCache,
     github: GitHubProvider,
+    /// Buckets cache lookups are limited to (`--sources`); all when empty
+    sources: Vec<String>,
 }
 
 impl<'a> PackageResolver<'a> {
@@ -93,9 +150,26 @@ impl<'a> PackageResolver<'a> {
             config,
             cache,
             github,
+            sources: Vec::new(),
         })
     }
 
+    /// Only match cache entries from these buckets (`--sources`)
+    ///
+    /// Fails if one of them is not a configured bucket.
+    pub fn with_sources(mut self, sources: &[String]) -> Result<Self> {
+        validate_sources(self.config, sources)?;
+        self.sources = sources.to_vec();
+        Ok(self)
+    }
+
+    /// Find a script in the cache, within the `--sources` buckets
+    pub fn find_script(&self, name: &str) -> Option<&'a CachedScript> {
+        self.cache
+            .find_script(name)
+            .filter(|cached| in_sources(&self.sources, &cached.source))
+    }
+
     /// Resolve package(s) from input
     ///
     /// Returns a list of resolved packages with their sources.
@@ -112,31 +186,59 @@ impl<'a> PackageResolver<'a> {
     }
 
     /// Resolve package from cache (supports glob patterns)
+    ///
+    /// Names and patterns match package aliases as well; an exact name picks
+    /// the packages called that over those that only have it as an alias.
     /// Falls back to checking installed packages if not found in cache
     fn resolve_from_cache(&self, name: &str) -> Result<Vec<ResolvedPackage>> {
         // Filter packages by name pattern
+        let scoped = self
+            .cache
+            .packages
+            .values()
+            .filter(|cached| in_sources(&self.sources, &cached.source));
         let matches: Vec<_> = if name.contains('*') {
             // Glob pattern matching
-            self.cache
-                .packages
-                .values()
-                .filter(|cached| glob_match(&cached.package.name, name))
+            scoped
+                .filter(|cached| {
+                    let pkg = &cached.package;
+                    glob_match(&pkg.name, name) || pkg.aliases.iter().any(|a| glob_match(a, name))
+                })
                 .collect()
         } else {
             // Exact name matching
-            self.cache
-                .packages
-                .values()
-                .filter(|cached| cached.package.name == name)
-                .collect()
+            let (named, aliased): (Vec<_>, Vec<_>) = scoped
+                .filter(|cached| {
+                    cached.package.name == name || cached.package.aliases.iter().any(|a| a == name)
+                })
+                .partition(|cached| cached.package.name == name);
+            if named.is_empty() {
+                aliased
+            } else {
+                named
+            }
         };
 
         if !matches.is_empty() {
-            // Found in cache - return these matches
-            return Ok(matches
+            // Found in cache - return these matches with ${VAR} placeholders expanded
+            return matches
                 .into_iter()
-                .map(|cached| ResolvedPackage::new(cached.package.clone(), cached.source.clone()))
-                .collect());
+                .map(|cached| {
+                    let mut package = cached.package.clone();
+                    package.normalize_platforms();
+                    package.expand_env_vars()?;
+                    Ok(ResolvedPackage::new(package, cached.source.clone()))
+                })
+                .collect();
+        }
+
+        if !self.sources.is_empty() {
+            return Err(WengetError::NotFound(format!(
+                "No packages found matching '{}' in bucket(s): {}",
+                name,
+                self.sources.join(", ")
+            ))
+            .into());
         }
 
         // Not found in cache - check if it's an installed package from direct URL
@@ -146,8 +248,9 @@ impl<'a> PackageResolver<'a> {
             if let Some(inst_pkg) = installed.get_package(name) {
                 // Check if it's a DirectRepo source
                 if let PackageSource::DirectRepo { url } = &inst_pkg.source {
-                    // Fetch the package info from the URL
-                    return self.resolve_from_url(url).map(|pkg| vec![pkg]);
+                    return self
+                        .resolve_installed_repo(name, url, inst_pkg)
+                        .map(|pkg| vec![pkg]);
                 }
             }
         }
@@ -158,28 +261,28 @@ impl<'a> PackageResolver<'a> {
 
         if cache_pkg_count == 0 {
             if bucket_count == 0 {
-                Err(anyhow!(
+                Err(WengetError::NotFound(format!(
                     "No packages found matching '{}'. No buckets configured. Run 'wenget bucket add' to add a bucket.",
                     name
-                ))
+                )).into())
             } else {
-                Err(anyhow!(
+                Err(WengetError::NotFound(format!(
                     "No packages found matching '{}'. Cache is empty. Run 'wenget bucket refresh' to rebuild cache.",
                     name
-                ))
+                )).into())
             }
         } else if name.contains('*') {
-            Err(anyhow!(
+            Err(WengetError::NotFound(format!(
                 "No packages found matching pattern '{}'. {} packages available in cache.",
-                name,
-                cache_pkg_count
+                name, cache_pkg_count
             ))
+            .into())
         } else {
-            Err(anyhow!(
+            Err(WengetError::NotFound(format!(
                 "Package '{}' not found. Use 'wenget search {}' to find similar packages.",
-                name,
-                name
+                name, name
             ))
+            .into())
         }
     }
 
@@ -197,12 +300,107 @@ impl<'a> PackageResolver<'a> {
         Ok(ResolvedPackage::new(package, source))
     }
 
-    /// Get the latest version from GitHub for a package
-    pub fn fetch_latest_version(&self, repo_url: &str) -> Result<String> {
-        self.github.fetch_latest_version(repo_url)
+    /// Resolve a package installed from a GitHub URL
+    ///
+    /// The binaries come from the release the package follows (its channel
+    /// and pin), the rest from what was recorded at install, so the
+    /// repository itself is not looked up.
+    fn resolve_installed_repo(
+        &self,
+        name: &str,
+        url: &str,
+        inst_pkg: &InstalledPackage,
+    ) -> Result<ResolvedPackage> {
+        let pin = inst_pkg.version_constraint();
+        let (_, platforms) = self
+            .github
+            .fetch_matching_platforms(url, inst_pkg.channel, &[], pin.as_ref())
+            .with_context(|| format!("Failed to fetch package from: {}", url))?;
+
+        let package = Package {
+            name: name.to_string(),
+            description: inst_pkg.description.clone(),
+            repo: url.to_string(),
+            homepage: None,
+            license: None,
+            platforms,
+            requires: inst_pkg.requires.clone(),
+            yanked: Vec::new(),
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            post_install_message: inst_pkg.post_install_message.clone(),
+            exe_name: inst_pkg.exe_name.clone(),
+        };
+        let source = PackageSource::DirectRepo {
+            url: url.to_string(),
+        };
+
+        Ok(ResolvedPackage::new(package, source))
+    }
+
+    /// Get the latest non-yanked version from GitHub for a package on a
+    /// release channel
+    pub fn fetch_latest_version(
+        &self,
+        repo_url: &str,
+        channel: Channel,
+        yanked: &[String],
+    ) -> Result<String> {
+        self.github.fetch_latest_version(repo_url, channel, yanked)
+    }
+}
+
+/// Check that every `--sources` name is a configured bucket
+pub fn validate_sources(config: &Config, sources: &[String]) -> Result<()> {
+    if sources.is_empty() {
+        return Ok(());
+    }
+
+    let buckets = config.get_or_create_buckets()?;
+    match sources
+        .iter()
+        .find(|name| buckets.find_bucket(name).is_none())
+    {
+        Some(name) => {
+            let configured: Vec<_> = buckets.buckets.iter().map(|b| b.name.as_str()).collect();
+            Err(WengetError::NotFound(format!(
+                "Bucket '{}' not found (--sources). Configured buckets: {}",
+                name,
+                if configured.is_empty() {
+                    "none".to_string()
+                } else {
+                    configured.join(", ")
+                }
+            ))
+            .into())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Check whether a cache entry comes from one of the `--sources` buckets
+///
+/// Every source qualifies when no buckets were named.
+pub fn in_sources(sources: &[String], source: &PackageSource) -> bool {
+    match source {
+        _ if sources.is_empty() => true,
+        PackageSource::Bucket { name } => sources.contains(name),
+        _ => false,
     }
 }
 
+/// Check whether a package name matches any `--exclude` pattern
+pub fn is_excluded(name: &str, excludes: &[String]) -> bool {
+    excludes.iter().any(|pattern| glob_match(name, pattern))
+}
+
+/// Check whether a package name matches any `--only` pattern
+///
+/// Every name matches when there are no patterns.
+pub fn is_selected(name: &str, only: &[String]) -> bool {
+    only.is_empty() || only.iter().any(|pattern| glob_match(name, pattern))
+}
+
 /// Simple glob pattern matching (supports * wildcard)
 ///
 /// Examples:
@@ -291,6 +489,128 @@ mod tests {
         ));
     }
 
+    /// A cached bucket package with the given aliases
+    fn cached_package(cache: &mut ManifestCache, name: &str, aliases: &[&str]) {
+        let package: Package = serde_json::from_value(serde_json::json!({
+            "name": name,
+            "description": "",
+            "repo": format!("https://github.com/test/{}", name),
+            "platforms": {},
+            "aliases": aliases,
+        }))
+        .unwrap();
+        let source = PackageSource::Bucket {
+            name: "main".to_string(),
+        };
+        cache.add_package(package, source);
+    }
+
+    #[test]
+    fn test_resolve_aliases() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = Config::with_paths(crate::core::WenPaths::with_root(
+            temp_dir.path().join(".wenget"),
+        ));
+        let mut cache = ManifestCache::new();
+        cached_package(&mut cache, "ripgrep", &["rg"]);
+        cached_package(&mut cache, "fd", &[]);
+        cached_package(&mut cache, "fd-find", &["fd"]);
+        let resolver = PackageResolver::new(&config, &cache).unwrap();
+        let names = |input: &str| {
+            let mut names: Vec<_> = resolver
+                .resolve(&PackageInput::parse(input))
+                .unwrap()
+                .into_iter()
+                .map(|r| r.package.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        // An alias resolves to the package under its canonical name
+        assert_eq!(names("rg"), ["ripgrep"]);
+        assert_eq!(names("r*"), ["ripgrep"]);
+        // A package's own name wins over another package's alias
+        assert_eq!(names("fd"), ["fd"]);
+        assert_eq!(names("fd*"), ["fd", "fd-find"]);
+        assert!(resolver.resolve(&PackageInput::parse("grep")).is_err());
+    }
+
+    #[test]
+    fn test_is_selected() {
+        let only = vec!["rip*".to_string(), "fd".to_string()];
+        assert!(is_selected("ripgrep", &only));
+        assert!(is_selected("fd", &only));
+        assert!(!is_selected("fd-find", &only));
+        assert!(!is_selected("bat", &only));
+        // No patterns selects everything
+        assert!(is_selected("bat", &[]));
+    }
+
+    #[test]
+    fn test_in_sources() {
+        let bucket = |name: &str| PackageSource::Bucket {
+            name: name.to_string(),
+        };
+        let direct = PackageSource::DirectRepo {
+            url: "https://github.com/user/repo".to_string(),
+        };
+
+        assert!(in_sources(&[], &bucket("main")));
+        assert!(in_sources(&[], &direct));
+
+        let sources = vec!["mine".to_string(), "extras".to_string()];
+        assert!(in_sources(&sources, &bucket("mine")));
+        assert!(in_sources(&sources, &bucket("extras")));
+        assert!(!in_sources(&sources, &bucket("main")));
+        assert!(!in_sources(&sources, &direct));
+    }
+
+    #[test]
+    fn test_parse_versioned_input() {
+        assert_eq!(split_version("ripgrep@13.*"), ("ripgrep", Some("13.*")));
+        assert!(matches!(
+            PackageInput::parse("ripgrep@13.*"),
+            PackageInput::CacheName(ref name) if name == "ripgrep"
+        ));
+
+        assert_eq!(
+            split_version("github.com/user/repo@*-beta"),
+            ("github.com/user/repo", Some("*-beta"))
+        );
+        assert!(matches!(
+            PackageInput::parse("github.com/user/repo@*-beta"),
+            PackageInput::DirectUrl(ref url) if url == "https://github.com/user/repo"
+        ));
+
+        // `@` in a URL's authority is not a version
+        assert_eq!(
+            split_version("https://git@github.com/user/repo"),
+            ("https://git@github.com/user/repo", None)
+        );
+
+        assert_eq!(split_version("@bad"), ("@bad", None));
+        assert_eq!(split_version("tool@"), ("tool@", None));
+    }
+
+    #[test]
+    fn test_parse_package_list() {
+        let content =
+            "# tools\n\nripgrep\n  fd@v9.0.0  # pinned\nhttps://github.com/user/repo\n@bad\n";
+        let entries = parse_package_list(content);
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].line, 3);
+        assert_eq!(entries[0].name, "ripgrep");
+        assert_eq!(entries[0].version, None);
+        assert_eq!(entries[1].line, 4);
+        assert_eq!(entries[1].name, "fd");
+        assert_eq!(entries[1].version.as_deref(), Some("9.0.0"));
+        assert_eq!(entries[2].name, "https://github.com/user/repo");
+        assert_eq!(entries[2].version, None);
+        assert_eq!(entries[3].name, "@bad");
+    }
+
     #[test]
     fn test_normalize_github_url() {
         // Basic cases
@@ -338,6 +658,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_include_with_exclude_globs() {
+        let names = ["ripgrep", "ripgrep-all", "rip-tool", "bat"];
+        let excludes = vec!["*-all".to_string(), "rip-t*".to_string()];
+
+        let selected: Vec<&str> = names
+            .iter()
+            .copied()
+            .filter(|name| glob_match(name, "rip*"))
+            .filter(|name| !is_excluded(name, &excludes))
+            .collect();
+        assert_eq!(selected, vec!["ripgrep"]);
+
+        // Exact names work as exclude patterns too
+        assert!(is_excluded("bat", &["bat".to_string()]));
+        assert!(!is_excluded("bat", &[]));
+    }
+
     #[test]
     fn test_glob_match() {
         // Exact match
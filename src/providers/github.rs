@@ -1,25 +1,188 @@
 //! GitHub provider implementation
 
 use super::base::SourceProvider;
-use crate::core::{BinaryAsset, BinarySelector, Package, PlatformBinary};
+use super::rate_limit::{breaker, RateLimitBreaker};
+use crate::core::manifest::is_yanked;
+use crate::core::version::Version;
+use crate::core::{
+    AssetPattern, BinaryAsset, BinarySelector, Channel, Package, Platform, PlatformBinary,
+    VersionConstraint,
+};
+use crate::error::WengetError;
+use crate::outln;
+use crate::utils::backoff::Backoff;
+use crate::utils::http::{HostHeaders, RateLimit, DEFAULT_TIMEOUT};
 use crate::utils::HttpClient;
 use anyhow::{Context, Result};
+use colored::Colorize;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Base URL of the GitHub REST API
+const GITHUB_API: &str = "https://api.github.com";
+
+/// Attempts made when GitHub keeps answering with a server error (5xx)
+const SERVER_ERROR_ATTEMPTS: u32 = 3;
+
+/// Release tags listed when no release matches a version constraint
+const MAX_LISTED_TAGS: usize = 10;
+
+/// Environment variables a GitHub token is read from, in order
+const TOKEN_VARS: [&str; 2] = ["GITHUB_TOKEN", "GH_TOKEN"];
+
+/// Successful API responses of this run, by URL
+///
+/// A release looked up once (say by `update`'s check) is not asked for again
+/// by the install that follows, which keeps working after the rate limit
+/// runs out.
+static RESPONSES: Mutex<BTreeMap<String, serde_json::Value>> = Mutex::new(BTreeMap::new());
+
+/// The environment variable holding the GitHub token, if one is set
+///
+/// The token is sent to the GitHub API only, raising the rate limit from 60
+/// to 5,000 requests per hour.
+pub fn github_token_var() -> Option<&'static str> {
+    TOKEN_VARS
+        .into_iter()
+        .find(|var| std::env::var(var).is_ok_and(|v| !v.trim().is_empty()))
+}
 
 /// GitHub provider
 pub struct GitHubProvider {
     http: HttpClient,
+    api_base: String,
+    token: Option<String>,
+    breaker: &'static RateLimitBreaker,
+}
+
+/// A private repository's release asset, downloaded through the API
+#[derive(Debug)]
+pub struct PrivateAsset {
+    /// The API asset endpoint
+    pub url: String,
+    pub size: u64,
+    /// The token and `Accept: application/octet-stream`, for the API host only
+    pub headers: HostHeaders,
+}
+
+/// Look up the API download of a private repository's release asset
+///
+/// Cheap for every other URL: the API is only asked when a token is set
+/// and `url` is a GitHub release download.
+pub fn private_asset(url: &str) -> Result<Option<PrivateAsset>> {
+    if github_token_var().is_none() || parse_release_download(url).is_none() {
+        return Ok(None);
+    }
+    GitHubProvider::new()?.private_asset(url)
+}
+
+/// Split a release download URL
+/// (`https://github.com/<owner>/<repo>/releases/download/<tag>/<file>`)
+/// into owner, repo and tag
+fn parse_release_download(url: &str) -> Option<(&str, &str, &str)> {
+    let path = url.strip_prefix("https://github.com/")?;
+    match path.split('/').collect::<Vec<_>>().as_slice() {
+        [owner, repo, "releases", "download", tag, file] if !file.is_empty() => {
+            Some((owner, repo, tag))
+        }
+        _ => None,
+    }
 }
 
 impl GitHubProvider {
     /// Create a new GitHub provider
     pub fn new() -> Result<Self> {
+        let token = match github_token_var() {
+            Some(var) => Some(std::env::var(var)?.trim().to_string()),
+            None => None,
+        };
+        let mut headers = BTreeMap::new();
+        if let Some(ref token) = token {
+            headers.insert("Authorization".to_string(), format!("Bearer {}", token));
+        }
+        let headers = HostHeaders::new(GITHUB_API, &headers)?;
+
         Ok(Self {
-            http: HttpClient::new()?,
+            http: HttpClient::with_headers(DEFAULT_TIMEOUT, headers)?,
+            api_base: GITHUB_API.to_string(),
+            token,
+            breaker: breaker(),
         })
     }
 
+    /// The API download of a release asset, if its repository is private
+    ///
+    /// Private assets are not served at their `browser_download_url`; they
+    /// have to be fetched from the API asset endpoint with the token and
+    /// `Accept: application/octet-stream`, which redirects to the file.
+    /// Returns None without a token, for public repositories, and for URLs
+    /// that are not release downloads.
+    pub fn private_asset(&self, download_url: &str) -> Result<Option<PrivateAsset>> {
+        let (Some(token), Some((owner, repo, tag))) =
+            (&self.token, parse_release_download(download_url))
+        else {
+            return Ok(None);
+        };
+        match self.fetch_repo_info(owner, repo) {
+            Ok(info) if info.private => {}
+            Ok(_) => return Ok(None),
+            // A public download needs no API; a private one fails at the download
+            Err(e) if WengetError::rate_limit(&e).is_some() => return Ok(None),
+            Err(e) => return Err(e),
+        }
+
+        let release = self.fetch_tagged_release(owner, repo, tag)?;
+        let asset = release
+            .assets
+            .iter()
+            .find(|a| a.browser_download_url == download_url && !a.url.is_empty())
+            .ok_or_else(|| {
+                WengetError::NotFound(format!(
+                    "Asset {} not found in release {} of {}/{}",
+                    download_url, release.tag_name, owner, repo
+                ))
+            })?;
+        log::debug!("Private asset {} is served from {}", asset.name, asset.url);
+
+        let headers = BTreeMap::from([
+            ("Authorization".to_string(), format!("Bearer {}", token)),
+            ("Accept".to_string(), "application/octet-stream".to_string()),
+        ]);
+        Ok(Some(PrivateAsset {
+            url: asset.url.clone(),
+            size: asset.size,
+            headers: HostHeaders::new(&asset.url, &headers)?,
+        }))
+    }
+
+    /// Rate-limit status from the latest API response, if any was received
+    pub fn rate_limit(&self) -> Option<RateLimit> {
+        self.http.last_rate_limit()
+    }
+
+    /// Ask the API for the current rate-limit status
+    pub fn fetch_rate_limit(&self) -> Result<RateLimit> {
+        self.http.check_rate_limit(&self.api_base)
+    }
+
+    /// Create a provider talking to a stand-in API server
+    #[cfg(test)]
+    pub(crate) fn with_api_base(api_base: &str) -> Self {
+        Self {
+            api_base: api_base.to_string(),
+            ..Self::new().unwrap()
+        }
+    }
+
+    /// Gate requests with a breaker of its own instead of the shared one
+    #[cfg(test)]
+    pub(crate) fn with_breaker(self, breaker: &'static RateLimitBreaker) -> Self {
+        Self { breaker, ..self }
+    }
+
     /// Parse GitHub URL to extract owner and repo
     ///
     /// Supports:
@@ -37,7 +200,7 @@ impl GitHubProvider {
             .collect();
 
         if parts.len() < 2 {
-            anyhow::bail!("Invalid GitHub URL: {}", url);
+            return Err(WengetError::InvalidUrl(url.to_string()).into());
         }
 
         let owner = parts[0].to_string();
@@ -46,92 +209,592 @@ impl GitHubProvider {
         Ok((owner, repo))
     }
 
+    /// Send a GitHub API request, unless this run already has its answer
+    ///
+    /// A URL answered once is served from [`RESPONSES`] afterwards, even
+    /// while the rate-limit breaker is open.
+    fn api_get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let known = RESPONSES.lock().unwrap().get(url).cloned();
+        if let Some(value) = known {
+            log::debug!("GET {} (JSON, already fetched)", url);
+            return serde_json::from_value(value).context("Failed to parse JSON response");
+        }
+
+        let value: serde_json::Value = self.get_json_gated(url)?;
+        RESPONSES
+            .lock()
+            .unwrap()
+            .insert(url.to_string(), value.clone());
+        serde_json::from_value(value).context("Failed to parse JSON response")
+    }
+
+    /// Send a GitHub API request through the rate-limit breaker
+    ///
+    /// A rate-limit response opens the breaker so later calls fail fast (or
+    /// wait for the reset when waiting is enabled, in which case this call is
+    /// retried after the wait). Secondary limits are retried after the
+    /// server's `Retry-After`, within the backoff's total wait budget.
+    fn get_json_gated(&self, url: &str) -> Result<serde_json::Value> {
+        let mut backoff = Backoff::new();
+        loop {
+            self.breaker.before_request()?;
+
+            match self.http.get_json(url) {
+                Err(e) => {
+                    let Some(exceeded) = WengetError::rate_limit(&e) else {
+                        return Err(e);
+                    };
+
+                    // Secondary limits clear quickly: wait as told and retry
+                    if exceeded.is_secondary() {
+                        let hint = exceeded.retry_after.map(Duration::from_secs);
+                        let Some(delay) = backoff.next_delay(hint) else {
+                            return Err(e);
+                        };
+                        outln!(
+                            "{} GitHub asked to slow down, waiting {}s before retrying...",
+                            "⏳".yellow(),
+                            delay.as_secs()
+                        );
+                        std::thread::sleep(delay);
+                        continue;
+                    }
+
+                    self.breaker.trip(exceeded.reset);
+                    if !self.breaker.waits() {
+                        return Err(e);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
     /// Fetch latest release from GitHub API
-    fn fetch_latest_release(&self, owner: &str, repo: &str) -> Result<GitHubRelease> {
+    ///
+    /// The prerelease channel takes the newest non-draft release, which may be
+    /// a prerelease; `/releases/latest` never returns those. Releases whose
+    /// version is in `yanked` are skipped in favor of the next newest one.
+    /// With a `pin`, the highest release satisfying it is used instead; an
+    /// exact pin fetches that release directly.
+    fn fetch_latest_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<GitHubRelease> {
+        if let Some(version) = pin.and_then(VersionConstraint::exact) {
+            return self.fetch_tagged_release(owner, repo, version);
+        }
+
+        if channel == Channel::Prerelease || !yanked.is_empty() || pin.is_some() {
+            return self.select_listed_release(owner, repo, channel, yanked, pin);
+        }
+
+        let url = format!("{}/repos/{}/{}/releases/latest", self.api_base, owner, repo);
+
+        match self.api_get_json(&url) {
+            // No "latest" release when a repo only has prereleases or drafts
+            Err(e) if is_not_found(&e) => {
+                log::debug!(
+                    "No latest release for {}/{}, picking from the release list",
+                    owner,
+                    repo
+                );
+                self.select_listed_release(owner, repo, channel, yanked, pin)
+            }
+            result => result
+                .with_context(|| format!("Failed to fetch latest release for {}/{}", owner, repo)),
+        }
+    }
+
+    /// Pick the release to use from the repository's release list
+    ///
+    /// See [`select_release`] for which releases qualify.
+    fn select_listed_release(
+        &self,
+        owner: &str,
+        repo: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<GitHubRelease> {
+        // Older series are further back in the list than the latest few releases
+        let per_page = if pin.is_some() { 100 } else { 20 };
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases/latest",
-            owner, repo
+            "{}/repos/{}/{}/releases?per_page={}",
+            self.api_base, owner, repo, per_page
         );
 
-        self.http
-            .get_json(&url)
-            .with_context(|| format!("Failed to fetch latest release for {}/{}", owner, repo))
+        let releases: Vec<GitHubRelease> = self
+            .api_get_json(&url)
+            .with_context(|| format!("Failed to fetch releases for {}/{}", owner, repo))?;
+        let tags: Vec<String> = releases
+            .iter()
+            .filter(|r| !r.draft)
+            .take(MAX_LISTED_TAGS)
+            .map(|r| r.tag_name.clone())
+            .collect();
+        let only_prereleases = channel.is_stable()
+            && releases.iter().any(|r| !r.draft)
+            && releases.iter().all(|r| r.draft || r.prerelease);
+
+        select_release(releases, channel, yanked, pin).ok_or_else(|| {
+            let mut suffix = String::new();
+            if let Some(pin) = pin {
+                suffix.push_str(&format!(" matching {}", pin));
+            }
+            if !yanked.is_empty() {
+                suffix.push_str(" that is not yanked");
+            }
+            if only_prereleases {
+                suffix.push_str(" (only prereleases are published; use --pre to install them)");
+            } else if pin.is_some() && !tags.is_empty() {
+                suffix.push_str(&format!(" (recent tags: {})", tags.join(", ")));
+            }
+            WengetError::NotFound(format!(
+                "No releases found for {}/{}{}",
+                owner, repo, suffix
+            ))
+            .into()
+        })
     }
 
     /// Get repository information
     fn fetch_repo_info(&self, owner: &str, repo: &str) -> Result<GitHubRepo> {
-        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+        let url = format!("{}/repos/{}/{}", self.api_base, owner, repo);
 
-        self.http
-            .get_json(&url)
+        self.api_get_json(&url)
             .with_context(|| format!("Failed to fetch repo info for {}/{}", owner, repo))
     }
 
-    /// Fetch latest version for a repository
-    pub fn fetch_latest_version(&self, repo_url: &str) -> Result<String> {
+    /// Fetch latest version for a repository on the given channel, skipping
+    /// yanked versions
+    pub fn fetch_latest_version(
+        &self,
+        repo_url: &str,
+        channel: Channel,
+        yanked: &[String],
+    ) -> Result<String> {
+        self.fetch_matching_version(repo_url, channel, yanked, None)
+    }
+
+    /// Fetch the newest version satisfying a pinned constraint (the latest
+    /// version when `pin` is `None`)
+    pub fn fetch_matching_version(
+        &self,
+        repo_url: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<String> {
         let (owner, repo) = self.parse_github_url(repo_url)?;
-        let release = self.fetch_latest_release(&owner, &repo)?;
+        let release = retry_server_errors(&format!("{}/{}", owner, repo), || {
+            self.fetch_latest_release(&owner, &repo, channel, yanked, pin)
+        })?;
         Ok(release.tag_name.trim_start_matches('v').to_string())
     }
-}
 
-impl SourceProvider for GitHubProvider {
-    fn fetch_package(&self, url: &str) -> Result<Package> {
-        log::info!("Fetching package from: {}", url);
+    /// Fetch the newest version satisfying a pinned constraint along with
+    /// the binaries its release offers, by platform id
+    ///
+    /// Unlike [`Self::fetch_matching_package`], only the release is looked
+    /// up, not the repository.
+    pub fn fetch_matching_platforms(
+        &self,
+        repo_url: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<(String, HashMap<String, PlatformBinary>)> {
+        let (owner, repo) = self.parse_github_url(repo_url)?;
+        let release = retry_server_errors(&format!("{}/{}", owner, repo), || {
+            self.fetch_latest_release(&owner, &repo, channel, yanked, pin)
+        })?;
+        let platforms = release_platforms(&release, &owner, &repo)?;
+        Ok((
+            release.tag_name.trim_start_matches('v').to_string(),
+            platforms,
+        ))
+    }
 
-        // Parse URL
-        let (owner, repo) = self.parse_github_url(url)?;
+    /// Resolve a manifest asset pattern against the latest release assets
+    /// (the newest release satisfying `pin`, if given)
+    ///
+    /// Returns the concrete binary for the single matching asset.
+    pub fn resolve_asset_pattern(
+        &self,
+        repo_url: &str,
+        pattern: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<PlatformBinary> {
+        self.resolve_asset_release(repo_url, pattern, channel, yanked, pin)
+            .map(|(_, binary)| binary)
+    }
 
-        // Fetch repo info for description and license
-        let repo_info = self.fetch_repo_info(&owner, &repo)?;
+    /// Resolve a manifest asset pattern like [`Self::resolve_asset_pattern`]
+    ///
+    /// Returns the release version along with the binary, both from the same
+    /// release lookup.
+    pub fn resolve_asset_release(
+        &self,
+        repo_url: &str,
+        pattern: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<(String, PlatformBinary)> {
+        let (owner, repo) = self.parse_github_url(repo_url)?;
+        let release = retry_server_errors(&format!("{}/{}", owner, repo), || {
+            self.fetch_latest_release(&owner, &repo, channel, yanked, pin)
+        })?;
 
-        // Fetch latest release
-        let release = self.fetch_latest_release(&owner, &repo)?;
+        let assets: Vec<BinaryAsset> = release.assets.iter().map(BinaryAsset::from).collect();
 
-        if release.assets.is_empty() {
-            anyhow::bail!(
-                "No binary assets found in latest release for {}/{}",
-                owner,
-                repo
-            );
+        let asset = AssetPattern::new(pattern)
+            .select(&assets, &release.tag_name)
+            .with_context(|| format!("Failed to resolve asset for {}/{}", owner, repo))?;
+
+        log::debug!(
+            "Pattern '{}' resolved to {} ({})",
+            pattern,
+            asset.name,
+            release.tag_name
+        );
+
+        let binary = PlatformBinary {
+            url: asset.url.clone(),
+            size: asset.size,
+            checksum: None,
+            asset_pattern: Some(pattern.to_string()),
+            bin: Vec::new(),
+        };
+        Ok((release.tag_name.trim_start_matches('v').to_string(), binary))
+    }
+
+    /// List the latest release assets usable on a platform, best match first
+    ///
+    /// Returns the release tag along with the candidates.
+    pub fn fetch_platform_candidates(
+        &self,
+        repo_url: &str,
+        platform: Platform,
+        channel: Channel,
+        yanked: &[String],
+    ) -> Result<(String, Vec<BinaryAsset>)> {
+        let (owner, repo) = self.parse_github_url(repo_url)?;
+        let release = self.fetch_latest_release(&owner, &repo, channel, yanked, None)?;
+
+        let assets: Vec<BinaryAsset> = release.assets.iter().map(BinaryAsset::from).collect();
+        let candidates = BinarySelector::candidates_for_platform(&assets, platform);
+
+        Ok((release.tag_name, candidates))
+    }
+
+    /// List the assets of a release: the given version, or the latest one on
+    /// the channel when `version` is `None`
+    ///
+    /// Returns the release tag along with the assets.
+    pub fn fetch_release_assets(
+        &self,
+        repo_url: &str,
+        version: Option<&str>,
+        channel: Channel,
+        yanked: &[String],
+    ) -> Result<(String, Vec<BinaryAsset>)> {
+        let (owner, repo) = self.parse_github_url(repo_url)?;
+        if let Some(version) = version {
+            return self.fetch_release_by_tag(repo_url, version);
         }
+        let release = self.fetch_latest_release(&owner, &repo, channel, yanked, None)?;
 
-        // Convert GitHub assets to BinaryAsset
-        let assets: Vec<BinaryAsset> = release
-            .assets
+        let assets = release.assets.iter().map(BinaryAsset::from).collect();
+        Ok((release.tag_name, assets))
+    }
+
+    /// Look up the download size of each of a package's platforms in its
+    /// latest release on the channel, without downloading anything
+    ///
+    /// Platforms with an asset pattern use the asset the pattern selects;
+    /// the others use the asset detected for the same platform id, or one
+    /// with the same file name as the manifest URL. Platforms with no
+    /// matching asset are left out. Returns the release tag along with the
+    /// sizes keyed by platform id.
+    pub fn fetch_asset_sizes(
+        &self,
+        pkg: &Package,
+        channel: Channel,
+    ) -> Result<(String, BTreeMap<String, u64>)> {
+        let (tag, assets) = self.fetch_release_assets(&pkg.repo, None, channel, &pkg.yanked)?;
+        let detected = BinarySelector::extract_platforms(&assets);
+
+        let sizes = pkg
+            .platforms
             .iter()
-            .map(|a| BinaryAsset {
-                name: a.name.clone(),
-                url: a.browser_download_url.clone(),
-                size: a.size,
+            .filter_map(|(id, binary)| {
+                let asset = match binary.asset_pattern {
+                    Some(ref pattern) => AssetPattern::new(pattern).select(&assets, &tag).ok(),
+                    None => detected.get(id).or_else(|| {
+                        let file_name = binary.url.rsplit('/').next()?;
+                        assets.iter().find(|a| a.name == file_name)
+                    }),
+                }?;
+                Some((id.clone(), asset.size))
             })
             .collect();
 
-        // Extract platforms using BinarySelector
-        let platform_map = BinarySelector::extract_platforms(&assets);
+        Ok((tag, sizes))
+    }
+
+    /// List the assets of the release with the given tag
+    ///
+    /// Asks `/releases/tags/<tag>` directly instead of paging through the
+    /// release list, so old releases cost a single request. Returns the
+    /// release tag along with the assets.
+    pub fn fetch_release_by_tag(
+        &self,
+        repo_url: &str,
+        tag: &str,
+    ) -> Result<(String, Vec<BinaryAsset>)> {
+        let (owner, repo) = self.parse_github_url(repo_url)?;
+        let release = self.fetch_tagged_release(&owner, &repo, tag)?;
+
+        let assets = release.assets.iter().map(BinaryAsset::from).collect();
+        Ok((release.tag_name, assets))
+    }
+
+    /// Fetch the release tagged with a version, with or without a `v` prefix
+    fn fetch_tagged_release(&self, owner: &str, repo: &str, tag: &str) -> Result<GitHubRelease> {
+        let version = tag.trim_start_matches('v');
 
-        if platform_map.is_empty() {
-            anyhow::bail!(
-                "No matching binaries found for any platform in {}/{}",
-                owner,
-                repo
+        for tag in [format!("v{}", version), version.to_string()] {
+            let url = format!(
+                "{}/repos/{}/{}/releases/tags/{}",
+                self.api_base, owner, repo, tag
             );
+            match self.api_get_json(&url) {
+                Err(e)
+                    if matches!(
+                        WengetError::find(&e),
+                        Some(WengetError::HttpStatus { status: 404, .. })
+                    ) =>
+                {
+                    continue
+                }
+                result => {
+                    return result.with_context(|| {
+                        format!("Failed to fetch release {} for {}/{}", tag, owner, repo)
+                    })
+                }
+            }
         }
 
-        // Convert to PlatformBinary map
-        let platforms: HashMap<String, PlatformBinary> = platform_map
-            .into_iter()
-            .map(|(platform_id, asset)| {
-                (
-                    platform_id,
-                    PlatformBinary {
-                        url: asset.url,
-                        size: asset.size,
-                        checksum: None,
-                    },
-                )
-            })
-            .collect();
+        Err(WengetError::NotFound(format!("tag {} not found for {}/{}", tag, owner, repo)).into())
+    }
+
+    /// Look up a latest release asset by its exact file name
+    ///
+    /// Bypasses platform matching entirely; errors with the list of available
+    /// assets when the name is not part of the release.
+    pub fn resolve_named_asset(
+        &self,
+        repo_url: &str,
+        asset_name: &str,
+        channel: Channel,
+    ) -> Result<PlatformBinary> {
+        let (owner, repo) = self.parse_github_url(repo_url)?;
+        let release = self.fetch_latest_release(&owner, &repo, channel, &[], None)?;
+        let asset = find_named_asset(&release, asset_name, &owner, &repo)?;
+
+        Ok(PlatformBinary {
+            url: asset.browser_download_url.clone(),
+            size: asset.size,
+            checksum: None,
+            asset_pattern: None,
+            bin: Vec::new(),
+        })
+    }
+
+    /// Fetch package info for a repository whose binary is the named release
+    /// asset, registered under `platform_id`
+    pub fn fetch_package_with_asset(
+        &self,
+        url: &str,
+        asset_name: &str,
+        platform_id: &str,
+        channel: Channel,
+    ) -> Result<Package> {
+        let (owner, repo) = self.parse_github_url(url)?;
+        let repo_info = self.fetch_repo_info(&owner, &repo)?;
+        let release = self.fetch_latest_release(&owner, &repo, channel, &[], None)?;
+        let asset = find_named_asset(&release, asset_name, &owner, &repo)?;
+
+        let mut platforms = HashMap::new();
+        platforms.insert(
+            platform_id.to_string(),
+            PlatformBinary {
+                url: asset.browser_download_url.clone(),
+                size: asset.size,
+                checksum: None,
+                asset_pattern: None,
+                bin: Vec::new(),
+            },
+        );
+
+        Ok(Package {
+            name: repo.clone(),
+            description: repo_info.description.unwrap_or_else(|| repo.clone()),
+            repo: url.to_string(),
+            homepage: Some(repo_info.html_url),
+            license: repo_info.license.map(|l| l.name),
+            platforms,
+            requires: Vec::new(),
+            yanked: Vec::new(),
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            post_install_message: None,
+            exe_name: None,
+        })
+    }
+}
+
+/// Run a GitHub request, retrying with backoff while it fails with a 5xx
+///
+/// Brief GitHub outages (502/503) would otherwise abort a whole
+/// `update all`. Rate limits are handled separately by `api_get_json`.
+fn retry_server_errors<T>(what: &str, mut request: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut backoff = Backoff::new();
+    let mut attempt = 1;
+
+    loop {
+        match request() {
+            Err(e) if attempt < SERVER_ERROR_ATTEMPTS && is_server_error(&e) => {
+                let Some(delay) = backoff.next_delay(None) else {
+                    return Err(e);
+                };
+                log::debug!(
+                    "GitHub server error for {} ({:#}), retrying in {}ms (attempt {}/{})",
+                    what,
+                    e,
+                    delay.as_millis(),
+                    attempt + 1,
+                    SERVER_ERROR_ATTEMPTS
+                );
+                std::thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether an error is a 404 from the API
+fn is_not_found(err: &anyhow::Error) -> bool {
+    matches!(
+        WengetError::find(err),
+        Some(WengetError::HttpStatus { status: 404, .. })
+    )
+}
+
+/// Whether an error is a server-side (5xx) HTTP failure
+fn is_server_error(err: &anyhow::Error) -> bool {
+    matches!(
+        WengetError::find(err),
+        Some(WengetError::HttpStatus { status, .. }) if *status >= 500
+    )
+}
+
+/// Pick the newest usable release from a newest-first release list
+///
+/// Drafts are never used, prereleases only on the prerelease channel, and
+/// yanked versions are skipped. With a `pin`, the highest version satisfying
+/// it wins, since backports can be published after a newer series.
+fn select_release(
+    releases: Vec<GitHubRelease>,
+    channel: Channel,
+    yanked: &[String],
+    pin: Option<&VersionConstraint>,
+) -> Option<GitHubRelease> {
+    // A tag glob names the releases it wants, prereleases included
+    let prereleases = channel == Channel::Prerelease || pin.is_some_and(VersionConstraint::is_glob);
+    let mut usable = releases
+        .into_iter()
+        .filter(|r| !r.draft && (prereleases || !r.prerelease) && !is_yanked(&r.tag_name, yanked));
+
+    let Some(pin) = pin else {
+        return usable.next();
+    };
+    usable
+        .filter(|r| pin.matches(&r.tag_name))
+        .max_by(|a, b| Version::parse(&a.tag_name).cmp(&Version::parse(&b.tag_name)))
+}
+
+/// Find a release asset by exact name, listing the available ones if missing
+fn find_named_asset<'a>(
+    release: &'a GitHubRelease,
+    asset_name: &str,
+    owner: &str,
+    repo: &str,
+) -> Result<&'a GitHubAsset> {
+    if let Some(asset) = release.assets.iter().find(|a| a.name == asset_name) {
+        return Ok(asset);
+    }
+
+    let available: Vec<&str> = release.assets.iter().map(|a| a.name.as_str()).collect();
+    let message = if available.is_empty() {
+        format!(
+            "Asset '{}' not found: release {} of {}/{} has no assets",
+            asset_name, release.tag_name, owner, repo
+        )
+    } else {
+        format!(
+            "Asset '{}' not found in release {} of {}/{}. Available assets:\n  {}",
+            asset_name,
+            release.tag_name,
+            owner,
+            repo,
+            available.join("\n  ")
+        )
+    };
+
+    Err(WengetError::NotFound(message).into())
+}
+
+impl GitHubProvider {
+    /// Fetch package info from the latest non-yanked release on the given channel
+    pub fn fetch_package_on(
+        &self,
+        url: &str,
+        channel: Channel,
+        yanked: &[String],
+    ) -> Result<Package> {
+        self.fetch_matching_package(url, channel, yanked, None)
+    }
+
+    /// Fetch package info from the newest release satisfying a pinned
+    /// constraint (the latest release when `pin` is `None`)
+    pub fn fetch_matching_package(
+        &self,
+        url: &str,
+        channel: Channel,
+        yanked: &[String],
+        pin: Option<&VersionConstraint>,
+    ) -> Result<Package> {
+        log::info!("Fetching package from: {}", url);
+
+        // Parse URL
+        let (owner, repo) = self.parse_github_url(url)?;
+
+        // Fetch latest release
+        let release = self.fetch_latest_release(&owner, &repo, channel, yanked, pin)?;
+        let platforms = release_platforms(&release, &owner, &repo)?;
+
+        // Fetch repo info for description and license
+        let repo_info = self.fetch_repo_info(&owner, &repo)?;
 
         // Create package
         let package = Package {
@@ -141,6 +804,12 @@ impl SourceProvider for GitHubProvider {
             homepage: Some(repo_info.html_url),
             license: repo_info.license.map(|l| l.name),
             platforms,
+            requires: Vec::new(),
+            yanked: Vec::new(),
+            tags: Vec::new(),
+            aliases: Vec::new(),
+            post_install_message: None,
+            exe_name: None,
         };
 
         let version = release.tag_name.trim_start_matches('v').to_string();
@@ -153,6 +822,62 @@ impl SourceProvider for GitHubProvider {
 
         Ok(package)
     }
+}
+
+/// The binaries a release offers, by platform id
+///
+/// Fails for source-only releases and releases with no binary for any
+/// platform.
+fn release_platforms(
+    release: &GitHubRelease,
+    owner: &str,
+    repo: &str,
+) -> Result<HashMap<String, PlatformBinary>> {
+    // Source-only releases have nothing to install on any platform
+    if release.assets.is_empty() {
+        return Err(WengetError::NoReleaseAssets {
+            repo: format!("{}/{}", owner, repo),
+            tag: release.tag_name.clone(),
+        }
+        .into());
+    }
+
+    // Convert GitHub assets to BinaryAsset
+    let assets: Vec<BinaryAsset> = release.assets.iter().map(BinaryAsset::from).collect();
+
+    // Extract platforms using BinarySelector
+    let platform_map = BinarySelector::extract_platforms(&assets);
+
+    if platform_map.is_empty() {
+        return Err(WengetError::NotFound(format!(
+            "No matching binaries found for any platform in {}/{}",
+            owner, repo
+        ))
+        .into());
+    }
+
+    // Convert to PlatformBinary map
+    Ok(platform_map
+        .into_iter()
+        .map(|(platform_id, asset)| {
+            (
+                platform_id,
+                PlatformBinary {
+                    url: asset.url,
+                    size: asset.size,
+                    checksum: None,
+                    asset_pattern: None,
+                    bin: Vec::new(),
+                },
+            )
+        })
+        .collect())
+}
+
+impl SourceProvider for GitHubProvider {
+    fn fetch_package(&self, url: &str) -> Result<Package> {
+        self.fetch_package_on(url, Channel::Stable, &[])
+    }
 
     fn name(&self) -> &str {
         "GitHub"
@@ -170,6 +895,10 @@ impl Default for GitHubProvider {
 #[derive(Debug, Deserialize)]
 struct GitHubRelease {
     tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
     assets: Vec<GitHubAsset>,
 }
 
@@ -177,12 +906,28 @@ struct GitHubRelease {
 struct GitHubAsset {
     name: String,
     browser_download_url: String,
+    /// API endpoint of the asset, the only way to download it from a
+    /// private repository
+    #[serde(default)]
+    url: String,
     size: u64,
 }
 
+impl From<&GitHubAsset> for BinaryAsset {
+    fn from(asset: &GitHubAsset) -> Self {
+        BinaryAsset {
+            name: asset.name.clone(),
+            url: asset.browser_download_url.clone(),
+            size: asset.size,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct GitHubRepo {
     description: Option<String>,
+    #[serde(default)]
+    private: bool,
     html_url: String,
     license: Option<GitHubLicense>,
 }
@@ -195,6 +940,97 @@ struct GitHubLicense {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::http::testing;
+    use std::sync::atomic::Ordering;
+
+    fn release(tag: &str, prerelease: bool) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            draft: false,
+            prerelease,
+            assets: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_release_skips_yanked() {
+        let releases = || {
+            vec![
+                release("v2.1.0-rc.1", true),
+                release("v2.0.0", false),
+                release("v1.9.0", false),
+            ]
+        };
+
+        // Newest stable release is used when nothing is yanked
+        let selected = select_release(releases(), Channel::Stable, &[], None).unwrap();
+        assert_eq!(selected.tag_name, "v2.0.0");
+
+        // A yanked newest release falls back to the previous one
+        let yanked = vec!["2.0.0".to_string()];
+        let selected = select_release(releases(), Channel::Stable, &yanked, None).unwrap();
+        assert_eq!(selected.tag_name, "v1.9.0");
+
+        // Prereleases are only considered on the prerelease channel
+        let selected = select_release(releases(), Channel::Prerelease, &yanked, None).unwrap();
+        assert_eq!(selected.tag_name, "v2.1.0-rc.1");
+
+        let all = vec!["v2.0.0".to_string(), "v1.9.0".to_string()];
+        assert!(select_release(releases(), Channel::Stable, &all, None).is_none());
+    }
+
+    #[test]
+    fn test_select_release_respects_pin() {
+        // A 1.x backport published after 2.0.0
+        let releases = || {
+            vec![
+                release("v1.9.1", false),
+                release("v2.0.0", false),
+                release("v1.9.0", false),
+                release("v1.10.0-rc.1", true),
+            ]
+        };
+        let pin = |s| VersionConstraint::parse(s).unwrap();
+
+        let selected = select_release(releases(), Channel::Stable, &[], Some(&pin("1.x")));
+        assert_eq!(selected.unwrap().tag_name, "v1.9.1");
+
+        let selected = select_release(releases(), Channel::Stable, &[], Some(&pin("^2")));
+        assert_eq!(selected.unwrap().tag_name, "v2.0.0");
+
+        let yanked = vec!["1.9.1".to_string()];
+        let selected = select_release(releases(), Channel::Stable, &yanked, Some(&pin("~1.9")));
+        assert_eq!(selected.unwrap().tag_name, "v1.9.0");
+
+        assert!(select_release(releases(), Channel::Stable, &[], Some(&pin("3.x"))).is_none());
+    }
+
+    #[test]
+    fn test_select_release_version_glob() {
+        let releases = || {
+            vec![
+                release("v14.0.0-beta", true),
+                release("v13.0.1", false),
+                release("v14.0.0", false),
+                release("v13.1.0-beta", true),
+                release("v13.0.0", false),
+            ]
+        };
+        let pin = |s| VersionConstraint::parse(s).unwrap();
+
+        // `13.*` is the newest stable 13.x release
+        let selected = select_release(releases(), Channel::Stable, &[], Some(&pin("13.*")));
+        assert_eq!(selected.unwrap().tag_name, "v13.0.1");
+
+        // A tag glob picks the highest matching tag, prereleases included
+        let selected = select_release(releases(), Channel::Stable, &[], Some(&pin("*-beta")));
+        assert_eq!(selected.unwrap().tag_name, "v14.0.0-beta");
+
+        let selected = select_release(releases(), Channel::Stable, &[], Some(&pin("v13.*-beta")));
+        assert_eq!(selected.unwrap().tag_name, "v13.1.0-beta");
+
+        assert!(select_release(releases(), Channel::Stable, &[], Some(&pin("*-rc*"))).is_none());
+    }
 
     #[test]
     fn test_parse_github_url() {
@@ -227,4 +1063,371 @@ mod tests {
         let result = provider.fetch_package("https://github.com/BurntSushi/ripgrep");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_fetch_package_assetless_release() {
+        let (url, requests) = testing::serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 66\r\nConnection: close\r\n\r\n{\"tag_name\":\"v2.0.0\",\"draft\":false,\"prerelease\":false,\"assets\":[]}",
+        ]);
+        let provider = GitHubProvider::with_api_base(&url);
+
+        let err = provider
+            .fetch_package("https://github.com/test/source-only")
+            .unwrap_err();
+
+        assert!(matches!(
+            WengetError::find(&err),
+            Some(WengetError::NoReleaseAssets { repo, tag })
+                if repo == "test/source-only" && tag == "v2.0.0"
+        ));
+        assert!(err.to_string().contains("may be source-only"));
+        // Fails before the repository info is fetched
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_fetch_release_by_tag() {
+        let body = r#"{"tag_name":"1.2.0","assets":[{"name":"tool-linux-x86_64.tar.gz","browser_download_url":"https://example.com/tool.tar.gz","size":42}]}"#;
+        let not_found = "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\
+                         Content-Length: 2\r\nConnection: close\r\n\r\n{}"
+            .to_string();
+        let (url, requests) = testing::serve_recording(vec![
+            not_found.clone(),
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+        ]);
+        let provider = GitHubProvider::with_api_base(&url);
+
+        // The tag is asked for directly, with and without a `v` prefix
+        let (tag, assets) = provider
+            .fetch_release_by_tag("https://github.com/test/tool", "1.2.0")
+            .unwrap();
+        assert_eq!(tag, "1.2.0");
+        assert_eq!(assets.len(), 1);
+        assert_eq!(assets[0].size, 42);
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("get /repos/test/tool/releases/tags/v1.2.0 "));
+        assert!(requests[1].starts_with("get /repos/test/tool/releases/tags/1.2.0 "));
+
+        let (url, _) = testing::serve_recording(vec![not_found.clone(), not_found]);
+        let provider = GitHubProvider::with_api_base(&url);
+        let err = provider
+            .fetch_release_by_tag("https://github.com/test/tool", "v9.9.9")
+            .unwrap_err();
+        assert!(matches!(
+            WengetError::find(&err),
+            Some(WengetError::NotFound(message)) if message == "tag v9.9.9 not found for test/tool"
+        ));
+    }
+
+    #[test]
+    fn test_fetch_release_gzipped() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let body = r#"{"tag_name":"v1.2.0","assets":[{"name":"tool-linux-x86_64.tar.gz","browser_download_url":"https://example.com/tool.tar.gz","size":42}]}"#;
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(body.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Encoding: gzip\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            gzipped.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(&gzipped);
+        let (url, requests) = testing::serve_recording(vec![response]);
+        let provider = GitHubProvider::with_api_base(&url);
+
+        let (tag, assets) = provider
+            .fetch_release_by_tag("https://github.com/test/tool", "v1.2.0")
+            .unwrap();
+        assert_eq!(tag, "v1.2.0");
+        assert_eq!(assets[0].size, 42);
+        assert!(requests.lock().unwrap()[0].contains("accept-encoding: gzip, deflate"));
+    }
+
+    #[test]
+    fn test_fetch_asset_sizes() {
+        let body = r#"{"tag_name":"v2.0.0","assets":[
+            {"name":"tool-v2.0.0-x86_64-unknown-linux-musl.tar.gz","browser_download_url":"https://example.com/a","size":2000},
+            {"name":"tool-v2.0.0-x86_64-pc-windows-msvc.zip","browser_download_url":"https://example.com/b","size":3000},
+            {"name":"tool-universal.pkg","browser_download_url":"https://example.com/c","size":4000}
+        ]}"#;
+        let (url, _) = testing::serve_recording(vec![format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )]);
+        let provider = GitHubProvider::with_api_base(&url);
+        let pkg: Package = serde_json::from_str(
+            r#"{"name":"tool","description":"","repo":"https://github.com/test/tool",
+                "platforms":{
+                    "linux-x86_64-musl":{"url":"https://example.com/old-linux.tar.gz","size":1},
+                    "windows-x86_64":{"url":"","size":1,"asset_pattern":"tool-{tag}-x86_64-pc-windows-msvc.zip"},
+                    "macos-aarch64":{"url":"https://github.com/test/tool/releases/download/v1/tool-universal.pkg","size":1},
+                    "freebsd-x86_64":{"url":"https://example.com/gone.tar.gz","size":1}
+                }}"#,
+        )
+        .unwrap();
+
+        let (tag, sizes) = provider.fetch_asset_sizes(&pkg, Channel::Stable).unwrap();
+        assert_eq!(tag, "v2.0.0");
+        // Detected by platform, by asset pattern and by file name; the
+        // platform without an asset in the release is left out
+        assert_eq!(
+            sizes,
+            BTreeMap::from([
+                ("linux-x86_64-musl".to_string(), 2000),
+                ("macos-aarch64".to_string(), 4000),
+                ("windows-x86_64".to_string(), 3000),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_fetched_release_is_reused_with_breaker_open() {
+        let body = r#"{"tag_name":"v2.0.0","assets":[
+            {"name":"tool-v2.0.0-x86_64-unknown-linux-musl.tar.gz","browser_download_url":"https://example.com/a","size":2000}
+        ]}"#;
+        let (url, requests) = testing::serve_recording(vec![format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )]);
+        let breaker: &'static RateLimitBreaker = Box::leak(Box::new(RateLimitBreaker::new()));
+        let repo = "https://github.com/test/tool";
+
+        // An update check looks the release up, then the limit runs out
+        let checker = GitHubProvider::with_api_base(&url).with_breaker(breaker);
+        let version = checker
+            .fetch_matching_version(repo, Channel::Stable, &[], None)
+            .unwrap();
+        assert_eq!(version, "2.0.0");
+        breaker.trip(None);
+
+        // The install that follows still gets the same release
+        let installer = GitHubProvider::with_api_base(&url).with_breaker(breaker);
+        let (version, platforms) = installer
+            .fetch_matching_platforms(repo, Channel::Stable, &[], None)
+            .unwrap();
+        assert_eq!(version, "2.0.0");
+        assert_eq!(platforms["linux-x86_64-musl"].url, "https://example.com/a");
+        let (version, binary) = installer
+            .resolve_asset_release(
+                repo,
+                "tool-{tag}-x86_64-unknown-linux-musl.tar.gz",
+                Channel::Stable,
+                &[],
+                None,
+            )
+            .unwrap();
+        assert_eq!(version, "2.0.0");
+        assert_eq!(binary.size, 2000);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+
+        // Anything not looked up yet fails fast
+        let err = installer
+            .fetch_matching_version(repo, Channel::Prerelease, &[], None)
+            .unwrap_err();
+        assert!(WengetError::rate_limit(&err).is_some());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_latest_release_falls_back_to_release_list() {
+        let not_found = "HTTP/1.1 404 Not Found\r\nContent-Type: application/json\r\n\
+                         Content-Length: 2\r\nConnection: close\r\n\r\n{}"
+            .to_string();
+        let ok = |body: &str| {
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        };
+        let body = r#"[
+            {"tag_name":"v2.0.0","draft":true,"assets":[]},
+            {"tag_name":"v1.1.0-rc1","prerelease":true,"assets":[]},
+            {"tag_name":"v1.0.0","assets":[]}
+        ]"#;
+        let (url, requests) = testing::serve_recording(vec![not_found.clone(), ok(body)]);
+        let provider = GitHubProvider::with_api_base(&url);
+
+        // Drafts never count, prereleases only with --pre
+        let version = provider
+            .fetch_latest_version("https://github.com/test/tool", Channel::Stable, &[])
+            .unwrap();
+        assert_eq!(version, "1.0.0");
+        let requests = requests.lock().unwrap();
+        assert!(requests[0].starts_with("get /repos/test/tool/releases/latest "));
+        assert!(requests[1].starts_with("get /repos/test/tool/releases?per_page=20 "));
+
+        // A repo that only publishes prereleases points at --pre
+        let body = r#"[{"tag_name":"v0.1.0-beta","prerelease":true,"assets":[]}]"#;
+        let (url, _) = testing::serve_recording(vec![not_found, ok(body)]);
+        let provider = GitHubProvider::with_api_base(&url);
+        let err = provider
+            .fetch_latest_version("https://github.com/test/tool", Channel::Stable, &[])
+            .unwrap_err();
+        assert!(format!("{:#}", err).contains("use --pre"));
+    }
+
+    #[test]
+    fn test_private_asset_downloads_through_api() {
+        let ok = |body: &str| {
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        };
+        let (cdn, cdn_requests) = testing::serve_recording(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 5\r\nConnection: close\r\n\r\nhello".to_string(),
+        ]);
+        let (assets, asset_requests) = testing::serve_recording(vec![format!(
+            "HTTP/1.1 302 Found\r\nLocation: {}/signed/tool.tar.gz\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            cdn
+        )]);
+        let download_url = "https://github.com/acme/tool/releases/download/v1.0.0/tool.tar.gz";
+        let release = format!(
+            r#"{{"tag_name":"v1.0.0","assets":[{{"name":"tool.tar.gz","browser_download_url":"{}","url":"{}/repos/acme/tool/releases/assets/7","size":5}}]}}"#,
+            download_url, assets
+        );
+        let (api, api_requests) = testing::serve_recording(vec![
+            ok(
+                r#"{"description":null,"html_url":"https://github.com/acme/tool","license":null,"private":true}"#,
+            ),
+            ok(&release),
+        ]);
+        let provider = GitHubProvider {
+            token: Some("t0ken".to_string()),
+            ..GitHubProvider::with_api_base(&api)
+        };
+
+        let asset = provider.private_asset(download_url).unwrap().unwrap();
+        assert_eq!(
+            asset.url,
+            format!("{}/repos/acme/tool/releases/assets/7", assets)
+        );
+        assert_eq!(asset.size, 5);
+        let requests = api_requests.lock().unwrap();
+        assert!(requests[0].starts_with("get /repos/acme/tool "));
+        assert!(requests[1].starts_with("get /repos/acme/tool/releases/tags/v1.0.0 "));
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dest = temp_dir.path().join("tool.tar.gz");
+        crate::downloader::download_file_with_headers(&asset.url, &dest, 0, &asset.headers)
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"hello");
+
+        // Token and octet-stream Accept go to the API only, not the signed URL
+        let request = &asset_requests.lock().unwrap()[0];
+        assert!(request.starts_with("get /repos/acme/tool/releases/assets/7 "));
+        assert!(request.contains("authorization: bearer t0ken"));
+        assert!(request.contains("accept: application/octet-stream"));
+        assert!(!cdn_requests.lock().unwrap()[0].contains("authorization"));
+
+        // Public repositories and other URLs keep the browser download URL
+        let (api, _) = testing::serve_recording(vec![ok(
+            r#"{"description":null,"html_url":"https://github.com/acme/tool","license":null,"private":false}"#,
+        )]);
+        let provider = GitHubProvider {
+            token: Some("t0ken".to_string()),
+            ..GitHubProvider::with_api_base(&api)
+        };
+        assert!(provider.private_asset(download_url).unwrap().is_none());
+        assert!(provider
+            .private_asset("https://example.com/tool.tar.gz")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_retry_server_errors() {
+        let status = |status| -> anyhow::Error {
+            WengetError::HttpStatus {
+                url: "https://api.github.com/repos/test/tool/releases/latest".to_string(),
+                status,
+            }
+            .into()
+        };
+
+        // Transient 502s are retried until the request succeeds
+        let mut calls = 0;
+        let result = retry_server_errors("test/tool", || {
+            calls += 1;
+            if calls < 3 {
+                Err(status(502))
+            } else {
+                Ok("1.0.0")
+            }
+        });
+        assert_eq!(result.unwrap(), "1.0.0");
+        assert_eq!(calls, 3);
+
+        // Client errors are returned immediately
+        let mut calls = 0;
+        let result: Result<()> = retry_server_errors("test/tool", || {
+            calls += 1;
+            Err(status(404))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+
+        // A persistent outage gives up after the attempt limit
+        let mut calls = 0;
+        let result: Result<()> = retry_server_errors("test/tool", || {
+            calls += 1;
+            Err(status(503))
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, SERVER_ERROR_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_api_get_json_retries_secondary_rate_limit() {
+        let (url, requests) = testing::serve(vec![
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 1\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: 11\r\nConnection: close\r\n\r\n{\"ok\":true}",
+        ]);
+        let provider = GitHubProvider::new().unwrap();
+
+        let started = std::time::Instant::now();
+        let value: serde_json::Value = provider.api_get_json(&url).unwrap();
+
+        assert_eq!(value["ok"], true);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_fetch_rate_limit() {
+        let body = r#"{"rate":{"limit":5000,"remaining":4990,"reset":1700000000}}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-RateLimit-Limit: 5000\r\n\
+             X-RateLimit-Remaining: 4990\r\nX-RateLimit-Reset: 1700000000\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let (url, requests) = testing::serve_recording(vec![response]);
+        let provider = GitHubProvider::with_api_base(&url);
+        assert!(provider.rate_limit().is_none());
+
+        let rate_limit = provider.fetch_rate_limit().unwrap();
+        assert_eq!(rate_limit.remaining, 4990);
+        assert_eq!(rate_limit.limit, 5000);
+        assert_eq!(provider.rate_limit(), Some(rate_limit));
+        assert!(requests.lock().unwrap()[0].starts_with("get /rate_limit "));
+    }
 }
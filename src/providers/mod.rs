@@ -2,6 +2,7 @@
 
 pub mod base;
 pub mod github;
+pub mod rate_limit;
 
 // Re-export commonly used items
 pub use base::SourceProvider;
@@ -0,0 +1,161 @@
+//! Rate-limit circuit breaker for GitHub API calls
+//!
+//! Once GitHub reports the primary rate limit is exhausted, every further API
+//! call would fail the same way until the reset time. The breaker remembers
+//! the reset time so subsequent calls either fail immediately without a
+//! request or, when waiting is enabled, sleep until the limit resets.
+
+use crate::error::WengetError;
+use crate::outln;
+use crate::utils::http::RateLimitExceeded;
+use anyhow::Result;
+use colored::Colorize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Fallback wait when the server didn't say when the limit resets
+const DEFAULT_WAIT_SECS: u64 = 60;
+
+/// Longest reset wait we are willing to sleep through
+///
+/// GitHub's primary limit resets hourly; a reset further out than that is
+/// more likely a bad header than something worth blocking on.
+const MAX_WAIT_SECS: u64 = 3600;
+
+/// Process-wide breaker shared by all provider instances
+static BREAKER: RateLimitBreaker = RateLimitBreaker::new();
+
+/// Get the shared rate-limit breaker
+pub fn breaker() -> &'static RateLimitBreaker {
+    &BREAKER
+}
+
+/// Circuit breaker tracking an exhausted rate limit
+pub struct RateLimitBreaker {
+    /// Set while the breaker is open: the reset timestamp (if known)
+    tripped: Mutex<Option<Option<u64>>>,
+    /// Sleep until reset instead of failing fast
+    wait: AtomicBool,
+}
+
+impl RateLimitBreaker {
+    /// Create a closed breaker
+    pub const fn new() -> Self {
+        Self {
+            tripped: Mutex::new(None),
+            wait: AtomicBool::new(false),
+        }
+    }
+
+    /// Choose whether callers wait for the reset instead of failing fast
+    pub fn set_wait(&self, wait: bool) {
+        self.wait.store(wait, Ordering::Relaxed);
+    }
+
+    /// Whether callers wait for the reset
+    pub fn waits(&self) -> bool {
+        self.wait.load(Ordering::Relaxed)
+    }
+
+    /// Open the breaker after a rate-limit response
+    pub fn trip(&self, reset: Option<u64>) {
+        log::debug!("Rate limit breaker tripped (reset: {:?})", reset);
+        *self.tripped.lock().unwrap() = Some(reset);
+    }
+
+    /// Gate an API request
+    ///
+    /// Returns immediately when the breaker is closed or the reset time has
+    /// passed. Otherwise waits for the reset if waiting is enabled (and the
+    /// reset is within [`MAX_WAIT_SECS`]), or fails with
+    /// [`WengetError::RateLimited`] without touching the network.
+    pub fn before_request(&self) -> Result<()> {
+        let Some(reset) = *self.tripped.lock().unwrap() else {
+            return Ok(());
+        };
+
+        let now = unix_now();
+        let remaining = match reset {
+            Some(reset) if reset <= now => {
+                self.close();
+                return Ok(());
+            }
+            Some(reset) => reset - now,
+            None => DEFAULT_WAIT_SECS,
+        };
+
+        if !self.waits() || remaining > MAX_WAIT_SECS {
+            return Err(WengetError::RateLimited(RateLimitExceeded {
+                reset,
+                retry_after: None,
+            })
+            .into());
+        }
+
+        outln!(
+            "{} GitHub API rate limit reached, waiting {}s for reset...",
+            "⏳".yellow(),
+            remaining
+        );
+        // One extra second so the reset has actually happened server-side
+        std::thread::sleep(Duration::from_secs(remaining + 1));
+        self.close();
+
+        Ok(())
+    }
+
+    fn close(&self) {
+        *self.tripped.lock().unwrap() = None;
+    }
+}
+
+impl Default for RateLimitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breaker_fails_fast_until_reset() {
+        let breaker = RateLimitBreaker::new();
+        assert!(breaker.before_request().is_ok());
+
+        breaker.trip(Some(unix_now() + 3600));
+
+        let err = breaker.before_request().unwrap_err();
+        let exceeded = WengetError::rate_limit(&err).unwrap();
+        assert!(exceeded.reset.is_some());
+        assert!(breaker.before_request().is_err());
+    }
+
+    #[test]
+    fn test_breaker_does_not_wait_past_cap() {
+        let breaker = RateLimitBreaker::new();
+        breaker.set_wait(true);
+        breaker.trip(Some(unix_now() + MAX_WAIT_SECS + 600));
+
+        assert!(breaker.before_request().is_err());
+        assert!(breaker.tripped.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn test_breaker_closes_after_reset() {
+        let breaker = RateLimitBreaker::new();
+        breaker.trip(Some(unix_now().saturating_sub(1)));
+
+        assert!(breaker.before_request().is_ok());
+        assert!(breaker.tripped.lock().unwrap().is_none());
+    }
+}
@@ -0,0 +1,120 @@
+//! Exponential backoff with jitter for retried network requests
+//!
+//! Each retry waits roughly twice as long as the previous one, with a random
+//! part so that parallel workers hitting the same limit don't all retry at
+//! the same instant. When the server says how long to wait (`Retry-After`,
+//! `X-RateLimit-Reset`) that hint is used instead of a guess. The total time
+//! spent waiting is capped so a misbehaving server can't stall a command.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// Delay before the first retry
+const BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest single guessed delay
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Longest total wait across all retries of one request
+pub const MAX_TOTAL_WAIT: Duration = Duration::from_secs(120);
+
+/// Backoff state for one request
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    attempt: u32,
+    waited: Duration,
+    budget: Duration,
+}
+
+impl Backoff {
+    /// Create a backoff limited to [`MAX_TOTAL_WAIT`]
+    pub fn new() -> Self {
+        Self::with_budget(MAX_TOTAL_WAIT)
+    }
+
+    /// Create a backoff limited to `budget` of total waiting
+    pub fn with_budget(budget: Duration) -> Self {
+        Self {
+            attempt: 0,
+            waited: Duration::ZERO,
+            budget,
+        }
+    }
+
+    /// Delay before the next retry, or `None` once the budget is spent
+    ///
+    /// `hint` is the wait the server asked for; it is honored as-is rather
+    /// than replaced by the exponential guess.
+    pub fn next_delay(&mut self, hint: Option<Duration>) -> Option<Duration> {
+        let delay = hint.unwrap_or_else(|| jittered(self.attempt));
+        if self.waited + delay > self.budget {
+            return None;
+        }
+
+        self.attempt += 1;
+        self.waited += delay;
+        Some(delay)
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Exponential delay for a zero-based retry attempt, with "equal jitter"
+///
+/// The result lies between half and all of `BASE_DELAY * 2^attempt`
+/// (capped at [`MAX_DELAY`]), so it always grows but never synchronizes.
+pub fn jittered(attempt: u32) -> Duration {
+    let ceiling = BASE_DELAY
+        .saturating_mul(1u32 << attempt.min(16))
+        .min(MAX_DELAY);
+    let half = ceiling / 2;
+    let spread = half.as_millis() as u64;
+
+    half + Duration::from_millis(random() % (spread + 1))
+}
+
+/// Cheap random number without an extra dependency
+fn random() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0),
+    );
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jittered_grows_within_bounds() {
+        for attempt in 0..8 {
+            let ceiling = BASE_DELAY.saturating_mul(1 << attempt).min(MAX_DELAY);
+            let delay = jittered(attempt);
+            assert!(delay >= ceiling / 2, "attempt {}: {:?}", attempt, delay);
+            assert!(delay <= ceiling, "attempt {}: {:?}", attempt, delay);
+        }
+        assert!(jittered(40) <= MAX_DELAY);
+    }
+
+    #[test]
+    fn test_backoff_honors_hint_and_budget() {
+        let mut backoff = Backoff::with_budget(Duration::from_secs(40));
+
+        assert_eq!(
+            backoff.next_delay(Some(Duration::from_secs(37))),
+            Some(Duration::from_secs(37))
+        );
+        // 37s spent, a second 37s wait would exceed the budget
+        assert_eq!(backoff.next_delay(Some(Duration::from_secs(37))), None);
+        assert_eq!(backoff.attempt, 1);
+    }
+}
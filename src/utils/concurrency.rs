@@ -0,0 +1,91 @@
+//! Concurrency control for network operations and file hashing
+//!
+//! Bucket fetches, update checks and `verify` hashing run on a small pool of
+//! worker threads. This module holds the global `--concurrency` setting so
+//! every pool uses the same limit; a limit of 1 runs everything on the
+//! calling thread.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Worker threads used when nothing is configured
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Upper bound for the worker count, to stay polite to the GitHub API
+pub const MAX_CONCURRENCY: usize = 16;
+
+static CONCURRENCY: AtomicUsize = AtomicUsize::new(DEFAULT_CONCURRENCY);
+
+/// Set the worker count, clamped to `1..=MAX_CONCURRENCY`
+///
+/// Returns the value actually used.
+pub fn set_concurrency(n: usize) -> usize {
+    let clamped = n.clamp(1, MAX_CONCURRENCY);
+    CONCURRENCY.store(clamped, Ordering::Relaxed);
+    clamped
+}
+
+/// Current worker count
+pub fn concurrency() -> usize {
+    CONCURRENCY.load(Ordering::Relaxed)
+}
+
+/// Run `f` for every item on a bounded pool of worker threads
+///
+/// Results are returned in the same order as `items`. With a concurrency of
+/// 1 the items are processed one after another on the calling thread.
+pub fn parallel_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = items.len().min(concurrency());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+
+                let result = f(item);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every item is processed by a worker"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel_map_keeps_order() {
+        let items: Vec<usize> = (0..20).collect();
+        let results = parallel_map(&items, |n| {
+            // Finish out of order
+            std::thread::sleep(std::time::Duration::from_millis((20 - *n as u64) % 5));
+            n * 2
+        });
+
+        assert_eq!(results, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_set_concurrency_clamps() {
+        assert_eq!(set_concurrency(0), 1);
+        assert_eq!(set_concurrency(1000), MAX_CONCURRENCY);
+        assert_eq!(set_concurrency(DEFAULT_CONCURRENCY), DEFAULT_CONCURRENCY);
+        assert_eq!(concurrency(), DEFAULT_CONCURRENCY);
+    }
+}
@@ -0,0 +1,66 @@
+//! Time limits that span several steps of one operation
+//!
+//! `wenget add --timeout` bounds the whole install of a package: download,
+//! extraction and launcher setup share one [`Deadline`], and each step
+//! checks it before (and while) doing its work.
+
+use std::time::{Duration, Instant};
+
+/// A point in time an operation must finish by
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline {
+    at: Instant,
+    limit: Duration,
+}
+
+impl Deadline {
+    /// A deadline `limit` from now
+    pub fn after(limit: Duration) -> Self {
+        Self {
+            at: Instant::now() + limit,
+            limit,
+        }
+    }
+
+    /// Time left, or an error once the deadline has passed
+    pub fn remaining(&self) -> anyhow::Result<Duration> {
+        match self.at.checked_duration_since(Instant::now()) {
+            Some(left) if !left.is_zero() => Ok(left),
+            _ => Err(self.error()),
+        }
+    }
+
+    /// Fail once the deadline has passed
+    pub fn check(&self) -> anyhow::Result<()> {
+        self.remaining().map(|_| ())
+    }
+
+    /// Whether the deadline has passed
+    pub fn expired(&self) -> bool {
+        self.remaining().is_err()
+    }
+
+    /// The error reported when the deadline has passed
+    pub fn error(&self) -> anyhow::Error {
+        anyhow::anyhow!("Timed out after {}s", self.limit.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let deadline = Deadline::after(Duration::from_secs(60));
+        assert!(deadline.remaining().unwrap() <= Duration::from_secs(60));
+        assert!(deadline.check().is_ok());
+
+        let deadline = Deadline::after(Duration::ZERO);
+        assert!(deadline.expired());
+        assert_eq!(
+            deadline.check().unwrap_err().to_string(),
+            "Timed out after 0s"
+        );
+    }
+}
@@ -1,75 +1,169 @@
 //! HTTP client utilities for WenPM
 
+use crate::error::WengetError;
 use anyhow::{Context, Result};
-use reqwest::blocking::Client;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, LOCATION,
+};
+use reqwest::redirect::Policy;
+use reqwest::{Method, StatusCode, Url};
 use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::Mutex;
 use std::time::Duration;
 
+/// Redirects followed before a request is given up on
+pub const MAX_REDIRECTS: usize = 10;
+
+/// How long a request may take unless the caller picks a timeout
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hosts downloads may be redirected to (the `redirect_hosts` setting);
+/// empty allows any host
+static REDIRECT_HOSTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Restrict the hosts downloads may be redirected to
+pub fn set_redirect_hosts(hosts: Vec<String>) {
+    *REDIRECT_HOSTS.lock().unwrap_or_else(|e| e.into_inner()) = hosts;
+}
+
+/// Hosts downloads may be redirected to; empty allows any host
+pub fn redirect_hosts() -> Vec<String> {
+    REDIRECT_HOSTS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
 /// HTTP client wrapper
 pub struct HttpClient {
     client: Client,
+    headers: HostHeaders,
+    /// Rate-limit headers of the latest JSON response that carried them
+    rate_limit: Mutex<Option<RateLimit>>,
 }
 
 impl HttpClient {
     /// Create a new HTTP client
     pub fn new() -> Result<Self> {
+        Self::with_timeout(DEFAULT_TIMEOUT)
+    }
+
+    /// Create an HTTP client whose requests give up after `timeout`
+    pub fn with_timeout(timeout: Duration) -> Result<Self> {
+        Self::with_headers(timeout, HostHeaders::default())
+    }
+
+    /// Create an HTTP client that adds `headers` to requests for their host
+    ///
+    /// Redirects are followed by [`send_get`], so headers never reach
+    /// another host. Responses may come compressed (see [`read_body`]);
+    /// asset downloads use their own client, so archives arrive as is.
+    pub fn with_headers(timeout: Duration, headers: HostHeaders) -> Result<Self> {
         let client = Client::builder()
             .user_agent(format!("WenPM/{}", env!("CARGO_PKG_VERSION")))
-            .timeout(Duration::from_secs(30))
+            .default_headers(HeaderMap::from_iter([(
+                ACCEPT_ENCODING,
+                HeaderValue::from_static("gzip, deflate"),
+            )]))
+            .timeout(timeout)
+            .redirect(Policy::none())
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            headers,
+            rate_limit: Mutex::new(None),
+        })
+    }
+
+    /// Rate-limit status reported by the latest JSON response, if any
+    pub fn last_rate_limit(&self) -> Option<RateLimit> {
+        self.rate_limit
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
     }
 
     /// Send a GET request and return the response as text
     pub fn get_text(&self, url: &str) -> Result<String> {
         log::debug!("GET {}", url);
 
-        let response = self
-            .client
-            .get(url)
-            .send()
-            .with_context(|| format!("Failed to send GET request to {}", url))?;
+        let response = send_get(&self.client, url, &self.headers, &[], |r| r)?;
+
+        check_status(&response, url)?;
 
-        if !response.status().is_success() {
-            anyhow::bail!("HTTP {} for {}", response.status(), url);
+        read_text(response)
+    }
+
+    /// Send a conditional GET request using a previously seen ETag
+    ///
+    /// Returns [`Conditional::NotModified`] when the server answers 304, so
+    /// callers can keep their cached copy without downloading it again.
+    pub fn get_text_conditional(&self, url: &str, etag: Option<&str>) -> Result<Conditional> {
+        log::debug!("GET {} (etag: {})", url, etag.unwrap_or("none"));
+
+        let response = send_get(
+            &self.client,
+            url,
+            &self.headers,
+            &[],
+            |request| match etag {
+                Some(etag) => request.header(reqwest::header::IF_NONE_MATCH, etag),
+                None => request,
+            },
+        )?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            return Ok(Conditional::NotModified);
         }
 
-        let text = response
-            .text()
-            .context("Failed to read response body as text")?;
+        check_status(&response, url)?;
+
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
 
-        Ok(text)
+        let body = read_text(response)?;
+
+        Ok(Conditional::Modified { body, etag })
     }
 
     /// Send a GET request and parse JSON response
     pub fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
         log::debug!("GET {} (JSON)", url);
 
-        let response = self
-            .client
-            .get(url)
-            .header("Accept", "application/json")
-            .send()
-            .with_context(|| format!("Failed to send GET request to {}", url))?;
+        let response = send_get(&self.client, url, &self.headers, &[], |r| {
+            r.header("Accept", "application/json")
+        })?;
+
+        if let Some(rate_limit) = RateLimit::from_headers(response.headers()) {
+            *self.rate_limit.lock().unwrap_or_else(|e| e.into_inner()) = Some(rate_limit);
+        }
 
-        if !response.status().is_success() {
-            anyhow::bail!("HTTP {} for {}", response.status(), url);
+        if let Some(exceeded) = RateLimitExceeded::from_response(&response) {
+            return Err(WengetError::RateLimited(exceeded).into());
         }
 
-        let data = response
-            .json::<T>()
-            .context("Failed to parse JSON response")?;
+        check_status(&response, url)?;
+
+        let body = read_body(response)?;
+        let data = serde_json::from_slice::<T>(&body).context("Failed to parse JSON response")?;
 
         Ok(data)
     }
 
-    /// Check GitHub API rate limit
-    #[allow(dead_code)]
-    pub fn check_rate_limit(&self) -> Result<RateLimit> {
+    /// Check the rate limit of the GitHub API at `api_base`
+    ///
+    /// Querying `/rate_limit` does not count against the limit.
+    pub fn check_rate_limit(&self, api_base: &str) -> Result<RateLimit> {
         let data: serde_json::Value = self
-            .get_json("https://api.github.com/rate_limit")
+            .get_json(&format!("{}/rate_limit", api_base))
             .context("Failed to check rate limit")?;
 
         let core = &data["rate"];
@@ -85,30 +179,370 @@ impl HttpClient {
     }
 }
 
+/// Extra request headers configured for one source (e.g. a private bucket)
+///
+/// They are only sent to the scheme, host and port of the URL they were
+/// configured for, so redirects to another host (such as a CDN) never see
+/// them. Values are marked sensitive and never logged.
+#[derive(Debug, Clone, Default)]
+pub struct HostHeaders {
+    origin: Option<Url>,
+    headers: HeaderMap,
+}
+
+impl HostHeaders {
+    /// Headers for requests to the host of `source_url`; `${VAR}`
+    /// placeholders in values are expanded from the environment
+    pub fn new(source_url: &str, headers: &BTreeMap<String, String>) -> Result<Self> {
+        if headers.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let origin =
+            Url::parse(source_url).with_context(|| format!("Invalid URL: {}", source_url))?;
+        let mut map = HeaderMap::new();
+        for (name, value) in headers {
+            let header = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name '{}'", name))?;
+            let value = crate::core::manifest::expand_env(value)
+                .with_context(|| format!("Invalid value for header '{}'", name))?;
+            let mut value = HeaderValue::from_str(&value)
+                .with_context(|| format!("Invalid value for header '{}'", name))?;
+            value.set_sensitive(true);
+            map.insert(header, value);
+        }
+
+        Ok(Self {
+            origin: Some(origin),
+            headers: map,
+        })
+    }
+
+    /// Headers to add to a request for `url`, if it is on the configured host
+    fn for_url(&self, url: &str) -> Option<&HeaderMap> {
+        let origin = self.origin.as_ref()?;
+        let url = Url::parse(url).ok()?;
+        (url.origin() == origin.origin()).then_some(&self.headers)
+    }
+}
+
+/// Parse a `Name: value` header argument
+pub fn parse_header_arg(arg: &str) -> Result<(String, String)> {
+    let (name, value) = arg
+        .split_once(':')
+        .with_context(|| format!("Invalid header '{}', expected 'Name: value'", arg))?;
+    let name = name.trim();
+    HeaderName::from_bytes(name.as_bytes())
+        .with_context(|| format!("Invalid header name '{}'", name))?;
+
+    Ok((name.to_string(), value.trim().to_string()))
+}
+
+/// Send a GET request, following up to [`MAX_REDIRECTS`] redirects
+///
+/// `client` must not follow redirects itself (`Policy::none()`). Custom
+/// `headers` are only added while on their own host, so credentials are
+/// dropped on a redirect elsewhere. A non-empty `allowed_hosts` refuses
+/// redirects to any host not listed (`*.example.com` matches subdomains).
+pub fn send_get(
+    client: &Client,
+    url: &str,
+    headers: &HostHeaders,
+    allowed_hosts: &[String],
+    build: impl Fn(RequestBuilder) -> RequestBuilder,
+) -> Result<Response> {
+    send_request(client, Method::GET, url, headers, allowed_hosts, build)
+}
+
+/// Send a request like [`send_get`] with any method
+pub fn send_request(
+    client: &Client,
+    method: Method,
+    url: &str,
+    headers: &HostHeaders,
+    allowed_hosts: &[String],
+    build: impl Fn(RequestBuilder) -> RequestBuilder,
+) -> Result<Response> {
+    let mut current = url.to_string();
+    let mut redirects = 0;
+    loop {
+        let mut request = build(client.request(method.clone(), &current));
+        if let Some(extra) = headers.for_url(&current) {
+            log::debug!("Adding {} custom header(s) for {}", extra.len(), current);
+            request = request.headers(extra.clone());
+        }
+        let response = request.send().map_err(|source| WengetError::Network {
+            url: current.clone(),
+            source,
+        })?;
+
+        let redirected = matches!(
+            response.status(),
+            StatusCode::MOVED_PERMANENTLY
+                | StatusCode::FOUND
+                | StatusCode::SEE_OTHER
+                | StatusCode::TEMPORARY_REDIRECT
+                | StatusCode::PERMANENT_REDIRECT
+        );
+        let next = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|l| l.to_str().ok())
+            .and_then(|l| response.url().join(l).ok());
+        let Some(next) = next.filter(|_| redirected) else {
+            return Ok(response);
+        };
+
+        if redirects == MAX_REDIRECTS {
+            anyhow::bail!(
+                "Too many redirects (more than {}) for {}",
+                MAX_REDIRECTS,
+                url
+            );
+        }
+        let host = next.host_str().unwrap_or_default();
+        if !is_host_allowed(host, allowed_hosts) {
+            anyhow::bail!(
+                "Refusing redirect from {} to {}: host is not in the redirect_hosts setting",
+                url,
+                host
+            );
+        }
+
+        log::debug!("Redirected to {}", next);
+        current = next.to_string();
+        redirects += 1;
+    }
+}
+
+/// Read a response body, decompressing it according to `Content-Encoding`
+///
+/// reqwest is built without its decompression features, so a `gzip` or
+/// `deflate` body is decoded here. Servers differ on whether `deflate` means
+/// zlib-wrapped or raw data; both are accepted.
+pub fn read_body(response: Response) -> Result<Vec<u8>> {
+    let encoding = response
+        .headers()
+        .get(CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_ascii_lowercase());
+    let raw = response.bytes().context("Failed to read response body")?;
+
+    let mut body = Vec::new();
+    match encoding.as_deref() {
+        None | Some("") | Some("identity") => return Ok(raw.to_vec()),
+        Some("gzip") | Some("x-gzip") => {
+            flate2::read::MultiGzDecoder::new(&raw[..])
+                .read_to_end(&mut body)
+                .context("Failed to decompress gzip response")?;
+        }
+        Some("deflate") => {
+            if flate2::read::ZlibDecoder::new(&raw[..])
+                .read_to_end(&mut body)
+                .is_err()
+            {
+                body.clear();
+                flate2::read::DeflateDecoder::new(&raw[..])
+                    .read_to_end(&mut body)
+                    .context("Failed to decompress deflate response")?;
+            }
+        }
+        Some(other) => anyhow::bail!("Unsupported response encoding '{}'", other),
+    }
+    Ok(body)
+}
+
+/// Read a response body as UTF-8 text, without a leading byte order mark
+fn read_text(response: Response) -> Result<String> {
+    let body = read_body(response)?;
+    let text = String::from_utf8(body).context("Failed to read response body as text")?;
+    Ok(match text.strip_prefix('\u{feff}') {
+        Some(rest) => rest.to_string(),
+        None => text,
+    })
+}
+
+/// Whether `host` is in `allowed` (any host when `allowed` is empty)
+fn is_host_allowed(host: &str, allowed: &[String]) -> bool {
+    allowed.is_empty()
+        || allowed.iter().any(|entry| match entry.strip_prefix("*.") {
+            Some(domain) => host
+                .to_ascii_lowercase()
+                .strip_suffix(&domain.to_ascii_lowercase())
+                .is_some_and(|sub| sub.ends_with('.')),
+            None => host.eq_ignore_ascii_case(entry),
+        })
+}
+
+/// Fail with [`WengetError::HttpStatus`] unless the response is a success
+pub fn check_status(response: &reqwest::blocking::Response, url: &str) -> Result<()> {
+    if !response.status().is_success() {
+        return Err(WengetError::HttpStatus {
+            url: url.to_string(),
+            status: response.status().as_u16(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Whether a failed request is worth retrying
+///
+/// Server errors (5xx), request timeouts, 429s and dropped or timed-out
+/// connections are transient; client errors such as a 404 are not.
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    match WengetError::find(err) {
+        Some(WengetError::HttpStatus { status, .. }) => {
+            *status >= 500 || *status == 408 || *status == 429
+        }
+        Some(WengetError::Network { source, .. }) => is_transient(source),
+        _ => err.chain().any(|e| {
+            let Some(io) = e.downcast_ref::<std::io::Error>() else {
+                return false;
+            };
+            // Errors while streaming a body come wrapped in io::Error
+            if let Some(source) = io
+                .get_ref()
+                .and_then(|e| e.downcast_ref::<reqwest::Error>())
+            {
+                return is_transient(source);
+            }
+            matches!(
+                io.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::UnexpectedEof
+            )
+        }),
+    }
+}
+
+/// Whether a transport error is likely to succeed on a second attempt
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_timeout() || err.is_connect() || err.is_body() || err.is_request()
+}
+
 impl Default for HttpClient {
     fn default() -> Self {
         Self::new().expect("Failed to create HTTP client")
     }
 }
 
-/// GitHub API rate limit information
+/// Result of a conditional GET request
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
+pub enum Conditional {
+    /// The resource changed (or no ETag was sent)
+    Modified { body: String, etag: Option<String> },
+    /// The server confirmed the cached copy is still current
+    NotModified,
+}
+
+/// Error returned when the API reports its rate limit is exhausted
+///
+/// Returned as [`WengetError::RateLimited`]; callers can detect it with
+/// [`WengetError::rate_limit`].
+#[derive(Debug, Clone, thiserror::Error)]
+#[error(
+    "GitHub API rate limit exceeded{}",
+    retry_after.map_or_else(|| reset_suffix(*reset), |s| format!(" (retry after {}s)", s))
+)]
+pub struct RateLimitExceeded {
+    /// Unix timestamp when the limit resets, if the server reported it
+    pub reset: Option<u64>,
+    /// Seconds to wait from a `Retry-After` header (secondary rate limits)
+    pub retry_after: Option<u64>,
+}
+
+impl RateLimitExceeded {
+    /// Whether this is a short-lived secondary limit rather than the
+    /// exhausted hourly quota
+    pub fn is_secondary(&self) -> bool {
+        self.retry_after.is_some()
+    }
+
+    /// Detect a rate-limit response
+    ///
+    /// Either the primary limit (403/429 with no remaining requests) or a
+    /// secondary limit (403/429 carrying `Retry-After`).
+    fn from_response(response: &reqwest::blocking::Response) -> Option<Self> {
+        let status = response.status();
+        if status != reqwest::StatusCode::FORBIDDEN
+            && status != reqwest::StatusCode::TOO_MANY_REQUESTS
+        {
+            return None;
+        }
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+
+        if let Some(retry_after) = header("retry-after") {
+            return Some(Self {
+                reset: None,
+                retry_after: Some(retry_after),
+            });
+        }
+
+        if header("x-ratelimit-remaining") != Some(0) {
+            return None;
+        }
+
+        Some(Self {
+            reset: header("x-ratelimit-reset"),
+            retry_after: None,
+        })
+    }
+}
+
+fn reset_suffix(reset: Option<u64>) -> String {
+    reset
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+        .map(|t| {
+            format!(
+                " (resets at {})",
+                t.with_timezone(&chrono::Local).format("%H:%M:%S")
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// GitHub API rate limit information
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RateLimit {
     pub limit: u64,
     pub remaining: u64,
+    /// Unix timestamp when the limit resets
     pub reset: u64,
 }
 
 impl RateLimit {
+    /// Read the `X-RateLimit-*` headers GitHub sends with every API response
+    fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+        };
+
+        Some(Self {
+            limit: header("x-ratelimit-limit")?,
+            remaining: header("x-ratelimit-remaining")?,
+            reset: header("x-ratelimit-reset")?,
+        })
+    }
+
     /// Check if we're close to the rate limit
-    #[allow(dead_code)]
     pub fn is_low(&self) -> bool {
         self.remaining < 10
     }
 
     /// Get a warning message if rate limit is low
-    #[allow(dead_code)]
     pub fn warning_message(&self) -> Option<String> {
         if self.is_low() {
             Some(format!(
@@ -121,6 +555,61 @@ impl RateLimit {
     }
 }
 
+/// Local HTTP server for tests that need canned responses
+#[cfg(test)]
+pub mod testing {
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Serve one canned response per connection, returning the server's base
+    /// URL and a counter of requests handled
+    pub fn serve(responses: Vec<&'static str>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let counter = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 1024];
+                let _ = stream.read(&mut buf);
+                counter.fetch_add(1, Ordering::SeqCst);
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    /// Like [`serve`], but keeps the raw text of every request received
+    pub fn serve_recording<R: AsRef<[u8]> + Send + 'static>(
+        responses: Vec<R>,
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 2048];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                recorded
+                    .lock()
+                    .unwrap()
+                    .push(String::from_utf8_lossy(&buf[..n]).to_lowercase());
+                stream.write_all(response.as_ref()).unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,11 +620,198 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_parse_header_arg() {
+        assert_eq!(
+            parse_header_arg("PRIVATE-TOKEN: ${GITLAB_TOKEN}").unwrap(),
+            ("PRIVATE-TOKEN".to_string(), "${GITLAB_TOKEN}".to_string())
+        );
+        assert!(parse_header_arg("no-colon").is_err());
+        assert!(parse_header_arg("bad name: x").is_err());
+    }
+
+    #[test]
+    fn test_is_host_allowed() {
+        let allowed = vec![
+            "github.com".to_string(),
+            "*.githubusercontent.com".to_string(),
+        ];
+        assert!(is_host_allowed("github.com", &allowed));
+        assert!(is_host_allowed("objects.githubusercontent.com", &allowed));
+        assert!(!is_host_allowed("githubusercontent.com", &allowed));
+        assert!(!is_host_allowed("evilgithubusercontent.com", &allowed));
+        assert!(!is_host_allowed("example.com", &allowed));
+        assert!(is_host_allowed("example.com", &[]));
+    }
+
+    #[test]
+    fn test_custom_headers_stay_on_source_host() {
+        let (cdn, cdn_requests) = testing::serve_recording(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok".to_string(),
+        ]);
+        let (gateway, gateway_requests) = testing::serve_recording(vec![format!(
+            "HTTP/1.1 302 Found\r\nLocation: {}/manifest.json\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            cdn
+        )]);
+
+        let headers = HostHeaders::new(
+            &format!("{}/bucket/manifest.json", gateway),
+            &BTreeMap::from([("PRIVATE-TOKEN".to_string(), "s3cret".to_string())]),
+        )
+        .unwrap();
+        assert!(!format!("{:?}", headers).contains("s3cret"));
+
+        let client = HttpClient::with_headers(Duration::from_secs(5), headers).unwrap();
+        let body = client
+            .get_text(&format!("{}/bucket/manifest.json", gateway))
+            .unwrap();
+        assert_eq!(body, "ok");
+
+        let gateway_requests = gateway_requests.lock().unwrap();
+        let cdn_requests = cdn_requests.lock().unwrap();
+        assert!(gateway_requests[0].contains("private-token: s3cret"));
+        assert!(gateway_requests[0].contains("user-agent: wenpm/"));
+        assert!(!cdn_requests[0].contains("private-token"));
+        assert!(!cdn_requests[0].contains("s3cret"));
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let status = |status| -> anyhow::Error {
+            WengetError::HttpStatus {
+                url: "https://example.com/tool.zip".to_string(),
+                status,
+            }
+            .into()
+        };
+
+        assert!(is_retryable(&status(503)));
+        assert!(is_retryable(&status(429)));
+        assert!(!is_retryable(&status(404)));
+        assert!(!is_retryable(&status(403)));
+
+        let reset = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+            .context("Failed to read response");
+        assert!(is_retryable(&reset));
+        assert!(!is_retryable(&anyhow::anyhow!("Failed to write to file")));
+    }
+
+    /// A 200 response carrying `body` with the given `Content-Encoding`
+    fn encoded_response(encoding: &str, body: &[u8]) -> Vec<u8> {
+        let mut response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Encoding: {}\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            encoding,
+            body.len()
+        )
+        .into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    #[test]
+    fn test_decompresses_responses() {
+        use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+
+        let json = br#"{"name":"tool"}"#;
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(json).unwrap();
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(json).unwrap();
+        let mut raw = DeflateEncoder::new(Vec::new(), Compression::default());
+        raw.write_all(json).unwrap();
+
+        let (url, requests) = testing::serve_recording(vec![
+            encoded_response("gzip", &gzip.finish().unwrap()),
+            encoded_response("deflate", &zlib.finish().unwrap()),
+            encoded_response("deflate", &raw.finish().unwrap()),
+            encoded_response("br", b"???"),
+        ]);
+        let client = HttpClient::new().unwrap();
+
+        let data: serde_json::Value = client.get_json(&url).unwrap();
+        assert_eq!(data["name"], "tool");
+        assert_eq!(client.get_text(&url).unwrap(), r#"{"name":"tool"}"#);
+        let data: serde_json::Value = client.get_json(&url).unwrap();
+        assert_eq!(data["name"], "tool");
+        let err = client.get_text(&url).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported response encoding 'br'"));
+
+        assert!(requests.lock().unwrap()[0].contains("accept-encoding: gzip, deflate"));
+    }
+
+    fn rate_limit_error(response: &'static str) -> RateLimitExceeded {
+        let (url, _) = testing::serve(vec![response]);
+        let client = HttpClient::new().unwrap();
+        let err = client.get_json::<serde_json::Value>(&url).unwrap_err();
+        WengetError::rate_limit(&err).cloned().unwrap()
+    }
+
+    #[test]
+    fn test_rate_limit_reset_header() {
+        let exceeded = rate_limit_error(
+            "HTTP/1.1 403 Forbidden\r\nX-RateLimit-Remaining: 0\r\n\
+             X-RateLimit-Reset: 1700000000\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        assert_eq!(exceeded.reset, Some(1700000000));
+        assert!(!exceeded.is_secondary());
+    }
+
+    #[test]
+    fn test_rate_limit_retry_after_header() {
+        let exceeded = rate_limit_error(
+            "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 37\r\n\
+             X-RateLimit-Remaining: 12\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        );
+        assert_eq!(exceeded.retry_after, Some(37));
+        assert!(exceeded.is_secondary());
+        assert!(exceeded.to_string().contains("retry after 37s"));
+    }
+
+    #[test]
+    fn test_forbidden_without_rate_limit_headers() {
+        let (url, _) = testing::serve(vec![
+            "HTTP/1.1 403 Forbidden\r\nX-RateLimit-Remaining: 12\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        ]);
+        let err = HttpClient::new()
+            .unwrap()
+            .get_json::<serde_json::Value>(&url)
+            .unwrap_err();
+        assert!(WengetError::rate_limit(&err).is_none());
+    }
+
     #[test]
     #[ignore] // Requires network access
     fn test_rate_limit_check() {
         let client = HttpClient::new().unwrap();
-        let rate_limit = client.check_rate_limit();
+        let rate_limit = client.check_rate_limit("https://api.github.com");
         assert!(rate_limit.is_ok());
     }
+
+    #[test]
+    fn test_tracks_rate_limit_headers() {
+        let (url, _) = testing::serve(vec![
+            "HTTP/1.1 200 OK\r\nX-RateLimit-Limit: 60\r\nX-RateLimit-Remaining: 41\r\n\
+             X-RateLimit-Reset: 1700000000\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}",
+        ]);
+        let client = HttpClient::new().unwrap();
+        assert_eq!(client.last_rate_limit(), None);
+
+        let expected = RateLimit {
+            limit: 60,
+            remaining: 41,
+            reset: 1700000000,
+        };
+        client.get_json::<serde_json::Value>(&url).unwrap();
+        assert_eq!(client.last_rate_limit(), Some(expected.clone()));
+
+        // Responses without the headers leave the last known status alone
+        client.get_json::<serde_json::Value>(&url).unwrap();
+        assert_eq!(client.last_rate_limit(), Some(expected));
+    }
 }
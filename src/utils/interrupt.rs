@@ -0,0 +1,245 @@
+//! Ctrl-C handling for in-progress installs
+//!
+//! Paths registered through a [`CleanupGuard`] are removed when the guard is
+//! dropped without being committed (the install failed) or when the user
+//! interrupts wenget, so a cancelled install leaves no partial files behind.
+//! A directory that replaced an earlier install is swapped back the same way.
+
+use colored::Colorize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// A change to take back if its install does not finish
+#[derive(Debug, Clone, PartialEq)]
+enum Undo {
+    /// Remove a partially written file or directory
+    Remove(PathBuf),
+    /// Put the directory moved aside to `previous` back at `target`
+    Restore { target: PathBuf, previous: PathBuf },
+}
+
+impl Undo {
+    fn run(&self) {
+        match self {
+            Undo::Remove(path) => remove_path(path),
+            Undo::Restore { target, previous } => {
+                remove_path(target);
+                if let Err(e) = fs::rename(previous, target) {
+                    log::error!(
+                        "Failed to restore previous install {}: {}",
+                        target.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Changes made by installs that have not finished yet
+static PENDING: Mutex<Vec<Undo>> = Mutex::new(Vec::new());
+
+/// Install the Ctrl-C handler
+///
+/// On interrupt, every pending change is taken back (newest first) before the
+/// process exits with status 130.
+pub fn install_handler() {
+    let spawned = std::thread::Builder::new()
+        .name("ctrl-c".to_string())
+        .spawn(|| {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(e) => {
+                    log::debug!("Failed to start Ctrl-C handler: {}", e);
+                    return;
+                }
+            };
+
+            if runtime.block_on(tokio::signal::ctrl_c()).is_err() {
+                return;
+            }
+
+            let pending = std::mem::take(&mut *PENDING.lock().unwrap_or_else(|e| e.into_inner()));
+            if !pending.is_empty() {
+                for undo in pending.iter().rev() {
+                    undo.run();
+                }
+                eprintln!();
+                eprintln!("{} Install cancelled, cleaned up", "✗".yellow());
+            }
+
+            std::process::exit(130);
+        });
+
+    if let Err(e) = spawned {
+        log::debug!("Failed to spawn Ctrl-C handler thread: {}", e);
+    }
+}
+
+/// Scope guard that removes partially written files and directories
+///
+/// Call [`CleanupGuard::commit`] once the work succeeded to keep them.
+#[derive(Debug, Default)]
+pub struct CleanupGuard {
+    undo: Vec<Undo>,
+    /// Paths to delete once the install is kept
+    discard: Vec<PathBuf>,
+}
+
+impl CleanupGuard {
+    /// Create an empty guard
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remove `path` if the install does not complete
+    pub fn track(&mut self, path: impl Into<PathBuf>) {
+        self.push(Undo::Remove(path.into()));
+    }
+
+    /// Move the file or directory at `previous` back to `target` if the
+    /// install does not complete, replacing whatever was put at `target` since
+    ///
+    /// With `discard`, `previous` is deleted on commit; otherwise it is kept.
+    pub fn track_replaced(&mut self, target: &Path, previous: &Path, discard: bool) {
+        self.push(Undo::Restore {
+            target: target.to_path_buf(),
+            previous: previous.to_path_buf(),
+        });
+        if discard {
+            self.remove_on_commit(previous);
+        }
+    }
+
+    /// Remove `path` once the install is kept, and leave it otherwise
+    pub fn remove_on_commit(&mut self, path: impl Into<PathBuf>) {
+        self.discard.push(path.into());
+    }
+
+    /// Keep everything tracked so far
+    pub fn commit(mut self) {
+        self.release();
+        for path in std::mem::take(&mut self.discard) {
+            log::debug!("Removing {}", path.display());
+            remove_path(&path);
+        }
+    }
+
+    fn push(&mut self, undo: Undo) {
+        lock_pending().push(undo.clone());
+        self.undo.push(undo);
+    }
+
+    /// Unregister this guard's changes from the interrupt handler
+    fn release(&mut self) -> Vec<Undo> {
+        let undo = std::mem::take(&mut self.undo);
+        lock_pending().retain(|u| !undo.contains(u));
+        undo
+    }
+}
+
+impl Drop for CleanupGuard {
+    fn drop(&mut self) {
+        for undo in self.release().iter().rev() {
+            log::debug!("Cleaning up {:?}", undo);
+            undo.run();
+        }
+    }
+}
+
+fn lock_pending() -> std::sync::MutexGuard<'static, Vec<Undo>> {
+    PENDING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn remove_path(path: &Path) {
+    if path.is_dir() && !path.is_symlink() {
+        let _ = fs::remove_dir_all(path);
+    } else if path.exists() || path.is_symlink() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_guard_removes_uncommitted_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join("download.part");
+        let dir = temp_dir.path().join("app");
+        fs::write(&file, b"partial").unwrap();
+        fs::create_dir_all(dir.join("bin")).unwrap();
+
+        {
+            let mut guard = CleanupGuard::new();
+            guard.track(&file);
+            guard.track(&dir);
+        }
+
+        assert!(!file.exists());
+        assert!(!dir.exists());
+        assert!(!lock_pending().contains(&Undo::Remove(file)));
+    }
+
+    #[test]
+    fn test_guard_keeps_committed_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join("app");
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut guard = CleanupGuard::new();
+        guard.track(&dir);
+        guard.commit();
+
+        assert!(dir.exists());
+        assert!(!lock_pending().contains(&Undo::Remove(dir)));
+    }
+
+    #[test]
+    fn test_guard_restores_replaced_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+        fs::create_dir_all(&backup).unwrap();
+        fs::write(backup.join("old"), b"1").unwrap();
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("new"), b"2").unwrap();
+
+        {
+            let mut guard = CleanupGuard::new();
+            guard.track_replaced(&target, &backup, true);
+        }
+
+        assert!(target.join("old").exists());
+        assert!(!target.join("new").exists());
+        assert!(!backup.exists());
+    }
+
+    #[test]
+    fn test_guard_discards_replaced_directory_on_commit() {
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("apps/tool");
+        let backup = temp_dir.path().join("staging/tool.old");
+        let retained = temp_dir.path().join("backups/tool/1.0.0");
+        for dir in [&target, &backup, &retained] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        let mut guard = CleanupGuard::new();
+        guard.track_replaced(&target, &backup, true);
+        guard.commit();
+        let mut guard = CleanupGuard::new();
+        guard.track_replaced(&target, &retained, false);
+        guard.commit();
+
+        assert!(target.exists());
+        assert!(!backup.exists());
+        assert!(retained.exists());
+    }
+}
@@ -1,6 +1,15 @@
 //! Utility modules for WenPM
 
+pub mod backoff;
+pub mod concurrency;
+pub mod deadline;
 pub mod http;
+pub mod interrupt;
+pub mod output;
+#[cfg(not(windows))]
+pub mod shell;
 
 // Re-export commonly used items
 pub use http::HttpClient;
+pub use interrupt::CleanupGuard;
+pub use output::is_quiet;
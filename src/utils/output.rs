@@ -0,0 +1,112 @@
+//! Output verbosity control
+//!
+//! Commands print progress directly to stdout; this module holds the global
+//! `--quiet` switch so informational lines can be suppressed uniformly, and
+//! the `--json` switch that moves progress to stderr so stdout carries only
+//! the machine-readable result. The `--utc` switch picks how timestamps are
+//! shown.
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static JSON: AtomicBool = AtomicBool::new(false);
+static UTC: AtomicBool = AtomicBool::new(false);
+
+/// `println!` for progress output of commands that support `--json`
+///
+/// Prints to stdout normally and to stderr in JSON mode.
+#[macro_export]
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        if $crate::utils::output::is_json() {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
+
+/// Enable or disable quiet mode
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Check whether informational output should be suppressed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Reserve stdout for a JSON result (progress goes to stderr)
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+/// Check whether stdout is reserved for a JSON result
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Show timestamps in UTC (`--utc`) instead of local time
+pub fn set_utc(utc: bool) {
+    UTC.store(utc, Ordering::Relaxed);
+}
+
+/// Format a timestamp for display
+///
+/// Local time to the second (`2024-05-01 14:03:09`) by default; with
+/// `--utc`, ISO 8601 in UTC (`2024-05-01T12:03:09Z`) for scripts.
+pub fn format_timestamp(time: &DateTime<Utc>) -> String {
+    if UTC.load(Ordering::Relaxed) {
+        format_utc(time)
+    } else {
+        time.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+}
+
+/// ISO 8601 in UTC, to the second
+fn format_utc(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Split a manifest-provided message into display lines
+///
+/// Trailing whitespace and blank lines around the message are dropped so it
+/// can be framed consistently wherever it is shown.
+pub fn message_lines(message: &str) -> Vec<&str> {
+    let lines: Vec<&str> = message.lines().map(str::trim_end).collect();
+    let start = lines
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(start, |i| i + 1);
+    lines[start..end].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_utc() {
+        let time = DateTime::parse_from_rfc3339("2024-05-01T14:03:09.123456+02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(format_utc(&time), "2024-05-01T12:03:09Z");
+    }
+
+    #[test]
+    fn test_message_lines() {
+        assert_eq!(
+            message_lines("\nRun `tool init`  \n\nthen restart your shell\n\n"),
+            vec!["Run `tool init`", "", "then restart your shell"]
+        );
+        assert_eq!(message_lines("one line"), vec!["one line"]);
+        assert!(message_lines(" \n\n").is_empty());
+    }
+}
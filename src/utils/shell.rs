@@ -0,0 +1,219 @@
+//! Shell startup files, for putting the launcher directory on PATH
+
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Marker line written above the PATH entry, also used to remove it again
+const MARKER: &str = "# Wenget";
+
+/// A login shell whose startup file wenget knows how to edit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    /// Any other POSIX shell, configured through ~/.profile
+    Other,
+}
+
+/// What [`add_path_entry`] did to a startup file
+#[derive(Debug, PartialEq, Eq)]
+pub enum RcUpdate {
+    /// The file already mentions the directory; nothing was written
+    AlreadyPresent,
+    /// The PATH entry was appended; `backup` holds the previous contents
+    /// (None if the file did not exist yet)
+    Appended { backup: Option<PathBuf> },
+}
+
+impl Shell {
+    /// The user's shell, from the `SHELL` environment variable
+    pub fn detect() -> Self {
+        std::env::var("SHELL")
+            .map(|shell| Self::from_path(&shell))
+            .unwrap_or(Self::Other)
+    }
+
+    /// Identify a shell by its executable path (`/usr/bin/zsh`)
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).file_name().and_then(|s| s.to_str()) {
+            Some("bash") => Self::Bash,
+            Some("zsh") => Self::Zsh,
+            Some("fish") => Self::Fish,
+            _ => Self::Other,
+        }
+    }
+
+    /// The startup file read by interactive shells
+    pub fn rc_file(self, home: &Path) -> PathBuf {
+        match self {
+            Self::Bash => home.join(".bashrc"),
+            Self::Zsh => home.join(".zshrc"),
+            Self::Fish => home.join(".config").join("fish").join("config.fish"),
+            Self::Other => home.join(".profile"),
+        }
+    }
+
+    /// The line that puts `dir` in front of PATH
+    pub fn path_line(self, dir: &Path) -> String {
+        match self {
+            Self::Fish => format!("fish_add_path \"{}\"", dir.display()),
+            _ => format!("export PATH=\"{}:$PATH\"", dir.display()),
+        }
+    }
+}
+
+/// Startup files present under `home`, each with the shell that reads it
+///
+/// Falls back to `~/.profile` (to be created) when there are none.
+pub fn existing_rc_files(home: &Path) -> Vec<(Shell, PathBuf)> {
+    let candidates = [
+        (Shell::Bash, home.join(".bashrc")),
+        (Shell::Bash, home.join(".bash_profile")),
+        (Shell::Zsh, Shell::Zsh.rc_file(home)),
+        (Shell::Fish, Shell::Fish.rc_file(home)),
+        (Shell::Other, Shell::Other.rc_file(home)),
+    ];
+
+    let existing: Vec<(Shell, PathBuf)> = candidates
+        .into_iter()
+        .filter(|(_, path)| path.exists())
+        .collect();
+
+    if existing.is_empty() {
+        vec![(Shell::Other, Shell::Other.rc_file(home))]
+    } else {
+        existing
+    }
+}
+
+/// Append `line` to a startup file unless it already mentions `dir`
+///
+/// An existing file is copied to `<file>.wenget-backup` first.
+pub fn add_path_entry(rc_file: &Path, line: &str, dir: &Path) -> Result<RcUpdate> {
+    let backup = if rc_file.exists() {
+        let content = fs::read_to_string(rc_file)
+            .with_context(|| format!("Failed to read {}", rc_file.display()))?;
+        if content.contains(&*dir.to_string_lossy()) {
+            return Ok(RcUpdate::AlreadyPresent);
+        }
+
+        let mut backup = rc_file.as_os_str().to_owned();
+        backup.push(".wenget-backup");
+        let backup = PathBuf::from(backup);
+        fs::copy(rc_file, &backup)
+            .with_context(|| format!("Failed to back up {}", rc_file.display()))?;
+        Some(backup)
+    } else {
+        if let Some(parent) = rc_file.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        None
+    };
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(rc_file)
+        .with_context(|| format!("Failed to open {}", rc_file.display()))?;
+    writeln!(file, "\n{}\n{}", MARKER, line)
+        .with_context(|| format!("Failed to write to {}", rc_file.display()))?;
+
+    Ok(RcUpdate::Appended { backup })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_shell_from_path() {
+        assert_eq!(Shell::from_path("/bin/bash"), Shell::Bash);
+        assert_eq!(Shell::from_path("/usr/bin/zsh"), Shell::Zsh);
+        assert_eq!(Shell::from_path("/opt/homebrew/bin/fish"), Shell::Fish);
+        assert_eq!(Shell::from_path("/bin/dash"), Shell::Other);
+        assert_eq!(Shell::from_path(""), Shell::Other);
+    }
+
+    #[test]
+    fn test_path_line() {
+        let dir = Path::new("/home/u/.wenget/bin");
+        assert_eq!(
+            Shell::Bash.path_line(dir),
+            "export PATH=\"/home/u/.wenget/bin:$PATH\""
+        );
+        assert_eq!(
+            Shell::Fish.path_line(dir),
+            "fish_add_path \"/home/u/.wenget/bin\""
+        );
+        assert!(Shell::Fish
+            .rc_file(Path::new("/home/u"))
+            .ends_with(".config/fish/config.fish"));
+    }
+
+    #[test]
+    fn test_add_path_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(".wenget").join("bin");
+        let line = Shell::Bash.path_line(&dir);
+
+        // An existing file is backed up before the entry is appended
+        let rc = temp_dir.path().join(".bashrc");
+        fs::write(&rc, "alias ll='ls -l'\n").unwrap();
+        let update = add_path_entry(&rc, &line, &dir).unwrap();
+        let backup = temp_dir.path().join(".bashrc.wenget-backup");
+        assert_eq!(
+            update,
+            RcUpdate::Appended {
+                backup: Some(backup.clone())
+            }
+        );
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "alias ll='ls -l'\n");
+        let content = fs::read_to_string(&rc).unwrap();
+        assert!(content.starts_with("alias ll='ls -l'\n"));
+        assert!(content.ends_with(&format!("# Wenget\n{}\n", line)));
+
+        // Running it again leaves the file alone
+        assert_eq!(
+            add_path_entry(&rc, &line, &dir).unwrap(),
+            RcUpdate::AlreadyPresent
+        );
+        assert_eq!(fs::read_to_string(&rc).unwrap(), content);
+
+        // A missing file (and its directory) is created
+        let fish = Shell::Fish.rc_file(temp_dir.path());
+        let update = add_path_entry(&fish, &Shell::Fish.path_line(&dir), &dir).unwrap();
+        assert_eq!(update, RcUpdate::Appended { backup: None });
+        assert!(fs::read_to_string(&fish).unwrap().contains("fish_add_path"));
+    }
+
+    #[test]
+    fn test_existing_rc_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let home = temp_dir.path();
+
+        // Nothing there yet: ~/.profile is created
+        assert_eq!(
+            existing_rc_files(home),
+            vec![(Shell::Other, home.join(".profile"))]
+        );
+
+        fs::write(home.join(".bash_profile"), "").unwrap();
+        fs::write(home.join(".zshrc"), "").unwrap();
+        let fish = Shell::Fish.rc_file(home);
+        fs::create_dir_all(fish.parent().unwrap()).unwrap();
+        fs::write(&fish, "").unwrap();
+        assert_eq!(
+            existing_rc_files(home),
+            vec![
+                (Shell::Bash, home.join(".bash_profile")),
+                (Shell::Zsh, home.join(".zshrc")),
+                (Shell::Fish, fish),
+            ]
+        );
+    }
+}